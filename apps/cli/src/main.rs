@@ -7,7 +7,7 @@ use futures_util::{Stream, StreamExt};
 use std::path::PathBuf;
 use std::time::Instant;
 use structopt::StructOpt;
-use yql_dataset::dataset::DataSet;
+use yql_dataset::dataset::{DataSet, DisplayOptions};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "yql-cli")]
@@ -15,6 +15,61 @@ struct Options {
     #[structopt(default_value = "http://localhost:33001")]
     /// YQL Server url
     url: String,
+
+    #[structopt(long)]
+    /// Number of digits after the decimal point of floats
+    float_precision: Option<usize>,
+
+    #[structopt(long)]
+    /// Display floats in scientific notation
+    scientific: bool,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Manage the checkpoints of the streams
+    Checkpoint(CheckpointCommand),
+
+    /// Manage the savepoints of the streams
+    Savepoint(SavepointCommand),
+}
+
+#[derive(Debug, StructOpt)]
+enum CheckpointCommand {
+    /// List the checkpoints of all streams
+    List,
+
+    /// Show the checkpoint of a stream, with the size of the state of each node
+    Inspect { stream: String },
+
+    /// Delete the checkpoints of the streams that no longer exist
+    Prune {
+        #[structopt(long)]
+        /// Also delete the checkpoints of the streams that are not running
+        stopped: bool,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum SavepointCommand {
+    /// Create a checkpoint of a running stream now
+    Trigger { stream: String },
+}
+
+impl Options {
+    fn display_options(&self) -> DisplayOptions {
+        let mut display_options = DisplayOptions::default();
+        if let Some(precision) = self.float_precision {
+            display_options = display_options.with_float_precision(precision);
+        }
+        if self.scientific {
+            display_options = display_options.with_scientific();
+        }
+        display_options
+    }
 }
 
 fn history_path() -> Option<PathBuf> {
@@ -67,11 +122,44 @@ fn create_dataset_stream(
     })
 }
 
+async fn run_command(cli: &reqwest::Client, url: &str, command: &Command) -> Result<()> {
+    let req = match command {
+        Command::Checkpoint(CheckpointCommand::List) => {
+            cli.get(format!("{}/admin/checkpoints", url))
+        }
+        Command::Checkpoint(CheckpointCommand::Inspect { stream }) => {
+            cli.get(format!("{}/admin/checkpoints/{}", url, stream))
+        }
+        Command::Checkpoint(CheckpointCommand::Prune { stopped }) => cli.post(format!(
+            "{}/admin/checkpoints/prune?stopped={}",
+            url, stopped
+        )),
+        Command::Savepoint(SavepointCommand::Trigger { stream }) => {
+            cli.post(format!("{}/admin/savepoints/{}", url, stream))
+        }
+    };
+
+    let resp = req.send().await?;
+    if !resp.status().is_success() {
+        anyhow::bail!("{}", resp.text().await?);
+    }
+    let value: serde_json::Value = resp.json().await?;
+    if !value.is_null() {
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let options: Options = Options::from_args();
+    let display_options = options.display_options();
     let cli = reqwest::Client::builder().gzip(true).brotli(true).build()?;
 
+    if let Some(command) = &options.command {
+        return run_command(&cli, &options.url, command).await;
+    }
+
     let mut rl = rustyline::Editor::<()>::new();
 
     if let Some(path) = history_path() {
@@ -129,10 +217,13 @@ async fn main() -> Result<()> {
                     match res {
                         Ok(dataset) => {
                             if first {
-                                print!("{}", dataset.display());
+                                print!("{}", dataset.display().with_options(display_options));
                                 first = false;
                             } else {
-                                print!("{}", dataset.display_no_header());
+                                print!(
+                                    "{}",
+                                    dataset.display_no_header().with_options(display_options)
+                                );
                             }
                         }
                         Err(err) => {
@@ -1,4 +1,7 @@
+mod pgwire;
+
 use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 
 use anyhow::Result;
@@ -6,6 +9,7 @@ use bytes::{BufMut, Bytes, BytesMut};
 use futures_util::stream::BoxStream;
 use futures_util::StreamExt;
 use hyper::Body;
+use serde::{Deserialize, Serialize};
 use structopt::StructOpt;
 use warp::http::StatusCode;
 use warp::reply::Response;
@@ -18,6 +22,15 @@ use yql_service::Service;
 struct Options {
     #[structopt(parse(from_os_str), long = "data-dir", default_value = "data")]
     data_dir: PathBuf,
+
+    /// Port of the Postgres wire-protocol frontend, which is only started if it is set.
+    #[structopt(long = "pg-port")]
+    pg_port: Option<u16>,
+
+    /// Address of the Postgres wire-protocol frontend, which has no authentication, so only the
+    /// local host can connect by default.
+    #[structopt(long = "pg-host", default_value = "127.0.0.1")]
+    pg_host: IpAddr,
 }
 
 fn create_body_stream(
@@ -48,11 +61,36 @@ fn create_body_stream(
     Response::new(Body::wrap_stream(bytes_stream))
 }
 
+fn create_json_response(res: Result<impl Serialize>) -> Response {
+    match res {
+        Ok(value) => warp::reply::json(&value).into_response(),
+        Err(err) => {
+            warp::reply::with_status(err.to_string(), StatusCode::BAD_REQUEST).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PruneQuery {
+    #[serde(default)]
+    stopped: bool,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let opt: Options = Options::from_args();
     let service = Service::open(&opt.data_dir)?;
 
+    if let Some(pg_port) = opt.pg_port {
+        let addr = SocketAddr::new(opt.pg_host, pg_port);
+        let service = service.clone();
+        tokio::spawn(async move {
+            if let Err(err) = pgwire::serve(service, addr).await {
+                tracing::error!(error = %err, "failed to start the pgwire frontend");
+            }
+        });
+    }
+
     let post_sql = warp::post()
         .and(warp::path!("sql"))
         .and(warp::body::bytes())
@@ -80,7 +118,69 @@ async fn main() -> Result<()> {
             }
         });
 
-    let routes = post_sql;
+    let get_metrics = warp::get()
+        .and(warp::path!("metrics"))
+        .map(yql_core::metrics::render);
+
+    let get_checkpoints = warp::get()
+        .and(warp::path!("admin" / "checkpoints"))
+        .and_then({
+            let service = service.clone();
+            move || {
+                let service = service.clone();
+                async move { Ok::<_, Infallible>(create_json_response(service.checkpoints().await)) }
+            }
+        });
+
+    let get_checkpoint = warp::get()
+        .and(warp::path!("admin" / "checkpoints" / String))
+        .and_then({
+            let service = service.clone();
+            move |name: String| {
+                let service = service.clone();
+                async move {
+                    Ok::<_, Infallible>(create_json_response(
+                        service.inspect_checkpoint(&name).await,
+                    ))
+                }
+            }
+        });
+
+    let post_prune_checkpoints = warp::post()
+        .and(warp::path!("admin" / "checkpoints" / "prune"))
+        .and(warp::query::<PruneQuery>())
+        .and_then({
+            let service = service.clone();
+            move |query: PruneQuery| {
+                let service = service.clone();
+                async move {
+                    Ok::<_, Infallible>(create_json_response(
+                        service.prune_checkpoints(query.stopped).await,
+                    ))
+                }
+            }
+        });
+
+    let post_savepoint = warp::post()
+        .and(warp::path!("admin" / "savepoints" / String))
+        .and_then({
+            let service = service.clone();
+            move |name: String| {
+                let service = service.clone();
+                async move {
+                    Ok::<_, Infallible>(create_json_response(
+                        service.trigger_savepoint(&name).await,
+                    ))
+                }
+            }
+        });
+
+    let routes = post_sql
+        .or(get_metrics)
+        .or(get_checkpoints)
+        .or(get_checkpoint)
+        .or(post_prune_checkpoints)
+        .or(post_savepoint);
 
     warp::serve(routes).bind(([0, 0, 0, 0], 33001)).await;
     Ok(())
@@ -0,0 +1,282 @@
+//! A minimal Postgres wire-protocol frontend.
+//!
+//! Only the simple query protocol is supported, all values are sent in text format.
+
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+use chrono::{Offset, TimeZone};
+use chrono_tz::Tz;
+use futures_util::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use yql_core::array::{ArrayExt, DataType, Scalar};
+use yql_core::dataset::{DataSet, SchemaRef};
+use yql_service::Service;
+
+const PROTOCOL_VERSION: i32 = 196608;
+const SSL_REQUEST_CODE: i32 = 80877103;
+const CANCEL_REQUEST_CODE: i32 = 80877102;
+
+/// Maximum length of a startup message, as in PostgreSQL.
+const MAX_STARTUP_MESSAGE_LEN: usize = 10000;
+/// Maximum length of the other messages, the buffer of a message is allocated before reading it.
+const MAX_MESSAGE_LEN: usize = 16 * 1024 * 1024;
+
+pub async fn serve(service: Service, addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = service.clone();
+        tokio::spawn(async move {
+            if let Err(err) = Connection::new(stream, service).run().await {
+                tracing::error!(error = %err, "pgwire connection error");
+            }
+        });
+    }
+}
+
+fn type_oid(data_type: DataType) -> (i32, i16) {
+    match data_type {
+        DataType::Boolean => (16, 1),
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 => (21, 2),
+        DataType::Int32 | DataType::UInt16 => (23, 4),
+        DataType::Int64 | DataType::UInt32 => (20, 8),
+        // `numeric`, as no integer type of PostgreSQL holds all `uint64` values.
+        DataType::UInt64 => (1700, -1),
+        DataType::Float32 => (700, 4),
+        DataType::Float64 => (701, 8),
+        DataType::Timestamp(_) => (1184, 8),
+        DataType::Date => (1082, 4),
+        DataType::Time => (1083, 8),
+        // Everything else is sent as text.
+        _ => (25, -1),
+    }
+}
+
+/// Returns the length of the body of a message from its length, which includes the 4 bytes of
+/// the length itself.
+fn body_len(len: i32, max_len: usize) -> Result<usize> {
+    anyhow::ensure!(
+        len >= 4 && len as usize <= max_len,
+        "invalid message length: {}",
+        len
+    );
+    Ok(len as usize - 4)
+}
+
+/// Formats a timestamp like PostgreSQL formats a `timestamptz` with the ISO date style, in the
+/// time zone of the column, or in UTC.
+fn format_timestamp(timestamp: i64, tz: Option<Tz>) -> String {
+    let datetime = tz.unwrap_or(Tz::UTC).timestamp_millis(timestamp);
+    let offset = datetime.offset().fix().local_minus_utc();
+    let mut s = format!(
+        "{}{}{:02}",
+        datetime.format("%Y-%m-%d %H:%M:%S%.3f"),
+        if offset < 0 { '-' } else { '+' },
+        offset.abs() / 3600
+    );
+    if offset % 3600 != 0 {
+        s.push_str(&format!(":{:02}", offset.abs() % 3600 / 60));
+    }
+    s
+}
+
+fn put_cstr(buf: &mut BytesMut, s: &str) {
+    buf.put_slice(s.as_bytes());
+    buf.put_u8(0);
+}
+
+struct Connection {
+    stream: TcpStream,
+    service: Service,
+    buf: BytesMut,
+}
+
+impl Connection {
+    fn new(stream: TcpStream, service: Service) -> Self {
+        Self {
+            stream,
+            service,
+            buf: BytesMut::new(),
+        }
+    }
+
+    async fn send(&mut self, tag: u8, body: &[u8]) -> Result<()> {
+        let mut buf = BytesMut::with_capacity(body.len() + 5);
+        buf.put_u8(tag);
+        buf.put_i32(body.len() as i32 + 4);
+        buf.put_slice(body);
+        self.stream.write_all(&buf).await?;
+        Ok(())
+    }
+
+    async fn read_exact(&mut self, len: usize) -> Result<Option<BytesMut>> {
+        while self.buf.len() < len {
+            if self.stream.read_buf(&mut self.buf).await? == 0 {
+                return Ok(None);
+            }
+        }
+        Ok(Some(self.buf.split_to(len)))
+    }
+
+    async fn startup(&mut self) -> Result<bool> {
+        loop {
+            let len = match self.read_exact(4).await? {
+                Some(mut data) => body_len(data.get_i32(), MAX_STARTUP_MESSAGE_LEN)?,
+                None => return Ok(false),
+            };
+            anyhow::ensure!(len >= 4, "invalid startup message");
+            let mut body = match self.read_exact(len).await? {
+                Some(body) => body,
+                None => return Ok(false),
+            };
+
+            match body.get_i32() {
+                SSL_REQUEST_CODE => self.stream.write_all(b"N").await?,
+                CANCEL_REQUEST_CODE => return Ok(false),
+                PROTOCOL_VERSION => break,
+                version => anyhow::bail!("unsupported protocol version: {}", version),
+            }
+        }
+
+        let mut body = BytesMut::new();
+        body.put_i32(0);
+        self.send(b'R', &body).await?;
+
+        for (name, value) in &[
+            ("server_version", "13.0"),
+            ("server_encoding", "UTF8"),
+            ("client_encoding", "UTF8"),
+            ("DateStyle", "ISO"),
+        ] {
+            let mut body = BytesMut::new();
+            put_cstr(&mut body, name);
+            put_cstr(&mut body, value);
+            self.send(b'S', &body).await?;
+        }
+
+        self.ready_for_query().await?;
+        Ok(true)
+    }
+
+    async fn ready_for_query(&mut self) -> Result<()> {
+        self.send(b'Z', b"I").await
+    }
+
+    async fn error(&mut self, message: &str) -> Result<()> {
+        let mut body = BytesMut::new();
+        body.put_u8(b'S');
+        put_cstr(&mut body, "ERROR");
+        body.put_u8(b'C');
+        put_cstr(&mut body, "XX000");
+        body.put_u8(b'M');
+        put_cstr(&mut body, message);
+        body.put_u8(0);
+        self.send(b'E', &body).await
+    }
+
+    async fn row_description(&mut self, schema: &SchemaRef) -> Result<()> {
+        let mut body = BytesMut::new();
+        body.put_i16(schema.fields().len() as i16);
+        for field in schema.fields() {
+            let (oid, size) = type_oid(field.data_type);
+            put_cstr(&mut body, &field.name);
+            body.put_i32(0);
+            body.put_i16(0);
+            body.put_i32(oid);
+            body.put_i16(size);
+            body.put_i32(-1);
+            body.put_i16(0);
+        }
+        self.send(b'T', &body).await
+    }
+
+    async fn data_rows(&mut self, dataset: &DataSet) -> Result<()> {
+        for row in 0..dataset.len() {
+            let mut body = BytesMut::new();
+            body.put_i16(dataset.columns().len() as i16);
+            for (field, column) in dataset.schema().fields().iter().zip(dataset.columns()) {
+                let value = match (field.data_type, column.scalar_value(row)) {
+                    (_, Scalar::Null) => {
+                        body.put_i32(-1);
+                        continue;
+                    }
+                    (_, Scalar::Boolean(value)) => (if value { "t" } else { "f" }).to_string(),
+                    (DataType::Timestamp(tz), Scalar::Timestamp(timestamp)) => {
+                        format_timestamp(timestamp, tz)
+                    }
+                    (_, value) => value.to_string(),
+                };
+                body.put_i32(value.len() as i32);
+                body.put_slice(value.as_bytes());
+            }
+            self.send(b'D', &body).await?;
+        }
+        Ok(())
+    }
+
+    async fn query(&mut self, sql: &str) -> Result<()> {
+        let sql = sql.trim().trim_end_matches(';');
+        if sql.is_empty() {
+            return self.send(b'I', &[]).await;
+        }
+
+        let (schema, mut stream) = match self.service.execute_with_schema(sql).await {
+            Ok(res) => res,
+            Err(err) => return self.error(&err.to_string()).await,
+        };
+
+        // The rows are described before the first dataset, a query without rows still returns
+        // its columns.
+        self.row_description(&schema).await?;
+        let mut rows = 0;
+        while let Some(res) = stream.next().await {
+            match res {
+                Ok(dataset) => {
+                    self.data_rows(&dataset).await?;
+                    rows += dataset.len();
+                }
+                Err(err) => return self.error(&err.to_string()).await,
+            }
+        }
+
+        let mut body = BytesMut::new();
+        put_cstr(&mut body, &format!("SELECT {}", rows));
+        self.send(b'C', &body).await
+    }
+
+    async fn run(mut self) -> Result<()> {
+        if !self.startup().await? {
+            return Ok(());
+        }
+
+        loop {
+            let (tag, len) = match self.read_exact(5).await? {
+                Some(mut header) => (header.get_u8(), header.get_i32()),
+                None => return Ok(()),
+            };
+            let body = match self.read_exact(body_len(len, MAX_MESSAGE_LEN)?).await? {
+                Some(body) => body,
+                None => return Ok(()),
+            };
+
+            match tag {
+                b'Q' => {
+                    let sql = String::from_utf8_lossy(&body);
+                    let sql = sql.trim_end_matches('\0').to_string();
+                    self.query(&sql).await?;
+                    self.ready_for_query().await?;
+                }
+                b'S' => self.ready_for_query().await?,
+                b'X' => return Ok(()),
+                b'P' => {
+                    self.error("extended query protocol is not supported")
+                        .await?
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use anyhow::Result;
+
+use crate::expr::Expr;
+use crate::sql::ast::Watermark;
+use crate::sql::planner::create_named_source;
+use crate::sql::SqlContext;
+use crate::{DataFrame, Emit, Window};
+
+enum Step {
+    Filter(Expr),
+    Select(Vec<Expr>),
+    Aggregate {
+        group_exprs: Vec<Expr>,
+        aggr_exprs: Vec<Expr>,
+        window: Option<Window>,
+        time_zone_expr: Option<Expr>,
+        allowed_lateness: i64,
+        emit: Emit,
+    },
+}
+
+/// Builds the plan of a query step by step, like a SQL query without its text.
+///
+/// ```ignore
+/// let df = LogicalPlanBuilder::scan("t")
+///     .filter(col("a").gt(value(Literal::Int(1))))
+///     .aggregate(vec![col("b")], vec![col("b"), call("count", vec![col("a")])])
+///     .window(Window::Fixed { length: 60000 })
+///     .build(&ctx)?;
+/// ```
+///
+/// The source is resolved by name when the plan is built, as the sources of SQL queries are.
+pub struct LogicalPlanBuilder {
+    source: String,
+    alias: Option<String>,
+    watermark: Option<Watermark>,
+    steps: Vec<Step>,
+}
+
+impl LogicalPlanBuilder {
+    /// Reads the rows of the source `name`.
+    pub fn scan(name: impl Into<String>) -> Self {
+        Self {
+            source: name.into(),
+            alias: None,
+            watermark: None,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Qualifies the columns of the source with `alias`.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Uses `time_expr` as the time of the rows of the source, with a watermark `delay`
+    /// milliseconds behind it.
+    pub fn watermark(mut self, time_expr: Expr, delay: i64) -> Self {
+        self.watermark = Some(Watermark { time_expr, delay });
+        self
+    }
+
+    pub fn filter(mut self, expr: Expr) -> Self {
+        self.steps.push(Step::Filter(expr));
+        self
+    }
+
+    pub fn select(mut self, exprs: Vec<Expr>) -> Self {
+        self.steps.push(Step::Select(exprs));
+        self
+    }
+
+    /// Aggregates the rows of each group, the window is set with [`LogicalPlanBuilder::window`].
+    pub fn aggregate(mut self, group_exprs: Vec<Expr>, aggr_exprs: Vec<Expr>) -> Self {
+        self.steps.push(Step::Aggregate {
+            group_exprs,
+            aggr_exprs,
+            window: None,
+            time_zone_expr: None,
+            allowed_lateness: 0,
+            emit: Emit::Final,
+        });
+        self
+    }
+
+    /// Sets the window of the last aggregation.
+    ///
+    /// Has no effect if the last step is not an aggregation.
+    pub fn window(mut self, window: Window) -> Self {
+        if let Some(Step::Aggregate { window: w, .. }) = self.steps.last_mut() {
+            *w = Some(window);
+        }
+        self
+    }
+
+    /// See [`DataFrame::window_time_zone`].
+    ///
+    /// Has no effect if the last step is not an aggregation.
+    pub fn window_time_zone(mut self, expr: Expr) -> Self {
+        if let Some(Step::Aggregate { time_zone_expr, .. }) = self.steps.last_mut() {
+            *time_zone_expr = Some(expr);
+        }
+        self
+    }
+
+    /// See [`DataFrame::allow_lateness`].
+    ///
+    /// Has no effect if the last step is not an aggregation.
+    pub fn allow_lateness(mut self, lateness: i64) -> Self {
+        if let Some(Step::Aggregate {
+            allowed_lateness, ..
+        }) = self.steps.last_mut()
+        {
+            *allowed_lateness = lateness;
+        }
+        self
+    }
+
+    /// See [`DataFrame::emit`].
+    ///
+    /// Has no effect if the last step is not an aggregation.
+    pub fn emit(mut self, emit: Emit) -> Self {
+        if let Some(Step::Aggregate { emit: e, .. }) = self.steps.last_mut() {
+            *e = emit;
+        }
+        self
+    }
+
+    /// Resolves the source with `ctx` and creates the data frame.
+    pub fn build(self, ctx: &dyn SqlContext) -> Result<DataFrame> {
+        let mut df = create_named_source(ctx, self.source, self.alias, self.watermark)?;
+        for step in self.steps {
+            df = match step {
+                Step::Filter(expr) => df.filter(expr),
+                Step::Select(exprs) => df.select(exprs),
+                Step::Aggregate {
+                    group_exprs,
+                    aggr_exprs,
+                    window,
+                    time_zone_expr,
+                    allowed_lateness,
+                    emit,
+                } => {
+                    let window = match window {
+                        Some(window) => window,
+                        None => anyhow::bail!("the window clause is missing."),
+                    };
+                    let mut df = df
+                        .aggregate(group_exprs, aggr_exprs, window)
+                        .allow_lateness(allowed_lateness)
+                        .emit(emit);
+                    if let Some(time_zone_expr) = time_zone_expr {
+                        df = df.window_time_zone(time_zone_expr);
+                    }
+                    df
+                }
+            };
+        }
+        Ok(df)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{Field, Schema};
+    use crate::dsl::*;
+    use crate::expr::Literal;
+    use crate::source_provider::{SourceProviderWrapper, TestSource};
+    use crate::sources::Debezium;
+    use crate::sql::SqlSourceProvider;
+
+    struct Context;
+
+    impl SqlContext for Context {
+        fn create_source_provider(&self, name: &str) -> Result<Option<SqlSourceProvider>> {
+            Ok(if name == "t" {
+                Some(SqlSourceProvider {
+                    source_provider: TestSource::provider(vec![
+                        Field::new("a", DataType::Int32),
+                        Field::new("b", DataType::String),
+                        Field::new("c", DataType::Float64),
+                    ]),
+                    time_expr: None,
+                    watermark_expr: None,
+                })
+            } else if name == "d" {
+                let schema = Schema::try_new(vec![Field::new("a", DataType::Int32)])?;
+                Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(Debezium::new(
+                        Arc::new(schema),
+                        "changes.json",
+                    )?)),
+                    time_expr: None,
+                    watermark_expr: None,
+                })
+            } else {
+                None
+            })
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let df = LogicalPlanBuilder::scan("t")
+            .filter(col("a").gt(value(Literal::Int(1))))
+            .select(vec![col("a") + value(Literal::Int(2)), col("b")])
+            .build(&Context)
+            .unwrap();
+        let sql_df = DataFrame::from_sql(&Context, "select a + 2, b from t where a > 1").unwrap();
+        assert_eq!(df.to_json().unwrap(), sql_df.to_json().unwrap());
+
+        let err = LogicalPlanBuilder::scan("t")
+            .aggregate(vec![col("b")], vec![col("b")])
+            .build(&Context)
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "the window clause is missing.");
+
+        let err = LogicalPlanBuilder::scan("t")
+            .aggregate(
+                vec![col("b")],
+                vec![call("anomaly_score", vec![col("c")]).alias("score")],
+            )
+            .window(Window::Session { gap: 1000 })
+            .build(&Context)
+            .unwrap()
+            .validate()
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "'score' cannot be aggregated in a session window, the states of its functions cannot be merged."
+        );
+
+        let err = LogicalPlanBuilder::scan("d")
+            .filter(col("a").gt(value(Literal::Int(1))))
+            .aggregate(vec![], vec![call("count", vec![col("a")])])
+            .window(Window::Fixed { length: 1000 })
+            .build(&Context)
+            .unwrap()
+            .validate()
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Cannot aggregate the changes of a 'debezium' source, its retracted rows are not supported yet."
+        );
+
+        let err = LogicalPlanBuilder::scan("u").build(&Context).err().unwrap();
+        assert_eq!(err.to_string(), "source 'u' not found.");
+    }
+}
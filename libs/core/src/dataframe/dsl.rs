@@ -1,4 +1,4 @@
-use crate::expr::{Expr, Literal};
+use crate::expr::{Expr, Literal, Span};
 
 pub fn value(value: impl Into<Literal>) -> Expr {
     Expr::Literal(value.into())
@@ -8,6 +8,7 @@ pub fn col(name: impl Into<String>) -> Expr {
     Expr::Column {
         qualifier: None,
         name: name.into(),
+        span: Span::default(),
     }
 }
 
@@ -15,16 +16,33 @@ pub fn qualified_col(table: impl Into<String>, name: impl Into<String>) -> Expr
     Expr::Column {
         qualifier: Some(table.into()),
         name: name.into(),
+        span: Span::default(),
     }
 }
 
 pub fn wildcard() -> Expr {
-    Expr::Wildcard { qualifier: None }
+    Expr::Wildcard {
+        qualifier: None,
+        exclude: vec![],
+    }
 }
 
 pub fn wildcard_with_table(table: impl Into<String>) -> Expr {
     Expr::Wildcard {
         qualifier: Some(table.into()),
+        exclude: vec![],
+    }
+}
+
+/// All columns except `columns`.
+pub fn wildcard_exclude<I>(columns: I) -> Expr
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    Expr::Wildcard {
+        qualifier: None,
+        exclude: columns.into_iter().map(Into::into).collect(),
     }
 }
 
@@ -33,6 +51,7 @@ pub fn call(name: impl Into<String>, args: Vec<Expr>) -> Expr {
         namespace: None,
         name: name.into(),
         args,
+        span: Span::default(),
     }
 }
 
@@ -45,5 +64,6 @@ pub fn call_with_namespace(
         namespace: Some(namespace.into()),
         name: name.into(),
         args,
+        span: Span::default(),
     }
 }
@@ -1,21 +1,25 @@
+mod builder;
 pub mod dsl;
-
-use std::future::Future;
+#[cfg(feature = "runtime")]
+mod run;
 
 use anyhow::Result;
-use futures_util::future::BoxFuture;
-use futures_util::stream::BoxStream;
-use futures_util::StreamExt;
-use yql_dataset::dataset::DataSet;
 
-use crate::execution::stream::create_data_stream;
-use crate::expr::Expr;
+use crate::dataset::SchemaRef;
+use crate::expr::{Expr, Span};
 use crate::planner::logical_plan::{
-    LogicalAggregatePlan, LogicalFilterPlan, LogicalPlan, LogicalProjectionPlan, LogicalSourcePlan,
+    LogicalAggregatePlan, LogicalAnalyticPlan, LogicalFilterPlan, LogicalPlan,
+    LogicalProjectionPlan, LogicalSourcePlan, SourceRef,
 };
+use crate::planner::physical_plan::{PhysicalPlan, QUALIFIER_ANALYTIC};
 use crate::sql::ast::Select;
 use crate::sql::SqlContext;
-use crate::{ExecutionContext, SinkProvider, SourceProvider, Window};
+use crate::{
+    CardinalityHints, Emit, ExecutionContext, PlanStatistics, SourceProvider, StateSizeReport,
+    StatisticsProvider, Window,
+};
+
+pub use builder::LogicalPlanBuilder;
 
 pub struct DataFrame(LogicalPlan);
 
@@ -27,13 +31,24 @@ impl DataFrame {
         watermark_expr: Option<Expr>,
     ) -> Self {
         Self(LogicalPlan::Source(LogicalSourcePlan {
+            name: None,
             qualifier,
             source_provider,
             time_expr,
             watermark_expr,
+            filters: Vec::new(),
         }))
     }
 
+    /// Loads a data frame saved with [`DataFrame::to_json`], `resolve` creates the provider of
+    /// each of its sources.
+    pub fn from_json<F>(json: &str, resolve: F) -> Result<Self>
+    where
+        F: FnMut(&SourceRef) -> Result<SourceProvider>,
+    {
+        Ok(Self(LogicalPlan::from_json(json, resolve)?))
+    }
+
     pub fn from_sql(ctx: &dyn SqlContext, sql: &str) -> Result<Self> {
         crate::sql::planner::create_data_frame_with_sql(ctx, sql)
     }
@@ -42,10 +57,60 @@ impl DataFrame {
         crate::sql::planner::create_data_frame(ctx, select)
     }
 
+    /// Records the name of the source of a data frame created by [`DataFrame::new`], it is
+    /// saved with the plan to resolve the source again.
+    pub(crate) fn with_source_name(self, name: impl Into<String>) -> Self {
+        match self.0 {
+            LogicalPlan::Source(source) => Self(LogicalPlan::Source(LogicalSourcePlan {
+                name: Some(name.into()),
+                ..source
+            })),
+            plan => Self(plan),
+        }
+    }
+
+    /// Serializes the plan of the data frame to JSON, with the version of the format.
+    ///
+    /// The sources are saved as [`SourceRef`]s.
+    pub fn to_json(&self) -> Result<String> {
+        self.0.to_json()
+    }
+
     pub fn select(self, exprs: Vec<Expr>) -> Self {
+        // Analytic function calls are evaluated by a separate plan, and the projection refers
+        // to their results by name.
+        let mut analytic_exprs = Vec::new();
+        let exprs = exprs
+            .into_iter()
+            .map(|expr| {
+                expr.transform(&mut |expr| match expr {
+                    Expr::Over { .. } => {
+                        let name = expr.to_string();
+                        if !analytic_exprs.contains(&expr) {
+                            analytic_exprs.push(expr);
+                        }
+                        Ok(Expr::Column {
+                            qualifier: Some(QUALIFIER_ANALYTIC.to_string()),
+                            name,
+                            span: Span::default(),
+                        })
+                    }
+                    expr => Ok(expr),
+                })
+                .expect("infallible")
+            })
+            .collect();
+
+        let mut input = self.0;
+        if !analytic_exprs.is_empty() {
+            input = LogicalPlan::Analytic(LogicalAnalyticPlan {
+                input: Box::new(input),
+                exprs: analytic_exprs,
+            });
+        }
         Self(LogicalPlan::Projection(LogicalProjectionPlan {
             exprs,
-            input: Box::new(self.0),
+            input: Box::new(input),
         }))
     }
 
@@ -56,56 +121,98 @@ impl DataFrame {
         }))
     }
 
+    /// Aggregates the rows of each group in `window`.
+    ///
+    /// The results have a column per expression of `aggr_exprs`, in the same order, and the
+    /// `@time` column with the start time of the window, placed according to
+    /// [`ExecutionContext::with_window_column_placement`]. Group keys are not added, select them
+    /// in `aggr_exprs` to output them.
     pub fn aggregate(self, group_exprs: Vec<Expr>, aggr_exprs: Vec<Expr>, window: Window) -> Self {
         Self(LogicalPlan::Aggregate(LogicalAggregatePlan {
             group_exprs,
             aggr_exprs,
             window,
+            time_zone_expr: None,
+            allowed_lateness: 0,
+            emit: Emit::Final,
             input: Box::new(self.0),
         }))
     }
 
-    pub fn into_stream(self, ctx: ExecutionContext) -> BoxStream<'static, Result<DataSet>> {
-        self.into_stream_with_graceful_shutdown(
-            ctx,
-            Option::<futures_util::future::Pending<()>>::None,
-        )
-    }
-
-    pub fn into_stream_with_graceful_shutdown(
-        self,
-        ctx: ExecutionContext,
-        signal: Option<impl Future<Output = ()> + Send + 'static>,
-    ) -> BoxStream<'static, Result<DataSet>> {
-        create_data_stream(ctx, self.0, signal)
-    }
-
-    pub fn into_task(
-        self,
-        ctx: ExecutionContext,
-        sink_provider: impl SinkProvider,
-    ) -> BoxFuture<'static, Result<()>> {
-        self.into_task_with_graceful_shutdown(
-            ctx,
-            sink_provider,
-            Option::<futures_util::future::Pending<()>>::None,
-        )
-    }
-
-    pub fn into_task_with_graceful_shutdown(
-        self,
-        ctx: ExecutionContext,
-        sink_provider: impl SinkProvider,
-        signal: Option<impl Future<Output = ()> + Send + 'static>,
-    ) -> BoxFuture<'static, Result<()>> {
-        let mut stream = self.into_stream_with_graceful_shutdown(ctx, signal);
-        Box::pin(async move {
-            let mut sink = sink_provider.create()?;
-            while let Some(res) = stream.next().await {
-                let dataset = res?;
-                sink.send(dataset).await?;
-            }
-            Ok(())
-        })
+    /// Keeps the windows of this aggregation open for `lateness` milliseconds after the
+    /// watermark passes their end, so that late rows still update them.
+    ///
+    /// Has no effect if this data frame is not an aggregation.
+    pub fn allow_lateness(mut self, lateness: i64) -> Self {
+        if let LogicalPlan::Aggregate(aggregate) = &mut self.0 {
+            aggregate.allowed_lateness = lateness;
+        }
+        self
+    }
+
+    /// Aligns the windows of each row of this aggregation to the time zone named by `expr`,
+    /// e.g. a column holding the time zone of the user, so that daily windows start at the
+    /// local midnight of each group.
+    ///
+    /// Only calendar windows (`day`, `week`, `month` and `year`) depend on the time zone, rows
+    /// whose time zone is null or unknown use the time zone of the time column.
+    ///
+    /// Has no effect if this data frame is not an aggregation.
+    pub fn window_time_zone(mut self, expr: Expr) -> Self {
+        if let LogicalPlan::Aggregate(aggregate) = &mut self.0 {
+            aggregate.time_zone_expr = Some(expr);
+        }
+        self
+    }
+
+    /// Sets when this aggregation emits the results of its windows.
+    ///
+    /// Has no effect if this data frame is not an aggregation.
+    pub fn emit(mut self, emit: Emit) -> Self {
+        if let LogicalPlan::Aggregate(aggregate) = &mut self.0 {
+            aggregate.emit = emit;
+        }
+        self
+    }
+
+    /// Returns the graph of the operators of the data frame in the Graphviz dot format, as it was
+    /// built and before it is optimized.
+    pub fn to_dot(&self) -> String {
+        self.0.to_dot()
+    }
+
+    /// Plans the data frame and returns the graph of the operators that would run, with the
+    /// schemas of the rows between them, in the Graphviz dot format.
+    pub fn to_physical_dot(&self) -> Result<String> {
+        let plan = PhysicalPlan::try_new(self.0.clone(), &ExecutionContext::new("to_dot"))?;
+        Ok(plan.to_dot())
+    }
+
+    /// Plans the data frame without running it, to report the errors of its expressions, such as
+    /// unknown columns or functions, before the streams are created.
+    pub fn validate(&self) -> Result<()> {
+        PhysicalPlan::try_new(self.0.clone(), &ExecutionContext::new("validate"))?;
+        Ok(())
+    }
+
+    /// Plans the data frame and returns the schema of its rows, like [`DataFrame::validate`] it
+    /// reports the errors of its expressions.
+    pub fn schema(&self) -> Result<SchemaRef> {
+        let plan = PhysicalPlan::try_new(self.0.clone(), &ExecutionContext::new("schema"))?;
+        Ok(plan.root.schema())
+    }
+
+    /// Estimates the worst-case state of the aggregations of this data frame, given the
+    /// expected number of distinct values of their group expressions.
+    pub fn state_size_report(self, hints: &CardinalityHints) -> Result<StateSizeReport> {
+        let plan = PhysicalPlan::try_new(self.0, &ExecutionContext::new("state_size_report"))?;
+        Ok(StateSizeReport::new(&plan, hints))
+    }
+
+    /// Estimates the rows per second of each operator of this data frame from the statistics
+    /// of `provider`.
+    pub fn plan_statistics(self, provider: &dyn StatisticsProvider) -> Result<PlanStatistics> {
+        let plan = PhysicalPlan::try_new(self.0, &ExecutionContext::new("plan_statistics"))?;
+        Ok(plan.statistics(provider))
     }
 }
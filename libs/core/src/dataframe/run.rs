@@ -0,0 +1,67 @@
+use std::future::Future;
+
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
+use tracing::Instrument;
+use yql_dataset::dataset::DataSet;
+
+use crate::execution::stream::{create_data_stream, create_timed_data_stream};
+use crate::{metrics, DataFrame, ExecutionContext, SinkProvider};
+
+impl DataFrame {
+    pub fn into_stream(self, ctx: ExecutionContext) -> BoxStream<'static, Result<DataSet>> {
+        self.into_stream_with_graceful_shutdown(
+            ctx,
+            Option::<futures_util::future::Pending<()>>::None,
+        )
+    }
+
+    pub fn into_stream_with_graceful_shutdown(
+        self,
+        ctx: ExecutionContext,
+        signal: Option<impl Future<Output = ()> + Send + 'static>,
+    ) -> BoxStream<'static, Result<DataSet>> {
+        create_data_stream(ctx, self.0, signal)
+    }
+
+    pub fn into_task(
+        self,
+        ctx: ExecutionContext,
+        sink_provider: impl SinkProvider,
+    ) -> BoxFuture<'static, Result<()>> {
+        self.into_task_with_graceful_shutdown(
+            ctx,
+            sink_provider,
+            Option::<futures_util::future::Pending<()>>::None,
+        )
+    }
+
+    pub fn into_task_with_graceful_shutdown(
+        self,
+        ctx: ExecutionContext,
+        sink_provider: impl SinkProvider,
+        signal: Option<impl Future<Output = ()> + Send + 'static>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let stream_name = ctx.name.clone();
+        let sink_name = sink_provider.provider_name();
+        let clock = ctx.clock.clone();
+        let mut stream = create_timed_data_stream(ctx, self.0, signal);
+        Box::pin(async move {
+            let mut sink = sink_provider.create_with_clock(clock)?;
+            while let Some(res) = stream.next().await {
+                let (dataset, ingest_time, correlation_id) = res?;
+                sink.send_with_correlation_id(dataset, &correlation_id)
+                    .instrument(tracing::debug_span!(
+                        "sink",
+                        sink = sink_name,
+                        correlation_id = %correlation_id
+                    ))
+                    .await?;
+                metrics::observe_sink_latency(&stream_name, sink_name, ingest_time.elapsed());
+            }
+            Ok(())
+        })
+    }
+}
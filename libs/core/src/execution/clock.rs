@@ -0,0 +1,102 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The source of the wall-clock time of a stream.
+///
+/// It timestamps the rows of the sources without a time expression, decides when the
+/// aggregations with `EMIT EVERY` emit, and is returned by `now()`.
+pub trait Clock: Send + Sync + 'static {
+    /// Returns the current time in milliseconds since the unix epoch.
+    fn now(&self) -> i64;
+}
+
+/// The time of the system, the default clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64
+    }
+}
+
+/// A clock that only moves when it is set, for tests and simulations.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: AtomicI64,
+}
+
+impl ManualClock {
+    pub fn new(now: i64) -> Self {
+        Self {
+            now: AtomicI64::new(now),
+        }
+    }
+
+    pub fn set(&self, now: i64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, millis: i64) {
+        self.now.fetch_add(millis, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> i64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{ArrayExt, ArrayRef, DataType, NullArray, TimestampArray};
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::call;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new(1000);
+        assert_eq!(clock.now(), 1000);
+        clock.advance(500);
+        assert_eq!(clock.now(), 1500);
+        clock.set(0);
+        assert_eq!(clock.now(), 0);
+    }
+
+    #[test]
+    fn test_now() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Null)]).unwrap());
+        let dataset = DataSet::try_new(
+            schema.clone(),
+            vec![Arc::new(NullArray::new(2)) as ArrayRef],
+        )
+        .unwrap();
+        let clock = Arc::new(ManualClock::new(1000));
+        let mut expr = call("now", vec![])
+            .into_physical_with_clock(schema, clock.clone())
+            .unwrap();
+
+        let array = expr.eval(&dataset).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<TimestampArray>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1000, 1000]
+        );
+
+        clock.advance(500);
+        let array = expr.eval(&dataset).unwrap();
+        assert_eq!(
+            array.downcast_ref::<TimestampArray>().iter().next(),
+            Some(1500)
+        );
+    }
+}
@@ -2,15 +2,18 @@ use std::sync::Arc;
 
 use ahash::AHashMap;
 use anyhow::Result;
+use chrono_tz::Tz;
 use itertools::Itertools;
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
+use crate::array::compute;
 use crate::array::{
-    Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, Float32Type, Float64Type,
-    Int16Type, Int32Type, Int64Type, Int8Type, NullArray, PrimitiveArray, PrimitiveBuilder,
-    StringArray, StringBuilder, TimestampArray, TimestampType,
+    Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, DateType, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, NullArray, PrimitiveArray,
+    PrimitiveBuilder, StringArray, StringBuilder, TimeType, TimestampArray, TimestampType,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 use crate::dataset::DataSet;
 use crate::expr::physical_expr::PhysicalExpr;
@@ -67,6 +70,16 @@ pub fn group_by_exprs<'a>(
     dataset: &'a DataSet,
     exprs: &mut [PhysicalExpr],
 ) -> Result<GroupByExprsIter<'a>> {
+    let keys_map = group_indexes_by_exprs(dataset, exprs)?;
+    Ok(Box::new(keys_map.into_iter().map(move |(key, indexes)| {
+        create_dataset(dataset, &indexes).map(|dataset| (key, dataset))
+    })))
+}
+
+pub fn group_indexes_by_exprs(
+    dataset: &DataSet,
+    exprs: &mut [PhysicalExpr],
+) -> Result<AHashMap<GroupedKey, Vec<usize>>> {
     let num_group_exprs = exprs.len();
     let keys = exprs
         .iter_mut()
@@ -89,6 +102,18 @@ pub fn group_by_exprs<'a>(
             DataType::Int64 => {
                 fill_integer_key!(record_keys, array, Int64Type, num_group_exprs, column)
             }
+            DataType::UInt8 => {
+                fill_integer_key!(record_keys, array, UInt8Type, num_group_exprs, column)
+            }
+            DataType::UInt16 => {
+                fill_integer_key!(record_keys, array, UInt16Type, num_group_exprs, column)
+            }
+            DataType::UInt32 => {
+                fill_integer_key!(record_keys, array, UInt32Type, num_group_exprs, column)
+            }
+            DataType::UInt64 => {
+                fill_integer_key!(record_keys, array, UInt64Type, num_group_exprs, column)
+            }
             DataType::Float32 => {
                 fill_float_key!(record_keys, array, Float32Type, num_group_exprs, column)
             }
@@ -106,6 +131,12 @@ pub fn group_by_exprs<'a>(
             DataType::Timestamp(_) => {
                 fill_integer_key!(record_keys, array, TimestampType, num_group_exprs, column)
             }
+            DataType::Date => {
+                fill_integer_key!(record_keys, array, DateType, num_group_exprs, column)
+            }
+            DataType::Time => {
+                fill_integer_key!(record_keys, array, TimeType, num_group_exprs, column)
+            }
             DataType::String => {
                 let array = array.downcast_ref::<StringArray>();
                 for row in 0..array.len() {
@@ -115,6 +146,7 @@ pub fn group_by_exprs<'a>(
                     }
                 }
             }
+            _ => anyhow::bail!("cannot group by a '{}'", array.data_type()),
         }
     }
 
@@ -126,18 +158,18 @@ pub fn group_by_exprs<'a>(
         }
         keys_map.entry(grouped_key).or_default().push(row);
     }
-
-    Ok(Box::new(keys_map.into_iter().map(move |(key, indexes)| {
-        create_dataset(dataset, &indexes).map(|dataset| (key, dataset))
-    })))
+    Ok(keys_map)
 }
 
 pub type GroupByWindowIter<'a> = Box<dyn Iterator<Item = Result<(i64, i64, DataSet)>> + 'a>;
 
+/// Groups the rows by the windows they belong to, `time_zones` holds the name of the time zone
+/// of each row, rows whose time zone is null or unknown use the time zone of the time column.
 pub fn group_by_window<'a>(
     dataset: &'a DataSet,
     time_idx: usize,
     window: &Window,
+    time_zones: Option<&StringArray>,
 ) -> Result<GroupByWindowIter<'a>> {
     let mut windows: AHashMap<_, (i64, Vec<usize>)> = AHashMap::new();
     let times = dataset.column(time_idx).unwrap();
@@ -147,6 +179,10 @@ pub fn group_by_window<'a>(
     };
     let times = times.downcast_ref::<TimestampArray>();
     for (idx, timestamp) in times.iter().enumerate() {
+        let tz = time_zones
+            .and_then(|time_zones| time_zones.value_opt(idx))
+            .and_then(|name| name.parse::<Tz>().ok())
+            .unwrap_or(tz);
         for (start, end) in window.windows(timestamp, tz) {
             let window = windows.entry(start).or_default();
             window.0 = end;
@@ -170,6 +206,10 @@ fn create_dataset(dataset: &DataSet, indexes: &[usize]) -> Result<DataSet> {
             DataType::Int16 => copy_grouped_primitive_values!(array, indexes, Int16Type),
             DataType::Int32 => copy_grouped_primitive_values!(array, indexes, Int32Type),
             DataType::Int64 => copy_grouped_primitive_values!(array, indexes, Int64Type),
+            DataType::UInt8 => copy_grouped_primitive_values!(array, indexes, UInt8Type),
+            DataType::UInt16 => copy_grouped_primitive_values!(array, indexes, UInt16Type),
+            DataType::UInt32 => copy_grouped_primitive_values!(array, indexes, UInt32Type),
+            DataType::UInt64 => copy_grouped_primitive_values!(array, indexes, UInt64Type),
             DataType::Float32 => copy_grouped_primitive_values!(array, indexes, Float32Type),
             DataType::Float64 => copy_grouped_primitive_values!(array, indexes, Float64Type),
             DataType::Boolean => copy_grouped_primitive_values!(array, indexes, BooleanType),
@@ -184,6 +224,10 @@ fn create_dataset(dataset: &DataSet, indexes: &[usize]) -> Result<DataSet> {
                 }
                 Arc::new(builder.finish()) as ArrayRef
             }
+            _ => compute::take(
+                array.clone(),
+                &indexes.iter().copied().map(Some).collect::<Vec<_>>(),
+            ),
         };
         columns.push(new_array);
     }
@@ -1,7 +1,9 @@
 mod group_by;
 
+use ahash::AHashMap;
 use anyhow::Result;
 
+use crate::array::StringArray;
 use crate::dataset::DataSet;
 use crate::expr::physical_expr::PhysicalExpr;
 use crate::planner::window::Window;
@@ -11,7 +13,18 @@ pub use group_by::{GroupByExprsIter, GroupByWindowIter, GroupedKey};
 pub trait DataSetExt {
     fn group_by_exprs(&self, exprs: &mut [PhysicalExpr]) -> Result<GroupByExprsIter>;
 
-    fn group_by_window(&self, time_idx: usize, window: &Window) -> Result<GroupByWindowIter>;
+    /// Returns the indexes of the rows of each group, in their original order.
+    fn group_indexes_by_exprs(
+        &self,
+        exprs: &mut [PhysicalExpr],
+    ) -> Result<AHashMap<GroupedKey, Vec<usize>>>;
+
+    fn group_by_window(
+        &self,
+        time_idx: usize,
+        window: &Window,
+        time_zones: Option<&StringArray>,
+    ) -> Result<GroupByWindowIter>;
 }
 
 impl DataSetExt for DataSet {
@@ -19,7 +32,19 @@ impl DataSetExt for DataSet {
         group_by::group_by_exprs(self, exprs)
     }
 
-    fn group_by_window(&self, time_idx: usize, window: &Window) -> Result<GroupByWindowIter> {
-        group_by::group_by_window(self, time_idx, window)
+    fn group_indexes_by_exprs(
+        &self,
+        exprs: &mut [PhysicalExpr],
+    ) -> Result<AHashMap<GroupedKey, Vec<usize>>> {
+        group_by::group_indexes_by_exprs(self, exprs)
+    }
+
+    fn group_by_window(
+        &self,
+        time_idx: usize,
+        window: &Window,
+        time_zones: Option<&StringArray>,
+    ) -> Result<GroupByWindowIter> {
+        group_by::group_by_window(self, time_idx, window, time_zones)
     }
 }
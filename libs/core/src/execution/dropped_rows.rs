@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::metrics::{self, DropReason};
+
+struct Sampling {
+    last_logged: Instant,
+    /// Rows dropped since the last log event.
+    suppressed: u64,
+}
+
+/// Counts the rows dropped by a source or an aggregation, and logs a sample of them.
+///
+/// A log event is written for the first drop of each reason, and then at most once per
+/// interval with the number of rows dropped in between, so that a flood of late rows doesn't
+/// flood the logs too.
+pub(crate) struct DroppedRows {
+    stream: String,
+    source_id: usize,
+    source: &'static str,
+    log_interval: Duration,
+    sampling: HashMap<DropReason, Sampling>,
+}
+
+impl DroppedRows {
+    pub(crate) fn new(
+        stream: impl Into<String>,
+        source_id: usize,
+        source: &'static str,
+        log_interval: Duration,
+    ) -> Self {
+        Self {
+            stream: stream.into(),
+            source_id,
+            source,
+            log_interval,
+            sampling: HashMap::new(),
+        }
+    }
+
+    /// Records `rows` rows dropped for `reason`, `sample` describes one of them.
+    ///
+    /// Returns whether a log event was written.
+    pub(crate) fn record(
+        &mut self,
+        reason: DropReason,
+        rows: usize,
+        sample: impl FnOnce() -> String,
+    ) -> bool {
+        if rows == 0 {
+            return false;
+        }
+        metrics::add_dropped_rows(
+            &self.stream,
+            self.source_id,
+            self.source,
+            reason,
+            rows as u64,
+        );
+
+        let now = Instant::now();
+        let log_interval = self.log_interval;
+        let (log, dropped) = match self.sampling.get_mut(&reason) {
+            Some(sampling) if now.duration_since(sampling.last_logged) < log_interval => {
+                sampling.suppressed += rows as u64;
+                (false, 0)
+            }
+            Some(sampling) => {
+                let dropped = sampling.suppressed + rows as u64;
+                sampling.last_logged = now;
+                sampling.suppressed = 0;
+                (true, dropped)
+            }
+            None => {
+                self.sampling.insert(
+                    reason,
+                    Sampling {
+                        last_logged: now,
+                        suppressed: 0,
+                    },
+                );
+                (true, rows as u64)
+            }
+        };
+
+        if log {
+            tracing::warn!(
+                name = %self.stream,
+                source = self.source,
+                source_id = self.source_id,
+                reason = reason.as_str(),
+                rows = dropped,
+                sample = %sample(),
+                "dropped rows"
+            );
+        }
+        log
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_rows_sampling() {
+        let mut dropped_rows = DroppedRows::new(
+            "test_dropped_rows_sampling",
+            0,
+            "csv",
+            Duration::from_secs(3600),
+        );
+        assert!(dropped_rows.record(DropReason::Late, 2, || "a".to_string()));
+        assert!(!dropped_rows.record(DropReason::Late, 3, || unreachable!()));
+        assert!(dropped_rows.record(DropReason::NullTime, 1, || "b".to_string()));
+        assert!(!dropped_rows.record(DropReason::NullTime, 0, || unreachable!()));
+        assert_eq!(
+            metrics::dropped_rows("test_dropped_rows_sampling", 0, DropReason::Late),
+            5
+        );
+
+        let mut dropped_rows = DroppedRows::new(
+            "test_dropped_rows_sampling",
+            0,
+            "csv",
+            Duration::from_secs(0),
+        );
+        assert!(dropped_rows.record(DropReason::Late, 1, || "c".to_string()));
+        assert!(dropped_rows.record(DropReason::Late, 1, || "d".to_string()));
+
+        metrics::remove_stream("test_dropped_rows_sampling");
+    }
+}
@@ -1,11 +1,69 @@
-use tokio::time::Duration;
+use std::sync::Arc;
+use std::time::Duration;
 
+#[cfg(feature = "runtime")]
+use tokio::sync::Notify;
+
+use crate::execution::clock::{Clock, SystemClock};
 use crate::execution::storage::Storage;
+use crate::execution::watermark::{WatermarkSkew, WatermarkSkewCallback};
+use crate::planner::window::WindowColumnPlacement;
+
+/// Bounds for adjusting the batch sizes of the sources to the speed of the pipeline.
+#[derive(Debug, Clone)]
+pub struct AdaptiveBatchSize {
+    pub min_batch_size: usize,
+    pub max_batch_size: usize,
+    /// How long the pipeline should take to process a batch.
+    pub target_latency: Duration,
+}
+
+impl AdaptiveBatchSize {
+    /// Returns the size of the next batch, given how long the pipeline took to process the
+    /// previous batch of `batch_size` rows.
+    ///
+    /// The batch size grows by a quarter while the pipeline keeps up, and is halved when it
+    /// falls behind.
+    pub(crate) fn next_batch_size(&self, batch_size: usize, latency: Duration) -> usize {
+        let batch_size = if latency > self.target_latency {
+            batch_size / 2
+        } else if latency < self.target_latency / 2 {
+            batch_size + batch_size / 4 + 1
+        } else {
+            batch_size
+        };
+        batch_size.max(self.min_batch_size).min(self.max_batch_size)
+    }
+}
+
+/// Called with the number of consecutive checkpoint failures and the last error.
+pub type CheckPointFailureCallback = Arc<dyn Fn(usize, &anyhow::Error) + Send + Sync>;
+
+/// What to do when several consecutive checkpoints fail to be saved.
+#[derive(Clone)]
+pub enum CheckPointFailureAction {
+    /// Logs an error and keeps running without durable state.
+    Log,
+    /// Calls the function with the number of consecutive failures and the last error.
+    Callback(CheckPointFailureCallback),
+    /// Stops the stream with an error.
+    Stop,
+}
 
 pub struct ExecutionContext {
     pub(crate) name: String,
     pub(crate) checkpoint_interval: Duration,
+    #[cfg(feature = "runtime")]
+    pub(crate) checkpoint_trigger: Option<Arc<Notify>>,
     pub(crate) storage: Option<Box<dyn Storage>>,
+    pub(crate) adaptive_batch_size: Option<AdaptiveBatchSize>,
+    pub(crate) watermark_skew_alert: Option<(Duration, WatermarkSkewCallback)>,
+    pub(crate) checkpoint_failure: Option<(usize, CheckPointFailureAction)>,
+    pub(crate) emit_batch_size: Option<usize>,
+    pub(crate) window_column_placement: WindowColumnPlacement,
+    pub(crate) dropped_rows_log_interval: Duration,
+    pub(crate) source_deduplication: bool,
+    pub(crate) clock: Arc<dyn Clock>,
 }
 
 impl ExecutionContext {
@@ -13,7 +71,17 @@ impl ExecutionContext {
         Self {
             name: name.into(),
             checkpoint_interval: Duration::from_secs(60 * 5),
+            #[cfg(feature = "runtime")]
+            checkpoint_trigger: None,
             storage: None,
+            adaptive_batch_size: None,
+            watermark_skew_alert: None,
+            checkpoint_failure: None,
+            emit_batch_size: None,
+            window_column_placement: WindowColumnPlacement::default(),
+            dropped_rows_log_interval: Duration::from_secs(10),
+            source_deduplication: false,
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -23,4 +91,127 @@ impl ExecutionContext {
             ..self
         }
     }
+
+    /// Lets the sources adjust their batch sizes within the bounds of `config`, instead of
+    /// using a fixed batch size.
+    pub fn with_adaptive_batch_size(self, config: AdaptiveBatchSize) -> Self {
+        assert!(config.min_batch_size > 0 && config.min_batch_size <= config.max_batch_size);
+        Self {
+            adaptive_batch_size: Some(config),
+            ..self
+        }
+    }
+
+    /// Merges the results of the windows that complete at the same time into datasets of up to
+    /// `batch_size` rows, instead of emitting a dataset per window.
+    ///
+    /// This speeds up backfills, where the watermark moves fast and many historical windows
+    /// complete at once.
+    pub fn with_emit_batch_size(self, batch_size: usize) -> Self {
+        assert!(batch_size > 0);
+        Self {
+            emit_batch_size: Some(batch_size),
+            ..self
+        }
+    }
+
+    /// Places the window column of the results of aggregations, after the columns of the
+    /// select list by default.
+    pub fn with_window_column_placement(self, placement: WindowColumnPlacement) -> Self {
+        Self {
+            window_column_placement: placement,
+            ..self
+        }
+    }
+
+    /// Logs the rows dropped by the sources at most once per `interval` for each source and
+    /// reason, 10 seconds by default.
+    ///
+    /// All dropped rows are counted in the metrics.
+    pub fn with_dropped_rows_log_interval(self, interval: Duration) -> Self {
+        Self {
+            dropped_rows_log_interval: interval,
+            ..self
+        }
+    }
+
+    /// Saves the offset after the last row emitted by each source in the checkpoints, and drops
+    /// the rows the sources deliver again after a restore.
+    ///
+    /// Only the sources that report the offsets of their rows are deduplicated, see
+    /// [`GenericSourceProvider::offset`](crate::GenericSourceProvider::offset).
+    pub fn with_source_deduplication(self) -> Self {
+        Self {
+            source_deduplication: true,
+            ..self
+        }
+    }
+
+    /// Reads the wall-clock time from `clock` instead of the system time.
+    ///
+    /// The checkpoint interval is measured by the timer of the runtime, which can be paused and
+    /// advanced in tests instead.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock, ..self }
+    }
+
+    /// Creates a checkpoint each time `trigger` is notified, in addition to the periodic
+    /// checkpoints.
+    #[cfg(feature = "runtime")]
+    pub fn with_checkpoint_trigger(self, trigger: Arc<Notify>) -> Self {
+        Self {
+            checkpoint_trigger: Some(trigger),
+            ..self
+        }
+    }
+
+    /// Triggers `action` when `max_failures` consecutive checkpoints fail, a successful
+    /// checkpoint resets the count.
+    ///
+    /// Without it, failed checkpoints are only logged.
+    pub fn with_checkpoint_failure_action(
+        self,
+        max_failures: usize,
+        action: CheckPointFailureAction,
+    ) -> Self {
+        assert!(max_failures > 0);
+        Self {
+            checkpoint_failure: Some((max_failures, action)),
+            ..self
+        }
+    }
+
+    /// Calls `callback` when the watermark of a source starts lagging the maximum watermark of
+    /// all sources by more than `threshold`.
+    ///
+    /// The lag of every source is also reported in the metrics, with or without an alert.
+    pub fn with_watermark_skew_alert(
+        self,
+        threshold: Duration,
+        callback: impl Fn(&WatermarkSkew) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            watermark_skew_alert: Some((threshold, Arc::new(callback))),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_batch_size() {
+        let config = AdaptiveBatchSize {
+            min_batch_size: 10,
+            max_batch_size: 1000,
+            target_latency: Duration::from_millis(100),
+        };
+        assert_eq!(config.next_batch_size(100, Duration::from_millis(10)), 126);
+        assert_eq!(config.next_batch_size(100, Duration::from_millis(80)), 100);
+        assert_eq!(config.next_batch_size(100, Duration::from_millis(200)), 50);
+        assert_eq!(config.next_batch_size(15, Duration::from_millis(200)), 10);
+        assert_eq!(config.next_batch_size(900, Duration::from_millis(10)), 1000);
+    }
 }
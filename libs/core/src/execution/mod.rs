@@ -1,7 +1,15 @@
+#[cfg(feature = "runtime")]
 mod checkpoint;
+#[cfg(feature = "runtime")]
 mod dataset;
+#[cfg(feature = "runtime")]
+mod dropped_rows;
+#[cfg(feature = "runtime")]
 mod streams;
 
+pub mod clock;
 pub mod execution_context;
 pub mod storage;
+#[cfg(feature = "runtime")]
 pub mod stream;
+pub mod watermark;
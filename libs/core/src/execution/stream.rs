@@ -4,22 +4,30 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Instant;
 
 use anyhow::{Context as _, Result};
 use futures_util::stream::{BoxStream, StreamExt};
 use futures_util::Stream;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use tokio::time::Interval;
 
 use crate::dataset::DataSet;
 use crate::execution::checkpoint::CheckPointBarrier;
-use crate::execution::execution_context::ExecutionContext;
+use crate::execution::execution_context::{CheckPointFailureAction, ExecutionContext};
+use crate::execution::watermark::WatermarkTracker;
 use crate::planner::logical_plan::LogicalPlan;
 use crate::planner::physical_plan::PhysicalPlan;
+use crate::progress::ProgressTracker;
 
 pub enum Event {
     DataSet {
         current_watermark: Option<i64>,
+        /// When the source received the rows of the dataset, used to measure the end-to-end
+        /// latency.
+        ingest_time: Instant,
+        /// The correlation ID of the source dataset the rows come from.
+        correlation_id: Arc<str>,
         dataset: DataSet,
     },
     CreateCheckPoint(Arc<CheckPointBarrier>),
@@ -30,10 +38,14 @@ impl Debug for Event {
         match self {
             Event::DataSet {
                 current_watermark,
+                ingest_time,
+                correlation_id,
                 dataset,
             } => f
                 .debug_struct("DataSet")
                 .field("current_watermark", current_watermark)
+                .field("ingest_time", ingest_time)
+                .field("correlation_id", correlation_id)
                 .field("dataset", dataset)
                 .finish(),
             Event::CreateCheckPoint(_) => f.debug_struct("CreateCheckPoint").finish(),
@@ -47,15 +59,20 @@ pub struct CreateStreamContext {
     pub ctx: Arc<ExecutionContext>,
     pub tx_barrier: broadcast::Sender<Arc<CheckPointBarrier>>,
     pub prev_state: HashMap<usize, Vec<u8>>,
+    pub(crate) watermarks: Arc<WatermarkTracker>,
+    pub(crate) progress: Arc<ProgressTracker>,
 }
 
 enum Message {
     CreateCheckPoint,
+    CheckPointSaved(Result<()>),
     Event(Result<Event>),
 }
 
 struct CombinedStream {
     interval: Pin<Box<Interval>>,
+    rx_trigger: mpsc::UnboundedReceiver<()>,
+    rx_saved: mpsc::UnboundedReceiver<Result<()>>,
     input: EventStream,
 }
 
@@ -68,6 +85,14 @@ impl Stream for CombinedStream {
             Poll::Pending => {}
         }
 
+        if let Poll::Ready(Some(())) = self.rx_trigger.poll_recv(cx) {
+            return Poll::Ready(Some(Message::CreateCheckPoint));
+        }
+
+        if let Poll::Ready(Some(res)) = self.rx_saved.poll_recv(cx) {
+            return Poll::Ready(Some(Message::CheckPointSaved(res)));
+        }
+
         match self.input.poll_next_unpin(cx) {
             Poll::Ready(Some(event)) => Poll::Ready(Some(Message::Event(event))),
             Poll::Ready(None) => Poll::Ready(None),
@@ -81,6 +106,18 @@ pub fn create_data_stream(
     plan: LogicalPlan,
     signal: Option<impl Future<Output = ()> + Send + 'static>,
 ) -> BoxStream<'static, Result<DataSet>> {
+    Box::pin(
+        create_timed_data_stream(ctx, plan, signal).map(|res| res.map(|(dataset, _, _)| dataset)),
+    )
+}
+
+/// Like [`create_data_stream`], but also yields the time the source received the rows of each
+/// dataset, and the correlation ID of the source dataset.
+pub fn create_timed_data_stream(
+    ctx: ExecutionContext,
+    plan: LogicalPlan,
+    signal: Option<impl Future<Output = ()> + Send + 'static>,
+) -> BoxStream<'static, Result<(DataSet, Instant, Arc<str>)>> {
     Box::pin(async_stream::try_stream! {
          let prev_state: HashMap<usize, Vec<u8>> = match &ctx.storage {
             Some(storage) => {
@@ -93,7 +130,7 @@ pub fn create_data_stream(
         };
 
         let ctx = Arc::new(ctx);
-        let plan = PhysicalPlan::try_new(plan)?;
+        let plan = PhysicalPlan::try_new(plan, &ctx)?;
         let node_count = plan.node_count;
         let source_count = plan.source_count;
         let (tx_barrier, _) = broadcast::channel(8);
@@ -101,6 +138,8 @@ pub fn create_data_stream(
             ctx: ctx.clone(),
             tx_barrier: tx_barrier.clone(),
             prev_state,
+            watermarks: Arc::new(WatermarkTracker::new(&ctx)),
+            progress: ProgressTracker::start(&ctx.name),
         };
         let event_stream = crate::execution::streams::create_stream(&mut create_ctx, plan.root)?;
         let checkpoint_interval = tokio::time::interval(ctx.checkpoint_interval);
@@ -116,10 +155,30 @@ pub fn create_data_stream(
             });
         }
 
+        let (tx_trigger, rx_trigger) = mpsc::unbounded_channel();
+        if let Some(trigger) = ctx.checkpoint_trigger.clone() {
+            tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        _ = trigger.notified() => {
+                            if tx_trigger.send(()).is_err() {
+                                break;
+                            }
+                        }
+                        _ = tx_trigger.closed() => break,
+                    }
+                }
+            });
+        }
+
+        let (tx_saved, rx_saved) = mpsc::unbounded_channel();
         let mut input = CombinedStream {
             interval: Box::pin(checkpoint_interval),
+            rx_trigger,
+            rx_saved,
             input: event_stream,
         };
+        let mut failed_checkpoints = 0;
 
         while let Some(message) = input.next().await {
             match message {
@@ -131,13 +190,41 @@ pub fn create_data_stream(
                     ));
                     let _ = tx_barrier.send(barrier.clone());
                     let ctx = ctx.clone();
-                    tokio::spawn(save_state(ctx, barrier));
+                    let tx_saved = tx_saved.clone();
+                    tokio::spawn(async move {
+                        let _ = tx_saved.send(save_state(ctx, barrier).await);
+                    });
+                }
+                Message::CheckPointSaved(Ok(())) => failed_checkpoints = 0,
+                Message::CheckPointSaved(Err(err)) => {
+                    failed_checkpoints += 1;
+                    if let Some((max_failures, action)) = &ctx.checkpoint_failure {
+                        if failed_checkpoints == *max_failures {
+                            match action {
+                                CheckPointFailureAction::Log => tracing::error!(
+                                    name = %ctx.name,
+                                    failures = failed_checkpoints,
+                                    error = %err,
+                                    "too many consecutive checkpoint failures"
+                                ),
+                                CheckPointFailureAction::Callback(callback) => {
+                                    callback(failed_checkpoints, &err)
+                                }
+                                CheckPointFailureAction::Stop => {
+                                    Err::<(), _>(err.context(format!(
+                                        "{} consecutive checkpoints failed",
+                                        failed_checkpoints
+                                    )))?;
+                                }
+                            }
+                        }
+                    }
                 }
                 Message::Event(res) => {
                     let event = res?;
-                    if let Event::DataSet { dataset, .. } = event {
+                    if let Event::DataSet { dataset, ingest_time, correlation_id, .. } = event {
                         if !dataset.is_empty() {
-                            yield dataset;
+                            yield (dataset, ingest_time, correlation_id);
                         }
                     }
                 }
@@ -146,7 +233,7 @@ pub fn create_data_stream(
     })
 }
 
-async fn save_state(ctx: Arc<ExecutionContext>, barrier: Arc<CheckPointBarrier>) {
+async fn save_state(ctx: Arc<ExecutionContext>, barrier: Arc<CheckPointBarrier>) -> Result<()> {
     tracing::info!(name = %ctx.name, "create checkpoint");
     barrier.wait().await;
 
@@ -158,7 +245,7 @@ async fn save_state(ctx: Arc<ExecutionContext>, barrier: Arc<CheckPointBarrier>)
                 error = %err,
                 "failed to serialize stream state"
             );
-            return;
+            return Err(err.into());
         }
     };
 
@@ -166,8 +253,10 @@ async fn save_state(ctx: Arc<ExecutionContext>, barrier: Arc<CheckPointBarrier>)
         match storage.save_state(data).await {
             Ok(()) => tracing::info!(name = %ctx.name, "checkpoint created"),
             Err(err) => {
-                tracing::info!(name = %ctx.name, error = %err, "failed to save checkpoint")
+                tracing::error!(name = %ctx.name, error = %err, "failed to save checkpoint");
+                return Err(err);
             }
         }
     }
+    Ok(())
 }
@@ -7,24 +7,28 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tokio_stream::StreamExt;
 
+use crate::array::compute::array_from_scalars;
 use crate::array::{
     ArrayExt, ArrayRef, BooleanType, DataType, Float32Type, Float64Type, Int16Type, Int32Type,
-    Int64Type, Int8Type, NullArray, PrimitiveBuilder, Scalar, StringBuilder, TimestampArray,
-    TimestampType,
+    Int64Type, Int8Type, NullArray, PrimitiveBuilder, Scalar, StringArray, StringBuilder,
+    TimestampArray, TimestampType,
 };
 use crate::dataset::{DataSet, SchemaRef};
+use crate::execution::clock::Clock;
 use crate::execution::dataset::{DataSetExt, GroupedKey};
+use crate::execution::dropped_rows::DroppedRows;
 use crate::execution::stream::{CreateStreamContext, Event, EventStream};
 use crate::execution::streams::create_stream;
 use crate::expr::physical_expr::PhysicalExpr;
 use crate::expr::ExprState;
+use crate::metrics::DropReason;
 use crate::planner::physical_plan::PhysicalAggregateNode;
-use crate::planner::window::Window;
+use crate::planner::window::{Emit, Window, WindowColumnPlacement};
 
 macro_rules! append_primitive_value {
     ($columns:expr, $aggregate_states:expr, $index:expr, $ty:ty, $scalar_ty:ident) => {{
         let mut builder = PrimitiveBuilder::<$ty>::with_capacity($aggregate_states.len());
-        for state in $aggregate_states.values() {
+        for state in $aggregate_states {
             builder.append_opt(if let Scalar::$scalar_ty(value) = &state.values[$index] {
                 Some(*value)
             } else {
@@ -35,22 +39,38 @@ macro_rules! append_primitive_value {
     }};
 }
 
-type SavedWindow = (i64, i64, Vec<(GroupedKey, Vec<ExprState>, Vec<Scalar>)>);
+type SavedGroup = (
+    GroupedKey,
+    i64,
+    Vec<ExprState>,
+    Vec<Scalar>,
+    Option<DataSet>,
+);
+
+type SavedWindow = (i64, i64, Vec<SavedGroup>);
 
 #[derive(Serialize, Deserialize)]
 struct SavedState {
     group_exprs: Vec<ExprState>,
     windows: Vec<SavedWindow>,
+    next_window: Option<i64>,
 }
 
 struct AggregateState {
     aggr_exprs: Vec<PhysicalExpr>,
     values: Vec<Scalar>,
+    /// The end time of the window of the group, the sessions of the groups that start at the
+    /// same time end independently of each other.
+    end_time: i64,
+    /// The last row of the group, only kept for panes and sessions to evaluate the merged
+    /// expressions.
+    last_row: Option<DataSet>,
 }
 
 #[derive(Default)]
 struct WindowState {
     start_time: i64,
+    /// The latest end time of the groups of the window.
     end_time: i64,
     children: AHashMap<GroupedKey, AggregateState>,
 }
@@ -60,8 +80,27 @@ pub struct AggregateManager {
     group_exprs: Vec<PhysicalExpr>,
     aggr_exprs: Vec<PhysicalExpr>,
     window: Window,
+    time_zone_expr: Option<PhysicalExpr>,
+    allowed_lateness: i64,
+    emit: Emit,
+    last_emit_time: Option<i64>,
+    clock: Arc<dyn Clock>,
     time_idx: usize,
+    window_column_placement: WindowColumnPlacement,
     windows: BTreeMap<i64, WindowState>,
+    /// The length of the panes when the sliding windows are aggregated by panes.
+    ///
+    /// Each row is then aggregated once into the pane that contains it, instead of once per
+    /// overlapping window, `windows` holds the panes and the states of the panes are merged when
+    /// a window is completed.
+    panes: Option<i64>,
+    /// The start time of the earliest sliding window that has not been emitted yet.
+    next_window: Option<i64>,
+    /// Merges the results of the windows completed at the same time into datasets of up to
+    /// this many rows.
+    emit_batch_size: Option<usize>,
+    /// Counts the rows of the panes whose windows have all been emitted.
+    dropped_rows: DroppedRows,
 }
 
 impl AggregateManager {
@@ -72,16 +111,19 @@ impl AggregateManager {
             expr.load_state(data)?;
         }
 
+        self.next_window = saved_state.next_window;
         for (start, end, groups) in saved_state.windows {
             let mut window_state = WindowState {
                 start_time: start,
                 end_time: end,
                 children: Default::default(),
             };
-            for (key, expr_state, scalars) in groups {
+            for (key, end_time, expr_state, scalars, last_row) in groups {
                 let mut aggregate_state = AggregateState {
                     aggr_exprs: self.aggr_exprs.clone(),
                     values: scalars,
+                    end_time,
+                    last_row,
                 };
                 for (expr, data) in aggregate_state.aggr_exprs.iter_mut().zip(expr_state) {
                     expr.load_state(data)?;
@@ -106,12 +148,14 @@ impl AggregateManager {
             for (grouped_key, aggregate_state) in &window.children {
                 groups.push((
                     grouped_key.clone(),
+                    aggregate_state.end_time,
                     aggregate_state
                         .aggr_exprs
                         .iter()
                         .map(|expr| expr.save_state())
                         .try_collect()?,
                     aggregate_state.values.clone(),
+                    aggregate_state.last_row.clone(),
                 ));
             }
             windows.push((*start, window.end_time, groups));
@@ -120,6 +164,7 @@ impl AggregateManager {
         let saved_state = SavedState {
             group_exprs,
             windows,
+            next_window: self.next_window,
         };
         Ok(bincode::serialize(&saved_state)?)
     }
@@ -136,6 +181,7 @@ impl AggregateManager {
             end_time: end,
             children: Default::default(),
         });
+        window_state.end_time = window_state.end_time.max(end);
 
         let aggregate_state = match window_state.children.get_mut(&grouped_key) {
             Some(aggregate_state) => aggregate_state,
@@ -145,8 +191,11 @@ impl AggregateManager {
                 .or_insert(AggregateState {
                     aggr_exprs: self.aggr_exprs.clone(),
                     values: vec![Scalar::Null; self.aggr_exprs.len()],
+                    end_time: end,
+                    last_row: None,
                 }),
         };
+        aggregate_state.end_time = aggregate_state.end_time.max(end);
         for (expr, scalar) in aggregate_state
             .aggr_exprs
             .iter_mut()
@@ -155,10 +204,277 @@ impl AggregateManager {
             let array = expr.eval(dataset)?;
             *scalar = array.scalar_value(array.len() - 1);
         }
+        if self.panes.is_some() || matches!(self.window, Window::Session { .. }) {
+            aggregate_state.last_row = Some(dataset.slice(dataset.len() - 1, 1));
+        }
 
         Ok(())
     }
 
+    /// Completes the sliding windows that end before `limit` by merging the states of their
+    /// panes, and removes the panes that no longer belong to an open window.
+    fn complete_pane_windows(&mut self, limit: i64) -> Result<Vec<WindowState>> {
+        let (length, interval, offset) = match self.window {
+            Window::Sliding {
+                length,
+                interval,
+                offset,
+            } => (length, interval, offset),
+            _ => unreachable!(),
+        };
+        // The start time of the earliest window that contains the pane.
+        let first_window =
+            |pane: i64| ((pane - length - offset).div_euclid(interval) + 1) * interval + offset;
+
+        let mut completed_windows = Vec::new();
+        let mut start = match self.windows.keys().next() {
+            Some(pane) => first_window(*pane).max(self.next_window.unwrap_or(i64::MIN)),
+            None => return Ok(completed_windows),
+        };
+
+        while start + length < limit {
+            let mut children: AHashMap<GroupedKey, AggregateState> = AHashMap::new();
+            for pane in self
+                .windows
+                .range(start..start + length)
+                .map(|(_, pane)| pane)
+            {
+                for (key, state) in &pane.children {
+                    match children.get_mut(key) {
+                        Some(merged_state) => {
+                            for (expr, other) in
+                                merged_state.aggr_exprs.iter_mut().zip(&state.aggr_exprs)
+                            {
+                                expr.merge(other)?;
+                            }
+                            merged_state.last_row = state.last_row.clone();
+                        }
+                        None => {
+                            children.insert(
+                                key.clone(),
+                                AggregateState {
+                                    aggr_exprs: state.aggr_exprs.clone(),
+                                    values: Vec::new(),
+                                    end_time: start + length,
+                                    last_row: state.last_row.clone(),
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+
+            for state in children.values_mut() {
+                let last_row = match &state.last_row {
+                    Some(last_row) => last_row,
+                    None => anyhow::bail!("internal error: the last row of the pane is missing"),
+                };
+                state.values = state
+                    .aggr_exprs
+                    .iter_mut()
+                    .map(|expr| {
+                        expr.eval_current(last_row)
+                            .map(|array| array.scalar_value(0))
+                    })
+                    .try_collect()?;
+            }
+            completed_windows.push(WindowState {
+                start_time: start,
+                end_time: start + length,
+                children,
+            });
+
+            start += interval;
+            // Skips the windows without any pane.
+            match self.windows.range(start..).next() {
+                Some((pane, _)) => start = start.max(first_window(*pane)),
+                None => break,
+            }
+        }
+
+        self.windows = self.windows.split_off(&start);
+        self.next_window = Some(start);
+        Ok(completed_windows)
+    }
+
+    /// Merges the session `[start, end)` of the group `grouped_key` with the open sessions of
+    /// the same group it overlaps, or opens a new one.
+    ///
+    /// Returns the bounds of the resulting session. The sessions are tracked per group, an event
+    /// that bridges several open sessions of its group merges them and their states into one.
+    fn merge_session(
+        &mut self,
+        grouped_key: &GroupedKey,
+        start: i64,
+        end: i64,
+    ) -> Result<(i64, i64)> {
+        let overlapped = self
+            .windows
+            .range(..end)
+            .filter(|(_, window)| {
+                matches!(window.children.get(grouped_key), Some(state) if state.end_time > start)
+            })
+            .map(|(window_start, _)| *window_start)
+            .collect::<Vec<_>>();
+
+        let (mut merged_start, mut merged_end) = (start, end);
+        let mut merged: Option<AggregateState> = None;
+        for window_start in overlapped {
+            let window = match self.windows.get_mut(&window_start) {
+                Some(window) => window,
+                None => continue,
+            };
+            let state = match window.children.remove(grouped_key) {
+                Some(state) => state,
+                None => continue,
+            };
+            let end_time = window.children.values().map(|state| state.end_time).max();
+            match end_time {
+                Some(end_time) => window.end_time = end_time,
+                None => {
+                    self.windows.remove(&window_start);
+                }
+            }
+
+            merged_start = merged_start.min(window_start);
+            merged_end = merged_end.max(state.end_time);
+            match &mut merged {
+                Some(merged) => merge_aggregate_state(merged, state)?,
+                None => merged = Some(state),
+            }
+        }
+
+        if let Some(mut state) = merged {
+            state.end_time = merged_end;
+            let window = self
+                .windows
+                .entry(merged_start)
+                .or_insert_with(|| WindowState {
+                    start_time: merged_start,
+                    end_time: merged_end,
+                    children: Default::default(),
+                });
+            window.end_time = window.end_time.max(merged_end);
+            window.children.insert(grouped_key.clone(), state);
+        }
+        Ok((merged_start, merged_end))
+    }
+
+    /// Completes the sessions that end before `limit`, the groups of a window are completed
+    /// independently of each other.
+    fn complete_sessions(&mut self, limit: i64) -> Vec<WindowState> {
+        let mut completed_windows = Vec::new();
+        let mut empty_windows = Vec::new();
+        for (start, window) in self.windows.range_mut(..limit) {
+            let keys = window
+                .children
+                .iter()
+                .filter(|(_, state)| limit > state.end_time)
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>();
+            if keys.is_empty() {
+                continue;
+            }
+
+            let mut completed = WindowState {
+                start_time: *start,
+                end_time: *start,
+                children: Default::default(),
+            };
+            for key in keys {
+                if let Some(state) = window.children.remove(&key) {
+                    completed.end_time = completed.end_time.max(state.end_time);
+                    completed.children.insert(key, state);
+                }
+            }
+            completed_windows.push(completed);
+
+            match window.children.values().map(|state| state.end_time).max() {
+                Some(end_time) => window.end_time = end_time,
+                None => empty_windows.push(*start),
+            }
+        }
+        for start in empty_windows {
+            self.windows.remove(&start);
+        }
+        completed_windows
+    }
+
+    /// Creates a dataset with the current results of `states`, which belong to the window that
+    /// starts at `start_time`.
+    fn create_dataset(&self, start_time: i64, states: &[&AggregateState]) -> Result<DataSet> {
+        let start_times = TimestampArray::new_scalar(states.len(), Some(start_time));
+        self.create_dataset_with_start_times(states, start_times)
+    }
+
+    /// Creates a dataset with the current results of `states`, which may belong to different
+    /// windows, `start_times` holds the start time of the window of each state.
+    fn create_dataset_with_start_times(
+        &self,
+        states: &[&AggregateState],
+        start_times: TimestampArray,
+    ) -> Result<DataSet> {
+        let mut columns = Vec::with_capacity(self.aggr_exprs.len() + 1);
+        let first_field = match self.window_column_placement {
+            WindowColumnPlacement::First => 1,
+            WindowColumnPlacement::Last => 0,
+        };
+
+        for index in 0..self.aggr_exprs.len() {
+            let field = &self.schema.fields()[first_field + index];
+
+            match field.data_type {
+                DataType::Null => columns.push(Arc::new(NullArray::new(states.len())) as ArrayRef),
+                DataType::Int8 => append_primitive_value!(columns, states, index, Int8Type, Int8),
+                DataType::Int16 => {
+                    append_primitive_value!(columns, states, index, Int16Type, Int16)
+                }
+                DataType::Int32 => {
+                    append_primitive_value!(columns, states, index, Int32Type, Int32)
+                }
+                DataType::Int64 => {
+                    append_primitive_value!(columns, states, index, Int64Type, Int64)
+                }
+                DataType::Float32 => {
+                    append_primitive_value!(columns, states, index, Float32Type, Float32)
+                }
+                DataType::Float64 => {
+                    append_primitive_value!(columns, states, index, Float64Type, Float64)
+                }
+                DataType::Boolean => {
+                    append_primitive_value!(columns, states, index, BooleanType, Boolean)
+                }
+                DataType::Timestamp(_) => {
+                    append_primitive_value!(columns, states, index, TimestampType, Timestamp)
+                }
+                DataType::String => {
+                    let mut builder = StringBuilder::with_capacity(states.len());
+                    for state in states {
+                        builder.append_opt(if let Scalar::String(value) = &state.values[index] {
+                            Some(value)
+                        } else {
+                            None
+                        });
+                    }
+                    columns.push(Arc::new(builder.finish()));
+                }
+                _ => columns.push(array_from_scalars(
+                    field.data_type,
+                    states
+                        .iter()
+                        .map(|state| state.values[index].clone())
+                        .collect(),
+                )),
+            }
+        }
+
+        match self.window_column_placement {
+            WindowColumnPlacement::First => columns.insert(0, Arc::new(start_times)),
+            WindowColumnPlacement::Last => columns.push(Arc::new(start_times)),
+        }
+        DataSet::try_new(self.schema.clone(), columns)
+    }
+
     fn aggregate(
         &mut self,
         dataset: &DataSet,
@@ -166,112 +482,183 @@ impl AggregateManager {
     ) -> Result<Vec<DataSet>> {
         let mut datasets = Vec::new();
 
-        for item in dataset.group_by_window(self.time_idx, &self.window)? {
-            let (start, end, dataset) = item?;
+        let window = match self.panes {
+            Some(length) => Window::Sliding {
+                length,
+                interval: length,
+                offset: 0,
+            },
+            None => self.window,
+        };
+        let time_zones = match &mut self.time_zone_expr {
+            Some(expr) => Some(expr.eval(dataset)?),
+            None => None,
+        };
+        let mut windows = dataset
+            .group_by_window(
+                self.time_idx,
+                &window,
+                time_zones
+                    .as_ref()
+                    .map(|array| array.downcast_ref::<StringArray>()),
+            )?
+            .try_collect::<_, Vec<_>, _>()?;
+        if let Window::Session { .. } = self.window {
+            windows.sort_by_key(|(start, _, _)| *start);
+        }
 
+        let mut updated: BTreeMap<i64, Vec<GroupedKey>> = BTreeMap::new();
+        for (start, end, dataset) in windows {
+            // The windows that contain the pane have all been emitted.
+            if let (Some(_), Some(next_window)) = (self.panes, self.next_window) {
+                if start < next_window {
+                    self.dropped_rows
+                        .record(DropReason::Late, dataset.len(), || {
+                            format!(
+                                "a row of the pane at {} arrives after its windows, the next window starts at {}",
+                                start, next_window
+                            )
+                        });
+                    continue;
+                }
+            }
             for item in dataset.group_by_exprs(&mut self.group_exprs)? {
                 let (grouped_key, dataset) = item?;
+                let (start, end) = match self.window {
+                    Window::Session { .. } => {
+                        let (start, end) = self.merge_session(&grouped_key, start, end)?;
+                        // The sessions of the group merged into this one are no longer open.
+                        let merged = updated
+                            .range(start..end)
+                            .map(|(merged_start, _)| *merged_start)
+                            .filter(|merged_start| *merged_start != start)
+                            .collect::<Vec<_>>();
+                        for merged_start in merged {
+                            if let Some(keys) = updated.get_mut(&merged_start) {
+                                keys.retain(|key| key != &grouped_key);
+                                if keys.is_empty() {
+                                    updated.remove(&merged_start);
+                                }
+                            }
+                        }
+                        (start, end)
+                    }
+                    _ => (start, end),
+                };
+                if self.emit == Emit::OnUpdate {
+                    let keys = updated.entry(start).or_default();
+                    if !keys.contains(&grouped_key) {
+                        keys.push(grouped_key.clone());
+                    }
+                }
                 self.process_dataset(start, end, grouped_key, &dataset)?;
             }
         }
 
         let mut completed_windows = Vec::new();
         if let Some(current_watermark) = current_watermark {
-            while let Some((start, window)) = self.windows.iter().next() {
-                if current_watermark > window.end_time {
-                    let start = *start;
-                    if let Some(window) = self.windows.remove(&start) {
-                        completed_windows.push(window);
+            if self.panes.is_some() {
+                completed_windows =
+                    self.complete_pane_windows(current_watermark - self.allowed_lateness)?;
+            } else if let Window::Session { .. } = self.window {
+                completed_windows =
+                    self.complete_sessions(current_watermark - self.allowed_lateness);
+            } else {
+                while let Some((start, window)) = self.windows.iter().next() {
+                    if current_watermark - self.allowed_lateness > window.end_time {
+                        let start = *start;
+                        if let Some(window) = self.windows.remove(&start) {
+                            completed_windows.push(window);
+                        }
+                    } else {
+                        break;
                     }
-                } else {
-                    break;
                 }
             }
         }
 
-        for window in completed_windows {
-            let mut columns = Vec::with_capacity(self.aggr_exprs.len());
-
-            for index in 0..self.aggr_exprs.len() {
-                let field = &self.schema.fields()[index];
+        match self.emit_batch_size {
+            Some(batch_size) => {
+                // Skips creating a dataset per window, which dominates when a backfill completes
+                // many small windows at once.
+                let states = completed_windows
+                    .iter()
+                    .flat_map(|window| {
+                        window
+                            .children
+                            .values()
+                            .map(move |state| (window.start_time, state))
+                    })
+                    .collect::<Vec<_>>();
+                for chunk in states.chunks(batch_size) {
+                    let (start_times, states): (Vec<_>, Vec<_>) = chunk.iter().copied().unzip();
+                    datasets.push(self.create_dataset_with_start_times(
+                        &states,
+                        TimestampArray::from_vec(start_times),
+                    )?);
+                }
+            }
+            None => {
+                for window in completed_windows {
+                    let states = window.children.values().collect::<Vec<_>>();
+                    datasets.push(self.create_dataset(window.start_time, &states)?);
+                }
+            }
+        }
 
-                match field.data_type {
-                    DataType::Null => {
-                        columns.push(Arc::new(NullArray::new(window.children.len())) as ArrayRef)
-                    }
-                    DataType::Int8 => {
-                        append_primitive_value!(columns, window.children, index, Int8Type, Int8)
-                    }
-                    DataType::Int16 => {
-                        append_primitive_value!(columns, window.children, index, Int16Type, Int16)
-                    }
-                    DataType::Int32 => {
-                        append_primitive_value!(columns, window.children, index, Int32Type, Int32)
-                    }
-                    DataType::Int64 => {
-                        append_primitive_value!(columns, window.children, index, Int64Type, Int64)
+        match self.emit {
+            Emit::Final => {}
+            Emit::Every { interval } => {
+                let now = self.clock.now();
+                let last_emit_time = *self.last_emit_time.get_or_insert(now);
+                if now - last_emit_time >= interval {
+                    for window in self.windows.values() {
+                        let states = window.children.values().collect::<Vec<_>>();
+                        datasets.push(self.create_dataset(window.start_time, &states)?);
                     }
-                    DataType::Float32 => {
-                        append_primitive_value!(
-                            columns,
-                            window.children,
-                            index,
-                            Float32Type,
-                            Float32
-                        )
-                    }
-                    DataType::Float64 => {
-                        append_primitive_value!(
-                            columns,
-                            window.children,
-                            index,
-                            Float64Type,
-                            Float64
-                        )
-                    }
-                    DataType::Boolean => {
-                        append_primitive_value!(
-                            columns,
-                            window.children,
-                            index,
-                            BooleanType,
-                            Boolean
-                        )
-                    }
-                    DataType::Timestamp(_) => append_primitive_value!(
-                        columns,
-                        window.children,
-                        index,
-                        TimestampType,
-                        Timestamp
-                    ),
-                    DataType::String => {
-                        let mut builder = StringBuilder::with_capacity(window.children.len());
-                        for state in window.children.values() {
-                            builder.append_opt(
-                                if let Scalar::String(value) = &state.values[index] {
-                                    Some(value)
-                                } else {
-                                    None
-                                },
-                            );
-                        }
-                        columns.push(Arc::new(builder.finish()));
+                    self.last_emit_time = Some(now);
+                }
+            }
+            Emit::OnUpdate => {
+                for (start, keys) in updated {
+                    // Windows that have just been completed are already emitted above.
+                    if let Some(window) = self.windows.get(&start) {
+                        let states = keys
+                            .iter()
+                            .filter_map(|key| window.children.get(key))
+                            .collect::<Vec<_>>();
+                        datasets.push(self.create_dataset(window.start_time, &states)?);
                     }
                 }
             }
-
-            columns.push(Arc::new(TimestampArray::new_scalar(
-                window.children.len(),
-                Some(window.start_time),
-            )));
-            datasets.push(DataSet::try_new(self.schema.clone(), columns)?);
         }
 
         Ok(datasets)
     }
 }
 
+/// Merges `other`, the state of the same group in a later session, into `state`.
+fn merge_aggregate_state(state: &mut AggregateState, other: AggregateState) -> Result<()> {
+    for (expr, other) in state.aggr_exprs.iter_mut().zip(&other.aggr_exprs) {
+        expr.merge(other)?;
+    }
+    state.end_time = state.end_time.max(other.end_time);
+    if other.last_row.is_some() {
+        state.last_row = other.last_row;
+    }
+    if let Some(last_row) = &state.last_row {
+        state.values = state
+            .aggr_exprs
+            .iter_mut()
+            .map(|expr| {
+                expr.eval_current(last_row)
+                    .map(|array| array.scalar_value(0))
+            })
+            .try_collect()?;
+    }
+    Ok(())
+}
+
 pub fn create_aggregate_stream(
     ctx: &mut CreateStreamContext,
     node: PhysicalAggregateNode,
@@ -282,16 +669,54 @@ pub fn create_aggregate_stream(
         group_exprs,
         aggr_exprs,
         window,
+        time_zone_expr,
+        allowed_lateness,
+        emit,
         time_idx,
+        window_column_placement,
         input,
+        ..
     } = node;
+    let panes = match window {
+        Window::Sliding {
+            length,
+            interval,
+            offset,
+        } if length > interval
+            && emit == Emit::Final
+            && aggr_exprs.iter().all(|expr| expr.can_merge()) =>
+        {
+            let pane = gcd(length, interval);
+            if offset % pane == 0 {
+                Some(pane)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
     let mut manager = AggregateManager {
         schema,
         group_exprs,
         aggr_exprs,
         window,
+        time_zone_expr,
+        allowed_lateness,
+        emit,
+        last_emit_time: None,
+        clock: ctx.ctx.clock.clone(),
         time_idx,
+        window_column_placement,
         windows: Default::default(),
+        panes,
+        next_window: None,
+        emit_batch_size: ctx.ctx.emit_batch_size,
+        dropped_rows: DroppedRows::new(
+            ctx.ctx.name.clone(),
+            id,
+            "aggregate",
+            ctx.ctx.dropped_rows_log_interval,
+        ),
     };
     if let Some(prev_state) = ctx.prev_state.remove(&id) {
         manager.load_state(prev_state)?;
@@ -302,9 +727,9 @@ pub fn create_aggregate_stream(
     Ok(Box::pin(async_stream::try_stream! {
         while let Some(event) = input.next().await.transpose()? {
             match event {
-                Event::DataSet{ current_watermark, dataset } => {
+                Event::DataSet{ current_watermark, ingest_time, correlation_id, dataset } => {
                     for dataset in manager.aggregate(&dataset, current_watermark)? {
-                        yield Event::DataSet{ current_watermark, dataset };
+                        yield Event::DataSet{ current_watermark, ingest_time, correlation_id: correlation_id.clone(), dataset };
                     }
                 }
                 Event::CreateCheckPoint(barrier) => {
@@ -320,3 +745,185 @@ pub fn create_aggregate_stream(
         }
     }))
 }
+
+fn gcd(mut a: i64, mut b: i64) -> i64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::array::{Float64Array, Int64Array};
+    use crate::dataset::{Field, Schema};
+    use crate::dsl::*;
+    use crate::execution::clock::SystemClock;
+    use crate::metrics;
+
+    fn input_schema() -> SchemaRef {
+        Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("k", DataType::String),
+                Field::new("v", DataType::Int64),
+            ])
+            .unwrap(),
+        )
+    }
+
+    /// Sums `v` by `k` in sessions with a gap of 10.
+    fn session_manager(emit: Emit) -> AggregateManager {
+        AggregateManager {
+            schema: Arc::new(
+                Schema::try_new(vec![
+                    Field::new("sum", DataType::Float64),
+                    Field::new("start", DataType::Timestamp(None)),
+                ])
+                .unwrap(),
+            ),
+            group_exprs: vec![col("k").into_physical(input_schema()).unwrap()],
+            aggr_exprs: vec![call("sum", vec![col("v")])
+                .into_physical(input_schema())
+                .unwrap()],
+            window: Window::Session { gap: 10 },
+            time_zone_expr: None,
+            allowed_lateness: 0,
+            emit,
+            last_emit_time: None,
+            clock: Arc::new(SystemClock),
+            time_idx: 0,
+            window_column_placement: WindowColumnPlacement::Last,
+            windows: Default::default(),
+            panes: None,
+            next_window: None,
+            emit_batch_size: None,
+            dropped_rows: DroppedRows::new("test", 0, "aggregate", Duration::from_secs(60)),
+        }
+    }
+
+    fn dataset(time: i64, k: &str, v: i64) -> DataSet {
+        DataSet::try_new(
+            input_schema(),
+            vec![
+                Arc::new(TimestampArray::from_vec(vec![time])) as ArrayRef,
+                Arc::new(StringArray::from_vec(vec![k])),
+                Arc::new(Int64Array::from_vec(vec![v])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn sums(dataset: &DataSet) -> Vec<f64> {
+        dataset.columns()[0]
+            .downcast_ref::<Float64Array>()
+            .iter()
+            .collect()
+    }
+
+    fn starts(dataset: &DataSet) -> Vec<i64> {
+        dataset.columns()[1]
+            .downcast_ref::<TimestampArray>()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_session_bridging_event() {
+        let mut manager = session_manager(Emit::OnUpdate);
+
+        manager.aggregate(&dataset(0, "a", 1), None).unwrap();
+        manager.aggregate(&dataset(15, "a", 2), None).unwrap();
+        assert_eq!(manager.windows.len(), 2);
+
+        // The event at 8 extends the session [0, 10) and overlaps the session [15, 25).
+        let datasets = manager.aggregate(&dataset(8, "a", 4), None).unwrap();
+        assert_eq!(manager.windows.len(), 1);
+        let window = &manager.windows[&0];
+        assert_eq!((window.start_time, window.end_time), (0, 25));
+        assert_eq!(datasets.len(), 1);
+        assert_eq!(sums(&datasets[0]), vec![7.0]);
+        assert_eq!(starts(&datasets[0]), vec![0]);
+
+        // The merged session keeps aggregating.
+        let datasets = manager.aggregate(&dataset(20, "a", 8), None).unwrap();
+        assert_eq!(sums(&datasets[0]), vec![15.0]);
+    }
+
+    #[test]
+    fn test_session_per_group() {
+        let mut manager = session_manager(Emit::Final);
+
+        manager.aggregate(&dataset(0, "a", 1), None).unwrap();
+        manager.aggregate(&dataset(5, "b", 2), None).unwrap();
+        // The session [12, 22) of "a" overlaps the session [5, 15) of "b", but not the session
+        // [0, 10) of "a".
+        manager.aggregate(&dataset(12, "a", 4), None).unwrap();
+        assert_eq!(
+            manager.windows.keys().copied().collect::<Vec<_>>(),
+            vec![0, 5, 12]
+        );
+
+        // The event at 14 extends the session of "b" to [5, 24), the watermark completes the
+        // first session of "a".
+        let datasets = manager.aggregate(&dataset(14, "b", 8), Some(14)).unwrap();
+        assert_eq!(datasets.len(), 1);
+        assert_eq!(sums(&datasets[0]), vec![1.0]);
+        assert_eq!(starts(&datasets[0]), vec![0]);
+
+        let datasets = manager.aggregate(&dataset(30, "a", 16), Some(30)).unwrap();
+        let mut results = datasets
+            .iter()
+            .flat_map(|dataset| starts(dataset).into_iter().zip(sums(dataset)))
+            .collect::<Vec<_>>();
+        results.sort_by_key(|(start, _)| *start);
+        assert_eq!(results, vec![(5, 10.0), (12, 4.0)]);
+        assert_eq!(
+            manager.windows.keys().copied().collect::<Vec<_>>(),
+            vec![30]
+        );
+    }
+
+    #[test]
+    fn test_late_pane_rows() {
+        // Sliding windows of 20 every 10, aggregated by panes of 10.
+        let mut manager = AggregateManager {
+            window: Window::Sliding {
+                length: 20,
+                interval: 10,
+                offset: 0,
+            },
+            panes: Some(10),
+            dropped_rows: DroppedRows::new(
+                "test_late_pane_rows",
+                0,
+                "aggregate",
+                Duration::from_secs(60),
+            ),
+            ..session_manager(Emit::Final)
+        };
+
+        manager.aggregate(&dataset(0, "a", 1), None).unwrap();
+        manager.aggregate(&dataset(15, "a", 2), None).unwrap();
+        let datasets = manager.aggregate(&dataset(25, "a", 4), Some(41)).unwrap();
+        assert_eq!(datasets.len(), 4);
+        assert_eq!(manager.next_window, Some(30));
+
+        // All the windows that contain the pane at 0 have been emitted.
+        assert!(manager
+            .aggregate(&dataset(5, "a", 8), None)
+            .unwrap()
+            .is_empty());
+        assert!(manager.windows.is_empty());
+        assert_eq!(
+            metrics::dropped_rows("test_late_pane_rows", 0, DropReason::Late),
+            1
+        );
+        metrics::remove_stream("test_late_pane_rows");
+    }
+}
@@ -0,0 +1,182 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::Result;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt;
+
+use crate::array::compute::array_from_scalars;
+use crate::array::{ArrayExt, ArrayRef, Scalar};
+use crate::dataset::DataSet;
+use crate::execution::dataset::{DataSetExt, GroupedKey};
+use crate::execution::stream::{CreateStreamContext, Event, EventStream};
+use crate::execution::streams::create_stream;
+use crate::expr::cast;
+use crate::planner::physical_plan::{AnalyticFunction, PhysicalAnalyticExpr, PhysicalAnalyticNode};
+
+/// The state of a partition, carried over from the previous datasets.
+#[derive(Default, Serialize, Deserialize)]
+struct PartitionState {
+    row_number: i64,
+    rank: i64,
+    dense_rank: i64,
+    last_order_key: Option<Vec<Scalar>>,
+    /// The last values of the partition, used by `lag`.
+    previous: VecDeque<Scalar>,
+}
+
+/// The states of the partitions seen so far.
+///
+/// A partition is never removed, as a row of it may arrive at any time, so the state grows with
+/// the number of distinct partitions over the lifetime of the stream. It is not included in the
+/// state size estimated by the planner, the partition keys should have a bounded cardinality.
+type Partitions = HashMap<GroupedKey, PartitionState>;
+
+pub fn create_analytic_stream(
+    ctx: &mut CreateStreamContext,
+    node: PhysicalAnalyticNode,
+) -> Result<EventStream> {
+    let mut input = create_stream(ctx, *node.input)?;
+    let id = node.id;
+    let schema = node.schema;
+    let mut exprs = node.exprs;
+    let mut partitions: Vec<Partitions> = match ctx.prev_state.remove(&id) {
+        Some(data) => bincode::deserialize(&data)?,
+        None => exprs.iter().map(|_| Partitions::default()).collect(),
+    };
+
+    Ok(Box::pin(async_stream::try_stream! {
+        while let Some(event) = input.next().await.transpose()? {
+            match event {
+                Event::DataSet { current_watermark, ingest_time, correlation_id, dataset } => {
+                    let mut columns = dataset.columns().to_vec();
+                    for (expr, partitions) in exprs.iter_mut().zip(&mut partitions) {
+                        columns.push(eval(expr, partitions, &dataset)?);
+                    }
+                    let result_dataset = DataSet::try_new(schema.clone(), columns)?;
+                    yield Event::DataSet { current_watermark, ingest_time, correlation_id, dataset: result_dataset };
+                }
+                Event::CreateCheckPoint(barrier) => {
+                    if !barrier.is_saved(id) {
+                        barrier.set_state(id, Some(bincode::serialize(&partitions)?));
+                    }
+                    yield Event::CreateCheckPoint(barrier.clone());
+                    if barrier.is_exit() {
+                        break;
+                    }
+                }
+            }
+        }
+    }))
+}
+
+/// Evaluates an analytic function over the rows of `dataset`.
+///
+/// The rows of a partition are sorted within the dataset, the rows of the following datasets
+/// always come after them.
+fn eval(
+    expr: &mut PhysicalAnalyticExpr,
+    partitions: &mut Partitions,
+    dataset: &DataSet,
+) -> Result<ArrayRef> {
+    let groups = dataset.group_indexes_by_exprs(&mut expr.partition_by)?;
+    let sort_keys = expr
+        .order_by
+        .iter_mut()
+        .map(|(expr, _)| expr.eval(dataset))
+        .try_collect::<_, Vec<_>, _>()?;
+    let descending = expr
+        .order_by
+        .iter()
+        .map(|(_, descending)| *descending)
+        .collect::<Vec<_>>();
+    let order_keys = (0..dataset.len())
+        .map(|row| {
+            sort_keys
+                .iter()
+                .map(|array| array.scalar_value(row))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut args = Vec::with_capacity(expr.args.len());
+    for arg in &mut expr.args {
+        args.push(arg.eval(dataset)?);
+    }
+    if args.len() == 2 {
+        args[1] = cast::array_cast_to(args[1].clone(), expr.data_type)?;
+    }
+    let default_value = |row: usize| match args.get(1) {
+        Some(array) => array.scalar_value(row),
+        None => Scalar::Null,
+    };
+
+    let mut values = vec![Scalar::Null; dataset.len()];
+    for (key, mut indexes) in groups {
+        if !sort_keys.is_empty() {
+            indexes.sort_by(|a, b| compare_keys(&order_keys[*a], &order_keys[*b], &descending));
+        }
+        let state = partitions.entry(key).or_default();
+
+        for row in indexes.iter().copied() {
+            values[row] = match expr.function {
+                AnalyticFunction::RowNumber => {
+                    state.row_number += 1;
+                    Scalar::Int64(state.row_number)
+                }
+                AnalyticFunction::Rank | AnalyticFunction::DenseRank => {
+                    state.row_number += 1;
+                    if state.last_order_key.as_ref() != Some(&order_keys[row]) {
+                        state.rank = state.row_number;
+                        state.dense_rank += 1;
+                        state.last_order_key = Some(order_keys[row].clone());
+                    }
+                    match expr.function {
+                        AnalyticFunction::Rank => Scalar::Int64(state.rank),
+                        _ => Scalar::Int64(state.dense_rank),
+                    }
+                }
+                AnalyticFunction::Lag { offset: 0 } => args[0].scalar_value(row),
+                AnalyticFunction::Lag { offset } => {
+                    let value = match state.previous.len().checked_sub(offset) {
+                        Some(idx) => state.previous[idx].clone(),
+                        None => default_value(row),
+                    };
+                    if state.previous.len() == offset {
+                        state.previous.pop_front();
+                    }
+                    state.previous.push_back(args[0].scalar_value(row));
+                    value
+                }
+            };
+        }
+    }
+
+    Ok(array_from_scalars(expr.data_type, values))
+}
+
+fn compare_keys(a: &[Scalar], b: &[Scalar], descending: &[bool]) -> Ordering {
+    for ((a, b), descending) in a.iter().zip(b).zip(descending) {
+        let ordering = compare_scalars(a, b);
+        let ordering = if *descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Compares two values of the same type, nulls are greater than any other value.
+fn compare_scalars(a: &Scalar, b: &Scalar) -> Ordering {
+    match (a, b) {
+        (Scalar::Null, Scalar::Null) => Ordering::Equal,
+        (Scalar::Null, _) => Ordering::Greater,
+        (_, Scalar::Null) => Ordering::Less,
+        _ => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+    }
+}
@@ -12,6 +12,7 @@ pub fn create_filter_stream(
 ) -> Result<EventStream> {
     let mut input = create_stream(ctx, *node.input)?;
     let id = node.id;
+    let mut common_exprs = node.common_exprs;
     let mut expr = node.expr;
 
     if let Some(data) = ctx.prev_state.remove(&id) {
@@ -21,11 +22,11 @@ pub fn create_filter_stream(
     Ok(Box::pin(async_stream::try_stream! {
         while let Some(event) = input.next().await.transpose()? {
             match event {
-                Event::DataSet{ current_watermark, dataset } => {
-                    let array = expr.eval(&dataset)?;
+                Event::DataSet { current_watermark, ingest_time, correlation_id, dataset } => {
+                    let array = expr.eval(&common_exprs.eval(&dataset)?)?;
                     let result_dataset = dataset.filter(array.downcast_ref::<BooleanArray>())?;
                     if !result_dataset.is_empty() {
-                        yield Event::DataSet { current_watermark, dataset: result_dataset };
+                        yield Event::DataSet { current_watermark, ingest_time, correlation_id, dataset: result_dataset };
                     }
                 }
                 Event::CreateCheckPoint(barrier) => {
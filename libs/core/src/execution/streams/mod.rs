@@ -1,4 +1,5 @@
 mod aggregate;
+mod analytic;
 mod filter;
 mod projection;
 mod source;
@@ -16,5 +17,6 @@ pub fn create_stream(ctx: &mut CreateStreamContext, node: PhysicalNode) -> Resul
         }
         PhysicalNode::Filter(filter) => filter::create_filter_stream(ctx, filter),
         PhysicalNode::Aggregate(aggregate) => aggregate::create_aggregate_stream(ctx, aggregate),
+        PhysicalNode::Analytic(analytic) => analytic::create_analytic_stream(ctx, analytic),
     }
 }
@@ -14,6 +14,7 @@ pub fn create_projection_stream(
     let mut input = create_stream(ctx, *node.input)?;
     let id = node.id;
     let schema = node.schema;
+    let mut common_exprs = node.common_exprs;
     let mut exprs = node.exprs;
 
     if let Some(data) = ctx.prev_state.remove(&id) {
@@ -26,13 +27,14 @@ pub fn create_projection_stream(
     Ok(Box::pin(async_stream::try_stream! {
         while let Some(event) = input.next().await.transpose()? {
             match event {
-                Event::DataSet { current_watermark, dataset } => {
+                Event::DataSet { current_watermark, ingest_time, correlation_id, dataset } => {
+                    let dataset = common_exprs.eval(&dataset)?;
                     let mut columns = Vec::with_capacity(exprs.len());
                     for expr in &mut exprs {
                         columns.push(expr.eval(&dataset)?);
                     }
                     let result_dataset = DataSet::try_new(schema.clone(), columns)?;
-                    yield Event::DataSet { current_watermark, dataset: result_dataset };
+                    yield Event::DataSet { current_watermark, ingest_time, correlation_id, dataset: result_dataset };
                 }
                 Event::CreateCheckPoint(barrier) => {
                     if !barrier.is_saved(id) {
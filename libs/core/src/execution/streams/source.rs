@@ -1,7 +1,7 @@
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
 use futures_util::stream::BoxStream;
@@ -10,14 +10,18 @@ use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::{Stream, StreamExt};
 
-use crate::array::{ArrayExt, BooleanBuilder, TimestampArray};
+use crate::array::{ArrayExt, BooleanArray, BooleanBuilder, TimestampArray};
 use crate::dataset::{DataSet, SchemaRef};
 use crate::execution::checkpoint::CheckPointBarrier;
+use crate::execution::clock::Clock;
+use crate::execution::dropped_rows::DroppedRows;
 use crate::execution::stream::{CreateStreamContext, Event, EventStream};
 use crate::expr::physical_expr::PhysicalExpr;
 use crate::expr::ExprState;
+use crate::metrics::DropReason;
 use crate::planner::physical_plan::PhysicalSourceNode;
-use crate::source_provider::SourceDataSet;
+use crate::source_provider::{BatchSize, SourceDataSet};
+use crate::sources::SchemaChanged;
 
 enum Message {
     CheckPointBarrier(Result<Arc<CheckPointBarrier>, BroadcastStreamRecvError>),
@@ -30,6 +34,8 @@ struct SavedState {
     source_state: Vec<u8>,
     time_expr: Option<ExprState>,
     watermark_expr: Option<ExprState>,
+    /// The offset after the last row emitted, with the source deduplication.
+    emitted_offset: Option<u64>,
 }
 
 struct CombinedStream {
@@ -61,27 +67,60 @@ pub fn create_source_stream(
 ) -> Result<EventStream> {
     let PhysicalSourceNode {
         id,
+        name,
         schema,
         source_provider: provider,
         mut time_expr,
         mut watermark_expr,
+        mut filters,
+        allowed_lateness,
+        ..
     } = node;
 
-    let (input, mut current_watermark) = if let Some(data) = ctx.prev_state.remove(&node.id) {
-        let saved_state: SavedState = bincode::deserialize(&data)?;
-        let input = provider.create_stream(Some(saved_state.source_state))?;
-        if let (Some(expr), Some(data)) = (&mut time_expr, saved_state.time_expr) {
-            expr.load_state(data)?;
-        }
-        if let (Some(expr), Some(data)) = (&mut watermark_expr, saved_state.watermark_expr) {
-            expr.load_state(data)?;
-        }
-        let current_watermark = saved_state.current_watermark;
-        (input, current_watermark)
-    } else {
-        (provider.create_stream(None)?, None)
+    // The batch size starts at the lower bound, and is adjusted after each batch to the time
+    // it took the rest of the pipeline to process it.
+    let adaptive_batch_size = ctx.ctx.adaptive_batch_size.clone().map(|config| {
+        let batch_size = BatchSize::new(config.min_batch_size);
+        (config, batch_size)
+    });
+    let clock = ctx.ctx.clock.clone();
+    let create_input = |state| {
+        let batch_size = adaptive_batch_size
+            .as_ref()
+            .map(|(_, batch_size)| batch_size.clone());
+        provider.create_stream_with_clock(state, batch_size, clock.clone())
     };
 
+    let (input, mut current_watermark, mut emitted_offset) =
+        if let Some(data) = ctx.prev_state.remove(&node.id) {
+            let saved_state: SavedState = bincode::deserialize(&data)?;
+            let input = create_input(Some(saved_state.source_state))?;
+            if let (Some(expr), Some(data)) = (&mut time_expr, saved_state.time_expr) {
+                expr.load_state(data)?;
+            }
+            if let (Some(expr), Some(data)) = (&mut watermark_expr, saved_state.watermark_expr) {
+                expr.load_state(data)?;
+            }
+            let current_watermark = saved_state.current_watermark;
+            (input, current_watermark, saved_state.emitted_offset)
+        } else {
+            (create_input(None)?, None, None)
+        };
+    let source_deduplication = ctx.ctx.source_deduplication;
+    let stream_name = ctx.ctx.name.clone();
+    let mut correlation_ids = CorrelationIds::new(id);
+
+    let watermarks = ctx.watermarks.clone();
+    watermarks.register(id, provider.provider_name(), current_watermark);
+    let progress = ctx.progress.clone();
+    progress.register(id, provider.clone(), current_watermark);
+    let mut dropped_rows = DroppedRows::new(
+        ctx.ctx.name.clone(),
+        id,
+        provider.provider_name(),
+        ctx.ctx.dropped_rows_log_interval,
+    );
+
     let rx_barrier = ctx.tx_barrier.subscribe();
     let mut input = CombinedStream {
         rx_barrier: BroadcastStream::new(rx_barrier),
@@ -108,47 +147,146 @@ pub fn create_source_stream(
                             source_state: current_state,
                             time_expr: time_expr_state,
                             watermark_expr: watermark_expr_state,
+                            emitted_offset,
                         })?;
                         barrier.set_state(id, Some(saved_data));
                         yield Event::CreateCheckPoint(barrier);
                     }
                 }
                 Message::DataSet(item) => {
-                    let SourceDataSet { state, dataset } = item?;
+                    let ingest_time = Instant::now();
+                    let SourceDataSet { state, mut dataset, correlation_id } = item?;
+                    // The stream was planned with the types of the source, a source that widened
+                    // a column has to be planned again.
+                    if dataset
+                        .schema()
+                        .fields()
+                        .iter()
+                        .zip(schema.fields())
+                        .any(|(field, planned)| field.data_type != planned.data_type)
+                    {
+                        let source = name.clone().unwrap_or_else(|| provider.provider_name().to_string());
+                        Err::<(), _>(SchemaChanged { source })?;
+                    }
+                    let rows = dataset.len();
+                    let correlation_id = correlation_id.unwrap_or_else(|| correlation_ids.next());
+                    tracing::debug!(
+                        name = %stream_name,
+                        source_id = id,
+                        correlation_id = %correlation_id,
+                        rows = rows as u64,
+                        "source dataset"
+                    );
+                    if source_deduplication {
+                        if let Some(offset) = provider.offset(&state) {
+                            let duplicates = duplicate_rows(offset, rows, &mut emitted_offset);
+                            if duplicates > 0 {
+                                dropped_rows.record(DropReason::Duplicate, duplicates, || {
+                                    format!("row {} was emitted before the restore", offset)
+                                });
+                                dataset = dataset.slice(duplicates, rows - duplicates);
+                            }
+                        }
+                    }
                     current_state = Some(state);
-                    let new_dataset = process_dataset(
+                    if rows > 0 && dataset.is_empty() {
+                        // All rows were emitted before the restore.
+                        continue;
+                    }
+                    let mut new_dataset = process_dataset(
                         schema.clone(),
                         &dataset,
                         time_expr.as_mut(),
                         watermark_expr.as_mut(),
                         &mut current_watermark,
+                        allowed_lateness,
+                        &mut dropped_rows,
+                        &*clock,
                     )?;
+                    for filter in &mut filters {
+                        if new_dataset.is_empty() {
+                            break;
+                        }
+                        let flags = filter.eval(&new_dataset)?;
+                        new_dataset = new_dataset.filter(flags.downcast_ref::<BooleanArray>())?;
+                    }
+                    if let Some(current_watermark) = current_watermark {
+                        watermarks.update(id, current_watermark);
+                    }
+                    if let Some(state) = &current_state {
+                        progress.update(id, state, rows, current_watermark);
+                    }
+                    let started_at = Instant::now();
                     yield Event::DataSet {
                         current_watermark,
+                        ingest_time,
+                        correlation_id,
                         dataset: new_dataset,
                     };
+                    if let Some((config, batch_size)) = &adaptive_batch_size {
+                        batch_size.set(config.next_batch_size(batch_size.get(), started_at.elapsed()));
+                    }
                 },
             }
         }
     }))
 }
 
+/// Generates the correlation IDs of the datasets of a source that doesn't provide them, unique
+/// across the runs of the stream.
+struct CorrelationIds {
+    prefix: String,
+    sequence: u64,
+}
+
+impl CorrelationIds {
+    fn new(source_id: usize) -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        Self {
+            prefix: format!("{:x}-{}", started_at, source_id),
+            sequence: 0,
+        }
+    }
+
+    fn next(&mut self) -> Arc<str> {
+        self.sequence += 1;
+        format!("{}-{}", self.prefix, self.sequence).into()
+    }
+}
+
+/// Returns the number of rows at the start of a batch of `rows` rows from `offset` that are before
+/// `emitted_offset`, and moves `emitted_offset` after the batch.
+fn duplicate_rows(offset: u64, rows: usize, emitted_offset: &mut Option<u64>) -> usize {
+    let end = offset + rows as u64;
+    match emitted_offset {
+        Some(emitted_offset) => {
+            let duplicates = emitted_offset.saturating_sub(offset).min(rows as u64) as usize;
+            *emitted_offset = (*emitted_offset).max(end);
+            duplicates
+        }
+        None => {
+            *emitted_offset = Some(end);
+            0
+        }
+    }
+}
+
 fn process_dataset(
     schema: SchemaRef,
     dataset: &DataSet,
     time_expr: Option<&mut PhysicalExpr>,
     watermark_expr: Option<&mut PhysicalExpr>,
     current_watermark: &mut Option<i64>,
+    allowed_lateness: i64,
+    dropped_rows: &mut DroppedRows,
+    clock: &dyn Clock,
 ) -> Result<DataSet> {
     let times_array = match time_expr {
         Some(expr) => expr.eval(dataset)?,
-        None => {
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as i64;
-            Arc::new(TimestampArray::new_scalar(dataset.len(), Some(now)))
-        }
+        None => Arc::new(TimestampArray::new_scalar(dataset.len(), Some(clock.now()))),
     };
     let watermarks_array = match watermark_expr {
         Some(expr) => expr.eval(dataset)?,
@@ -158,8 +296,10 @@ fn process_dataset(
     let times = times_array.downcast_ref::<TimestampArray>();
     let watermarks = watermarks_array.downcast_ref::<TimestampArray>();
     let mut flags = BooleanBuilder::default();
+    let mut late_rows = Vec::new();
+    let mut null_time_rows = Vec::new();
 
-    for (time, watermark) in times.iter_opt().zip(watermarks.iter_opt()) {
+    for (row, (time, watermark)) in times.iter_opt().zip(watermarks.iter_opt()).enumerate() {
         if let Some(time) = time {
             let watermark = watermark.unwrap_or(time);
 
@@ -179,12 +319,28 @@ fn process_dataset(
                 }
             };
 
-            flags.append(time >= current_watermark);
+            let on_time = time >= current_watermark - allowed_lateness;
+            if !on_time {
+                late_rows.push((row, time, current_watermark));
+            }
+            flags.append(on_time);
         } else {
+            null_time_rows.push(row);
             flags.append(false);
         }
     }
 
+    dropped_rows.record(DropReason::Late, late_rows.len(), || {
+        let (row, time, watermark) = late_rows[0];
+        format!(
+            "row {} at {} is more than {}ms behind the watermark {}",
+            row, time, allowed_lateness, watermark
+        )
+    });
+    dropped_rows.record(DropReason::NullTime, null_time_rows.len(), || {
+        format!("row {} has no time", null_time_rows[0])
+    });
+
     let new_dataset = DataSet::try_new(
         schema,
         dataset
@@ -196,3 +352,33 @@ fn process_dataset(
     )?;
     new_dataset.filter(&flags.finish())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlation_ids() {
+        let mut ids = CorrelationIds::new(3);
+        let first = ids.next();
+        let second = ids.next();
+        assert!(first.ends_with("-3-1"));
+        assert!(second.ends_with("-3-2"));
+        assert_eq!(first[..first.len() - 1], second[..second.len() - 1]);
+    }
+
+    #[test]
+    fn test_duplicate_rows() {
+        let mut emitted_offset = None;
+        assert_eq!(duplicate_rows(0, 10, &mut emitted_offset), 0);
+        assert_eq!(emitted_offset, Some(10));
+
+        // Restored from the state of the last batch, which is delivered again.
+        assert_eq!(duplicate_rows(0, 10, &mut emitted_offset), 10);
+        assert_eq!(duplicate_rows(5, 10, &mut emitted_offset), 5);
+        assert_eq!(emitted_offset, Some(15));
+        assert_eq!(duplicate_rows(15, 10, &mut emitted_offset), 0);
+        assert_eq!(duplicate_rows(25, 0, &mut emitted_offset), 0);
+        assert_eq!(emitted_offset, Some(25));
+    }
+}
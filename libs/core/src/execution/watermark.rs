@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::execution::execution_context::ExecutionContext;
+use crate::metrics;
+
+/// A source whose watermark lags the maximum watermark of all sources of the stream by more
+/// than the configured threshold, commonly a sign of a stuck partition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatermarkSkew {
+    /// Name of the stream.
+    pub stream: String,
+    /// Id of the source node in the plan.
+    pub source_id: usize,
+    /// Name of the source provider.
+    pub source: &'static str,
+    pub watermark: i64,
+    pub max_watermark: i64,
+}
+
+impl WatermarkSkew {
+    /// Returns how far the watermark of the source lags behind.
+    pub fn lag(&self) -> Duration {
+        Duration::from_millis((self.max_watermark - self.watermark) as u64)
+    }
+}
+
+/// Called when the watermark of a source starts lagging by more than the threshold.
+pub type WatermarkSkewCallback = Arc<dyn Fn(&WatermarkSkew) + Send + Sync>;
+
+struct SourceWatermark {
+    name: &'static str,
+    watermark: Option<i64>,
+    skewed: bool,
+}
+
+/// Tracks the watermarks of all sources of a stream.
+pub(crate) struct WatermarkTracker {
+    stream: String,
+    alert: Option<(Duration, WatermarkSkewCallback)>,
+    sources: Mutex<BTreeMap<usize, SourceWatermark>>,
+}
+
+impl WatermarkTracker {
+    pub(crate) fn new(ctx: &ExecutionContext) -> Self {
+        Self {
+            stream: ctx.name.clone(),
+            alert: ctx.watermark_skew_alert.clone(),
+            sources: Default::default(),
+        }
+    }
+
+    pub(crate) fn register(&self, id: usize, name: &'static str, watermark: Option<i64>) {
+        self.sources.lock().insert(
+            id,
+            SourceWatermark {
+                name,
+                watermark,
+                skewed: false,
+            },
+        );
+    }
+
+    /// Updates the watermark of the source `id`, and reports the sources that started lagging
+    /// behind the others.
+    pub(crate) fn update(&self, id: usize, watermark: i64) {
+        let mut skews = Vec::new();
+
+        {
+            let mut sources = self.sources.lock();
+            if let Some(source) = sources.get_mut(&id) {
+                source.watermark = Some(watermark);
+            }
+            let max_watermark = match sources.values().filter_map(|s| s.watermark).max() {
+                Some(max_watermark) => max_watermark,
+                None => return,
+            };
+
+            for (source_id, source) in sources.iter_mut() {
+                let watermark = match source.watermark {
+                    Some(watermark) => watermark,
+                    None => continue,
+                };
+                let skew = WatermarkSkew {
+                    stream: self.stream.clone(),
+                    source_id: *source_id,
+                    source: source.name,
+                    watermark,
+                    max_watermark,
+                };
+                metrics::set_watermark_lag(&self.stream, *source_id, source.name, skew.lag());
+
+                if let Some((threshold, _)) = &self.alert {
+                    let skewed = skew.lag() > *threshold;
+                    if skewed && !source.skewed {
+                        skews.push(skew);
+                    }
+                    source.skewed = skewed;
+                }
+            }
+        }
+
+        if let Some((_, callback)) = &self.alert {
+            for skew in &skews {
+                tracing::warn!(
+                    name = %skew.stream,
+                    source = skew.source,
+                    source_id = skew.source_id,
+                    lag = ?skew.lag(),
+                    "watermark skew"
+                );
+                callback(skew);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watermark_skew() {
+        let skews = Arc::new(Mutex::new(Vec::new()));
+        let ctx = ExecutionContext::new("test_watermark_skew").with_watermark_skew_alert(
+            Duration::from_secs(10),
+            {
+                let skews = skews.clone();
+                move |skew: &WatermarkSkew| skews.lock().push(skew.clone())
+            },
+        );
+        let tracker = WatermarkTracker::new(&ctx);
+        tracker.register(0, "a", None);
+        tracker.register(1, "b", None);
+
+        tracker.update(0, 1000);
+        tracker.update(1, 5000);
+        assert!(skews.lock().is_empty());
+
+        tracker.update(1, 20000);
+        tracker.update(1, 30000);
+        assert_eq!(
+            *skews.lock(),
+            vec![WatermarkSkew {
+                stream: "test_watermark_skew".to_string(),
+                source_id: 0,
+                source: "a",
+                watermark: 1000,
+                max_watermark: 20000,
+            }]
+        );
+        assert_eq!(
+            metrics::watermark_lag("test_watermark_skew", 0),
+            Some(Duration::from_secs(29))
+        );
+
+        // The source catches up and falls behind again.
+        tracker.update(0, 30000);
+        tracker.update(1, 50000);
+        assert_eq!(skews.lock().len(), 2);
+        assert_eq!(skews.lock()[1].lag(), Duration::from_secs(20));
+
+        metrics::remove_stream("test_watermark_skew");
+    }
+}
@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::sync::Arc;
 
 use anyhow::{Error, Result};
@@ -5,39 +6,40 @@ use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
 use crate::array::{
-    Array, ArrayExt, ArrayRef, BooleanArray, BooleanBuilder, BooleanType, DataType, Float32Type,
-    Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, PrimitiveArray, PrimitiveBuilder,
-    PrimitiveType, StringArray,
+    Array, ArrayExt, ArrayRef, BooleanArray, BooleanBuilder, BooleanType, DataType, DateType,
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, PrimitiveArray,
+    PrimitiveBuilder, PrimitiveType, Scalar, StringArray, StringBuilder, TimeType, TimestampType,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 
 macro_rules! binary_arithmetic_array {
-    ($opcode:expr, $lhs:expr, $rhs:expr, $op:tt) => {
+    ($opcode:expr, $lhs:expr, $rhs:expr, $op:tt, $checked:ident) => {
         match ($lhs.data_type(), $rhs.data_type()) {
-            (DataType::Int8, DataType::Int8) => math_op::<Int8Type, Int8Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int8, DataType::Int16) => math_op::<Int8Type, Int16Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int8, DataType::Int32) => math_op::<Int8Type, Int32Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int8, DataType::Int64) => math_op::<Int8Type, Int64Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op b),
+            (DataType::Int8, DataType::Int8) => checked_math_op::<Int8Type, Int8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int8, DataType::Int16) => checked_math_op::<Int8Type, Int16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int8, DataType::Int32) => checked_math_op::<Int8Type, Int32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int8, DataType::Int64) => checked_math_op::<Int8Type, Int64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b)),
             (DataType::Int8, DataType::Float32) => math_op::<Int8Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
             (DataType::Int8, DataType::Float64) => math_op::<Int8Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op b),
 
-            (DataType::Int16, DataType::Int8) => math_op::<Int16Type, Int8Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int16, DataType::Int16) => math_op::<Int16Type, Int16Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int16, DataType::Int32) => math_op::<Int16Type, Int32Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int16, DataType::Int64) => math_op::<Int16Type, Int64Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op b),
+            (DataType::Int16, DataType::Int8) => checked_math_op::<Int16Type, Int8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int16, DataType::Int16) => checked_math_op::<Int16Type, Int16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int16, DataType::Int32) => checked_math_op::<Int16Type, Int32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int16, DataType::Int64) => checked_math_op::<Int16Type, Int64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b)),
             (DataType::Int16, DataType::Float32) => math_op::<Int16Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
             (DataType::Int16, DataType::Float64) => math_op::<Int16Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op b),
 
-            (DataType::Int32, DataType::Int8) => math_op::<Int32Type, Int8Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int32, DataType::Int16) => math_op::<Int32Type, Int16Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int32, DataType::Int32) => math_op::<Int32Type, Int32Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
-            (DataType::Int32, DataType::Int64) => math_op::<Int32Type, Int64Type, Int64Type, _>($lhs, $rhs, |a, b| (a as i64) $op b),
+            (DataType::Int32, DataType::Int8) => checked_math_op::<Int32Type, Int8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int32, DataType::Int16) => checked_math_op::<Int32Type, Int16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int32, DataType::Int32) => checked_math_op::<Int32Type, Int32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int32, DataType::Int64) => checked_math_op::<Int32Type, Int64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b)),
             (DataType::Int32, DataType::Float32) => math_op::<Int32Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
             (DataType::Int32, DataType::Float64) => math_op::<Int32Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op b),
 
-            (DataType::Int64, DataType::Int8) => math_op::<Int64Type, Int8Type, Int64Type, _>($lhs, $rhs, |a, b| a $op (b as i64)),
-            (DataType::Int64, DataType::Int16) => math_op::<Int64Type, Int16Type, Int64Type, _>($lhs, $rhs, |a, b| a $op (b as i64)),
-            (DataType::Int64, DataType::Int32) => math_op::<Int64Type, Int32Type, Int64Type, _>($lhs, $rhs, |a, b| a $op (b as i64)),
-            (DataType::Int64, DataType::Int64) => math_op::<Int64Type, Int64Type, Int64Type, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::Int64, DataType::Int8) => checked_math_op::<Int64Type, Int8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Int64, DataType::Int16) => checked_math_op::<Int64Type, Int16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Int64, DataType::Int32) => checked_math_op::<Int64Type, Int32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Int64, DataType::Int64) => checked_math_op::<Int64Type, Int64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b)),
             (DataType::Int64, DataType::Float32) => math_op::<Int64Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
             (DataType::Int64, DataType::Float64) => math_op::<Int64Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op b),
 
@@ -55,6 +57,80 @@ macro_rules! binary_arithmetic_array {
             (DataType::Float64, DataType::Float32) => math_op::<Float64Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| a $op (b as f64)),
             (DataType::Float64, DataType::Float64) => math_op::<Float64Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| a $op b),
 
+            (DataType::Int8, DataType::UInt8) => checked_math_op::<Int8Type, UInt8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int8, DataType::UInt16) => checked_math_op::<Int8Type, UInt16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int8, DataType::UInt32) => checked_math_op::<Int8Type, UInt32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int8, DataType::UInt64) => checked_math_op::<Int8Type, UInt64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(b).ok().and_then(|b| (a as i64).$checked(b))),
+            (DataType::Int16, DataType::UInt8) => checked_math_op::<Int16Type, UInt8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int16, DataType::UInt16) => checked_math_op::<Int16Type, UInt16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int16, DataType::UInt32) => checked_math_op::<Int16Type, UInt32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int16, DataType::UInt64) => checked_math_op::<Int16Type, UInt64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(b).ok().and_then(|b| (a as i64).$checked(b))),
+            (DataType::Int32, DataType::UInt8) => checked_math_op::<Int32Type, UInt8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int32, DataType::UInt16) => checked_math_op::<Int32Type, UInt16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int32, DataType::UInt32) => checked_math_op::<Int32Type, UInt32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::Int32, DataType::UInt64) => checked_math_op::<Int32Type, UInt64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(b).ok().and_then(|b| (a as i64).$checked(b))),
+            (DataType::Int64, DataType::UInt8) => checked_math_op::<Int64Type, UInt8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Int64, DataType::UInt16) => checked_math_op::<Int64Type, UInt16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Int64, DataType::UInt32) => checked_math_op::<Int64Type, UInt32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Int64, DataType::UInt64) => checked_math_op::<Int64Type, UInt64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(b).ok().and_then(|b| a.$checked(b))),
+            (DataType::UInt8, DataType::Int8) => checked_math_op::<UInt8Type, Int8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt8, DataType::Int16) => checked_math_op::<UInt8Type, Int16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt8, DataType::Int32) => checked_math_op::<UInt8Type, Int32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt8, DataType::Int64) => checked_math_op::<UInt8Type, Int64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b)),
+            (DataType::UInt8, DataType::UInt8) => checked_math_op::<UInt8Type, UInt8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt8, DataType::UInt16) => checked_math_op::<UInt8Type, UInt16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt8, DataType::UInt32) => checked_math_op::<UInt8Type, UInt32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt8, DataType::UInt64) => checked_math_op::<UInt8Type, UInt64Type, UInt64Type, _>($opcode, $lhs, $rhs, |a, b| (a as u64).$checked(b)),
+            (DataType::UInt8, DataType::Float32) => math_op::<UInt8Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt8, DataType::Float64) => math_op::<UInt8Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt16, DataType::Int8) => checked_math_op::<UInt16Type, Int8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt16, DataType::Int16) => checked_math_op::<UInt16Type, Int16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt16, DataType::Int32) => checked_math_op::<UInt16Type, Int32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt16, DataType::Int64) => checked_math_op::<UInt16Type, Int64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b)),
+            (DataType::UInt16, DataType::UInt8) => checked_math_op::<UInt16Type, UInt8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt16, DataType::UInt16) => checked_math_op::<UInt16Type, UInt16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt16, DataType::UInt32) => checked_math_op::<UInt16Type, UInt32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt16, DataType::UInt64) => checked_math_op::<UInt16Type, UInt64Type, UInt64Type, _>($opcode, $lhs, $rhs, |a, b| (a as u64).$checked(b)),
+            (DataType::UInt16, DataType::Float32) => math_op::<UInt16Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt16, DataType::Float64) => math_op::<UInt16Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt32, DataType::Int8) => checked_math_op::<UInt32Type, Int8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt32, DataType::Int16) => checked_math_op::<UInt32Type, Int16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt32, DataType::Int32) => checked_math_op::<UInt32Type, Int32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt32, DataType::Int64) => checked_math_op::<UInt32Type, Int64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b)),
+            (DataType::UInt32, DataType::UInt8) => checked_math_op::<UInt32Type, UInt8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt32, DataType::UInt16) => checked_math_op::<UInt32Type, UInt16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt32, DataType::UInt32) => checked_math_op::<UInt32Type, UInt32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| (a as i64).$checked(b as i64)),
+            (DataType::UInt32, DataType::UInt64) => checked_math_op::<UInt32Type, UInt64Type, UInt64Type, _>($opcode, $lhs, $rhs, |a, b| (a as u64).$checked(b)),
+            (DataType::UInt32, DataType::Float32) => math_op::<UInt32Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt32, DataType::Float64) => math_op::<UInt32Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt64, DataType::Int8) => checked_math_op::<UInt64Type, Int8Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(a).ok().and_then(|a| a.$checked(b as i64))),
+            (DataType::UInt64, DataType::Int16) => checked_math_op::<UInt64Type, Int16Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(a).ok().and_then(|a| a.$checked(b as i64))),
+            (DataType::UInt64, DataType::Int32) => checked_math_op::<UInt64Type, Int32Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(a).ok().and_then(|a| a.$checked(b as i64))),
+            (DataType::UInt64, DataType::Int64) => checked_math_op::<UInt64Type, Int64Type, Int64Type, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(a).ok().and_then(|a| a.$checked(b))),
+            (DataType::UInt64, DataType::UInt8) => checked_math_op::<UInt64Type, UInt8Type, UInt64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as u64)),
+            (DataType::UInt64, DataType::UInt16) => checked_math_op::<UInt64Type, UInt16Type, UInt64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as u64)),
+            (DataType::UInt64, DataType::UInt32) => checked_math_op::<UInt64Type, UInt32Type, UInt64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as u64)),
+            (DataType::UInt64, DataType::UInt64) => checked_math_op::<UInt64Type, UInt64Type, UInt64Type, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b)),
+            (DataType::UInt64, DataType::Float32) => math_op::<UInt64Type, Float32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt64, DataType::Float64) => math_op::<UInt64Type, Float64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::Float32, DataType::UInt8) => math_op::<Float32Type, UInt8Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt16) => math_op::<Float32Type, UInt16Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt32) => math_op::<Float32Type, UInt32Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt64) => math_op::<Float32Type, UInt64Type, Float64Type, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float64, DataType::UInt8) => math_op::<Float64Type, UInt8Type, Float64Type, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt16) => math_op::<Float64Type, UInt16Type, Float64Type, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt32) => math_op::<Float64Type, UInt32Type, Float64Type, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt64) => math_op::<Float64Type, UInt64Type, Float64Type, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+
+            (DataType::Timestamp(_), DataType::Int8) => checked_math_op::<TimestampType, Int8Type, TimestampType, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Timestamp(_), DataType::Int16) => checked_math_op::<TimestampType, Int16Type, TimestampType, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Timestamp(_), DataType::Int32) => checked_math_op::<TimestampType, Int32Type, TimestampType, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Timestamp(_), DataType::Int64) => checked_math_op::<TimestampType, Int64Type, TimestampType, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b)),
+            (DataType::Timestamp(_), DataType::UInt8) => checked_math_op::<TimestampType, UInt8Type, TimestampType, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Timestamp(_), DataType::UInt16) => checked_math_op::<TimestampType, UInt16Type, TimestampType, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Timestamp(_), DataType::UInt32) => checked_math_op::<TimestampType, UInt32Type, TimestampType, _>($opcode, $lhs, $rhs, |a, b| a.$checked(b as i64)),
+            (DataType::Timestamp(_), DataType::UInt64) => checked_math_op::<TimestampType, UInt64Type, TimestampType, _>($opcode, $lhs, $rhs, |a, b| i64::try_from(b).ok().and_then(|b| a.$checked(b))),
+
             _ => Err(binary_error($opcode, $lhs.data_type(), $rhs.data_type())),
         }
     };
@@ -87,21 +163,112 @@ macro_rules! binary_equal_array {
             (DataType::Int8, DataType::Int16) => math_op::<Int8Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
             (DataType::Int8, DataType::Int32) => math_op::<Int8Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
             (DataType::Int8, DataType::Int64) => math_op::<Int8Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op b),
+            (DataType::Int8, DataType::Float32) => math_op::<Int8Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Int8, DataType::Float64) => math_op::<Int8Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
 
             (DataType::Int16, DataType::Int8) => math_op::<Int16Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
             (DataType::Int16, DataType::Int16) => math_op::<Int16Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
             (DataType::Int16, DataType::Int32) => math_op::<Int16Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
             (DataType::Int16, DataType::Int64) => math_op::<Int16Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op b),
+            (DataType::Int16, DataType::Float32) => math_op::<Int16Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Int16, DataType::Float64) => math_op::<Int16Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
 
             (DataType::Int32, DataType::Int8) => math_op::<Int32Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
             (DataType::Int32, DataType::Int16) => math_op::<Int32Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
             (DataType::Int32, DataType::Int32) => math_op::<Int32Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op (b as i64)),
             (DataType::Int32, DataType::Int64) => math_op::<Int32Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i64) $op b),
+            (DataType::Int32, DataType::Float32) => math_op::<Int32Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Int32, DataType::Float64) => math_op::<Int32Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
 
             (DataType::Int64, DataType::Int8) => math_op::<Int64Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as i64)),
             (DataType::Int64, DataType::Int16) => math_op::<Int64Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as i64)),
             (DataType::Int64, DataType::Int32) => math_op::<Int64Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as i64)),
             (DataType::Int64, DataType::Int64) => math_op::<Int64Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::Int64, DataType::Float32) => math_op::<Int64Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Int64, DataType::Float64) => math_op::<Int64Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+
+            (DataType::Float32, DataType::Int8) => math_op::<Float32Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::Int16) => math_op::<Float32Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::Int32) => math_op::<Float32Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::Int64) => math_op::<Float32Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::Float32) => math_op::<Float32Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::Float64) => math_op::<Float32Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+
+            (DataType::Float64, DataType::Int8) => math_op::<Float64Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::Int16) => math_op::<Float64Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::Int32) => math_op::<Float64Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::Int64) => math_op::<Float64Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::Float32) => math_op::<Float64Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::Float64) => math_op::<Float64Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+
+            (DataType::Int8, DataType::UInt8) => math_op::<Int8Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int8, DataType::UInt16) => math_op::<Int8Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int8, DataType::UInt32) => math_op::<Int8Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int8, DataType::UInt64) => math_op::<Int8Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int16, DataType::UInt8) => math_op::<Int16Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int16, DataType::UInt16) => math_op::<Int16Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int16, DataType::UInt32) => math_op::<Int16Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int16, DataType::UInt64) => math_op::<Int16Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int32, DataType::UInt8) => math_op::<Int32Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int32, DataType::UInt16) => math_op::<Int32Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int32, DataType::UInt32) => math_op::<Int32Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int32, DataType::UInt64) => math_op::<Int32Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int64, DataType::UInt8) => math_op::<Int64Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int64, DataType::UInt16) => math_op::<Int64Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int64, DataType::UInt32) => math_op::<Int64Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int64, DataType::UInt64) => math_op::<Int64Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Int8) => math_op::<UInt8Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Int16) => math_op::<UInt8Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Int32) => math_op::<UInt8Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Int64) => math_op::<UInt8Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::UInt8) => math_op::<UInt8Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::UInt8, DataType::UInt16) => math_op::<UInt8Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::UInt32) => math_op::<UInt8Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::UInt64) => math_op::<UInt8Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Float32) => math_op::<UInt8Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt8, DataType::Float64) => math_op::<UInt8Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt16, DataType::Int8) => math_op::<UInt16Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::Int16) => math_op::<UInt16Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::Int32) => math_op::<UInt16Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::Int64) => math_op::<UInt16Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::UInt8) => math_op::<UInt16Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::UInt16) => math_op::<UInt16Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::UInt16, DataType::UInt32) => math_op::<UInt16Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::UInt64) => math_op::<UInt16Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::Float32) => math_op::<UInt16Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt16, DataType::Float64) => math_op::<UInt16Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt32, DataType::Int8) => math_op::<UInt32Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::Int16) => math_op::<UInt32Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::Int32) => math_op::<UInt32Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::Int64) => math_op::<UInt32Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::UInt8) => math_op::<UInt32Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::UInt16) => math_op::<UInt32Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::UInt32) => math_op::<UInt32Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::UInt32, DataType::UInt64) => math_op::<UInt32Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::Float32) => math_op::<UInt32Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt32, DataType::Float64) => math_op::<UInt32Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt64, DataType::Int8) => math_op::<UInt64Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::Int16) => math_op::<UInt64Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::Int32) => math_op::<UInt64Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::Int64) => math_op::<UInt64Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::UInt8) => math_op::<UInt64Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::UInt16) => math_op::<UInt64Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::UInt32) => math_op::<UInt64Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::UInt64) => math_op::<UInt64Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::UInt64, DataType::Float32) => math_op::<UInt64Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt64, DataType::Float64) => math_op::<UInt64Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::Float32, DataType::UInt8) => math_op::<Float32Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt16) => math_op::<Float32Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt32) => math_op::<Float32Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt64) => math_op::<Float32Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float64, DataType::UInt8) => math_op::<Float64Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt16) => math_op::<Float64Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt32) => math_op::<Float64Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt64) => math_op::<Float64Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+
+            (DataType::Timestamp(_), DataType::Timestamp(_)) => math_op::<TimestampType, TimestampType, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::Date, DataType::Date) => math_op::<DateType, DateType, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::Time, DataType::Time) => math_op::<TimeType, TimeType, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
 
             _ => Err(binary_error($opcode, $lhs.data_type(), $rhs.data_type())),
         }
@@ -166,6 +333,75 @@ macro_rules! binary_order_array {
             (DataType::Float64, DataType::Float32) => math_op::<Float64Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
             (DataType::Float64, DataType::Float64) => math_op::<Float64Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
 
+            (DataType::Int8, DataType::UInt8) => math_op::<Int8Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int8, DataType::UInt16) => math_op::<Int8Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int8, DataType::UInt32) => math_op::<Int8Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int8, DataType::UInt64) => math_op::<Int8Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int16, DataType::UInt8) => math_op::<Int16Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int16, DataType::UInt16) => math_op::<Int16Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int16, DataType::UInt32) => math_op::<Int16Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int16, DataType::UInt64) => math_op::<Int16Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int32, DataType::UInt8) => math_op::<Int32Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int32, DataType::UInt16) => math_op::<Int32Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int32, DataType::UInt32) => math_op::<Int32Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int32, DataType::UInt64) => math_op::<Int32Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int64, DataType::UInt8) => math_op::<Int64Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int64, DataType::UInt16) => math_op::<Int64Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int64, DataType::UInt32) => math_op::<Int64Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::Int64, DataType::UInt64) => math_op::<Int64Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Int8) => math_op::<UInt8Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Int16) => math_op::<UInt8Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Int32) => math_op::<UInt8Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Int64) => math_op::<UInt8Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::UInt8) => math_op::<UInt8Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::UInt8, DataType::UInt16) => math_op::<UInt8Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::UInt32) => math_op::<UInt8Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::UInt64) => math_op::<UInt8Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt8, DataType::Float32) => math_op::<UInt8Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt8, DataType::Float64) => math_op::<UInt8Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt16, DataType::Int8) => math_op::<UInt16Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::Int16) => math_op::<UInt16Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::Int32) => math_op::<UInt16Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::Int64) => math_op::<UInt16Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::UInt8) => math_op::<UInt16Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::UInt16) => math_op::<UInt16Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::UInt16, DataType::UInt32) => math_op::<UInt16Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::UInt64) => math_op::<UInt16Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt16, DataType::Float32) => math_op::<UInt16Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt16, DataType::Float64) => math_op::<UInt16Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt32, DataType::Int8) => math_op::<UInt32Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::Int16) => math_op::<UInt32Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::Int32) => math_op::<UInt32Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::Int64) => math_op::<UInt32Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::UInt8) => math_op::<UInt32Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::UInt16) => math_op::<UInt32Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::UInt32) => math_op::<UInt32Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::UInt32, DataType::UInt64) => math_op::<UInt32Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt32, DataType::Float32) => math_op::<UInt32Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt32, DataType::Float64) => math_op::<UInt32Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::UInt64, DataType::Int8) => math_op::<UInt64Type, Int8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::Int16) => math_op::<UInt64Type, Int16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::Int32) => math_op::<UInt64Type, Int32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::Int64) => math_op::<UInt64Type, Int64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::UInt8) => math_op::<UInt64Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::UInt16) => math_op::<UInt64Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::UInt32) => math_op::<UInt64Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as i128) $op (b as i128)),
+            (DataType::UInt64, DataType::UInt64) => math_op::<UInt64Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::UInt64, DataType::Float32) => math_op::<UInt64Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::UInt64, DataType::Float64) => math_op::<UInt64Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op b),
+            (DataType::Float32, DataType::UInt8) => math_op::<Float32Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt16) => math_op::<Float32Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt32) => math_op::<Float32Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float32, DataType::UInt64) => math_op::<Float32Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| (a as f64) $op (b as f64)),
+            (DataType::Float64, DataType::UInt8) => math_op::<Float64Type, UInt8Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt16) => math_op::<Float64Type, UInt16Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt32) => math_op::<Float64Type, UInt32Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+            (DataType::Float64, DataType::UInt64) => math_op::<Float64Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| a $op (b as f64)),
+
+            (DataType::Timestamp(_), DataType::Timestamp(_)) => math_op::<TimestampType, TimestampType, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::Date, DataType::Date) => math_op::<DateType, DateType, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+            (DataType::Time, DataType::Time) => math_op::<TimeType, TimeType, BooleanType, _>($lhs, $rhs, |a, b| a $op b),
+
             _ => Err(binary_error($opcode, $lhs.data_type(), $rhs.data_type())),
         }
     };
@@ -235,9 +471,22 @@ pub enum BinaryOperator {
 
     #[display(fmt = "/")]
     Divide,
+
+    #[display(fmt = "%")]
+    Modulo,
+
+    #[display(fmt = "||")]
+    Concat,
 }
 
 impl BinaryOperator {
+    /// Returns `true` if the operator compares its operands.
+    pub(crate) fn is_comparison(&self) -> bool {
+        use BinaryOperator::*;
+
+        matches!(self, Eq | NotEq | Lt | LtEq | Gt | GtEq)
+    }
+
     pub(crate) fn data_type(&self, left: DataType, right: DataType) -> Result<DataType> {
         use BinaryOperator::*;
         use DataType::*;
@@ -251,8 +500,12 @@ impl BinaryOperator {
                 }
             }
             Eq | NotEq => {
-                if (left.is_string() && right.is_string())
-                    || (left.is_integer() && right.is_integer())
+                if (left.is_boolean() && right.is_boolean())
+                    || (left.is_string() && right.is_string())
+                    || (left.is_numeric() && right.is_numeric())
+                    || (left.is_timestamp() && right.is_timestamp())
+                    || (left.is_date() && right.is_date())
+                    || (left.is_time() && right.is_time())
                 {
                     Ok(Boolean)
                 } else {
@@ -262,23 +515,42 @@ impl BinaryOperator {
             Lt | LtEq | Gt | GtEq => {
                 if (left.is_numeric() && right.is_numeric())
                     || (left.is_string() && right.is_string())
+                    || (left.is_timestamp() && right.is_timestamp())
+                    || (left.is_date() && right.is_date())
+                    || (left.is_time() && right.is_time())
                 {
                     Ok(Boolean)
                 } else {
                     Err(binary_error(*self, left, right))
                 }
             }
-            Plus | Minus | Multiply | Divide => {
-                if (left.is_float() && right.is_integer())
-                    || (left.is_integer() && right.is_float())
+            Plus | Minus if left.is_timestamp() && right.is_integer() => Ok(left),
+            Plus | Minus | Multiply | Divide | Modulo => {
+                if (left.is_float() && right.is_numeric())
+                    || (left.is_numeric() && right.is_float())
                 {
                     Ok(DataType::Float64)
+                } else if left.is_unsigned()
+                    && right.is_unsigned()
+                    && (left == UInt64 || right == UInt64)
+                {
+                    Ok(DataType::UInt64)
                 } else if left.is_integer() && right.is_integer() {
                     Ok(DataType::Int64)
                 } else {
                     Err(binary_error(*self, left, right))
                 }
             }
+            Concat => {
+                let is_concatable = |data_type: DataType| {
+                    data_type.is_string() || data_type.is_numeric() || data_type == Boolean
+                };
+                if is_concatable(left) && is_concatable(right) {
+                    Ok(DataType::String)
+                } else {
+                    Err(binary_error(*self, left, right))
+                }
+            }
         }
     }
 
@@ -297,10 +569,12 @@ impl BinaryOperator {
             BinaryOperator::LtEq => binary_order_array!(*self, lhs, rhs, <=),
             BinaryOperator::Gt => binary_order_array!(*self, lhs, rhs, >),
             BinaryOperator::GtEq => binary_order_array!(*self, lhs, rhs, >=),
-            BinaryOperator::Plus => binary_arithmetic_array!(*self, lhs, rhs, +),
-            BinaryOperator::Minus => binary_arithmetic_array!(*self, lhs, rhs, -),
-            BinaryOperator::Multiply => binary_arithmetic_array!(*self, lhs, rhs, *),
-            BinaryOperator::Divide => binary_arithmetic_array!(*self, lhs, rhs, /),
+            BinaryOperator::Plus => binary_arithmetic_array!(*self, lhs, rhs, +, checked_add),
+            BinaryOperator::Minus => binary_arithmetic_array!(*self, lhs, rhs, -, checked_sub),
+            BinaryOperator::Multiply => binary_arithmetic_array!(*self, lhs, rhs, *, checked_mul),
+            BinaryOperator::Divide => binary_arithmetic_array!(*self, lhs, rhs, /, checked_div),
+            BinaryOperator::Modulo => binary_arithmetic_array!(*self, lhs, rhs, %, checked_rem),
+            BinaryOperator::Concat => concat_array(lhs, rhs),
         }
     }
 }
@@ -314,6 +588,49 @@ fn binary_error(op: BinaryOperator, left: DataType, right: DataType) -> Error {
     )
 }
 
+fn concat_array(lhs: &dyn Array, rhs: &dyn Array) -> Result<ArrayRef> {
+    if let (DataType::String, DataType::String) = (lhs.data_type(), rhs.data_type()) {
+        let a = lhs.downcast_ref::<StringArray>();
+        let b = rhs.downcast_ref::<StringArray>();
+        if let (Some(a_scalar), Some(b_scalar)) = (a.to_scalar(), b.to_scalar()) {
+            return match (a_scalar, b_scalar) {
+                (Some(a_scalar), Some(b_scalar)) => Ok(Arc::new(StringArray::new_scalar(
+                    a.len(),
+                    Some(format!("{}{}", a_scalar, b_scalar)),
+                ))),
+                _ => Ok(Arc::new(StringArray::new_scalar(
+                    a.len(),
+                    Option::<&str>::None,
+                ))),
+            };
+        }
+        let mut builder = StringBuilder::with_capacity(a.len());
+        let mut buf = String::new();
+        for (a, b) in a.iter_opt().zip(b.iter_opt()) {
+            match (a, b) {
+                (Some(a), Some(b)) => {
+                    buf.clear();
+                    buf.push_str(a);
+                    buf.push_str(b);
+                    builder.append(&buf);
+                }
+                _ => builder.append_null(),
+            }
+        }
+        return Ok(Arc::new(builder.finish()));
+    }
+
+    // Numeric and boolean operands are converted to strings.
+    let mut builder = StringBuilder::with_capacity(lhs.len());
+    for row in 0..lhs.len() {
+        match (lhs.scalar_value(row), rhs.scalar_value(row)) {
+            (Scalar::Null, _) | (_, Scalar::Null) => builder.append_null(),
+            (a, b) => builder.append(&format!("{}{}", a, b)),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
 #[inline]
 fn math_op<A, B, R, F>(a: &dyn Array, b: &dyn Array, f: F) -> Result<ArrayRef>
 where
@@ -342,3 +659,166 @@ where
     }
     Ok(Arc::new(builder.finish()))
 }
+
+/// Like [`math_op`], for the integer operations, `f` returns `None` on overflow and division by
+/// zero, which fail the operation.
+fn checked_math_op<A, B, R, F>(
+    op: BinaryOperator,
+    a: &dyn Array,
+    b: &dyn Array,
+    f: F,
+) -> Result<ArrayRef>
+where
+    A: PrimitiveType,
+    B: PrimitiveType,
+    R: PrimitiveType,
+    F: Fn(A::Native, B::Native) -> Option<R::Native>,
+{
+    let overflow = || {
+        anyhow::anyhow!(
+            "integer overflow or division by zero in the '{}' operator.",
+            op
+        )
+    };
+    let a = a.downcast_ref::<PrimitiveArray<A>>();
+    let b = b.downcast_ref::<PrimitiveArray<B>>();
+    if let (Some(a_scalar), Some(b_scalar)) = (a.to_scalar(), b.to_scalar()) {
+        return match (a_scalar, b_scalar) {
+            (Some(a_scalar), Some(b_scalar)) => Ok(Arc::new(PrimitiveArray::<R>::new_scalar(
+                a.len(),
+                Some(f(a_scalar, b_scalar).ok_or_else(overflow)?),
+            ))),
+            _ => Ok(Arc::new(PrimitiveArray::<R>::new_scalar(a.len(), None))),
+        };
+    }
+    let mut builder = PrimitiveBuilder::<R>::with_capacity(a.len());
+    for (a, b) in a.iter_opt().zip(b.iter_opt()) {
+        match (a, b) {
+            (Some(a), Some(b)) => builder.append(f(a, b).ok_or_else(overflow)?),
+            _ => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Float64Array, Int64Array, Int8Array, UInt64Array, UInt8Array};
+
+    #[test]
+    fn test_unsigned() {
+        let a = UInt8Array::from_vec(vec![1, 200]);
+        let b = UInt8Array::from_vec(vec![2, 100]);
+        assert_eq!(
+            BinaryOperator::Minus
+                .data_type(DataType::UInt8, DataType::UInt8)
+                .unwrap(),
+            DataType::Int64
+        );
+        let array = BinaryOperator::Minus.eval_array(&a, &b).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![-1, 100]
+        );
+
+        let a = UInt64Array::from_vec(vec![u64::MAX, 0]);
+        let b = Int8Array::from_vec(vec![-1, -1]);
+        let array = BinaryOperator::Gt.eval_array(&a, &b).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<BooleanArray>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![true, true]
+        );
+
+        let a = UInt64Array::from_vec(vec![u64::MAX - 1, 3]);
+        let b = UInt64Array::from_vec(vec![1, 2]);
+        assert_eq!(
+            BinaryOperator::Plus
+                .data_type(DataType::UInt64, DataType::UInt64)
+                .unwrap(),
+            DataType::UInt64
+        );
+        let array = BinaryOperator::Plus.eval_array(&a, &b).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<UInt64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![u64::MAX, 5]
+        );
+        assert_eq!(
+            BinaryOperator::Multiply
+                .eval_array(&a, &a)
+                .unwrap_err()
+                .to_string(),
+            "integer overflow or division by zero in the '*' operator."
+        );
+        assert_eq!(
+            BinaryOperator::Minus
+                .eval_array(&b, &a)
+                .unwrap_err()
+                .to_string(),
+            "integer overflow or division by zero in the '-' operator."
+        );
+
+        let b = Int8Array::from_vec(vec![-1, -1]);
+        assert_eq!(
+            BinaryOperator::Plus
+                .data_type(DataType::UInt64, DataType::Int8)
+                .unwrap(),
+            DataType::Int64
+        );
+        assert!(BinaryOperator::Plus.eval_array(&a, &b).is_err());
+        let array = BinaryOperator::Plus
+            .eval_array(&*a.slice(1, 1), &*b.slice(1, 1))
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+
+        assert!(crate::expr::UnaryOperator::Neg
+            .data_type(DataType::UInt8)
+            .is_err());
+    }
+
+    #[test]
+    fn test_modulo() {
+        let a = Int64Array::from_vec(vec![7, -7, i64::MIN]);
+        let b = Int8Array::from_vec(vec![3, 3, 2]);
+        let array = BinaryOperator::Modulo.eval_array(&a, &b).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, -1, 0]
+        );
+
+        let zero = Int8Array::from_vec(vec![3, 0, 2]);
+        assert_eq!(
+            BinaryOperator::Modulo
+                .eval_array(&a, &zero)
+                .unwrap_err()
+                .to_string(),
+            "integer overflow or division by zero in the '%' operator."
+        );
+        assert!(BinaryOperator::Divide.eval_array(&a, &zero).is_err());
+        let minus_one = Int64Array::new_scalar(3, Some(-1));
+        assert!(BinaryOperator::Modulo.eval_array(&a, &minus_one).is_err());
+
+        let a = Float64Array::from_vec(vec![7.5]);
+        let b = Int64Array::from_vec(vec![0]);
+        let array = BinaryOperator::Modulo.eval_array(&a, &b).unwrap();
+        assert!(array.downcast_ref::<Float64Array>().value(0).is_nan());
+    }
+}
@@ -1,12 +1,21 @@
 use std::sync::Arc;
 
 use anyhow::Result;
+use chrono::{LocalResult, TimeZone};
+use chrono_tz::Tz;
 
+use crate::array::compute::new_null_array;
+use crate::array::temporal::{
+    date_to_naive, date_to_timestamp, format_date, format_time, parse_date, parse_time,
+    timestamp_to_date, timestamp_to_time,
+};
 use crate::array::{
-    Array, ArrayExt, ArrayRef, BooleanType, DataType, Float32Type, Float64Type, Int16Type,
-    Int32Type, Int64Type, Int8Type, PrimitiveArray, PrimitiveBuilder, PrimitiveType, StringArray,
-    StringBuilder,
+    Array, ArrayExt, ArrayRef, BooleanType, DataType, DateType, Float32Type, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, PrimitiveArray, PrimitiveBuilder, PrimitiveType,
+    StringArray, StringBuilder, TimeType, TimestampBuilder, TimestampType, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
 };
+use crate::expr::literal::parse_timestamp_in;
 
 macro_rules! numeric_array_cast {
     ($array:expr, $from:ty, $to:ty) => {{
@@ -42,10 +51,77 @@ macro_rules! array_cast_to_string {
     }};
 }
 
+/// Casts each value of `array` with `f`, the values it returns `None` for become nulls.
+fn map_cast<From, To, F>(array: &ArrayRef, f: F) -> ArrayRef
+where
+    From: PrimitiveType,
+    To: PrimitiveType,
+    F: Fn(From::Native) -> Option<To::Native>,
+{
+    let array = array.downcast_ref::<PrimitiveArray<From>>();
+    if let Some(scalar) = array.to_scalar() {
+        return Arc::new(PrimitiveArray::<To>::new_scalar(
+            array.len(),
+            scalar.and_then(&f),
+        ));
+    }
+    let mut builder = PrimitiveBuilder::<To>::with_capacity(array.len());
+    for value in array.iter_opt() {
+        builder.append_opt(value.and_then(&f));
+    }
+    Arc::new(builder.finish())
+}
+
+fn format_cast<From, F>(array: &ArrayRef, f: F) -> ArrayRef
+where
+    From: PrimitiveType,
+    F: Fn(From::Native) -> String,
+{
+    let array = array.downcast_ref::<PrimitiveArray<From>>();
+    let mut builder = StringBuilder::with_capacity(array.len());
+    for value in array.iter_opt() {
+        builder.append_opt(value.map(&f).as_deref());
+    }
+    Arc::new(builder.finish())
+}
+
+fn parse_cast<To, F>(array: &ArrayRef, f: F) -> ArrayRef
+where
+    To: PrimitiveType,
+    F: Fn(&str) -> Option<To::Native>,
+{
+    let array = array.downcast_ref::<StringArray>();
+    let mut builder = PrimitiveBuilder::<To>::with_capacity(array.len());
+    for value in array.iter_opt() {
+        builder.append_opt(value.and_then(&f));
+    }
+    Arc::new(builder.finish())
+}
+
+/// Returns the timestamp of the midnight of `date` in `tz`, or in UTC if the timestamps have no
+/// time zone.
+fn date_to_timestamp_in(date: i32, tz: Option<Tz>) -> Option<i64> {
+    match tz {
+        Some(tz) => match tz.from_local_datetime(&date_to_naive(date)?.and_hms(0, 0, 0)) {
+            LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => {
+                Some(datetime.timestamp_millis())
+            }
+            LocalResult::None => None,
+        },
+        None => Some(date_to_timestamp(date)),
+    }
+}
+
+/// Casts `array` to `data_type`.
+///
+/// Timestamps are cast to dates and times of day in UTC, as the arrays don't carry the time zone
+/// of their column. Dates are cast to the midnight of the time zone of the timestamp type.
 pub fn array_cast_to(array: ArrayRef, data_type: DataType) -> Result<ArrayRef> {
     use DataType::*;
 
     match (array.data_type(), data_type) {
+        (Null, _) => Ok(new_null_array(data_type, array.len())),
+
         (Int8, Int8) => Ok(array.clone()),
         (Int8, Int16) => numeric_array_cast!(array, Int8Type, Int16Type),
         (Int8, Int32) => numeric_array_cast!(array, Int8Type, Int32Type),
@@ -53,6 +129,7 @@ pub fn array_cast_to(array: ArrayRef, data_type: DataType) -> Result<ArrayRef> {
         (Int8, Float32) => numeric_array_cast!(array, Int8Type, Float32Type),
         (Int8, Float64) => numeric_array_cast!(array, Int8Type, Float64Type),
         (Int8, String) => array_cast_to_string!(array, Int8Type),
+        (Int8, Timestamp(_)) => numeric_array_cast!(array, Int8Type, TimestampType),
 
         (Int16, Int16) => Ok(array.clone()),
         (Int16, Int32) => numeric_array_cast!(array, Int16Type, Int32Type),
@@ -60,17 +137,57 @@ pub fn array_cast_to(array: ArrayRef, data_type: DataType) -> Result<ArrayRef> {
         (Int16, Float32) => numeric_array_cast!(array, Int16Type, Float32Type),
         (Int16, Float64) => numeric_array_cast!(array, Int16Type, Float64Type),
         (Int16, String) => array_cast_to_string!(array, Int16Type),
+        (Int16, Timestamp(_)) => numeric_array_cast!(array, Int16Type, TimestampType),
 
         (Int32, Int32) => Ok(array.clone()),
         (Int32, Int64) => numeric_array_cast!(array, Int32Type, Int64Type),
         (Int32, Float32) => numeric_array_cast!(array, Int32Type, Float32Type),
         (Int32, Float64) => numeric_array_cast!(array, Int32Type, Float64Type),
         (Int32, String) => array_cast_to_string!(array, Int32Type),
+        (Int32, Timestamp(_)) => numeric_array_cast!(array, Int32Type, TimestampType),
 
         (Int64, Int64) => Ok(array.clone()),
         (Int64, Float32) => numeric_array_cast!(array, Int64Type, Float32Type),
         (Int64, Float64) => numeric_array_cast!(array, Int64Type, Float64Type),
         (Int64, String) => array_cast_to_string!(array, Int64Type),
+        (Int64, Timestamp(_)) => numeric_array_cast!(array, Int64Type, TimestampType),
+
+        (UInt8, UInt8) => Ok(array.clone()),
+        (UInt8, UInt16) => numeric_array_cast!(array, UInt8Type, UInt16Type),
+        (UInt8, UInt32) => numeric_array_cast!(array, UInt8Type, UInt32Type),
+        (UInt8, UInt64) => numeric_array_cast!(array, UInt8Type, UInt64Type),
+        (UInt8, Int16) => numeric_array_cast!(array, UInt8Type, Int16Type),
+        (UInt8, Int32) => numeric_array_cast!(array, UInt8Type, Int32Type),
+        (UInt8, Int64) => numeric_array_cast!(array, UInt8Type, Int64Type),
+        (UInt8, Float32) => numeric_array_cast!(array, UInt8Type, Float32Type),
+        (UInt8, Float64) => numeric_array_cast!(array, UInt8Type, Float64Type),
+        (UInt8, String) => array_cast_to_string!(array, UInt8Type),
+        (UInt8, Timestamp(_)) => numeric_array_cast!(array, UInt8Type, TimestampType),
+
+        (UInt16, UInt16) => Ok(array.clone()),
+        (UInt16, UInt32) => numeric_array_cast!(array, UInt16Type, UInt32Type),
+        (UInt16, UInt64) => numeric_array_cast!(array, UInt16Type, UInt64Type),
+        (UInt16, Int32) => numeric_array_cast!(array, UInt16Type, Int32Type),
+        (UInt16, Int64) => numeric_array_cast!(array, UInt16Type, Int64Type),
+        (UInt16, Float32) => numeric_array_cast!(array, UInt16Type, Float32Type),
+        (UInt16, Float64) => numeric_array_cast!(array, UInt16Type, Float64Type),
+        (UInt16, String) => array_cast_to_string!(array, UInt16Type),
+        (UInt16, Timestamp(_)) => numeric_array_cast!(array, UInt16Type, TimestampType),
+
+        (UInt32, UInt32) => Ok(array.clone()),
+        (UInt32, UInt64) => numeric_array_cast!(array, UInt32Type, UInt64Type),
+        (UInt32, Int64) => numeric_array_cast!(array, UInt32Type, Int64Type),
+        (UInt32, Float32) => numeric_array_cast!(array, UInt32Type, Float32Type),
+        (UInt32, Float64) => numeric_array_cast!(array, UInt32Type, Float64Type),
+        (UInt32, String) => array_cast_to_string!(array, UInt32Type),
+        (UInt32, Timestamp(_)) => numeric_array_cast!(array, UInt32Type, TimestampType),
+
+        (UInt64, UInt64) => Ok(array.clone()),
+        (UInt64, Int64) => numeric_array_cast!(array, UInt64Type, Int64Type),
+        (UInt64, Float32) => numeric_array_cast!(array, UInt64Type, Float32Type),
+        (UInt64, Float64) => numeric_array_cast!(array, UInt64Type, Float64Type),
+        (UInt64, String) => array_cast_to_string!(array, UInt64Type),
+        (UInt64, Timestamp(_)) => numeric_array_cast!(array, UInt64Type, TimestampType),
 
         (Float32, Float32) => Ok(array.clone()),
         (Float32, Float64) => numeric_array_cast!(array, Float32Type, Float64Type),
@@ -83,8 +200,40 @@ pub fn array_cast_to(array: ArrayRef, data_type: DataType) -> Result<ArrayRef> {
         (Boolean, String) => array_cast_to_string!(array, BooleanType),
 
         (Timestamp(_), Timestamp(_)) => Ok(array.clone()),
+        (Timestamp(_), Date) => Ok(map_cast::<TimestampType, DateType, _>(&array, |value| {
+            Some(timestamp_to_date(value))
+        })),
+        (Timestamp(_), Time) => Ok(map_cast::<TimestampType, TimeType, _>(&array, |value| {
+            Some(timestamp_to_time(value))
+        })),
+
+        (Date, Date) => Ok(array.clone()),
+        (Date, Timestamp(tz)) => Ok(map_cast::<DateType, TimestampType, _>(&array, |value| {
+            date_to_timestamp_in(value, tz)
+        })),
+        (Date, String) => Ok(format_cast::<DateType, _>(&array, format_date)),
+
+        (Time, Time) => Ok(array.clone()),
+        (Time, String) => Ok(format_cast::<TimeType, _>(&array, format_time)),
 
         (String, String) => Ok(array.clone()),
+        (String, Timestamp(tz)) => {
+            // Strings that are not valid timestamps become nulls.
+            let array = array.downcast_ref::<StringArray>();
+            let mut builder = TimestampBuilder::with_capacity(array.len());
+            for value in array.iter_opt() {
+                builder.append_opt(value.and_then(|value| parse_timestamp_in(value, tz)));
+            }
+            Ok(Arc::new(builder.finish()))
+        }
+        (String, Date) => Ok(parse_cast::<DateType, _>(&array, parse_date)),
+        (String, Time) => Ok(parse_cast::<TimeType, _>(&array, parse_time)),
+
+        (List(a), List(b)) if a == b => Ok(array.clone()),
+        (Struct(a), Struct(b)) if a == b => Ok(array.clone()),
+        (Map(a_key, a_value), Map(b_key, b_value)) if a_key == b_key && a_value == b_value => {
+            Ok(array.clone())
+        }
 
         _ => anyhow::bail!(
             "cannot cast type from '{}' to '{}'",
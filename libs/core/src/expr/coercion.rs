@@ -0,0 +1,222 @@
+//! Implicit casts of the operands of binary operators.
+//!
+//! The operands of a binary operator are cast to the types below before it is evaluated, the
+//! pairs that are not listed are passed as they are, and are rejected by the operator if it
+//! doesn't support them. The rules are symmetric.
+//!
+//! | operator             | left        | right       | cast                              |
+//! |----------------------|-------------|-------------|-----------------------------------|
+//! | any                  | `Null`      | any `T`     | `Null` to `T`                     |
+//! | comparison           | `String`    | `Timestamp` | `String` to `Timestamp`, parsed   |
+//! | comparison           | integer     | `Timestamp` | integer to `Timestamp`, in millis |
+//! | comparison           | `Date`      | `Timestamp` | `Date` to its midnight            |
+//! | comparison           | `String`    | `Date`      | `String` to `Date`, parsed        |
+//! | comparison           | `String`    | `Time`      | `String` to `Time`, parsed        |
+//! | comparison           | `String`    | `Boolean`   | `Boolean` to `String`             |
+//! | arithmetic           | integer     | `Timestamp` | the operands are swapped for `+`  |
+//!
+//! Numbers of different types are not cast, the operators widen them to `Int64` or `Float64`
+//! while they are evaluated. Arithmetic on unsigned integers is done in `UInt64` when one of them
+//! is a `UInt64`, a `UInt64` value above `i64::MAX` mixed with signed integers fails the
+//! expression, like any integer overflow or division by zero. Comparisons of integers are exact.
+//! A string literal compared with a timestamp is parsed when the
+//! expression is planned, and an invalid timestamp fails the plan, other strings that are not
+//! valid timestamps become nulls. Strings compared with dates and times are parsed the same way.
+
+use crate::array::DataType;
+use crate::expr::BinaryOperator;
+
+/// Returns the types the operands of `op` are cast to, `None` for an operand that is not cast.
+pub(crate) fn coerce_binary(
+    op: BinaryOperator,
+    left: DataType,
+    right: DataType,
+) -> (Option<DataType>, Option<DataType>) {
+    match coerce_operand(op, left, right) {
+        Some(data_type) => (Some(data_type), None),
+        None => (None, coerce_operand(op, right, left)),
+    }
+}
+
+/// Returns the type `operand` is cast to, given the type of the other operand.
+fn coerce_operand(op: BinaryOperator, operand: DataType, other: DataType) -> Option<DataType> {
+    use DataType::*;
+
+    match (operand, other) {
+        (Null, Null) => None,
+        (Null, other) => Some(other),
+        (String, Timestamp(tz)) if op.is_comparison() => Some(Timestamp(tz)),
+        (operand, Timestamp(tz)) if op.is_comparison() && operand.is_integer() => {
+            Some(Timestamp(tz))
+        }
+        (Date, Timestamp(tz)) if op.is_comparison() => Some(Timestamp(tz)),
+        (String, Date) if op.is_comparison() => Some(Date),
+        (String, Time) if op.is_comparison() => Some(Time),
+        (Boolean, String) if op.is_comparison() => Some(String),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the operands of `op` must be swapped to be evaluated, `1000 + ts` is
+/// evaluated as `ts + 1000`.
+pub(crate) fn swap_operands(op: BinaryOperator, left: DataType, right: DataType) -> bool {
+    op == BinaryOperator::Plus && left.is_integer() && right.is_timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{
+        ArrayExt, ArrayRef, BooleanArray, Float32Array, Float64Array, StringArray, TimestampArray,
+    };
+    use crate::dataframe::dsl::{col, value};
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::expr::Literal;
+
+    #[test]
+    fn test_coerce_binary() {
+        use DataType::*;
+
+        assert_eq!(
+            coerce_binary(BinaryOperator::Eq, Null, Int32),
+            (Some(Int32), None)
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::Plus, Float64, Null),
+            (None, Some(Float64))
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::Lt, Timestamp(None), String),
+            (None, Some(Timestamp(None)))
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::GtEq, Int64, Timestamp(None)),
+            (Some(Timestamp(None)), None)
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::Lt, Timestamp(None), Date),
+            (None, Some(Timestamp(None)))
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::Eq, String, Time),
+            (Some(Time), None)
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::Eq, String, Boolean),
+            (None, Some(String))
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::Plus, Int32, Float64),
+            (None, None)
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::Plus, Float32, Float64),
+            (None, None)
+        );
+        assert_eq!(
+            BinaryOperator::Plus.data_type(Float32, Float64).unwrap(),
+            Float64
+        );
+        assert_eq!(
+            BinaryOperator::Multiply
+                .data_type(Float64, Float64)
+                .unwrap(),
+            Float64
+        );
+        assert_eq!(
+            BinaryOperator::Modulo.data_type(Float32, Float32).unwrap(),
+            Float64
+        );
+        assert_eq!(
+            coerce_binary(BinaryOperator::Concat, String, Timestamp(None)),
+            (None, None)
+        );
+
+        assert!(swap_operands(BinaryOperator::Plus, Int64, Timestamp(None)));
+        assert!(!swap_operands(
+            BinaryOperator::Minus,
+            Int64,
+            Timestamp(None)
+        ));
+    }
+
+    #[test]
+    fn test_eval_coerced() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("ts", DataType::Timestamp(None)),
+                Field::new("s", DataType::String),
+                Field::new("f32", DataType::Float32),
+                Field::new("f64", DataType::Float64),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(TimestampArray::from_vec(vec![1000, 2000])) as ArrayRef,
+                Arc::new(StringArray::from_vec(vec![
+                    "1970-01-01T00:00:01Z",
+                    "invalid",
+                ])),
+                Arc::new(Float32Array::from_vec(vec![0.5, 1.5])),
+                Arc::new(Float64Array::from_vec(vec![2.0, 3.0])),
+            ],
+        )
+        .unwrap();
+
+        let mut expr = col("ts")
+            .eq(col("s"))
+            .into_physical(schema.clone())
+            .unwrap();
+        let array = expr.eval(&dataset).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<BooleanArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(true), None]
+        );
+
+        let mut expr = (col("f32") + col("f64"))
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Float64);
+        let array = expr.eval(&dataset).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![2.5, 4.5]
+        );
+
+        let mut expr = (col("f64") * col("f64"))
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Float64);
+        let array = expr.eval(&dataset).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![4.0, 9.0]
+        );
+
+        let mut expr = (value(Literal::Int(500)) + col("ts"))
+            .into_physical(schema)
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Timestamp(None));
+        let array = expr.eval(&dataset).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<TimestampArray>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1500, 2500]
+        );
+    }
+}
@@ -1,14 +1,74 @@
 use std::fmt::{self, Display, Formatter, Write};
 
-use crate::expr::Expr;
+use crate::expr::literal::write_escaped;
+use crate::expr::{Expr, OrderBy};
+
+/// Formats a name, quoting it if it is not a valid identifier.
+pub(crate) struct DisplayName<'a>(pub &'a str);
+
+impl<'a> Display for DisplayName<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut chars = self.0.chars();
+        let is_ident = match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '@' => {
+                chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            }
+            _ => false,
+        };
+        if is_ident {
+            f.write_str(self.0)
+        } else {
+            f.write_char('"')?;
+            write_escaped(f, self.0)?;
+            f.write_char('"')
+        }
+    }
+}
+
+/// Formats the partitioning and ordering of an `OVER` clause, `(partition by .. order by ..)`.
+pub(crate) struct DisplayOver<'a>(pub &'a [Expr], pub &'a [OrderBy]);
+
+impl<'a> Display for DisplayOver<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let DisplayOver(partition_by, order_by) = self;
+        f.write_char('(')?;
+        if !partition_by.is_empty() {
+            f.write_str("partition by ")?;
+            for (idx, expr) in partition_by.iter().enumerate() {
+                if idx > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{}", expr)?;
+            }
+        }
+        if !order_by.is_empty() {
+            if !partition_by.is_empty() {
+                f.write_char(' ')?;
+            }
+            f.write_str("order by ")?;
+            for (idx, OrderBy { expr, descending }) in order_by.iter().enumerate() {
+                if idx > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{}", expr)?;
+                if *descending {
+                    f.write_str(" desc")?;
+                }
+            }
+        }
+        f.write_char(')')
+    }
+}
 
 impl Display for Expr {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Expr::Literal(value) => write!(f, "{}", value),
-            Expr::Column { qualifier, name } => match qualifier {
-                Some(qualifier) => write!(f, "{}.{}", qualifier, name),
-                None => write!(f, "{}", name),
+            Expr::Column {
+                qualifier, name, ..
+            } => match qualifier {
+                Some(qualifier) => write!(f, "{}.{}", DisplayName(qualifier), DisplayName(name)),
+                None => write!(f, "{}", DisplayName(name)),
             },
             Expr::Binary { op, lhs, rhs } => write!(f, "({} {} {})", lhs, op, rhs),
             Expr::Unary { op, expr } => write!(f, "{} {}", op, expr),
@@ -16,6 +76,7 @@ impl Display for Expr {
                 namespace,
                 name,
                 args: arguments,
+                ..
             } => {
                 match namespace {
                     Some(namespace) => write!(f, "{}.{}", namespace, name)?,
@@ -30,10 +91,55 @@ impl Display for Expr {
                 }
                 f.write_char(')')
             }
-            Expr::Wildcard { .. } => unreachable!(),
+            Expr::AggregateFilter { expr, condition } => {
+                write!(f, "{} filter (where {})", expr, condition)
+            }
+            Expr::Over {
+                expr,
+                partition_by,
+                order_by,
+            } => write!(f, "{} over {}", expr, DisplayOver(partition_by, order_by)),
+            Expr::OverWindow { expr, window } => {
+                write!(f, "{} over {}", expr, DisplayName(window))
+            }
+            Expr::Array(elements) => {
+                f.write_char('[')?;
+                for (idx, element) in elements.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                f.write_char(']')
+            }
+            Expr::Index { expr, index } => write!(f, "{}[{}]", expr, index),
+            Expr::Field { expr, name } => match &**expr {
+                // `a.b` would be parsed as the column `b` of the table `a`.
+                Expr::Column { .. } => write!(f, "({}).{}", expr, DisplayName(name)),
+                _ => write!(f, "{}.{}", expr, DisplayName(name)),
+            },
+            Expr::Wildcard { qualifier, exclude } => {
+                match qualifier {
+                    Some(qualifier) => write!(f, "{}.*", DisplayName(qualifier))?,
+                    None => f.write_char('*')?,
+                }
+                if !exclude.is_empty() {
+                    f.write_str(" exclude (")?;
+                    for (idx, name) in exclude.iter().enumerate() {
+                        if idx > 0 {
+                            f.write_str(", ")?;
+                        }
+                        write!(f, "{}", DisplayName(name))?;
+                    }
+                    f.write_char(')')?;
+                }
+                Ok(())
+            }
             Expr::Alias(expr, name) => {
-                write!(f, "{} as {}", expr, name)
+                write!(f, "{} as {}", expr, DisplayName(name))
             }
+            Expr::Placeholder(Some(index)) => write!(f, "${}", index),
+            Expr::Placeholder(None) => f.write_char('?'),
         }
     }
 }
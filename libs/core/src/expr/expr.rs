@@ -1,18 +1,27 @@
-use std::ops::{Add, Div, Mul, Neg, Not, Sub};
+use std::ops::{Add, Div, Mul, Neg, Not, Rem, Sub};
 
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use crate::expr::func::FunctionType;
+use crate::expr::funcs::find_function;
+use crate::expr::registry::{find_udf, UdfType};
 use crate::expr::{BinaryOperator, Literal, UnaryOperator};
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum Expr {
     Literal(Literal),
     Column {
         qualifier: Option<String>,
         name: String,
+        #[serde(skip)]
+        span: Span,
     },
     Wildcard {
         qualifier: Option<String>,
+        /// Columns left out of the wildcard, `* EXCLUDE (a, b)`.
+        exclude: Vec<String>,
     },
     Binary {
         op: BinaryOperator,
@@ -27,8 +36,76 @@ pub enum Expr {
         namespace: Option<String>,
         name: String,
         args: Vec<Expr>,
+        #[serde(skip)]
+        span: Span,
+    },
+    /// An aggregate function call that only aggregates the rows matching `condition`.
+    AggregateFilter {
+        expr: Box<Expr>,
+        condition: Box<Expr>,
+    },
+    /// An analytic function call, evaluated over the ordered rows of a partition.
+    Over {
+        expr: Box<Expr>,
+        partition_by: Vec<Expr>,
+        order_by: Vec<OrderBy>,
+    },
+    /// An analytic function call over a window defined by name in the `WINDOW` clause.
+    OverWindow {
+        expr: Box<Expr>,
+        window: String,
+    },
+    /// An array literal, `[a, b, c]`.
+    Array(Vec<Expr>),
+    /// An element of an array, `expr[index]` with a 1-based index.
+    Index {
+        expr: Box<Expr>,
+        index: Box<Expr>,
+    },
+    /// A field of a struct, `expr.name`.
+    Field {
+        expr: Box<Expr>,
+        name: String,
     },
     Alias(Box<Expr>, String),
+    /// A query parameter, `$n` has an index and `?` does not.
+    Placeholder(Option<usize>),
+}
+
+/// A sort key of an `OVER` clause.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct OrderBy {
+    pub expr: Expr,
+    pub descending: bool,
+}
+
+/// Where an expression starts in the SQL text it was parsed from, kept as the length of the text
+/// from there to the end, which is all the parser sees of the text.
+///
+/// The expressions built in code have no span. Spans are ignored when expressions are compared,
+/// and they are not serialized.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Span(Option<usize>);
+
+impl Span {
+    /// The span of an expression that starts at the start of `remaining`, the input of a parser.
+    pub fn new(remaining: &str) -> Self {
+        Self(Some(remaining.len()))
+    }
+
+    /// Returns the byte offset of the span in `sql`, the text the expression was parsed from.
+    pub fn offset(&self, sql: &str) -> Option<usize> {
+        self.0
+            .filter(|len| *len <= sql.len())
+            .map(|len| sql.len() - len)
+            .filter(|offset| sql.is_char_boundary(*offset))
+    }
+}
+
+impl PartialEq for Span {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
 }
 
 impl Expr {
@@ -36,6 +113,14 @@ impl Expr {
         Expr::Alias(Box::new(self), alias.into())
     }
 
+    pub fn over(self, partition_by: Vec<Expr>, order_by: Vec<OrderBy>) -> Expr {
+        Expr::Over {
+            expr: Box::new(self),
+            partition_by,
+            order_by,
+        }
+    }
+
     pub fn eq(self, rhs: Expr) -> Expr {
         Expr::Binary {
             op: BinaryOperator::Eq,
@@ -100,9 +185,117 @@ impl Expr {
         }
     }
 
+    /// Rewrites the expression tree from the bottom up with `f`.
+    pub fn transform<F>(self, f: &mut F) -> Result<Expr>
+    where
+        F: FnMut(Expr) -> Result<Expr>,
+    {
+        let expr = self.map_children(&mut |expr| expr.transform(f))?;
+        f(expr)
+    }
+
+    /// Rewrites the direct children of the expression with `f`.
+    pub(crate) fn map_children<F>(self, f: &mut F) -> Result<Expr>
+    where
+        F: FnMut(Expr) -> Result<Expr>,
+    {
+        Ok(match self {
+            Expr::Binary { op, lhs, rhs } => Expr::Binary {
+                op,
+                lhs: Box::new(f(*lhs)?),
+                rhs: Box::new(f(*rhs)?),
+            },
+            Expr::Unary { op, expr } => Expr::Unary {
+                op,
+                expr: Box::new(f(*expr)?),
+            },
+            Expr::Call {
+                namespace,
+                name,
+                args,
+                span,
+            } => Expr::Call {
+                namespace,
+                name,
+                args: args.into_iter().map(&mut *f).collect::<Result<_>>()?,
+                span,
+            },
+            Expr::AggregateFilter { expr, condition } => Expr::AggregateFilter {
+                expr: Box::new(f(*expr)?),
+                condition: Box::new(f(*condition)?),
+            },
+            Expr::Over {
+                expr,
+                partition_by,
+                order_by,
+            } => Expr::Over {
+                expr: Box::new(f(*expr)?),
+                partition_by: partition_by
+                    .into_iter()
+                    .map(&mut *f)
+                    .collect::<Result<_>>()?,
+                order_by: order_by
+                    .into_iter()
+                    .map(|OrderBy { expr, descending }| {
+                        Ok(OrderBy {
+                            expr: f(expr)?,
+                            descending,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            },
+            Expr::OverWindow { expr, window } => Expr::OverWindow {
+                expr: Box::new(f(*expr)?),
+                window,
+            },
+            Expr::Array(elements) => {
+                Expr::Array(elements.into_iter().map(&mut *f).collect::<Result<_>>()?)
+            }
+            Expr::Index { expr, index } => Expr::Index {
+                expr: Box::new(f(*expr)?),
+                index: Box::new(f(*index)?),
+            },
+            Expr::Field { expr, name } => Expr::Field {
+                expr: Box::new(f(*expr)?),
+                name,
+            },
+            Expr::Alias(expr, name) => Expr::Alias(Box::new(f(*expr)?), name),
+            expr => expr,
+        })
+    }
+
+    /// Returns `true` if the value of the expression for a row only depends on that row, it
+    /// calls no aggregate or analytic functions.
+    pub(crate) fn is_stateless(&self) -> bool {
+        let mut stateless = true;
+        let _ = self.clone().transform(&mut |expr| {
+            match &expr {
+                Expr::Call {
+                    namespace, name, ..
+                } => {
+                    let stateful = match find_function(namespace.as_deref(), name) {
+                        Some(func) => matches!(func.function_type, FunctionType::Stateful(_)),
+                        None => matches!(
+                            find_udf(name),
+                            Some(udf) if matches!(udf.udf_type, UdfType::Aggregate(_))
+                        ),
+                    };
+                    stateless &= !stateful;
+                }
+                Expr::AggregateFilter { .. } | Expr::Over { .. } | Expr::OverWindow { .. } => {
+                    stateless = false
+                }
+                _ => {}
+            }
+            Ok(expr)
+        });
+        stateless
+    }
+
     pub fn create_name(&self) -> String {
         match self {
             Expr::Column { name, .. } => name.clone(),
+            Expr::Field { name, .. } => name.clone(),
             Expr::Alias(_, name) => name.clone(),
             _ => self.to_string(),
         }
@@ -157,6 +350,18 @@ impl Div for Expr {
     }
 }
 
+impl Rem for Expr {
+    type Output = Expr;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        Expr::Binary {
+            op: BinaryOperator::Modulo,
+            lhs: Box::new(self),
+            rhs: Box::new(rhs),
+        }
+    }
+}
+
 impl Neg for Expr {
     type Output = Expr;
 
@@ -0,0 +1,172 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use crate::array::{ArrayExt, ArrayRef, DataType, NullArray};
+use crate::dataset::{DataSet, Field, Schema};
+use crate::expr::funcs::find_function;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::expr::{Expr, Literal};
+
+impl Expr {
+    /// Replaces the sub-expressions that only depend on literals with their values.
+    ///
+    /// Calls without arguments, such as `now()`, and calls of functions that are not
+    /// deterministic, such as `convert()` or the aggregate functions, and of user-defined
+    /// functions are never folded. A sub-expression is left as it is if it fails to
+    /// evaluate, so the error is still reported when the rows are processed.
+    pub(crate) fn fold_constants(self) -> Expr {
+        self.transform(&mut |expr| Ok(fold(expr)))
+            .expect("folding constants never fails")
+    }
+
+    /// Folds the constants of an expression that produces a column, keeping its name and type.
+    ///
+    /// An expression that folds into a literal of another type, `1000 * 60` is an `Int64` but the
+    /// literal `60000` is an `Int32`, is left as it is.
+    pub(crate) fn fold_constants_in_output(self) -> Expr {
+        let (expr, alias) = match self {
+            Expr::Alias(expr, alias) => (*expr, Some(alias)),
+            expr => (expr, None),
+        };
+        let folded = expr.clone().fold_constants();
+        let folded = match &folded {
+            Expr::Literal(literal) if !matches!(expr, Expr::Literal(_)) => {
+                match constant_data_type(&expr) {
+                    Some(data_type) if data_type == literal.data_type() => folded,
+                    _ => expr.clone(),
+                }
+            }
+            _ => folded,
+        };
+        match alias {
+            Some(alias) => folded.alias(alias),
+            None if folded != expr => folded.alias(expr.create_name()),
+            None => folded,
+        }
+    }
+}
+
+fn fold(expr: Expr) -> Expr {
+    if !is_constant(&expr) {
+        return expr;
+    }
+    match eval_constant(&expr) {
+        Some(literal) => Expr::Literal(literal),
+        None => expr,
+    }
+}
+
+/// Returns `true` if the operands of the expression are literals and it can be evaluated
+/// without any rows.
+fn is_constant(expr: &Expr) -> bool {
+    let is_literal = |expr: &Expr| matches!(expr, Expr::Literal(_));
+
+    match expr {
+        Expr::Binary { lhs, rhs, .. } => is_literal(lhs) && is_literal(rhs),
+        Expr::Unary { expr, .. } => is_literal(expr),
+        Expr::Call {
+            namespace,
+            name,
+            args,
+            ..
+        } => {
+            !args.is_empty()
+                && args.iter().all(is_literal)
+                && matches!(
+                    find_function(namespace.as_deref(), name),
+                    Some(func) if func.deterministic
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Converts a constant expression, it is evaluated with a single row.
+fn constant_to_physical(expr: &Expr) -> Option<PhysicalExpr> {
+    let schema = Arc::new(Schema::try_new(vec![Field::new("", DataType::Null)]).ok()?);
+    expr.clone().into_physical(schema).ok()
+}
+
+fn constant_data_type(expr: &Expr) -> Option<DataType> {
+    Some(constant_to_physical(expr)?.data_type())
+}
+
+fn eval_constant(expr: &Expr) -> Option<Literal> {
+    let mut physical_expr = constant_to_physical(expr)?;
+    let data_type = physical_expr.data_type();
+    let dataset = DataSet::try_new(
+        Arc::new(Schema::try_new(vec![Field::new("", DataType::Null)]).ok()?),
+        vec![Arc::new(NullArray::new(1)) as ArrayRef],
+    )
+    .ok()?;
+    let literal = Literal::try_from(physical_expr.eval(&dataset).ok()?.scalar_value(0)).ok()?;
+    // The literal must have the same type where the expression was, a `Timestamp` literal has no
+    // time zone.
+    let compatible = literal.data_type() == data_type
+        || literal.data_type_in_context(data_type) == Some(data_type);
+    if compatible {
+        Some(literal)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsl::*;
+
+    fn int(n: i64) -> Expr {
+        value(Literal::Int(n))
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        assert_eq!((int(1000) * int(60) * int(5)).fold_constants(), int(300000));
+        assert_eq!(
+            (col("a") + int(1000) * int(60)).fold_constants(),
+            col("a") + int(60000)
+        );
+        assert_eq!(call("abs", vec![-int(3)]).fold_constants(), int(3));
+        assert_eq!(
+            call("sum", vec![int(1) + int(2)]).fold_constants(),
+            call("sum", vec![int(3)])
+        );
+
+        assert_eq!(call("now", vec![]).fold_constants(), call("now", vec![]));
+        // The rates can change while the stream runs.
+        let convert = call(
+            "convert",
+            vec![
+                int(1),
+                value(Literal::String("usd".to_string())),
+                value(Literal::String("eur".to_string())),
+            ],
+        );
+        assert_eq!(convert.clone().fold_constants(), convert);
+        assert_eq!((int(1) / int(0)).fold_constants(), int(1) / int(0));
+        assert_eq!(
+            call("sqrt", vec![value(Literal::String("a".to_string()))]).fold_constants(),
+            call("sqrt", vec![value(Literal::String("a".to_string()))])
+        );
+    }
+
+    #[test]
+    fn test_fold_constants_in_output() {
+        assert_eq!(
+            (col("a") + int(2) * int(3)).fold_constants_in_output(),
+            (col("a") + int(6)).alias("(a + (2 * 3))")
+        );
+        assert_eq!(
+            (int(2) * int(3)).alias("b").fold_constants_in_output(),
+            (int(2) * int(3)).alias("b")
+        );
+        assert_eq!(
+            value(Literal::Boolean(true))
+                .and(value(Literal::Boolean(false)))
+                .fold_constants_in_output(),
+            value(Literal::Boolean(false)).alias("(true and false)")
+        );
+        assert_eq!(col("a").fold_constants_in_output(), col("a"));
+    }
+}
@@ -1,8 +1,10 @@
+use std::any::Any;
+
 use anyhow::Result;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::array::{ArrayRef, DataType};
+use crate::array::{compute, ArrayRef, BooleanBuilder, DataType, Scalar};
 use crate::expr::signature::Signature;
 
 pub trait StatefulFunction: dyn_clone::DynClone + Sync + Send + 'static {
@@ -11,6 +13,33 @@ pub trait StatefulFunction: dyn_clone::DynClone + Sync + Send + 'static {
     fn save_state(&self) -> Result<Vec<u8>>;
 
     fn load_state(&mut self, state: Vec<u8>) -> Result<()>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns an upper bound of the size of the state in bytes, or `None` if the state can
+    /// grow without bound.
+    fn max_state_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns whether the states of two instances of the function can be merged.
+    fn can_merge(&self) -> bool {
+        false
+    }
+
+    /// Merges the state of `other`, an instance of the same function that was called with the
+    /// rows following the rows of this instance.
+    fn merge(&mut self, _other: &dyn StatefulFunction) -> Result<()> {
+        anyhow::bail!("the function does not support merging states")
+    }
+
+    /// Returns the result of the function for the rows it was called with so far, without
+    /// updating its state. `data_type` is the return type of the call.
+    ///
+    /// The functions whose states can be merged return the result of a merged state with it.
+    fn current(&self, _data_type: DataType) -> Result<Scalar> {
+        anyhow::bail!("the function does not support returning its current result")
+    }
 }
 
 dyn_clone::clone_trait_object!(StatefulFunction);
@@ -22,6 +51,9 @@ where
 {
     state: T,
     f: fn(&mut T, &[ArrayRef]) -> Result<ArrayRef>,
+    merge: Option<fn(&mut T, &T)>,
+    current: Option<fn(&T, DataType) -> Scalar>,
+    max_state_size: Option<usize>,
 }
 
 impl<T> AggregateFunction<T>
@@ -32,6 +64,28 @@ where
         Self {
             state: T::default(),
             f,
+            merge: None,
+            current: None,
+            max_state_size: None,
+        }
+    }
+
+    /// Lets the states of the function be merged with `merge`, which combines the state of
+    /// the earlier rows with the state of the following rows, `current` returns the result of
+    /// a state, given the return type of the call.
+    pub fn with_merge(self, merge: fn(&mut T, &T), current: fn(&T, DataType) -> Scalar) -> Self {
+        Self {
+            merge: Some(merge),
+            current: Some(current),
+            ..self
+        }
+    }
+
+    /// Declares that the state of the function never exceeds `size` bytes.
+    pub fn with_max_state_size(self, size: usize) -> Self {
+        Self {
+            max_state_size: Some(size),
+            ..self
         }
     }
 }
@@ -55,6 +109,143 @@ where
         self.state = state;
         Ok(())
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn max_state_size(&self) -> Option<usize> {
+        self.max_state_size
+    }
+
+    fn can_merge(&self) -> bool {
+        self.merge.is_some()
+    }
+
+    fn merge(&mut self, other: &dyn StatefulFunction) -> Result<()> {
+        let other = other
+            .as_any()
+            .downcast_ref::<Self>()
+            .ok_or_else(|| anyhow::anyhow!("cannot merge the states of different functions"))?;
+        match self.merge {
+            Some(merge) => {
+                merge(&mut self.state, &other.state);
+                Ok(())
+            }
+            None => anyhow::bail!("the function does not support merging states"),
+        }
+    }
+
+    fn current(&self, data_type: DataType) -> Result<Scalar> {
+        match self.current {
+            Some(current) => Ok(current(&self.state, data_type)),
+            None => anyhow::bail!("the function does not support returning its current result"),
+        }
+    }
+}
+
+/// The state of a user-defined aggregate function, registered with
+/// [`FunctionRegistry::register_aggregate`](crate::expr::FunctionRegistry::register_aggregate).
+///
+/// An accumulator aggregates the rows of a group in a window, its state is saved in the
+/// checkpoints of the stream.
+pub trait Accumulator: dyn_clone::DynClone + Sync + Send + 'static {
+    /// Resets the state to the state of a group without rows.
+    fn init(&mut self);
+
+    /// Adds the rows of `args` to the state, the rows in which any argument is null are skipped
+    /// like the builtin aggregate functions do.
+    fn update(&mut self, args: &[ArrayRef]) -> Result<()>;
+
+    /// Merges the state of `other`, an accumulator of the same function that was updated with
+    /// the rows following the rows of this accumulator.
+    fn merge(&mut self, other: &dyn Accumulator) -> Result<()>;
+
+    /// Returns the value of the aggregation.
+    fn finish(&self) -> Result<Scalar>;
+
+    fn save_state(&self) -> Result<Vec<u8>>;
+
+    fn load_state(&mut self, state: &[u8]) -> Result<()>;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+dyn_clone::clone_trait_object!(Accumulator);
+
+/// Calls an [`Accumulator`] as a stateful function.
+#[derive(Clone)]
+pub(crate) struct AccumulatorFunction {
+    accumulator: Box<dyn Accumulator>,
+    return_type: DataType,
+}
+
+impl AccumulatorFunction {
+    pub(crate) fn new(accumulator: &dyn Accumulator, return_type: DataType) -> Self {
+        let mut accumulator = dyn_clone::clone_box(accumulator);
+        accumulator.init();
+        Self {
+            accumulator,
+            return_type,
+        }
+    }
+}
+
+impl StatefulFunction for AccumulatorFunction {
+    fn call(&mut self, args: &[ArrayRef]) -> Result<ArrayRef> {
+        let len = args[0].len();
+        let mut flags = BooleanBuilder::with_capacity(len);
+        let mut valid_rows = 0;
+        for row in 0..len {
+            let valid = args.iter().all(|array| array.is_valid(row));
+            flags.append(valid);
+            valid_rows += valid as usize;
+        }
+        if valid_rows == len {
+            self.accumulator.update(args)?;
+        } else if valid_rows > 0 {
+            let flags = flags.finish();
+            let args = args
+                .iter()
+                .map(|array| compute::filter(array.clone(), &flags))
+                .collect::<Vec<_>>();
+            self.accumulator.update(&args)?;
+        }
+
+        let value = self.accumulator.finish()?;
+        Ok(compute::array_from_scalars(
+            self.return_type,
+            vec![value; len],
+        ))
+    }
+
+    fn save_state(&self) -> Result<Vec<u8>> {
+        self.accumulator.save_state()
+    }
+
+    fn load_state(&mut self, state: Vec<u8>) -> Result<()> {
+        self.accumulator.load_state(&state)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn can_merge(&self) -> bool {
+        true
+    }
+
+    fn merge(&mut self, other: &dyn StatefulFunction) -> Result<()> {
+        let other = other
+            .as_any()
+            .downcast_ref::<Self>()
+            .ok_or_else(|| anyhow::anyhow!("cannot merge the states of different functions"))?;
+        self.accumulator.merge(&*other.accumulator)
+    }
+
+    fn current(&self, _data_type: DataType) -> Result<Scalar> {
+        self.accumulator.finish()
+    }
 }
 
 #[derive(Clone)]
@@ -78,5 +269,8 @@ pub struct Function {
     pub name: &'static str,
     pub signature: &'static Signature,
     pub return_type: fn(&[DataType]) -> DataType,
+    /// Whether the function always returns the same result for the same arguments, only the
+    /// calls of deterministic functions are folded into literals when their arguments are.
+    pub deterministic: bool,
     pub function_type: FunctionType,
 }
@@ -1,9 +1,15 @@
+use std::cmp::Ordering;
+use std::mem::size_of;
 use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
+use crate::array::compute::array_from_scalars;
 use crate::array::{
-    Array, ArrayExt, BooleanType, DataType, Float32Type, Float64Array, Float64Builder, Float64Type,
-    Int16Type, Int32Type, Int64Builder, Int64Type, Int8Type, NullArray, PrimitiveArray,
-    PrimitiveBuilder, Scalar, StringArray, StringBuilder, TimestampType,
+    Array, ArrayExt, BooleanArray, BooleanBuilder, BooleanType, DataType, DateType, Float32Type,
+    Float64Array, Float64Builder, Float64Type, Int16Type, Int32Type, Int64Builder, Int64Type,
+    Int8Type, NullArray, PrimitiveArray, PrimitiveBuilder, Scalar, StringArray, StringBuilder,
+    TimeType, TimestampArray, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 use crate::expr::func::{AggregateFunction, Function, FunctionType};
 use crate::expr::signature::Signature;
@@ -13,19 +19,30 @@ pub const AVG: Function = Function {
     name: "avg",
     signature: &Signature::Uniform(1, &[DataType::Float64]),
     return_type: |_| DataType::Float64,
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
-        Box::new(AggregateFunction::<(f64, f64)>::new(|state, args| {
-            let array = args[0].downcast_ref::<Float64Array>();
-            let mut builder = Float64Builder::with_capacity(array.len());
-            for value in array.iter_opt() {
-                if let Some(value) = value {
-                    state.0 += value;
-                    state.1 += 1.0;
+        Box::new(
+            AggregateFunction::<(f64, f64)>::new(|state, args| {
+                let array = args[0].downcast_ref::<Float64Array>();
+                let mut builder = Float64Builder::with_capacity(array.len());
+                for value in array.iter_opt() {
+                    if let Some(value) = value {
+                        state.0 += value;
+                        state.1 += 1.0;
+                    }
+                    builder.append(state.0 / state.1);
                 }
-                builder.append(state.0 / state.1);
-            }
-            Ok(Arc::new(builder.finish()))
-        }))
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(
+                |state, other| {
+                    state.0 += other.0;
+                    state.1 += other.1;
+                },
+                |state, _| Scalar::Float64(state.0 / state.1),
+            )
+            .with_max_state_size(size_of::<(f64, f64)>()),
+        )
     }),
 };
 
@@ -34,18 +51,26 @@ pub const SUM: Function = Function {
     name: "sum",
     signature: &Signature::Uniform(1, &[DataType::Float64]),
     return_type: |_| DataType::Float64,
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
-        Box::new(AggregateFunction::<f64>::new(|state, args| {
-            let array = args[0].downcast_ref::<Float64Array>();
-            let mut builder = Float64Builder::with_capacity(array.len());
-            for value in array.iter_opt() {
-                if let Some(value) = value {
-                    *state += value;
+        Box::new(
+            AggregateFunction::<f64>::new(|state, args| {
+                let array = args[0].downcast_ref::<Float64Array>();
+                let mut builder = Float64Builder::with_capacity(array.len());
+                for value in array.iter_opt() {
+                    if let Some(value) = value {
+                        *state += value;
+                    }
+                    builder.append(*state);
                 }
-                builder.append(*state);
-            }
-            Ok(Arc::new(builder.finish()))
-        }))
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(
+                |state, other| *state += *other,
+                |state, _| Scalar::Float64(*state),
+            )
+            .with_max_state_size(size_of::<f64>()),
+        )
     }),
 };
 
@@ -54,21 +79,234 @@ pub const COUNT: Function = Function {
     name: "count",
     signature: &Signature::Any(1),
     return_type: |_| DataType::Float64,
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
-        Box::new(AggregateFunction::<i64>::new(|state, args| {
-            let array = &args[0];
-            let mut builder = Int64Builder::with_capacity(array.len());
-            for i in 0..args[0].len() {
-                if args[0].is_valid(i) {
-                    *state += 1;
+        Box::new(
+            AggregateFunction::<i64>::new(|state, args| {
+                let array = &args[0];
+                let mut builder = Int64Builder::with_capacity(array.len());
+                for i in 0..args[0].len() {
+                    if args[0].is_valid(i) {
+                        *state += 1;
+                    }
+                    builder.append(*state);
                 }
-                builder.append(*state);
-            }
-            Ok(Arc::new(builder.finish()))
-        }))
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(
+                |state, other| *state += *other,
+                |state, _| Scalar::Float64(*state as f64),
+            )
+            .with_max_state_size(size_of::<i64>()),
+        )
     }),
 };
 
+/// `count_if(condition)`, the number of rows where `condition` is true.
+pub const COUNT_IF: Function = Function {
+    namespace: None,
+    name: "count_if",
+    signature: &Signature::Exact(&[DataType::Boolean]),
+    return_type: |_| DataType::Int64,
+    deterministic: false,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            AggregateFunction::<i64>::new(|state, args| {
+                let array = args[0].downcast_ref::<BooleanArray>();
+                let mut builder = Int64Builder::with_capacity(array.len());
+                for value in array.iter_opt() {
+                    if value == Some(true) {
+                        *state += 1;
+                    }
+                    builder.append(*state);
+                }
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(
+                |state, other| *state += *other,
+                |state, _| Scalar::Int64(*state),
+            )
+            .with_max_state_size(size_of::<i64>()),
+        )
+    }),
+};
+
+macro_rules! make_bool_func {
+    ($id:ident, $name:expr, $doc:literal, $op:tt) => {
+        #[doc = $doc]
+        pub const $id: Function = Function {
+            namespace: None,
+            name: $name,
+            signature: &Signature::Exact(&[DataType::Boolean]),
+            return_type: |_| DataType::Boolean,
+            deterministic: false,
+            function_type: FunctionType::Stateful(|| {
+                Box::new(
+                    AggregateFunction::<Option<bool>>::new(|state, args| {
+                        let array = args[0].downcast_ref::<BooleanArray>();
+                        let mut builder = BooleanBuilder::with_capacity(array.len());
+                        for value in array.iter_opt() {
+                            if let Some(value) = value {
+                                *state = Some(state.map_or(value, |current| current $op value));
+                            }
+                            builder.append_opt(*state);
+                        }
+                        Ok(Arc::new(builder.finish()))
+                    })
+                    .with_merge(
+                        |state, other| {
+                            if let Some(other) = *other {
+                                *state = Some(state.map_or(other, |current| current $op other));
+                            }
+                        },
+                        |state, _| state.map_or(Scalar::Null, Scalar::Boolean),
+                    )
+                    .with_max_state_size(size_of::<Option<bool>>()),
+                )
+            }),
+        };
+    };
+}
+
+make_bool_func!(
+    BOOL_AND,
+    "bool_and",
+    "`bool_and(x)`, true if all the values of `x` that are not null are true.",
+    &&
+);
+make_bool_func!(
+    BOOL_OR,
+    "bool_or",
+    "`bool_or(x)`, true if any value of `x` is true.",
+    ||
+);
+
+/// The central moments of the values seen so far, updated in a single pass with Welford's
+/// algorithm so that they stay accurate when the mean is large compared to the variance.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+struct Moments {
+    count: f64,
+    mean: f64,
+    /// The sum of the squared differences from the mean.
+    m2: f64,
+    /// The sum of the cubed differences from the mean.
+    m3: f64,
+}
+
+impl Moments {
+    fn update(&mut self, value: f64) {
+        let n1 = self.count;
+        self.count += 1.0;
+        let delta = value - self.mean;
+        let delta_n = delta / self.count;
+        let term = delta * delta_n * n1;
+        self.mean += delta_n;
+        self.m3 += term * delta_n * (self.count - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term;
+    }
+
+    fn merge(&mut self, other: &Moments) {
+        if other.count == 0.0 {
+            return;
+        }
+        if self.count == 0.0 {
+            *self = *other;
+            return;
+        }
+
+        let (na, nb) = (self.count, other.count);
+        let n = na + nb;
+        let delta = other.mean - self.mean;
+        let m3 = self.m3
+            + other.m3
+            + delta.powi(3) * na * nb * (na - nb) / (n * n)
+            + 3.0 * delta * (na * other.m2 - nb * self.m2) / n;
+        self.m2 += other.m2 + delta * delta * na * nb / n;
+        self.m3 = m3;
+        self.mean += delta * nb / n;
+        self.count = n;
+    }
+
+    fn var_samp(&self) -> Option<f64> {
+        (self.count >= 2.0).then(|| self.m2 / (self.count - 1.0))
+    }
+
+    fn var_pop(&self) -> Option<f64> {
+        (self.count >= 1.0).then(|| self.m2 / self.count)
+    }
+
+    fn stddev_samp(&self) -> Option<f64> {
+        self.var_samp().map(f64::sqrt)
+    }
+
+    fn stddev_pop(&self) -> Option<f64> {
+        self.var_pop().map(f64::sqrt)
+    }
+
+    fn skewness(&self) -> Option<f64> {
+        (self.count >= 1.0 && self.m2 > 0.0)
+            .then(|| self.count.sqrt() * self.m3 / self.m2.powf(1.5))
+    }
+}
+
+macro_rules! make_moments_func {
+    ($ident:ident, $name:literal, $doc:literal, $f:ident) => {
+        #[doc = $doc]
+        pub const $ident: Function = Function {
+            namespace: None,
+            name: $name,
+            signature: &Signature::Uniform(1, &[DataType::Float64]),
+            return_type: |_| DataType::Float64,
+            deterministic: false,
+            function_type: FunctionType::Stateful(|| {
+                Box::new(
+                    AggregateFunction::<Moments>::new(|state, args| {
+                        let array = args[0].downcast_ref::<Float64Array>();
+                        let mut builder = Float64Builder::with_capacity(array.len());
+                        for value in array.iter_opt() {
+                            if let Some(value) = value {
+                                state.update(value);
+                            }
+                            builder.append_opt(state.$f());
+                        }
+                        Ok(Arc::new(builder.finish()))
+                    })
+                    .with_merge(Moments::merge, |state, _| {
+                        state.$f().map_or(Scalar::Null, Scalar::Float64)
+                    })
+                    .with_max_state_size(size_of::<Moments>()),
+                )
+            }),
+        };
+    };
+}
+
+make_moments_func!(
+    VAR_SAMP,
+    "var_samp",
+    "The sample variance, null for less than two values.",
+    var_samp
+);
+make_moments_func!(VAR_POP, "var_pop", "The population variance.", var_pop);
+make_moments_func!(
+    STDDEV_SAMP,
+    "stddev_samp",
+    "The sample standard deviation, null for less than two values.",
+    stddev_samp
+);
+make_moments_func!(
+    STDDEV_POP,
+    "stddev_pop",
+    "The population standard deviation.",
+    stddev_pop
+);
+make_moments_func!(
+    SKEWNESS,
+    "skewness",
+    "The population skewness, null if all values are equal.",
+    skewness
+);
+
 macro_rules! max_min {
     ($array:expr, $state:expr, $ty:ty, $scalar_ty:ident, $func:ident) => {{
         let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
@@ -97,8 +335,31 @@ macro_rules! max_min {
     }};
 }
 //
+/// Merges the states of `max` and `min`, `other` replaces `state` if it compares as `ordering`
+/// to it.
+fn merge_extreme(state: &mut Scalar, other: &Scalar, ordering: Ordering) {
+    let replace = match (&*state, other) {
+        (_, Scalar::Null) => false,
+        (Scalar::Null, _) => true,
+        (Scalar::Int8(a), Scalar::Int8(b)) => b.cmp(a) == ordering,
+        (Scalar::Int16(a), Scalar::Int16(b)) => b.cmp(a) == ordering,
+        (Scalar::Int32(a), Scalar::Int32(b)) => b.cmp(a) == ordering,
+        (Scalar::Int64(a), Scalar::Int64(b)) => b.cmp(a) == ordering,
+        (Scalar::UInt8(a), Scalar::UInt8(b)) => b.cmp(a) == ordering,
+        (Scalar::UInt16(a), Scalar::UInt16(b)) => b.cmp(a) == ordering,
+        (Scalar::UInt32(a), Scalar::UInt32(b)) => b.cmp(a) == ordering,
+        (Scalar::UInt64(a), Scalar::UInt64(b)) => b.cmp(a) == ordering,
+        (Scalar::Float32(a), Scalar::Float32(b)) => b.partial_cmp(a) == Some(ordering),
+        (Scalar::Float64(a), Scalar::Float64(b)) => b.partial_cmp(a) == Some(ordering),
+        _ => false,
+    };
+    if replace {
+        *state = other.clone();
+    }
+}
+
 macro_rules! make_max_min_func {
-    ($ident:ident, $name:literal, $func:ident) => {
+    ($ident:ident, $name:literal, $func:ident, $ordering:ident) => {
         pub const $ident: Function = Function {
             namespace: None,
             name: $name,
@@ -111,41 +372,66 @@ macro_rules! make_max_min_func {
                     DataType::Int32,
                     DataType::Int16,
                     DataType::Int8,
+                    DataType::UInt64,
+                    DataType::UInt32,
+                    DataType::UInt16,
+                    DataType::UInt8,
                 ],
             ),
             return_type: |args| args[0],
+            deterministic: false,
             function_type: FunctionType::Stateful(|| {
-                Box::new(AggregateFunction::<Scalar>::new(|state, args| {
-                    let array = &args[0];
-                    match array.data_type() {
-                        DataType::Float64 => {
-                            max_min!(array, state, Float64Type, Float64, $func)
-                        }
-                        DataType::Float32 => {
-                            max_min!(array, state, Float32Type, Float32, $func)
-                        }
-                        DataType::Int64 => {
-                            max_min!(array, state, Int64Type, Int64, $func)
-                        }
-                        DataType::Int32 => {
-                            max_min!(array, state, Int32Type, Int32, $func)
-                        }
-                        DataType::Int16 => {
-                            max_min!(array, state, Int16Type, Int16, $func)
-                        }
-                        DataType::Int8 => {
-                            max_min!(array, state, Int8Type, Int8, $func)
+                Box::new(
+                    AggregateFunction::<Scalar>::new(|state, args| {
+                        let array = &args[0];
+                        match array.data_type() {
+                            DataType::Float64 => {
+                                max_min!(array, state, Float64Type, Float64, $func)
+                            }
+                            DataType::Float32 => {
+                                max_min!(array, state, Float32Type, Float32, $func)
+                            }
+                            DataType::Int64 => {
+                                max_min!(array, state, Int64Type, Int64, $func)
+                            }
+                            DataType::UInt8 => {
+                                max_min!(array, state, UInt8Type, UInt8, $func)
+                            }
+                            DataType::UInt16 => {
+                                max_min!(array, state, UInt16Type, UInt16, $func)
+                            }
+                            DataType::UInt32 => {
+                                max_min!(array, state, UInt32Type, UInt32, $func)
+                            }
+                            DataType::UInt64 => {
+                                max_min!(array, state, UInt64Type, UInt64, $func)
+                            }
+                            DataType::Int32 => {
+                                max_min!(array, state, Int32Type, Int32, $func)
+                            }
+                            DataType::Int16 => {
+                                max_min!(array, state, Int16Type, Int16, $func)
+                            }
+                            DataType::Int8 => {
+                                max_min!(array, state, Int8Type, Int8, $func)
+                            }
+                            _ => unreachable!(),
                         }
-                        _ => unreachable!(),
-                    }
-                }))
+                    })
+                    .with_merge(
+                        |state, other| merge_extreme(state, other, Ordering::$ordering),
+                        |state, _| state.clone(),
+                    )
+                    // Only numbers, which are stored inline.
+                    .with_max_state_size(size_of::<Scalar>()),
+                )
             }),
         };
     };
 }
 
-make_max_min_func!(MAX, "max", max);
-make_max_min_func!(MIN, "min", min);
+make_max_min_func!(MAX, "max", max, Greater);
+make_max_min_func!(MIN, "min", min, Less);
 
 macro_rules! first_value {
     ($array:expr, $state:expr, $ty:ty, $scalar_ty:ident) => {{
@@ -180,46 +466,73 @@ pub const FIRST: Function = Function {
     name: "first",
     signature: &Signature::Any(1),
     return_type: |args| args[0],
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
-        Box::new(AggregateFunction::<Scalar>::new(|state, args| {
-            let array = &args[0];
-            match array.data_type() {
-                DataType::Null => Ok(Arc::new(NullArray::new(array.len()))),
-                DataType::Int8 => first_value!(array, state, Int8Type, Int8),
-                DataType::Int16 => first_value!(array, state, Int16Type, Int16),
-                DataType::Int32 => first_value!(array, state, Int32Type, Int32),
-                DataType::Int64 => first_value!(array, state, Int64Type, Int64),
-                DataType::Float32 => first_value!(array, state, Float32Type, Float32),
-                DataType::Float64 => first_value!(array, state, Float64Type, Float64),
-                DataType::Boolean => first_value!(array, state, BooleanType, Boolean),
-                DataType::Timestamp(_) => first_value!(array, state, TimestampType, Timestamp),
-                DataType::String => {
-                    let array = array.downcast_ref::<StringArray>();
-                    let mut builder = StringBuilder::with_capacity(array.len());
-                    for value in array.iter_opt() {
-                        match value {
-                            Some(value) => match state {
-                                Scalar::String(first_value) => {
-                                    builder.append(first_value);
-                                }
-                                _ => {
-                                    *state = Scalar::String(value.into());
-                                    builder.append(value);
-                                }
-                            },
-                            None => {
-                                if let Scalar::String(first_value) = state {
-                                    builder.append(first_value);
-                                } else {
-                                    builder.append_null();
+        Box::new(
+            AggregateFunction::<Scalar>::new(|state, args| {
+                let array = &args[0];
+                match array.data_type() {
+                    DataType::Null => Ok(Arc::new(NullArray::new(array.len()))),
+                    DataType::Int8 => first_value!(array, state, Int8Type, Int8),
+                    DataType::Int16 => first_value!(array, state, Int16Type, Int16),
+                    DataType::Int32 => first_value!(array, state, Int32Type, Int32),
+                    DataType::Int64 => first_value!(array, state, Int64Type, Int64),
+                    DataType::UInt8 => first_value!(array, state, UInt8Type, UInt8),
+                    DataType::UInt16 => first_value!(array, state, UInt16Type, UInt16),
+                    DataType::UInt32 => first_value!(array, state, UInt32Type, UInt32),
+                    DataType::UInt64 => first_value!(array, state, UInt64Type, UInt64),
+                    DataType::Float32 => first_value!(array, state, Float32Type, Float32),
+                    DataType::Float64 => first_value!(array, state, Float64Type, Float64),
+                    DataType::Boolean => first_value!(array, state, BooleanType, Boolean),
+                    DataType::Timestamp(_) => first_value!(array, state, TimestampType, Timestamp),
+                    DataType::Date => first_value!(array, state, DateType, Date),
+                    DataType::Time => first_value!(array, state, TimeType, Time),
+                    DataType::String => {
+                        let array = array.downcast_ref::<StringArray>();
+                        let mut builder = StringBuilder::with_capacity(array.len());
+                        for value in array.iter_opt() {
+                            match value {
+                                Some(value) => match state {
+                                    Scalar::String(first_value) => {
+                                        builder.append(first_value);
+                                    }
+                                    _ => {
+                                        *state = Scalar::String(value.into());
+                                        builder.append(value);
+                                    }
+                                },
+                                None => {
+                                    if let Scalar::String(first_value) = state {
+                                        builder.append(first_value);
+                                    } else {
+                                        builder.append_null();
+                                    }
                                 }
                             }
                         }
+                        Ok(Arc::new(builder.finish()))
+                    }
+                    _ => {
+                        let mut values = Vec::with_capacity(array.len());
+                        for row in 0..array.len() {
+                            if state.is_null() {
+                                *state = array.scalar_value(row);
+                            }
+                            values.push(state.clone());
+                        }
+                        Ok(array_from_scalars(array.data_type(), values))
                     }
-                    Ok(Arc::new(builder.finish()))
                 }
-            }
-        }))
+            })
+            .with_merge(
+                |state, other| {
+                    if state.is_null() {
+                        *state = other.clone();
+                    }
+                },
+                |state, _| state.clone(),
+            ),
+        )
     }),
 };
 
@@ -251,47 +564,150 @@ pub const LAST: Function = Function {
     name: "last",
     signature: &Signature::Any(1),
     return_type: |args| args[0],
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
-        Box::new(AggregateFunction::<Scalar>::new(|state, args| {
-            let array = &args[0];
-            match array.data_type() {
-                DataType::Null => Ok(Arc::new(NullArray::new(array.len()))),
-                DataType::Int8 => last_value!(array, state, Int8Type, Int8),
-                DataType::Int16 => last_value!(array, state, Int16Type, Int16),
-                DataType::Int32 => last_value!(array, state, Int32Type, Int32),
-                DataType::Int64 => last_value!(array, state, Int64Type, Int64),
-                DataType::Float32 => last_value!(array, state, Float32Type, Float32),
-                DataType::Float64 => last_value!(array, state, Float64Type, Float64),
-                DataType::Boolean => last_value!(array, state, BooleanType, Boolean),
-                DataType::Timestamp(_) => last_value!(array, state, TimestampType, Timestamp),
-                DataType::String => {
-                    let array = array.downcast_ref::<StringArray>();
-                    let mut builder = StringBuilder::with_capacity(array.len());
-                    for value in array.iter_opt() {
-                        match value {
-                            Some(value) => {
-                                *state = Scalar::String(value.into());
-                                builder.append(value);
-                            }
-                            None => {
-                                if let Scalar::String(last_value) = state {
-                                    builder.append(last_value);
-                                } else {
-                                    builder.append_null();
+        Box::new(
+            AggregateFunction::<Scalar>::new(|state, args| {
+                let array = &args[0];
+                match array.data_type() {
+                    DataType::Null => Ok(Arc::new(NullArray::new(array.len()))),
+                    DataType::Int8 => last_value!(array, state, Int8Type, Int8),
+                    DataType::Int16 => last_value!(array, state, Int16Type, Int16),
+                    DataType::Int32 => last_value!(array, state, Int32Type, Int32),
+                    DataType::Int64 => last_value!(array, state, Int64Type, Int64),
+                    DataType::UInt8 => last_value!(array, state, UInt8Type, UInt8),
+                    DataType::UInt16 => last_value!(array, state, UInt16Type, UInt16),
+                    DataType::UInt32 => last_value!(array, state, UInt32Type, UInt32),
+                    DataType::UInt64 => last_value!(array, state, UInt64Type, UInt64),
+                    DataType::Float32 => last_value!(array, state, Float32Type, Float32),
+                    DataType::Float64 => last_value!(array, state, Float64Type, Float64),
+                    DataType::Boolean => last_value!(array, state, BooleanType, Boolean),
+                    DataType::Timestamp(_) => last_value!(array, state, TimestampType, Timestamp),
+                    DataType::Date => last_value!(array, state, DateType, Date),
+                    DataType::Time => last_value!(array, state, TimeType, Time),
+                    DataType::String => {
+                        let array = array.downcast_ref::<StringArray>();
+                        let mut builder = StringBuilder::with_capacity(array.len());
+                        for value in array.iter_opt() {
+                            match value {
+                                Some(value) => {
+                                    *state = Scalar::String(value.into());
+                                    builder.append(value);
                                 }
+                                None => {
+                                    if let Scalar::String(last_value) = state {
+                                        builder.append(last_value);
+                                    } else {
+                                        builder.append_null();
+                                    }
+                                }
+                            }
+                        }
+                        Ok(Arc::new(builder.finish()))
+                    }
+                    _ => {
+                        let mut values = Vec::with_capacity(array.len());
+                        for row in 0..array.len() {
+                            let value = array.scalar_value(row);
+                            if !value.is_null() {
+                                *state = value;
                             }
+                            values.push(state.clone());
                         }
+                        Ok(array_from_scalars(array.data_type(), values))
                     }
-                    Ok(Arc::new(builder.finish()))
                 }
-            }
-        }))
+            })
+            .with_merge(
+                |state, other| {
+                    if !other.is_null() {
+                        *state = other.clone();
+                    }
+                },
+                |state, _| state.clone(),
+            ),
+        )
     }),
 };
 
+/// The value of a row and its time, the state of `first_value` and `last_value`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TimedValue {
+    time: Option<i64>,
+    value: Scalar,
+}
+
+impl TimedValue {
+    /// Keeps `value` if `replace` returns true for its time and the time of the current value.
+    fn update(&mut self, time: i64, value: Scalar, replace: fn(i64, i64) -> bool) {
+        match self.time {
+            Some(current) if !replace(time, current) => {}
+            _ => {
+                self.time = Some(time);
+                self.value = value;
+            }
+        }
+    }
+}
+
+macro_rules! make_timed_value_func {
+    ($id:ident, $name:expr, $doc:literal, $replace:expr) => {
+        #[doc = $doc]
+        pub const $id: Function = Function {
+            namespace: None,
+            name: $name,
+            signature: &Signature::AnyThen(&[DataType::Timestamp(None)]),
+            return_type: |args| args[0],
+            deterministic: false,
+            function_type: FunctionType::Stateful(|| {
+                Box::new(
+                    AggregateFunction::<TimedValue>::new(|state, args| {
+                        let array = &args[0];
+                        let times = args[1].downcast_ref::<TimestampArray>();
+                        let mut values = Vec::with_capacity(array.len());
+                        for (row, time) in times.iter_opt().enumerate() {
+                            let value = array.scalar_value(row);
+                            if let (Some(time), false) = (time, value.is_null()) {
+                                state.update(time, value, $replace);
+                            }
+                            values.push(state.value.clone());
+                        }
+                        Ok(array_from_scalars(array.data_type(), values))
+                    })
+                    .with_merge(
+                        |state, other| {
+                            if let Some(time) = other.time {
+                                state.update(time, other.value.clone(), $replace);
+                            }
+                        },
+                        |state, _| state.value.clone(),
+                    ),
+                )
+            }),
+        };
+    };
+}
+
+make_timed_value_func!(
+    FIRST_VALUE,
+    "first_value",
+    "`first_value(x, time)`, the value of `x` with the earliest time, the first one to arrive \
+     on ties.",
+    |time, current| time < current
+);
+make_timed_value_func!(
+    LAST_VALUE,
+    "last_value",
+    "`last_value(x, time)`, the value of `x` with the latest time, the last one to arrive on \
+     ties.",
+    |time, current| time >= current
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::array::{ArrayRef, Int64Array};
+    use crate::expr::func::StatefulFunction;
 
     fn test_unary_func(func: &Function, first: (Vec<f64>, Vec<f64>), second: (Vec<f64>, Vec<f64>)) {
         let mut f = func.function_type.create_stateful_fun();
@@ -375,4 +791,263 @@ mod tests {
             (vec![7.0, 3.0, 35.0], vec![7.0, 3.0, 35.0]),
         );
     }
+
+    fn call_timed(
+        f: &mut dyn StatefulFunction,
+        values: Vec<Option<f64>>,
+        times: Vec<Option<i64>>,
+    ) -> Vec<Option<f64>> {
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_opt_vec(values)) as ArrayRef,
+                Arc::new(TimestampArray::from_opt_vec(times)),
+            ])
+            .unwrap();
+        array.downcast_ref::<Float64Array>().iter_opt().collect()
+    }
+
+    #[test]
+    fn test_first_value_last_value() {
+        let values = vec![Some(10.0), Some(5.0), None, Some(30.0), Some(8.0)];
+        let times = vec![Some(2000), Some(1000), Some(500), None, Some(3000)];
+
+        let mut f = FIRST_VALUE.function_type.create_stateful_fun();
+        assert_eq!(
+            call_timed(f.as_mut(), values.clone(), times.clone()),
+            vec![Some(10.0), Some(5.0), Some(5.0), Some(5.0), Some(5.0)]
+        );
+        let state = f.save_state().unwrap();
+        let mut f = FIRST_VALUE.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        assert_eq!(
+            call_timed(
+                f.as_mut(),
+                vec![Some(1.0), Some(2.0)],
+                vec![Some(1000), Some(900)]
+            ),
+            vec![Some(5.0), Some(2.0)]
+        );
+
+        let mut f = LAST_VALUE.function_type.create_stateful_fun();
+        assert_eq!(
+            call_timed(f.as_mut(), values, times),
+            vec![Some(10.0), Some(10.0), Some(10.0), Some(10.0), Some(8.0)]
+        );
+        assert_eq!(
+            call_timed(
+                f.as_mut(),
+                vec![Some(1.0), Some(2.0)],
+                vec![Some(2500), Some(3000)]
+            ),
+            vec![Some(8.0), Some(2.0)]
+        );
+
+        let mut other = LAST_VALUE.function_type.create_stateful_fun();
+        call_timed(other.as_mut(), vec![Some(42.0)], vec![Some(4000)]);
+        f.merge(other.as_ref()).unwrap();
+        assert_eq!(
+            call_timed(f.as_mut(), vec![None], vec![None]),
+            vec![Some(42.0)]
+        );
+
+        let mut f = FIRST_VALUE.function_type.create_stateful_fun();
+        assert!(f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![1.0])) as ArrayRef,
+                Arc::new(Float64Array::from_vec(vec![1.0])),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_count_if() {
+        let mut f = COUNT_IF.function_type.create_stateful_fun();
+        let array = f
+            .call(&[Arc::new(BooleanArray::from_opt_vec(vec![
+                Some(true),
+                None,
+                Some(false),
+                Some(true),
+            ]))])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, 1, 1, 2]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = COUNT_IF.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        let array = f
+            .call(&[Arc::new(BooleanArray::from_vec(vec![true]))])
+            .unwrap();
+        assert_eq!(array.downcast_ref::<Int64Array>().value(0), 3);
+    }
+
+    #[test]
+    fn test_bool_and_or() {
+        for (func, expected) in &[
+            (&BOOL_AND, vec![None, Some(true), Some(false), Some(false)]),
+            (&BOOL_OR, vec![None, Some(true), Some(true), Some(true)]),
+        ] {
+            let mut f = func.function_type.create_stateful_fun();
+            let array = f
+                .call(&[Arc::new(BooleanArray::from_opt_vec(vec![
+                    None,
+                    Some(true),
+                    Some(false),
+                    None,
+                ]))])
+                .unwrap();
+            assert_eq!(
+                &array
+                    .downcast_ref::<BooleanArray>()
+                    .iter_opt()
+                    .collect::<Vec<_>>(),
+                expected,
+                "{}",
+                func.name
+            );
+        }
+
+        let mut f = BOOL_OR.function_type.create_stateful_fun();
+        f.call(&[Arc::new(BooleanArray::from_vec(vec![false]))])
+            .unwrap();
+        let mut other = BOOL_OR.function_type.create_stateful_fun();
+        other
+            .call(&[Arc::new(BooleanArray::from_vec(vec![true]))])
+            .unwrap();
+        f.merge(other.as_ref()).unwrap();
+        let state = f.save_state().unwrap();
+        let mut f = BOOL_OR.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        let array = f
+            .call(&[Arc::new(BooleanArray::from_opt_vec(vec![None]))])
+            .unwrap();
+        assert_eq!(
+            array.downcast_ref::<BooleanArray>().value_opt(0),
+            Some(true)
+        );
+    }
+
+    fn assert_close(actual: Option<f64>, expected: Option<f64>) {
+        match (actual, expected) {
+            (Some(actual), Some(expected)) => assert!(
+                (actual - expected).abs() < 1e-9,
+                "{} != {}",
+                actual,
+                expected
+            ),
+            _ => assert_eq!(actual, expected),
+        }
+    }
+
+    fn moments(values: &[f64]) -> Moments {
+        let mut moments = Moments::default();
+        for value in values {
+            moments.update(*value);
+        }
+        moments
+    }
+
+    #[test]
+    fn test_moments() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let all = moments(&values);
+        assert_close(all.var_pop(), Some(4.0));
+        assert_close(all.stddev_pop(), Some(2.0));
+        assert_close(all.var_samp(), Some(32.0 / 7.0));
+        assert_close(all.skewness(), Some(0.65625));
+
+        let mut merged = moments(&values[..3]);
+        merged.merge(&moments(&values[3..]));
+        assert_close(merged.var_pop(), all.var_pop());
+        assert_close(merged.skewness(), all.skewness());
+
+        let mut merged = Moments::default();
+        merged.merge(&all);
+        assert_close(merged.var_samp(), all.var_samp());
+
+        assert_close(moments(&[1.0]).var_samp(), None);
+        assert_close(moments(&[1.0]).var_pop(), Some(0.0));
+        assert_close(moments(&[3.0, 3.0]).skewness(), None);
+
+        // The differences from the mean are small compared to the values.
+        let large = moments(&[1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0]);
+        assert_close(large.var_samp(), Some(30.0));
+    }
+
+    #[test]
+    fn test_stddev_state() {
+        let expected = [
+            0.0,
+            1.0,
+            0.9428090415820634,
+            0.8660254037844386,
+            0.9797958971132712,
+            1.0,
+            1.3997084244475304,
+            2.0,
+        ];
+
+        let mut f = STDDEV_POP.function_type.create_stateful_fun();
+        let array = f
+            .call(&[Arc::new(Float64Array::from_vec(vec![2.0, 4.0, 4.0, 4.0]))])
+            .unwrap();
+        let state = f.save_state().unwrap();
+        let mut f = STDDEV_POP.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        let next = f
+            .call(&[Arc::new(Float64Array::from_vec(vec![5.0, 5.0, 7.0, 9.0]))])
+            .unwrap();
+
+        let values = array
+            .downcast_ref::<Float64Array>()
+            .iter()
+            .chain(next.downcast_ref::<Float64Array>().iter())
+            .collect::<Vec<_>>();
+        assert_eq!(values.len(), expected.len());
+        for (value, expected) in values.into_iter().zip(expected.iter()) {
+            assert_close(Some(value), Some(*expected));
+        }
+
+        let mut f = VAR_SAMP.function_type.create_stateful_fun();
+        let array = f
+            .call(&[Arc::new(Float64Array::from_opt_vec(vec![Some(1.0), None]))])
+            .unwrap();
+        assert!(array.is_null(0));
+        assert!(array.is_null(1));
+    }
+
+    #[test]
+    fn test_merge() {
+        for (func, expected) in &[
+            (&AVG, 15.0),
+            (&SUM, 90.0),
+            (&MAX, 35.0),
+            (&MIN, 3.0),
+            (&FIRST, 10.0),
+            (&LAST, 35.0),
+        ] {
+            let mut f = func.function_type.create_stateful_fun();
+            f.call(&[Arc::new(Float64Array::from_vec(vec![10.0, 5.0, 30.0]))])
+                .unwrap();
+            let mut other = func.function_type.create_stateful_fun();
+            other
+                .call(&[Arc::new(Float64Array::from_vec(vec![7.0, 3.0, 35.0]))])
+                .unwrap();
+            assert!(f.can_merge());
+            f.merge(other.as_ref()).unwrap();
+
+            assert_eq!(
+                f.current(DataType::Float64).unwrap(),
+                Scalar::Float64(*expected),
+                "{}",
+                func.name
+            );
+        }
+    }
 }
@@ -0,0 +1,245 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem::size_of;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::array::{
+    Array, ArrayExt, DataType, Float64Array, Float64Builder, Int64Array, StringArray,
+};
+use crate::expr::func::{AggregateFunction, Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// Number of previous values used when no window size is specified.
+const DEFAULT_WINDOW_SIZE: usize = 100;
+
+/// The largest window size.
+const MAX_WINDOW_SIZE: usize = 10_000;
+
+/// The largest number of keys, the least recently seen key is forgotten to make room for a new
+/// one.
+const MAX_KEYS: usize = 1024;
+
+/// The last values of a key, with their mean and the sum of their squared differences from the
+/// mean, updated with Welford's algorithm as values enter and leave the window.
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct Rolling {
+    values: VecDeque<f64>,
+    mean: f64,
+    m2: f64,
+    /// When the key was last seen, to forget the least recently seen key.
+    last_seen: u64,
+}
+
+impl Rolling {
+    /// Returns the z-score of `value` against the current values.
+    fn score(&self, value: f64) -> Option<f64> {
+        if self.values.len() < 2 {
+            return None;
+        }
+        let stddev = (self.m2 / self.values.len() as f64).max(0.0).sqrt();
+        if stddev == 0.0 {
+            return None;
+        }
+        Some((value - self.mean) / stddev)
+    }
+
+    fn push(&mut self, value: f64, limit: usize) {
+        while self.values.len() >= limit {
+            if let Some(x) = self.values.pop_front() {
+                match self.values.len() {
+                    0 => {
+                        self.mean = 0.0;
+                        self.m2 = 0.0;
+                    }
+                    n => {
+                        let delta = x - self.mean;
+                        self.mean -= delta / n as f64;
+                        self.m2 = (self.m2 - delta * (x - self.mean)).max(0.0);
+                    }
+                }
+            }
+        }
+        self.values.push_back(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.values.len() as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+}
+
+/// The state of `anomaly_score`, the last values of each key.
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct AnomalyState {
+    keys: HashMap<String, Rolling>,
+    /// Counts the rows, the time at which the keys are seen.
+    rows: u64,
+}
+
+impl AnomalyState {
+    fn rolling(&mut self, key: &str) -> &mut Rolling {
+        self.rows += 1;
+        if !self.keys.contains_key(key) && self.keys.len() >= MAX_KEYS {
+            let oldest = self
+                .keys
+                .iter()
+                .min_by_key(|(_, rolling)| rolling.last_seen)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.keys.remove(&oldest);
+            }
+        }
+        let rolling = self.keys.entry(key.to_string()).or_default();
+        rolling.last_seen = self.rows;
+        rolling
+    }
+}
+
+/// `anomaly_score(x[, n[, key]])`
+///
+/// Returns how many standard deviations `x` is away from the mean of the previous `n` values
+/// of the same key, or null if there are fewer than two previous values or they are all equal.
+/// Rows can be flagged with a filter like `abs(anomaly_score(x)) > 3`.
+pub const ANOMALY_SCORE: Function = Function {
+    namespace: None,
+    name: "anomaly_score",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::Float64]),
+        Signature::Exact(&[DataType::Float64, DataType::Int64]),
+        Signature::Exact(&[DataType::Float64, DataType::Int64, DataType::String]),
+    ]),
+    return_type: |_| DataType::Float64,
+    deterministic: false,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            AggregateFunction::<AnomalyState>::new(|state, args| {
+                let array = args[0].downcast_ref::<Float64Array>();
+                let window_sizes = args.get(1).map(|array| array.downcast_ref::<Int64Array>());
+                let keys = args.get(2).map(|array| array.downcast_ref::<StringArray>());
+                let mut builder = Float64Builder::with_capacity(array.len());
+
+                for (idx, value) in array.iter_opt().enumerate() {
+                    let value = match value {
+                        Some(value) => value,
+                        None => {
+                            builder.append_null();
+                            continue;
+                        }
+                    };
+                    let limit = match window_sizes.map(|sizes| sizes.value_opt(idx)) {
+                        Some(Some(n)) if n > 1 && n as usize <= MAX_WINDOW_SIZE => n as usize,
+                        Some(_) => anyhow::bail!(
+                            "the window size must be between 2 and {}",
+                            MAX_WINDOW_SIZE
+                        ),
+                        None => DEFAULT_WINDOW_SIZE,
+                    };
+                    let key = keys
+                        .and_then(|keys| keys.value_opt(idx))
+                        .unwrap_or_default();
+
+                    let rolling = state.rolling(key);
+                    builder.append_opt(rolling.score(value));
+                    rolling.push(value, limit);
+                }
+
+                Ok(Arc::new(builder.finish()))
+            })
+            // The keys themselves are not counted, like the group keys of the aggregations.
+            .with_max_state_size(
+                MAX_KEYS * (MAX_WINDOW_SIZE * size_of::<f64>() + size_of::<Rolling>()),
+            ),
+        )
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayRef;
+
+    fn scores(array: ArrayRef) -> Vec<Option<f64>> {
+        array.downcast_ref::<Float64Array>().iter_opt().collect()
+    }
+
+    #[test]
+    fn test_anomaly_score() {
+        let mut f = ANOMALY_SCORE.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![1.0, 3.0, 1.0])),
+                Arc::new(Int64Array::new_scalar(3, Some(2))),
+            ])
+            .unwrap();
+        assert_eq!(scores(array), vec![None, None, Some(-1.0)]);
+
+        let state = f.save_state().unwrap();
+        let mut f = ANOMALY_SCORE.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![3.0, 11.0])),
+                Arc::new(Int64Array::new_scalar(2, Some(2))),
+            ])
+            .unwrap();
+        assert_eq!(scores(array), vec![Some(1.0), Some(9.0)]);
+    }
+
+    #[test]
+    fn test_anomaly_score_keyed() {
+        let mut f = ANOMALY_SCORE.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![
+                    1.0, 100.0, 3.0, 300.0, 11.0, 300.0,
+                ])),
+                Arc::new(Int64Array::new_scalar(6, Some(2))),
+                Arc::new(StringArray::from_vec(vec!["a", "b", "a", "b", "a", "b"])),
+            ])
+            .unwrap();
+        assert_eq!(
+            scores(array),
+            vec![None, None, None, None, Some(9.0), Some(1.0)]
+        );
+    }
+
+    #[test]
+    fn test_anomaly_score_large_values() {
+        // Values far from zero keep their variance, it isn't lost to cancellation or drift.
+        let values = (0..10_000)
+            .map(|idx| 1e9 + if idx % 2 == 0 { 1.0 } else { 3.0 })
+            .collect::<Vec<_>>();
+        let mut f = ANOMALY_SCORE.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(values)),
+                Arc::new(Int64Array::new_scalar(10_000, Some(2))),
+            ])
+            .unwrap();
+        for (idx, score) in scores(array).into_iter().enumerate().skip(2) {
+            let expected = if idx % 2 == 0 { -1.0 } else { 1.0 };
+            assert!((score.unwrap() - expected).abs() < 1e-6, "{}", idx);
+        }
+
+        assert!(f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![1.0])),
+                Arc::new(Int64Array::new_scalar(1, Some(MAX_WINDOW_SIZE as i64 + 1))),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_anomaly_score_max_keys() {
+        let mut state = AnomalyState::default();
+        for key in 0..=MAX_KEYS {
+            state.rolling(&key.to_string()).push(1.0, 2);
+        }
+        state.rolling("1");
+        state.rolling("new");
+        assert_eq!(state.keys.len(), MAX_KEYS);
+        assert!(!state.keys.contains_key("0"));
+        assert!(state.keys.contains_key("1"));
+        assert!(!state.keys.contains_key("2"));
+    }
+}
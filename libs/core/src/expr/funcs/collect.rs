@@ -0,0 +1,340 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::array::compute::array_from_scalars;
+use crate::array::{Array, ArrayExt, DataType, Int64Array, Scalar, StringArray, StringBuilder};
+use crate::expr::func::{AggregateFunction, Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// The state of `string_agg`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StringAgg {
+    value: Option<String>,
+    /// The last delimiter, used to merge the states.
+    delimiter: String,
+}
+
+impl StringAgg {
+    fn push(&mut self, value: &str, delimiter: &str) {
+        match &mut self.value {
+            Some(current) => {
+                current.push_str(delimiter);
+                current.push_str(value);
+            }
+            None => self.value = Some(value.to_string()),
+        }
+    }
+
+    fn merge(&mut self, other: &StringAgg) {
+        if let Some(value) = &other.value {
+            let delimiter = std::mem::take(&mut self.delimiter);
+            self.push(value, &delimiter);
+            self.delimiter = delimiter;
+        }
+    }
+}
+
+/// `string_agg(x, delimiter)`, the values of `x` that are not null, separated by `delimiter`.
+pub const STRING_AGG: Function = Function {
+    namespace: None,
+    name: "string_agg",
+    signature: &Signature::Exact(&[DataType::String, DataType::String]),
+    return_type: |_| DataType::String,
+    deterministic: false,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            AggregateFunction::<StringAgg>::new(|state, args| {
+                let array = args[0].downcast_ref::<StringArray>();
+                let delimiters = args[1].downcast_ref::<StringArray>();
+                let mut builder = StringBuilder::with_capacity(array.len());
+                for (value, delimiter) in array.iter_opt().zip(delimiters.iter_opt()) {
+                    let delimiter = delimiter.unwrap_or_default();
+                    if let Some(value) = value {
+                        state.push(value, delimiter);
+                    }
+                    if state.delimiter != delimiter {
+                        state.delimiter = delimiter.to_string();
+                    }
+                    builder.append_opt(state.value.as_deref());
+                }
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(StringAgg::merge, |state, _| {
+                state.value.as_deref().map_or(Scalar::Null, Scalar::from)
+            }),
+        )
+    }),
+};
+
+/// `array_agg(x)`, a list of the values of `x`, including the nulls.
+pub const ARRAY_AGG: Function = Function {
+    namespace: None,
+    name: "array_agg",
+    signature: &Signature::Any(1),
+    return_type: |args| DataType::list(args[0]),
+    deterministic: false,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            AggregateFunction::<Vec<Scalar>>::new(|state, args| {
+                let array = &args[0];
+                let element_type = array.data_type();
+                let mut values = Vec::with_capacity(array.len());
+                for row in 0..array.len() {
+                    state.push(array.scalar_value(row));
+                    values.push(Scalar::List(element_type, state.clone()));
+                }
+                Ok(array_from_scalars(DataType::list(element_type), values))
+            })
+            .with_merge(
+                |state, other| state.extend_from_slice(other),
+                |state, data_type| {
+                    Scalar::List(
+                        data_type.element_type().unwrap_or(DataType::Null),
+                        state.clone(),
+                    )
+                },
+            ),
+        )
+    }),
+};
+
+/// The state of `top_k` and `bottom_k`, the values sorted from the best one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TopK {
+    k: usize,
+    values: Vec<Scalar>,
+}
+
+impl TopK {
+    /// Inserts `value` if it is better than one of the `k` values, by `ordering`.
+    fn insert(&mut self, value: Scalar, ordering: Ordering) {
+        let position = self
+            .values
+            .iter()
+            .position(|current| value.partial_cmp(current) == Some(ordering))
+            .unwrap_or(self.values.len());
+        if position < self.k {
+            self.values.insert(position, value);
+            self.values.truncate(self.k);
+        }
+    }
+
+    fn merge(&mut self, other: &TopK, ordering: Ordering) {
+        self.k = self.k.max(other.k);
+        for value in &other.values {
+            self.insert(value.clone(), ordering);
+        }
+    }
+}
+
+macro_rules! make_top_k_func {
+    ($id:ident, $name:expr, $doc:literal, $ordering:ident) => {
+        #[doc = $doc]
+        pub const $id: Function = Function {
+            namespace: None,
+            name: $name,
+            signature: &Signature::AnyThen(&[DataType::Int64]),
+            return_type: |args| DataType::list(args[0]),
+            deterministic: false,
+            function_type: FunctionType::Stateful(|| {
+                Box::new(
+                    AggregateFunction::<TopK>::new(|state, args| {
+                        let array = &args[0];
+                        let element_type = array.data_type();
+                        let ks = args[1].downcast_ref::<Int64Array>();
+                        let mut values = Vec::with_capacity(array.len());
+                        for (row, k) in ks.iter_opt().enumerate() {
+                            match k {
+                                Some(k) if k > 0 => state.k = k as usize,
+                                _ => anyhow::bail!("the k of {} must be greater than 0", $name),
+                            }
+                            let value = array.scalar_value(row);
+                            if !value.is_null() {
+                                state.insert(value, Ordering::$ordering);
+                            }
+                            values.push(Scalar::List(element_type, state.values.clone()));
+                        }
+                        Ok(array_from_scalars(DataType::list(element_type), values))
+                    })
+                    .with_merge(
+                        |state, other| state.merge(other, Ordering::$ordering),
+                        |state, data_type| {
+                            Scalar::List(
+                                data_type.element_type().unwrap_or(DataType::Null),
+                                state.values.clone(),
+                            )
+                        },
+                    ),
+                )
+            }),
+        };
+    };
+}
+
+make_top_k_func!(
+    TOP_K,
+    "top_k",
+    "`top_k(x, k)`, a list of the `k` largest values of `x`, from the largest.",
+    Greater
+);
+make_top_k_func!(
+    BOTTOM_K,
+    "bottom_k",
+    "`bottom_k(x, k)`, a list of the `k` smallest values of `x`, from the smallest.",
+    Less
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayRef;
+
+    #[test]
+    fn test_string_agg() {
+        let mut f = STRING_AGG.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(StringArray::from_opt_vec(vec![
+                    None,
+                    Some("a"),
+                    None,
+                    Some("b"),
+                ])) as ArrayRef,
+                Arc::new(StringArray::new_scalar(4, Some(", "))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![None, Some("a"), Some("a"), Some("a, b")]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = STRING_AGG.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        let mut other = STRING_AGG.function_type.create_stateful_fun();
+        other
+            .call(&[
+                Arc::new(StringArray::from_vec(vec!["c"])) as ArrayRef,
+                Arc::new(StringArray::new_scalar(1, Some(", "))),
+            ])
+            .unwrap();
+        f.merge(other.as_ref()).unwrap();
+        let array = f
+            .call(&[
+                Arc::new(StringArray::from_vec(vec!["d"])) as ArrayRef,
+                Arc::new(StringArray::new_scalar(1, Some(", "))),
+            ])
+            .unwrap();
+        assert_eq!(array.downcast_ref::<StringArray>().value(0), "a, b, c, d");
+    }
+
+    #[test]
+    fn test_array_agg() {
+        let mut f = ARRAY_AGG.function_type.create_stateful_fun();
+        let array = f
+            .call(&[Arc::new(Int64Array::from_opt_vec(vec![Some(1), None]))])
+            .unwrap();
+        assert_eq!(array.data_type(), DataType::list(DataType::Int64));
+        assert_eq!(
+            array.scalar_value(1),
+            Scalar::List(DataType::Int64, vec![Scalar::Int64(1), Scalar::Null])
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = ARRAY_AGG.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        // Returning the current list doesn't append to it.
+        for _ in 0..2 {
+            assert_eq!(
+                f.current(DataType::list(DataType::Int64)).unwrap(),
+                Scalar::List(DataType::Int64, vec![Scalar::Int64(1), Scalar::Null])
+            );
+        }
+        let array = f.call(&[Arc::new(Int64Array::from_vec(vec![3]))]).unwrap();
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::List(
+                DataType::Int64,
+                vec![Scalar::Int64(1), Scalar::Null, Scalar::Int64(3)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut f = TOP_K.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(StringArray::from_opt_vec(vec![
+                    Some("b"),
+                    None,
+                    Some("d"),
+                    Some("a"),
+                    Some("c"),
+                ])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(5, Some(2))),
+            ])
+            .unwrap();
+        assert_eq!(array.data_type(), DataType::list(DataType::String));
+        assert_eq!(
+            array.scalar_value(4),
+            Scalar::List(DataType::String, vec![Scalar::from("d"), Scalar::from("c")])
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = TOP_K.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        let mut other = TOP_K.function_type.create_stateful_fun();
+        other
+            .call(&[
+                Arc::new(StringArray::from_vec(vec!["e"])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(1, Some(2))),
+            ])
+            .unwrap();
+        f.merge(other.as_ref()).unwrap();
+        assert_eq!(
+            f.current(DataType::list(DataType::String)).unwrap(),
+            Scalar::List(DataType::String, vec![Scalar::from("e"), Scalar::from("d")])
+        );
+        let array = f
+            .call(&[
+                Arc::new(StringArray::from_opt_vec(vec![None::<&str>])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(1, Some(2))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::List(DataType::String, vec![Scalar::from("e"), Scalar::from("d")])
+        );
+
+        assert!(f
+            .call(&[
+                Arc::new(StringArray::from_vec(vec!["a"])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(1, Some(0))),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_bottom_k() {
+        let mut f = BOTTOM_K.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(Int64Array::from_vec(vec![5, 3, 8, 1])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(4, Some(3))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array.scalar_value(3),
+            Scalar::List(
+                DataType::Int64,
+                vec![Scalar::Int64(1), Scalar::Int64(3), Scalar::Int64(5)]
+            )
+        );
+    }
+}
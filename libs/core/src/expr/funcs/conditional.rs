@@ -0,0 +1,220 @@
+use std::cmp::Ordering;
+
+use anyhow::Result;
+
+use crate::array::compute::interleave;
+use crate::array::{ArrayExt, ArrayRef, DataType, Scalar};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+
+fn compare(a: &Scalar, b: &Scalar) -> Option<Ordering> {
+    match (a, b) {
+        (Scalar::Int8(a), Scalar::Int8(b)) => Some(a.cmp(b)),
+        (Scalar::Int16(a), Scalar::Int16(b)) => Some(a.cmp(b)),
+        (Scalar::Int32(a), Scalar::Int32(b)) => Some(a.cmp(b)),
+        (Scalar::Int64(a), Scalar::Int64(b)) => Some(a.cmp(b)),
+        (Scalar::UInt8(a), Scalar::UInt8(b)) => Some(a.cmp(b)),
+        (Scalar::UInt16(a), Scalar::UInt16(b)) => Some(a.cmp(b)),
+        (Scalar::UInt32(a), Scalar::UInt32(b)) => Some(a.cmp(b)),
+        (Scalar::UInt64(a), Scalar::UInt64(b)) => Some(a.cmp(b)),
+        (Scalar::Float32(a), Scalar::Float32(b)) => a.partial_cmp(b),
+        (Scalar::Float64(a), Scalar::Float64(b)) => a.partial_cmp(b),
+        (Scalar::Boolean(a), Scalar::Boolean(b)) => Some(a.cmp(b)),
+        (Scalar::Timestamp(a), Scalar::Timestamp(b)) => Some(a.cmp(b)),
+        (Scalar::Date(a), Scalar::Date(b)) => Some(a.cmp(b)),
+        (Scalar::Time(a), Scalar::Time(b)) => Some(a.cmp(b)),
+        (Scalar::String(a), Scalar::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Selects, for each row, the argument that is not null and compares as `ordering` to all
+/// other arguments that are not null.
+fn extreme(args: &[ArrayRef], name: &str, ordering: Ordering) -> Result<ArrayRef> {
+    let data_type = args[0].data_type();
+    anyhow::ensure!(
+        !matches!(
+            data_type,
+            DataType::List(_) | DataType::Struct(_) | DataType::Map(_, _)
+        ),
+        "the arguments of {} must be comparable, but they are '{}'",
+        name,
+        data_type
+    );
+
+    let len = args[0].len();
+    let mut indices = Vec::with_capacity(len);
+    for row in 0..len {
+        let mut selected: Option<(usize, Scalar)> = None;
+        for (idx, array) in args.iter().enumerate() {
+            if array.is_null(row) {
+                continue;
+            }
+            let value = array.scalar_value(row);
+            let replace = match &selected {
+                Some((_, current)) => compare(&value, current) == Some(ordering),
+                None => true,
+            };
+            if replace {
+                selected = Some((idx, value));
+            }
+        }
+        indices.push(selected.map(|(idx, _)| (idx, row)));
+    }
+    Ok(interleave(data_type, args, &indices))
+}
+
+/// `coalesce(a, b, ...)`, the first argument that is not null.
+pub const COALESCE: Function = Function {
+    namespace: None,
+    name: "coalesce",
+    signature: &Signature::VariadicEqual,
+    return_type: |args| args[0],
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let len = args[0].len();
+        let indices = (0..len)
+            .map(|row| {
+                args.iter()
+                    .position(|array| !array.is_null(row))
+                    .map(|idx| (idx, row))
+            })
+            .collect::<Vec<_>>();
+        Ok(interleave(args[0].data_type(), args, &indices))
+    }),
+};
+
+/// `nullif(a, b)`, null if `a` equals `b`, otherwise `a`.
+pub const NULLIF: Function = Function {
+    namespace: None,
+    name: "nullif",
+    signature: &Signature::VariadicEqual,
+    return_type: |args| args[0],
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        anyhow::ensure!(args.len() == 2, "nullif requires two arguments");
+        let len = args[0].len();
+        let indices = (0..len)
+            .map(|row| {
+                if !args[1].is_null(row) && args[0].scalar_value(row) == args[1].scalar_value(row) {
+                    None
+                } else {
+                    Some((0, row))
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(interleave(args[0].data_type(), args, &indices))
+    }),
+};
+
+/// `greatest(a, b, ...)`, the largest argument, ignoring nulls.
+pub const GREATEST: Function = Function {
+    namespace: None,
+    name: "greatest",
+    signature: &Signature::VariadicEqual,
+    return_type: |args| args[0],
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| extreme(args, "greatest", Ordering::Greater)),
+};
+
+/// `least(a, b, ...)`, the smallest argument, ignoring nulls.
+pub const LEAST: Function = Function {
+    namespace: None,
+    name: "least",
+    signature: &Signature::VariadicEqual,
+    return_type: |args| args[0],
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| extreme(args, "least", Ordering::Less)),
+};
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{Float64Array, Int64Array, StringArray};
+    use crate::expr::funcs::call;
+
+    fn values(array: ArrayRef) -> Vec<Scalar> {
+        (0..array.len())
+            .map(|row| array.scalar_value(row))
+            .collect()
+    }
+
+    #[test]
+    fn test_common_type() {
+        let signature = Signature::VariadicEqual;
+        assert_eq!(
+            signature
+                .data_types(&[DataType::Int32, DataType::Int64, DataType::Float64])
+                .unwrap(),
+            vec![DataType::Float64; 3]
+        );
+        assert_eq!(
+            signature
+                .data_types(&[DataType::String, DataType::String])
+                .unwrap(),
+            vec![DataType::String; 2]
+        );
+        assert!(signature
+            .data_types(&[DataType::Int64, DataType::String])
+            .is_err());
+        assert!(signature
+            .data_types(&[DataType::Boolean, DataType::Int64])
+            .is_err());
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let array = call(
+            &COALESCE,
+            &[
+                Arc::new(StringArray::from_opt_vec(vec![Some("a"), None, None])) as ArrayRef,
+                Arc::new(StringArray::from_opt_vec(vec![Some("b"), Some("c"), None])),
+                Arc::new(StringArray::new_scalar(3, Some("d"))),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            values(array),
+            vec![Scalar::from("a"), Scalar::from("c"), Scalar::from("d")]
+        );
+    }
+
+    #[test]
+    fn test_nullif() {
+        let array = call(
+            &NULLIF,
+            &[
+                Arc::new(Int64Array::from_opt_vec(vec![Some(1), Some(2), None])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(3, Some(2))),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            values(array),
+            vec![Scalar::Int64(1), Scalar::Null, Scalar::Null]
+        );
+    }
+
+    #[test]
+    fn test_greatest_least() {
+        let args: Vec<ArrayRef> = vec![
+            Arc::new(Float64Array::from_opt_vec(vec![Some(1.0), None, None])),
+            Arc::new(Float64Array::from_opt_vec(vec![Some(3.0), Some(2.0), None])),
+            Arc::new(Float64Array::from_opt_vec(vec![
+                Some(-1.0),
+                Some(5.0),
+                None,
+            ])),
+        ];
+        assert_eq!(
+            values(call(&GREATEST, &args).unwrap()),
+            vec![Scalar::Float64(3.0), Scalar::Float64(5.0), Scalar::Null]
+        );
+        assert_eq!(
+            values(call(&LEAST, &args).unwrap()),
+            vec![Scalar::Float64(-1.0), Scalar::Float64(2.0), Scalar::Null]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use crate::array::{Array, ArrayExt, DataType, Float64Array, Float64Builder, StringArray};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+use crate::rates;
+
+pub const CONVERT: Function = Function {
+    namespace: None,
+    name: "convert",
+    signature: &Signature::Exact(&[DataType::Float64, DataType::String, DataType::String]),
+    return_type: |_| DataType::Float64,
+    deterministic: false,
+    function_type: FunctionType::Stateless(|args| {
+        let amounts = args[0].downcast_ref::<Float64Array>();
+        let from = args[1].downcast_ref::<StringArray>();
+        let to = args[2].downcast_ref::<StringArray>();
+
+        let mut builder = Float64Builder::with_capacity(amounts.len());
+        for ((amount, from), to) in amounts.iter_opt().zip(from.iter_opt()).zip(to.iter_opt()) {
+            builder.append_opt(match (amount, from, to) {
+                (Some(amount), Some(from), Some(to)) => rates::convert(amount, from, to),
+                _ => None,
+            });
+        }
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::funcs::call;
+
+    #[test]
+    fn test_convert() {
+        rates::set_rate("usd", 1.0);
+        rates::set_rate("EUR", 1.25);
+
+        let array = call(
+            &CONVERT,
+            &[
+                Arc::new(Float64Array::from_vec(vec![10.0, 10.0, 10.0])),
+                Arc::new(StringArray::from_vec(vec!["EUR", "USD", "GBP"])),
+                Arc::new(StringArray::new_scalar(3, Some("usd"))),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(12.5), Some(10.0), None]
+        );
+    }
+}
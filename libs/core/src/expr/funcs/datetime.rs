@@ -0,0 +1,509 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone, Timelike,
+};
+use chrono_tz::Tz;
+
+use super::regexp::constant_string_arg;
+use crate::array::{
+    Array, ArrayExt, ArrayRef, DataType, Float64Array, Int64Type, PrimitiveArray, PrimitiveBuilder,
+    PrimitiveType, StringArray, StringBuilder, TimestampArray, TimestampBuilder, TimestampType,
+};
+use crate::execution::clock::{Clock, SystemClock};
+use crate::expr::cast::array_cast_to;
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// Returns the time zone argument at `idx`, UTC if it is omitted.
+fn time_zone_arg(args: &[ArrayRef], idx: usize) -> Result<Tz> {
+    if args.len() <= idx {
+        return Ok(Tz::UTC);
+    }
+    let name = constant_string_arg(args, idx, "time zone")?;
+    name.parse::<Tz>()
+        .map_err(|_| anyhow::anyhow!("unknown time zone: '{}'", name))
+}
+
+fn format_arg(args: &[ArrayRef], idx: usize) -> Result<&str> {
+    let format = constant_string_arg(args, idx, "format")?;
+    anyhow::ensure!(
+        StrftimeItems::new(format).all(|item| item != Item::Error),
+        "invalid format: '{}'",
+        format
+    );
+    Ok(format)
+}
+
+fn to_datetime(timestamp: i64, tz: Tz) -> DateTime<Tz> {
+    tz.timestamp_millis(timestamp)
+}
+
+/// Converts a local time of `tz` to milliseconds since the unix epoch, the earliest time if it
+/// is ambiguous.
+fn local_to_timestamp(tz: Tz, datetime: &NaiveDateTime) -> Option<i64> {
+    match tz.from_local_datetime(datetime) {
+        LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => {
+            Some(datetime.timestamp_millis())
+        }
+        LocalResult::None => None,
+    }
+}
+
+fn map_timestamps<T, F>(array: &ArrayRef, f: F) -> ArrayRef
+where
+    T: PrimitiveType,
+    F: Fn(i64) -> Option<T::Native>,
+{
+    let array = array.downcast_ref::<TimestampArray>();
+    if let Some(scalar) = array.to_scalar() {
+        return Arc::new(PrimitiveArray::<T>::new_scalar(
+            array.len(),
+            scalar.and_then(&f),
+        ));
+    }
+    let mut builder = PrimitiveBuilder::<T>::with_capacity(array.len());
+    for value in array.iter_opt() {
+        builder.append_opt(value.and_then(&f));
+    }
+    Arc::new(builder.finish())
+}
+
+/// Truncates `datetime` to the start of the `unit` that contains it.
+fn truncate(datetime: DateTime<Tz>, unit: &str) -> Option<NaiveDateTime> {
+    let local = datetime.naive_local();
+    let date = local.date();
+    Some(match unit {
+        "millisecond" => return Some(local),
+        "second" => local.with_nanosecond(0)?,
+        "minute" => date.and_hms(local.hour(), local.minute(), 0),
+        "hour" => date.and_hms(local.hour(), 0, 0),
+        "day" => date.and_hms(0, 0, 0),
+        "week" => {
+            (date - Duration::days(date.weekday().num_days_from_monday() as i64)).and_hms(0, 0, 0)
+        }
+        "month" => NaiveDate::from_ymd(date.year(), date.month(), 1).and_hms(0, 0, 0),
+        "quarter" => {
+            NaiveDate::from_ymd(date.year(), (date.month() - 1) / 3 * 3 + 1, 1).and_hms(0, 0, 0)
+        }
+        "year" => NaiveDate::from_ymd(date.year(), 1, 1).and_hms(0, 0, 0),
+        _ => return None,
+    })
+}
+
+fn unit_arg(args: &[ArrayRef], units: &[&str]) -> Result<String> {
+    let unit = constant_string_arg(args, 0, "unit")?.to_ascii_lowercase();
+    let unit = unit.strip_suffix('s').unwrap_or(&unit).to_string();
+    anyhow::ensure!(
+        units.contains(&unit.as_str()),
+        "invalid unit: '{}', expected one of: {}",
+        unit,
+        units.join(", ")
+    );
+    Ok(unit)
+}
+
+pub const DATE_TRUNC: Function = Function {
+    namespace: None,
+    name: "date_trunc",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::String, DataType::Timestamp(None)]),
+        Signature::Exact(&[
+            DataType::String,
+            DataType::Timestamp(None),
+            DataType::String,
+        ]),
+    ]),
+    return_type: |args| args[1],
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let unit = unit_arg(
+            args,
+            &[
+                "millisecond",
+                "second",
+                "minute",
+                "hour",
+                "day",
+                "week",
+                "month",
+                "quarter",
+                "year",
+            ],
+        )?;
+        let tz = time_zone_arg(args, 2)?;
+        Ok(map_timestamps::<TimestampType, _>(&args[1], |timestamp| {
+            let datetime = to_datetime(timestamp, tz);
+            let truncated = truncate(datetime, &unit)?;
+            // The start of the unit may be skipped by a daylight saving time transition.
+            local_to_timestamp(tz, &truncated)
+                .or_else(|| Some((truncated - datetime.offset().fix()).timestamp_millis()))
+        }))
+    }),
+};
+
+pub const EXTRACT: Function = Function {
+    namespace: None,
+    name: "extract",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::String, DataType::Timestamp(None)]),
+        Signature::Exact(&[
+            DataType::String,
+            DataType::Timestamp(None),
+            DataType::String,
+        ]),
+    ]),
+    return_type: |_| DataType::Int64,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let unit = unit_arg(
+            args,
+            &[
+                "year",
+                "quarter",
+                "month",
+                "week",
+                "day",
+                "dow",
+                "doy",
+                "hour",
+                "minute",
+                "second",
+                "millisecond",
+                "epoch",
+            ],
+        )?;
+        let tz = time_zone_arg(args, 2)?;
+        Ok(map_timestamps::<Int64Type, _>(&args[1], |timestamp| {
+            let datetime = to_datetime(timestamp, tz);
+            Some(match unit.as_str() {
+                "year" => datetime.year() as i64,
+                "quarter" => ((datetime.month() - 1) / 3 + 1) as i64,
+                "month" => datetime.month() as i64,
+                "week" => datetime.iso_week().week() as i64,
+                "day" => datetime.day() as i64,
+                "dow" => datetime.weekday().num_days_from_sunday() as i64,
+                "doy" => datetime.ordinal() as i64,
+                "hour" => datetime.hour() as i64,
+                "minute" => datetime.minute() as i64,
+                "second" => datetime.second() as i64,
+                "millisecond" => datetime.timestamp_subsec_millis() as i64,
+                _ => timestamp.div_euclid(1000),
+            })
+        }))
+    }),
+};
+
+/// Parses `s` with `format`, the times without an offset are in `tz`.
+fn parse_with_format(s: &str, format: &str, tz: Tz) -> Option<i64> {
+    if let Ok(datetime) = DateTime::parse_from_str(s, format) {
+        return Some(datetime.timestamp_millis());
+    }
+    let datetime = NaiveDateTime::parse_from_str(s, format).ok().or_else(|| {
+        NaiveDate::parse_from_str(s, format)
+            .ok()
+            .map(|date| date.and_hms(0, 0, 0))
+    })?;
+    local_to_timestamp(tz, &datetime)
+}
+
+pub const TO_TIMESTAMP: Function = Function {
+    namespace: None,
+    name: "to_timestamp",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::String, DataType::String]),
+        Signature::Exact(&[DataType::String, DataType::String, DataType::String]),
+    ]),
+    return_type: |_| DataType::Timestamp(None),
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let array = args[0].downcast_ref::<StringArray>();
+        let format = format_arg(args, 1)?;
+        let tz = time_zone_arg(args, 2)?;
+        let mut builder = TimestampBuilder::with_capacity(array.len());
+        for value in array.iter_opt() {
+            builder.append_opt(value.and_then(|s| parse_with_format(s, format, tz)));
+        }
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+/// `to_date(x)`, the date of a timestamp in UTC, or of a string in the format `YYYY-MM-DD`.
+pub const TO_DATE: Function = Function {
+    namespace: None,
+    name: "to_date",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::Timestamp(None)]),
+        Signature::Exact(&[DataType::String]),
+    ]),
+    return_type: |_| DataType::Date,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| array_cast_to(args[0].clone(), DataType::Date)),
+};
+
+/// `to_time(x)`, the time of day of a timestamp in UTC, or of a string in the format
+/// `HH:MM[:SS[.fff]]`.
+pub const TO_TIME: Function = Function {
+    namespace: None,
+    name: "to_time",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::Timestamp(None)]),
+        Signature::Exact(&[DataType::String]),
+    ]),
+    return_type: |_| DataType::Time,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| array_cast_to(args[0].clone(), DataType::Time)),
+};
+
+pub const FROM_UNIXTIME: Function = Function {
+    namespace: None,
+    name: "from_unixtime",
+    signature: &Signature::Uniform(1, &[DataType::Float64]),
+    return_type: |_| DataType::Timestamp(None),
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let array = args[0].downcast_ref::<Float64Array>();
+        let to_timestamp = |seconds: f64| (seconds * 1000.0).round() as i64;
+        if let Some(scalar) = array.to_scalar() {
+            return Ok(Arc::new(TimestampArray::new_scalar(
+                array.len(),
+                scalar.map(to_timestamp),
+            )));
+        }
+        let mut builder = TimestampBuilder::with_capacity(array.len());
+        for value in array.iter_opt() {
+            builder.append_opt(value.map(to_timestamp));
+        }
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+pub const FORMAT_TIMESTAMP: Function = Function {
+    namespace: None,
+    name: "format_timestamp",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::Timestamp(None), DataType::String]),
+        Signature::Exact(&[
+            DataType::Timestamp(None),
+            DataType::String,
+            DataType::String,
+        ]),
+    ]),
+    return_type: |_| DataType::String,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let array = args[0].downcast_ref::<TimestampArray>();
+        let format = format_arg(args, 1)?;
+        let tz = time_zone_arg(args, 2)?;
+        let mut builder = StringBuilder::with_capacity(array.len());
+        for value in array.iter_opt() {
+            match value {
+                Some(timestamp) => {
+                    builder.append(&to_datetime(timestamp, tz).format(format).to_string())
+                }
+                None => builder.append_null(),
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+pub const NOW: Function = Function {
+    namespace: None,
+    name: "now",
+    signature: &Signature::Exact(&[]),
+    return_type: |_| DataType::Timestamp(None),
+    deterministic: false,
+    function_type: FunctionType::Stateless(|args| {
+        Ok(Arc::new(TimestampArray::new_scalar(
+            args[0].len(),
+            Some(SystemClock.now()),
+        )))
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Int64Array, Scalar};
+    use crate::expr::funcs::call;
+
+    fn string(s: &str) -> ArrayRef {
+        Arc::new(StringArray::new_scalar(2, Some(s)))
+    }
+
+    // 2024-05-15 13:45:30.250 UTC, a Wednesday.
+    const TIMESTAMP: i64 = 1715780730250;
+
+    fn timestamps() -> ArrayRef {
+        Arc::new(TimestampArray::from_opt_vec(vec![Some(TIMESTAMP), None]))
+    }
+
+    fn parse(s: &str) -> i64 {
+        parse_with_format(s, "%Y-%m-%d %H:%M:%S", Tz::UTC).unwrap()
+    }
+
+    #[test]
+    fn test_date_trunc() {
+        for (unit, expected) in &[
+            ("second", TIMESTAMP - 250),
+            ("minute", parse("2024-05-15 13:45:00")),
+            ("hours", parse("2024-05-15 13:00:00")),
+            ("DAY", parse("2024-05-15 00:00:00")),
+            ("week", parse("2024-05-13 00:00:00")),
+            ("month", parse("2024-05-01 00:00:00")),
+            ("quarter", parse("2024-04-01 00:00:00")),
+            ("year", parse("2024-01-01 00:00:00")),
+        ] {
+            let array = call(&DATE_TRUNC, &[string(unit), timestamps()]).unwrap();
+            assert_eq!(
+                array.scalar_value(0),
+                Scalar::Timestamp(*expected),
+                "{}",
+                unit
+            );
+            assert_eq!(array.scalar_value(1), Scalar::Null);
+        }
+
+        let array = call(
+            &DATE_TRUNC,
+            &[string("day"), timestamps(), string("Asia/Shanghai")],
+        )
+        .unwrap();
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::Timestamp(parse("2024-05-14 16:00:00"))
+        );
+
+        assert!(call(&DATE_TRUNC, &[string("fortnight"), timestamps()]).is_err());
+        assert!(call(
+            &DATE_TRUNC,
+            &[string("day"), timestamps(), string("Mars/Base")]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        for (unit, expected) in &[
+            ("year", 2024),
+            ("quarter", 2),
+            ("month", 5),
+            ("week", 20),
+            ("day", 15),
+            ("dow", 3),
+            ("doy", 136),
+            ("hour", 13),
+            ("minute", 45),
+            ("second", 30),
+            ("millisecond", 250),
+            ("epoch", 1715780730),
+        ] {
+            let array = call(&EXTRACT, &[string(unit), timestamps()]).unwrap();
+            assert_eq!(
+                array
+                    .downcast_ref::<Int64Array>()
+                    .iter_opt()
+                    .collect::<Vec<_>>(),
+                vec![Some(*expected), None],
+                "{}",
+                unit
+            );
+        }
+
+        let array = call(
+            &EXTRACT,
+            &[string("hour"), timestamps(), string("America/New_York")],
+        )
+        .unwrap();
+        assert_eq!(array.scalar_value(0), Scalar::Int64(9));
+    }
+
+    #[test]
+    fn test_to_timestamp() {
+        let array = call(
+            &TO_TIMESTAMP,
+            &[
+                Arc::new(StringArray::from_vec(vec![
+                    "15/05/2024 13:45",
+                    "2024-05-15",
+                    "yesterday",
+                ])) as ArrayRef,
+                Arc::new(StringArray::new_scalar(3, Some("%d/%m/%Y %H:%M"))),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<TimestampArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(parse("2024-05-15 13:45:00")), None, None]
+        );
+
+        let array = call(
+            &TO_TIMESTAMP,
+            &[
+                string("2024-05-15"),
+                string("%Y-%m-%d"),
+                string("Asia/Shanghai"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::Timestamp(parse("2024-05-14 16:00:00"))
+        );
+
+        assert!(call(&TO_TIMESTAMP, &[string("x"), string("%Q")]).is_err());
+    }
+
+    #[test]
+    fn test_from_unixtime_and_format_timestamp() {
+        let array = call(
+            &FROM_UNIXTIME,
+            &[Arc::new(Float64Array::from_vec(vec![1715780730.25])) as ArrayRef],
+        )
+        .unwrap();
+        assert_eq!(array.scalar_value(0), Scalar::Timestamp(TIMESTAMP));
+
+        let array = call(
+            &FORMAT_TIMESTAMP,
+            &[
+                timestamps(),
+                string("%Y-%m-%d %H:%M:%S%.3f"),
+                string("Europe/Paris"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some("2024-05-15 15:45:30.250"), None]
+        );
+    }
+
+    #[test]
+    fn test_to_date_and_time() {
+        let array = call(&TO_DATE, &[timestamps()]).unwrap();
+        assert_eq!(array.scalar_value(0), Scalar::Date(19858));
+        assert_eq!(array.scalar_value(0).to_string(), "2024-05-15");
+        assert_eq!(array.scalar_value(1), Scalar::Null);
+
+        let array = call(&TO_TIME, &[timestamps()]).unwrap();
+        assert_eq!(array.scalar_value(0), Scalar::Time(49_530_250));
+        assert_eq!(array.scalar_value(0).to_string(), "13:45:30.250");
+
+        let array = call(&TO_DATE, &[string("2024-05-15")]).unwrap();
+        assert_eq!(array.scalar_value(1), Scalar::Date(19858));
+        let array = call(&TO_DATE, &[string("2024-05-32")]).unwrap();
+        assert_eq!(array.scalar_value(0), Scalar::Null);
+
+        let array = call(&TO_TIME, &[string("13:45")]).unwrap();
+        assert_eq!(array.scalar_value(0), Scalar::Time(49_500_000));
+    }
+}
@@ -0,0 +1,212 @@
+use std::hash::Hasher;
+use std::mem::size_of;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use twox_hash::XxHash64;
+
+use crate::array::{ArrayExt, ArrayRef, DataType, Int64Builder, Scalar, StringArray};
+use crate::expr::func::{AggregateFunction, Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// The number of bits of the hashes that select a register, the standard error of the
+/// estimates is `1.04 / sqrt(2^PRECISION)`, about 0.8%.
+const PRECISION: u32 = 14;
+
+const REGISTERS: usize = 1 << PRECISION;
+
+/// A HyperLogLog sketch, which estimates the number of distinct values of a stream in a fixed
+/// amount of memory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct HyperLogLog {
+    /// The maximum number of leading zeros of the hashes selecting each register, allocated by
+    /// the first value.
+    registers: Vec<u8>,
+    /// The last estimate, until a register changes.
+    #[serde(skip)]
+    estimate: Option<u64>,
+}
+
+impl HyperLogLog {
+    pub(crate) fn add_hash(&mut self, hash: u64) {
+        if self.registers.is_empty() {
+            self.registers = vec![0; REGISTERS];
+        }
+        let index = (hash >> (64 - PRECISION)) as usize;
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+            self.estimate = None;
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: &HyperLogLog) {
+        if other.registers.is_empty() {
+            return;
+        }
+        if self.registers.is_empty() {
+            self.registers = vec![0; REGISTERS];
+        }
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+        self.estimate = None;
+    }
+
+    pub(crate) fn estimate(&mut self) -> u64 {
+        if self.registers.is_empty() {
+            return 0;
+        }
+        if let Some(estimate) = self.estimate {
+            return estimate;
+        }
+
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum::<f64>();
+        let mut estimate = alpha * m * m / sum;
+
+        // Linear counting is more accurate while many registers are empty.
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            estimate = m * (m / zeros as f64).ln();
+        }
+
+        let estimate = estimate.round() as u64;
+        self.estimate = Some(estimate);
+        estimate
+    }
+}
+
+/// Hashes the value of `array` at `row`, `None` if it is null.
+fn hash_value(array: &ArrayRef, row: usize) -> Option<u64> {
+    if array.is_null(row) {
+        return None;
+    }
+
+    let mut hasher = XxHash64::with_seed(0);
+    if array.data_type() == DataType::String {
+        hasher.write(array.downcast_ref::<StringArray>().value(row).as_bytes());
+        return Some(hasher.finish());
+    }
+    match array.scalar_value(row) {
+        Scalar::Int8(n) => hasher.write_i8(n),
+        Scalar::Int16(n) => hasher.write_i16(n),
+        Scalar::Int32(n) | Scalar::Date(n) | Scalar::Time(n) => hasher.write_i32(n),
+        Scalar::Int64(n) | Scalar::Timestamp(n) => hasher.write_i64(n),
+        Scalar::UInt8(n) => hasher.write_u8(n),
+        Scalar::UInt16(n) => hasher.write_u16(n),
+        Scalar::UInt32(n) => hasher.write_u32(n),
+        Scalar::UInt64(n) => hasher.write_u64(n),
+        Scalar::Float32(n) => hasher.write_u32(n.to_bits()),
+        Scalar::Float64(n) => hasher.write_u64(n.to_bits()),
+        Scalar::Boolean(b) => hasher.write_u8(b as u8),
+        value => hasher.write(value.to_string().as_bytes()),
+    }
+    Some(hasher.finish())
+}
+
+/// `approx_count_distinct(x)`, an estimate of the number of distinct values of `x` that are not
+/// null, computed with a [`HyperLogLog`] sketch of 16KB.
+pub const APPROX_COUNT_DISTINCT: Function = Function {
+    namespace: None,
+    name: "approx_count_distinct",
+    signature: &Signature::Any(1),
+    return_type: |_| DataType::Int64,
+    deterministic: false,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            AggregateFunction::<HyperLogLog>::new(|state, args| {
+                let array = &args[0];
+                let mut builder = Int64Builder::with_capacity(array.len());
+                for row in 0..array.len() {
+                    if let Some(hash) = hash_value(array, row) {
+                        state.add_hash(hash);
+                    }
+                    builder.append(state.estimate() as i64);
+                }
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(HyperLogLog::merge, |state, _| {
+                Scalar::Int64(state.clone().estimate() as i64)
+            })
+            .with_max_state_size(REGISTERS + size_of::<HyperLogLog>()),
+        )
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::Int64Array;
+
+    fn assert_estimate(hll: &mut HyperLogLog, expected: u64) {
+        let estimate = hll.estimate();
+        let error = (estimate as f64 - expected as f64).abs() / expected as f64;
+        assert!(error < 0.03, "estimate={} expected={}", estimate, expected);
+    }
+
+    #[test]
+    fn test_hyper_log_log() {
+        let mut hll = HyperLogLog::default();
+        assert_eq!(hll.estimate(), 0);
+
+        let array: ArrayRef = Arc::new(Int64Array::from_vec((0..100_000).collect()));
+        for _ in 0..2 {
+            for row in 0..array.len() {
+                hll.add_hash(hash_value(&array, row).unwrap());
+            }
+        }
+        assert_estimate(&mut hll, 100_000);
+
+        let mut other = HyperLogLog::default();
+        let array: ArrayRef = Arc::new(Int64Array::from_vec((50_000..150_000).collect()));
+        for row in 0..array.len() {
+            other.add_hash(hash_value(&array, row).unwrap());
+        }
+        hll.merge(&other);
+        assert_estimate(&mut hll, 150_000);
+    }
+
+    #[test]
+    fn test_approx_count_distinct() {
+        let mut f = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        let array = f
+            .call(&[Arc::new(StringArray::from_opt_vec(vec![
+                Some("a"),
+                Some("b"),
+                None,
+                Some("a"),
+            ]))])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, 2, 2, 2]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        let array = f
+            .call(&[Arc::new(StringArray::from_vec(vec!["c", "b"]))])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![3, 3]
+        );
+    }
+}
@@ -36,6 +36,7 @@ pub const ALL: Function = Function {
     name: "all",
     signature: &Signature::Exact(&[DataType::Boolean, DataType::Int64]),
     return_type: |_| DataType::Boolean,
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
         Box::new(AggregateFunction::<AllState>::new(|state, args| {
             let array = args[0].downcast_ref::<BooleanArray>();
@@ -93,6 +94,7 @@ pub const ANY: Function = Function {
     name: "any",
     signature: &Signature::Exact(&[DataType::Boolean, DataType::Int64]),
     return_type: |_| DataType::Boolean,
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
         Box::new(AggregateFunction::<AnyState>::new(|state, args| {
             let array = args[0].downcast_ref::<BooleanArray>();
@@ -149,6 +151,7 @@ pub const BARSLAST: Function = Function {
     name: "barslast",
     signature: &Signature::Exact(&[DataType::Boolean]),
     return_type: |_| DataType::Int64,
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
         Box::new(AggregateFunction::<BarsLastState>::new(|state, args| {
             let array = args[0].downcast_ref::<BooleanArray>();
@@ -182,6 +185,7 @@ pub const BARSSINCE: Function = Function {
     name: "barssince",
     signature: &Signature::Exact(&[DataType::Boolean]),
     return_type: |_| DataType::Int64,
+    deterministic: false,
     function_type: FunctionType::Stateful(|| {
         Box::new(AggregateFunction::<BarsLastState>::new(|state, args| {
             let array = args[0].downcast_ref::<BooleanArray>();
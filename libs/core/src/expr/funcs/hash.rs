@@ -0,0 +1,118 @@
+use std::fmt::Write;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use twox_hash::XxHash64;
+
+use super::url::map_strings;
+use crate::array::{Array, ArrayExt, DataType, Int64Array, Int64Builder, StringArray};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(s, "{:02x}", byte);
+    }
+    s
+}
+
+fn xxhash64(s: &str) -> i64 {
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(s.as_bytes());
+    hasher.finish() as i64
+}
+
+/// `md5(s)`, the MD5 digest of `s` as a lowercase hex string.
+pub const MD5: Function = Function {
+    namespace: None,
+    name: "md5",
+    signature: &Signature::Exact(&[DataType::String]),
+    return_type: |_| DataType::String,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        Ok(map_strings(&args[0], |s| {
+            Some(to_hex(&Md5::digest(s.as_bytes())))
+        }))
+    }),
+};
+
+/// `sha256(s)`, the SHA-256 digest of `s` as a lowercase hex string.
+pub const SHA256: Function = Function {
+    namespace: None,
+    name: "sha256",
+    signature: &Signature::Exact(&[DataType::String]),
+    return_type: |_| DataType::String,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        Ok(map_strings(&args[0], |s| {
+            Some(to_hex(&Sha256::digest(s.as_bytes())))
+        }))
+    }),
+};
+
+/// `xxhash(s)`, the 64-bit xxHash of `s` with seed 0, for bucketing keys.
+pub const XXHASH: Function = Function {
+    namespace: None,
+    name: "xxhash",
+    signature: &Signature::Exact(&[DataType::String]),
+    return_type: |_| DataType::Int64,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let array = args[0].downcast_ref::<StringArray>();
+        if let Some(scalar) = array.to_scalar() {
+            return Ok(Arc::new(Int64Array::new_scalar(
+                array.len(),
+                scalar.map(xxhash64),
+            )));
+        }
+        let mut builder = Int64Builder::with_capacity(array.len());
+        for value in array.iter_opt() {
+            builder.append_opt(value.map(xxhash64));
+        }
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayRef, Scalar};
+    use crate::expr::funcs::call;
+
+    fn hash(func: &Function, values: Vec<Option<&str>>) -> ArrayRef {
+        call(func, &[Arc::new(StringArray::from_opt_vec(values))]).unwrap()
+    }
+
+    #[test]
+    fn test_md5() {
+        let array = hash(&MD5, vec![Some("abc"), None]);
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::from("900150983cd24fb0d6963f7d28e17f72")
+        );
+        assert_eq!(array.scalar_value(1), Scalar::Null);
+    }
+
+    #[test]
+    fn test_sha256() {
+        let array = hash(&SHA256, vec![Some("abc")]);
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::from("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+
+    #[test]
+    fn test_xxhash() {
+        let array = hash(&XXHASH, vec![Some(""), Some("abc"), Some("abc"), None]);
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::Int64(0xef46db3751d8e999_u64 as i64)
+        );
+        assert_eq!(array.scalar_value(1), array.scalar_value(2));
+        assert_eq!(array.scalar_value(3), Scalar::Null);
+    }
+}
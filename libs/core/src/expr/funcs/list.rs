@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::array::compute::{interleave, take};
+use crate::array::{Array, ArrayExt, ArrayRef, DataType, Int64Array, ListArray};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// `make_array(a, b, ...)`, also used for the array literal `[a, b, ...]`.
+pub const MAKE_ARRAY: Function = Function {
+    namespace: None,
+    name: "make_array",
+    signature: &Signature::Variadic(&[
+        DataType::Int64,
+        DataType::Float64,
+        DataType::Boolean,
+        DataType::Timestamp(None),
+        DataType::Date,
+        DataType::Time,
+        DataType::String,
+    ]),
+    return_type: |args| DataType::list(args.first().copied().unwrap_or(DataType::Null)),
+    deterministic: true,
+    function_type: FunctionType::Stateless(make_array),
+};
+
+/// Creates a list of the arguments for each row, all arguments have the same type.
+pub(crate) fn make_array(args: &[ArrayRef]) -> Result<ArrayRef> {
+    anyhow::ensure!(
+        !args.is_empty(),
+        "make_array requires at least one argument"
+    );
+
+    let len = args[0].len();
+    let mut indices = Vec::with_capacity(len * args.len());
+    for row in 0..len {
+        indices.extend((0..args.len()).map(|idx| Some((idx, row))));
+    }
+    let values = interleave(args[0].data_type(), args, &indices);
+    Ok(Arc::new(ListArray::new(
+        values,
+        (0..len).map(|_| Some(args.len())),
+    )))
+}
+
+/// Returns the element of a list at a 1-based index, used for `list[index]`.
+///
+/// The result is null if the list or the index is null, or the index is out of range.
+pub(crate) fn element_at(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let lists = args[0].downcast_ref::<ListArray>();
+    let indexes = args[1].downcast_ref::<Int64Array>();
+
+    let mut indices = Vec::with_capacity(lists.len());
+    for row in 0..lists.len() {
+        let index = match (lists.value_len_opt(row), indexes.value_opt(row)) {
+            (Some(len), Some(index)) if index >= 1 && index as usize <= len => {
+                Some(lists.value_range(row).start + index as usize - 1)
+            }
+            _ => None,
+        };
+        indices.push(index);
+    }
+    Ok(take(lists.values().clone(), &indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Scalar, StringArray};
+
+    #[test]
+    fn test_make_array() {
+        let array = make_array(&[
+            Arc::new(StringArray::from_vec(vec!["a", "b"])) as ArrayRef,
+            Arc::new(StringArray::new_scalar(2, Some("c"))),
+        ])
+        .unwrap();
+        assert_eq!(array.data_type(), DataType::list(DataType::String));
+        assert_eq!(
+            array.scalar_value(1),
+            Scalar::List(DataType::String, vec![Scalar::from("b"), Scalar::from("c")])
+        );
+    }
+
+    #[test]
+    fn test_element_at() {
+        let lists: ArrayRef = Arc::new(ListArray::new(
+            Arc::new(Int64Array::from_vec(vec![1, 2, 3, 4, 5])),
+            vec![Some(2), None, Some(3), Some(0)],
+        ));
+        let array = element_at(&[
+            lists.clone(),
+            Arc::new(Int64Array::from_vec(vec![2, 1, 1, 1])),
+        ])
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(2), None, Some(3), None]
+        );
+
+        let array = element_at(&[lists, Arc::new(Int64Array::new_scalar(4, Some(3)))]).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![None, None, Some(5), None]
+        );
+    }
+}
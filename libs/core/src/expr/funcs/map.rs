@@ -0,0 +1,46 @@
+use anyhow::Result;
+
+use crate::array::compute::take;
+use crate::array::{Array, ArrayExt, ArrayRef, MapArray};
+
+/// Returns the value of a key in a map, used for `map[key]`.
+///
+/// The result is null if the map or the key is null, or the map has no such key. The last entry
+/// wins if a key appears more than once.
+pub(crate) fn map_value(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let maps = args[0].downcast_ref::<MapArray>();
+    let keys = &args[1];
+
+    let mut indices = Vec::with_capacity(maps.len());
+    for row in 0..maps.len() {
+        let index = match keys.is_valid(row) {
+            true => maps.find(row, &keys.scalar_value(row)),
+            false => None,
+        };
+        indices.push(index);
+    }
+    Ok(take(maps.values().clone(), &indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{Int64Array, StringArray};
+
+    #[test]
+    fn test_map_value() {
+        let maps = MapArray::new(
+            Arc::new(StringArray::from_vec(vec!["a", "b", "a", "a"])),
+            Arc::new(Int64Array::from_vec(vec![1, 2, 3, 4])),
+            vec![Some(2), None, Some(2), Some(0)],
+        );
+        let keys = StringArray::from_opt_vec(vec![Some("b"), Some("a"), Some("a"), None]);
+        let array = map_value(&[Arc::new(maps), Arc::new(keys)]).unwrap();
+        assert_eq!(
+            &*array,
+            &Int64Array::from_opt_vec(vec![Some(2), None, Some(4), None]) as &dyn Array
+        );
+    }
+}
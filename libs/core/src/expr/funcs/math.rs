@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
+use anyhow::Result;
+
 use crate::array::{
-    Array, ArrayExt, DataType, Float32Array, Float32Builder, Float64Array, Float64Builder,
+    Array, ArrayExt, ArrayRef, DataType, Float32Array, Float32Builder, Float32Type, Float64Array,
+    Float64Builder, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, PrimitiveArray,
+    PrimitiveBuilder, PrimitiveType,
 };
 use crate::expr::func::{Function, FunctionType};
 use crate::expr::signature::Signature;
@@ -13,6 +17,7 @@ macro_rules! make_math_func {
             name: $name,
             signature: &Signature::Uniform(1, &[DataType::Float64, DataType::Float32]),
             return_type: |args| args[0],
+            deterministic: true,
             function_type: FunctionType::Stateless(|args| {
                 let array = &args[0];
                 match array.data_type() {
@@ -57,6 +62,91 @@ macro_rules! make_math_func {
     };
 }
 
+/// Defines a function of one numeric argument that returns a value of the same type,
+/// `$int_func` and `$float_func` return `None` if the result overflows.
+macro_rules! make_numeric_func {
+    ($ident:ident, $name:literal, $int_func:expr, $float_func:expr) => {
+        pub const $ident: Function = Function {
+            namespace: None,
+            name: $name,
+            signature: &Signature::Uniform(
+                1,
+                &[
+                    DataType::Float64,
+                    DataType::Float32,
+                    DataType::Int8,
+                    DataType::Int16,
+                    DataType::Int32,
+                    DataType::Int64,
+                ],
+            ),
+            return_type: |args| args[0],
+            deterministic: true,
+            function_type: FunctionType::Stateless(|args| {
+                let array = &*args[0];
+                match array.data_type() {
+                    DataType::Int8 => numeric_op::<Int8Type, _>($name, array, $int_func),
+                    DataType::Int16 => numeric_op::<Int16Type, _>($name, array, $int_func),
+                    DataType::Int32 => numeric_op::<Int32Type, _>($name, array, $int_func),
+                    DataType::Int64 => numeric_op::<Int64Type, _>($name, array, $int_func),
+                    DataType::Float32 => numeric_op::<Float32Type, _>($name, array, $float_func),
+                    DataType::Float64 => numeric_op::<Float64Type, _>($name, array, $float_func),
+                    _ => unreachable!(),
+                }
+            }),
+        };
+    };
+}
+
+fn numeric_op<T, F>(name: &str, array: &dyn Array, f: F) -> Result<ArrayRef>
+where
+    T: PrimitiveType,
+    F: Fn(T::Native) -> Option<T::Native>,
+{
+    let array = array.downcast_ref::<PrimitiveArray<T>>();
+    let f = |value| {
+        f(value).ok_or_else(|| anyhow::anyhow!("integer overflow in the function '{}'.", name))
+    };
+    if let Some(scalar) = array.to_scalar() {
+        return Ok(Arc::new(PrimitiveArray::<T>::new_scalar(
+            array.len(),
+            scalar.map(f).transpose()?,
+        )));
+    }
+    let mut builder = PrimitiveBuilder::<T>::with_capacity(array.len());
+    for value in array.iter_opt() {
+        builder.append_opt(value.map(f).transpose()?);
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+pub const POWER: Function = Function {
+    namespace: None,
+    name: "power",
+    signature: &Signature::Uniform(2, &[DataType::Float64]),
+    return_type: |_| DataType::Float64,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let base = args[0].downcast_ref::<Float64Array>();
+        let exponent = args[1].downcast_ref::<Float64Array>();
+        if let (Some(base), Some(exponent)) = (base.to_scalar(), exponent.to_scalar()) {
+            return Ok(Arc::new(Float64Array::new_scalar(
+                args[0].len(),
+                base.zip(exponent)
+                    .map(|(base, exponent)| base.powf(exponent)),
+            )));
+        }
+        let mut builder = Float64Builder::with_capacity(base.len());
+        for (base, exponent) in base.iter_opt().zip(exponent.iter_opt()) {
+            builder.append_opt(
+                base.zip(exponent)
+                    .map(|(base, exponent)| base.powf(exponent)),
+            );
+        }
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
 make_math_func!(SQRT, "sqrt", sqrt);
 make_math_func!(SIN, "sin", sin);
 make_math_func!(COS, "cos", cos);
@@ -64,13 +154,102 @@ make_math_func!(TAN, "tan", tan);
 make_math_func!(ASIN, "asin", asin);
 make_math_func!(ACOS, "acos", acos);
 make_math_func!(ATAN, "atan", atan);
-make_math_func!(FLOOR, "floor", floor);
-make_math_func!(CEIL, "ceil", ceil);
-make_math_func!(ROUND, "round", round);
-make_math_func!(TRUNC, "trunc", trunc);
-make_math_func!(ABS, "abs", abs);
 make_math_func!(SIGNUM, "signum", signum);
 make_math_func!(EXP, "exp", exp);
 make_math_func!(LN, "ln", ln);
 make_math_func!(LOG2, "log2", log2);
 make_math_func!(LOG10, "log10", log10);
+
+// Integers are already rounded.
+make_numeric_func!(FLOOR, "floor", Some, |x| Some(x.floor()));
+make_numeric_func!(CEIL, "ceil", Some, |x| Some(x.ceil()));
+make_numeric_func!(ROUND, "round", Some, |x| Some(x.round()));
+make_numeric_func!(TRUNC, "trunc", Some, |x| Some(x.trunc()));
+make_numeric_func!(ABS, "abs", |x| x.checked_abs(), |x| Some(x.abs()));
+// Unlike `signum`, the sign of zero is zero.
+make_numeric_func!(SIGN, "sign", |x| Some(x.signum()), |x| Some(if x == 0.0 {
+    x
+} else {
+    x.signum()
+}));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Int8Array, Scalar};
+    use crate::expr::funcs::call;
+
+    #[test]
+    fn test_numeric_funcs() {
+        let ints = Arc::new(Int8Array::from_opt_vec(vec![
+            Some(-3),
+            None,
+            Some(0),
+            Some(5),
+        ])) as ArrayRef;
+        let array = call(&ABS, std::slice::from_ref(&ints)).unwrap();
+        assert_eq!(array.data_type(), DataType::Int8);
+        assert_eq!(
+            array
+                .downcast_ref::<Int8Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(3), None, Some(0), Some(5)]
+        );
+        let array = call(&SIGN, std::slice::from_ref(&ints)).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int8Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(-1), None, Some(0), Some(1)]
+        );
+        assert_eq!(
+            call(&FLOOR, &[ints]).unwrap().scalar_value(3),
+            Scalar::Int8(5)
+        );
+
+        let overflow = Arc::new(Int8Array::new_scalar(2, Some(i8::MIN))) as ArrayRef;
+        assert_eq!(
+            call(&ABS, &[overflow]).unwrap_err().to_string(),
+            "integer overflow in the function 'abs'."
+        );
+
+        let floats = Arc::new(Float64Array::from_vec(vec![-2.5, 0.0, 1.4])) as ArrayRef;
+        let array = call(&ROUND, std::slice::from_ref(&floats)).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![-3.0, 0.0, 1.0]
+        );
+        let array = call(&SIGN, &[floats]).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![-1.0, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_power() {
+        let array = call(
+            &POWER,
+            &[
+                Arc::new(Float64Array::from_opt_vec(vec![Some(2.0), Some(9.0), None])) as ArrayRef,
+                Arc::new(Float64Array::new_scalar(3, Some(0.5))),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(2f64.sqrt()), Some(3.0), None]
+        );
+    }
+}
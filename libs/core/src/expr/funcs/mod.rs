@@ -1,27 +1,101 @@
 mod aggregate;
+mod anomaly;
+mod collect;
+mod conditional;
+mod convert;
+mod datetime;
+mod distinct;
+mod hash;
+mod list;
+mod map;
 mod math;
+mod percentile;
+mod record;
+mod regexp;
+mod session;
+mod url;
+mod user_agent;
 
 mod f_ref;
 
 use aggregate::*;
+use anomaly::*;
+use collect::*;
+use conditional::*;
+use convert::*;
+use datetime::*;
+use distinct::*;
+use hash::*;
 use math::*;
+use percentile::*;
+use regexp::*;
+use session::*;
+use url::*;
+use user_agent::*;
 
 use f_ref::*;
 
 use crate::expr::func::Function;
 
+pub(crate) use datetime::NOW;
+pub(crate) use list::{element_at, make_array, MAKE_ARRAY};
+pub(crate) use map::map_value;
+pub(crate) use record::struct_field;
+pub(crate) use regexp::groups_type;
+
 #[rustfmt::skip]
 const FUNCS: &[Function] = &[
     // math
-    SQRT, SIN, COS, TAN, ASIN, ACOS, ATAN, FLOOR, CEIL, ROUND, TRUNC, ABS, SIGNUM, EXP, LN, LOG2, LOG10,
+    SQRT, SIN, COS, TAN, ASIN, ACOS, ATAN, FLOOR, CEIL, ROUND, TRUNC, ABS, SIGNUM, SIGN, EXP, LN, LOG2, LOG10,
+    POWER,
+    
+    // regexp
+    REGEXP_GROUPS, REGEXP_MATCH, REGEXP_EXTRACT, REGEXP_REPLACE, GROK,
+    
+    // web
+    URL_HOST, URL_PATH, URL_PARAM, PARSE_USER_AGENT,
+    
+    // hash
+    MD5, SHA256, XXHASH,
+    
+    // conditional
+    COALESCE, NULLIF, GREATEST, LEAST,
+    
+    // date and time
+    DATE_TRUNC, EXTRACT, TO_TIMESTAMP, TO_DATE, TO_TIME, FROM_UNIXTIME, FORMAT_TIMESTAMP, NOW,
+    
+    // conversion
+    CONVERT,
+    
+    // array
+    MAKE_ARRAY,
     
     // aggregate
-    AVG, SUM, COUNT, MIN, MAX, FIRST, LAST,
+    AVG, SUM, COUNT, COUNT_IF, BOOL_AND, BOOL_OR, MIN, MAX, FIRST, LAST, FIRST_VALUE, LAST_VALUE, VAR_SAMP, VAR_POP, STDDEV_SAMP, STDDEV_POP, SKEWNESS,
+    APPROX_PERCENTILE, APPROX_COUNT_DISTINCT, STRING_AGG, ARRAY_AGG, TOP_K, BOTTOM_K,
+    
+    // anomaly detection
+    ANOMALY_SCORE, ROLLING_PERCENTILE,
+    
+    // sessions
+    SESSIONIZE,
     
     // ref
     ALL, ANY, BARSLAST, BARSSINCE,
 ];
 
+/// Calls the stateless `func` with `args`, for the tests of the functions.
+#[cfg(test)]
+fn call(
+    func: &Function,
+    args: &[crate::array::ArrayRef],
+) -> anyhow::Result<crate::array::ArrayRef> {
+    match func.function_type {
+        crate::expr::func::FunctionType::Stateless(f) => f(args),
+        crate::expr::func::FunctionType::Stateful(_) => unreachable!(),
+    }
+}
+
 pub fn find_function(namespace: Option<&str>, name: &str) -> Option<&'static Function> {
     FUNCS.iter().find(|func| match namespace {
         Some(namespace) => match func.namespace {
@@ -34,3 +108,17 @@ pub fn find_function(namespace: Option<&str>, name: &str) -> Option<&'static Fun
         None => func.namespace.is_none() && func.name.eq_ignore_ascii_case(&name),
     })
 }
+
+/// The names of the builtin functions in `namespace`.
+pub(crate) fn function_names(namespace: Option<&str>) -> impl Iterator<Item = &'static str> + '_ {
+    FUNCS
+        .iter()
+        .filter(move |func| match (namespace, func.namespace) {
+            (Some(namespace), Some(func_namespace)) => {
+                func_namespace.eq_ignore_ascii_case(namespace)
+            }
+            (None, None) => true,
+            _ => false,
+        })
+        .map(|func| func.name)
+}
@@ -0,0 +1,475 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::array::{
+    Array, ArrayExt, DataType, Float64Array, Float64Builder, Int64Array, Scalar, StringArray,
+};
+use crate::expr::func::{AggregateFunction, Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// Bounds the number of centroids of a [`TDigest`], about twice as many are kept.
+const COMPRESSION: f64 = 100.0;
+
+/// The number of values buffered before they are merged into the centroids.
+const BUFFER_SIZE: usize = 500;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// A merging t-digest, a sketch of the distribution of a stream of values that estimates its
+/// quantiles in bounded memory.
+///
+/// The centroids are small near the tails, so that extreme quantiles such as the 99th percentile
+/// stay accurate.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct TDigest {
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    count: f64,
+    min: f64,
+    max: f64,
+}
+
+/// The scale function, maps a quantile to the index of its centroid.
+fn k(q: f64) -> f64 {
+    COMPRESSION / (2.0 * PI) * (2.0 * q - 1.0).asin()
+}
+
+/// The inverse of [`k`].
+fn k_inverse(k: f64) -> f64 {
+    if k >= COMPRESSION / 4.0 {
+        1.0
+    } else {
+        ((k * 2.0 * PI / COMPRESSION).sin() + 1.0) / 2.0
+    }
+}
+
+impl TDigest {
+    pub(crate) fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        if self.count == 0.0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1.0;
+        self.buffer.push(value);
+        if self.buffer.len() >= BUFFER_SIZE {
+            self.compress();
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: &TDigest) {
+        if other.count == 0.0 {
+            return;
+        }
+        if self.count == 0.0 {
+            *self = other.clone();
+            return;
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+        self.centroids.extend_from_slice(&other.centroids);
+        self.buffer.extend_from_slice(&other.buffer);
+        self.compress();
+    }
+
+    /// Merges the buffered values and the centroids that are close enough to each other.
+    fn compress(&mut self) {
+        let mut centroids = std::mem::take(&mut self.centroids);
+        centroids.extend(self.buffer.drain(..).map(|value| Centroid {
+            mean: value,
+            weight: 1.0,
+        }));
+        centroids.sort_by(|a, b| a.mean.partial_cmp(&b.mean).unwrap_or(Ordering::Equal));
+
+        let mut centroids = centroids.into_iter();
+        let mut current = match centroids.next() {
+            Some(centroid) => centroid,
+            None => return,
+        };
+        let mut weight_before = 0.0;
+        let mut limit = self.count * k_inverse(k(0.0) + 1.0);
+        for centroid in centroids {
+            if weight_before + current.weight + centroid.weight <= limit {
+                let weight = current.weight + centroid.weight;
+                current.mean += (centroid.mean - current.mean) * centroid.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_before += current.weight;
+                self.centroids.push(current);
+                limit = self.count * k_inverse(k(weight_before / self.count) + 1.0);
+                current = centroid;
+            }
+        }
+        self.centroids.push(current);
+    }
+
+    /// Estimates the value at quantile `q`, `None` if no value was added.
+    pub(crate) fn quantile(&mut self, q: f64) -> Option<f64> {
+        if self.count == 0.0 {
+            return None;
+        }
+        if !self.buffer.is_empty() {
+            self.compress();
+        }
+        if q <= 0.0 {
+            return Some(self.min);
+        }
+        if q >= 1.0 {
+            return Some(self.max);
+        }
+
+        // The centroids are treated as points at the middle of their weight, the quantiles in
+        // between are interpolated.
+        let target = q * self.count;
+        let first = self.centroids[0];
+        if target < first.weight / 2.0 {
+            return Some(self.min + (first.mean - self.min) * target / (first.weight / 2.0));
+        }
+
+        let mut position = first.weight / 2.0;
+        for pair in self.centroids.windows(2) {
+            let distance = (pair[0].weight + pair[1].weight) / 2.0;
+            if target <= position + distance {
+                let fraction = (target - position) / distance;
+                return Some(pair[0].mean + (pair[1].mean - pair[0].mean) * fraction);
+            }
+            position += distance;
+        }
+
+        let last = self.centroids[self.centroids.len() - 1];
+        let fraction = ((target - position) / (last.weight / 2.0)).min(1.0);
+        Some(last.mean + (self.max - last.mean) * fraction)
+    }
+}
+
+/// The state of `approx_percentile`, with the percentile of the last row to return the result
+/// of merged states.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ApproxPercentile {
+    digest: TDigest,
+    percentile: Option<f64>,
+}
+
+impl ApproxPercentile {
+    fn merge(&mut self, other: &ApproxPercentile) {
+        self.digest.merge(&other.digest);
+        if other.percentile.is_some() {
+            self.percentile = other.percentile;
+        }
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.digest.clone().quantile(self.percentile?)
+    }
+}
+
+/// `approx_percentile(x, p)`, an estimate of the value below which a fraction `p` of the values
+/// of `x` fall, computed with a [`TDigest`].
+pub const APPROX_PERCENTILE: Function = Function {
+    namespace: None,
+    name: "approx_percentile",
+    signature: &Signature::Uniform(2, &[DataType::Float64]),
+    return_type: |_| DataType::Float64,
+    deterministic: false,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            AggregateFunction::<ApproxPercentile>::new(|state, args| {
+                let array = args[0].downcast_ref::<Float64Array>();
+                let percentiles = args[1].downcast_ref::<Float64Array>();
+                let mut builder = Float64Builder::with_capacity(array.len());
+                for (value, percentile) in array.iter_opt().zip(percentiles.iter_opt()) {
+                    if let Some(value) = value {
+                        state.digest.add(value);
+                    }
+                    match percentile {
+                        Some(percentile) => {
+                            anyhow::ensure!(
+                                (0.0..=1.0).contains(&percentile),
+                                "the percentile of approx_percentile must be between 0 and 1, \
+                                 but it is {}",
+                                percentile
+                            );
+                            state.percentile = Some(percentile);
+                            builder.append_opt(state.digest.quantile(percentile));
+                        }
+                        None => builder.append_null(),
+                    }
+                }
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(ApproxPercentile::merge, |state, _| {
+                state.current().map_or(Scalar::Null, Scalar::Float64)
+            }),
+        )
+    }),
+};
+
+/// The number of panes of the window of `rolling_percentile`.
+const PANES: usize = 4;
+
+/// The last values of a key, in panes of consecutive values with a digest each.
+///
+/// Digests can't forget values, so the oldest pane is dropped as a whole once the window is
+/// full, and the percentile covers between the last three quarters of the window and the whole
+/// window.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RollingDigest {
+    panes: VecDeque<(TDigest, usize)>,
+}
+
+impl RollingDigest {
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let mut digest = TDigest::default();
+        for (pane, _) in &self.panes {
+            digest.merge(pane);
+        }
+        digest.quantile(q)
+    }
+
+    fn push(&mut self, value: f64, window: usize) {
+        let pane_size = (window + PANES - 1) / PANES;
+        match self.panes.back_mut() {
+            Some((digest, count)) if *count < pane_size => {
+                digest.add(value);
+                *count += 1;
+            }
+            _ => {
+                let mut digest = TDigest::default();
+                digest.add(value);
+                self.panes.push_back((digest, 1));
+            }
+        }
+        while self.panes.len() > (window + pane_size - 1) / pane_size {
+            self.panes.pop_front();
+        }
+    }
+}
+
+/// `rolling_percentile(x, p, n[, key])`
+///
+/// Returns an estimate of the percentile `p` of the previous `n` values of `x` with the same
+/// key, or null if there are no previous values. Rows can be flagged with a filter like
+/// `latency > rolling_percentile(latency, 0.99, 1000)`.
+pub const ROLLING_PERCENTILE: Function = Function {
+    namespace: None,
+    name: "rolling_percentile",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::Float64, DataType::Float64, DataType::Int64]),
+        Signature::Exact(&[
+            DataType::Float64,
+            DataType::Float64,
+            DataType::Int64,
+            DataType::String,
+        ]),
+    ]),
+    return_type: |_| DataType::Float64,
+    deterministic: false,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(AggregateFunction::<HashMap<String, RollingDigest>>::new(
+            |state, args| {
+                let array = args[0].downcast_ref::<Float64Array>();
+                let percentiles = args[1].downcast_ref::<Float64Array>();
+                let window_sizes = args[2].downcast_ref::<Int64Array>();
+                let keys = args.get(3).map(|array| array.downcast_ref::<StringArray>());
+                let mut builder = Float64Builder::with_capacity(array.len());
+
+                for (idx, value) in array.iter_opt().enumerate() {
+                    let window = match window_sizes.value_opt(idx) {
+                        Some(n) if n > 0 => n as usize,
+                        _ => anyhow::bail!("the window size must be greater than 0"),
+                    };
+                    let key = keys
+                        .and_then(|keys| keys.value_opt(idx))
+                        .unwrap_or_default();
+                    let rolling = match state.get_mut(key) {
+                        Some(rolling) => rolling,
+                        None => state.entry(key.to_string()).or_default(),
+                    };
+
+                    match percentiles.value_opt(idx) {
+                        Some(percentile) => {
+                            anyhow::ensure!(
+                                (0.0..=1.0).contains(&percentile),
+                                "the percentile of rolling_percentile must be between 0 and 1, \
+                                 but it is {}",
+                                percentile
+                            );
+                            builder.append_opt(rolling.quantile(percentile));
+                        }
+                        None => builder.append_null(),
+                    }
+                    if let Some(value) = value {
+                        rolling.push(value, window);
+                    }
+                }
+
+                Ok(Arc::new(builder.finish()))
+            },
+        ))
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayRef;
+
+    fn digest(values: impl IntoIterator<Item = f64>) -> TDigest {
+        let mut digest = TDigest::default();
+        for value in values {
+            digest.add(value);
+        }
+        digest
+    }
+
+    #[test]
+    fn test_tdigest() {
+        let mut sketch = digest((1..=100_000).map(f64::from));
+        for q in &[0.01, 0.25, 0.5, 0.75, 0.95, 0.99] {
+            let value = sketch.quantile(*q).unwrap();
+            let expected = q * 100_000.0;
+            assert!(
+                (value - expected).abs() / expected < 0.01,
+                "q={} value={}",
+                q,
+                value
+            );
+        }
+        assert_eq!(sketch.quantile(0.0), Some(1.0));
+        assert_eq!(sketch.quantile(1.0), Some(100_000.0));
+        assert!(sketch.centroids.len() <= 2 * COMPRESSION as usize);
+
+        assert_eq!(TDigest::default().quantile(0.5), None);
+        assert_eq!(digest(vec![42.0]).quantile(0.5), Some(42.0));
+    }
+
+    #[test]
+    fn test_tdigest_merge() {
+        let mut merged = digest((1..=50_000).map(f64::from));
+        merged.merge(&digest((50_001..=100_000).map(f64::from)));
+        let p95 = merged.quantile(0.95).unwrap();
+        assert!((p95 - 95_000.0).abs() < 950.0, "{}", p95);
+        assert_eq!(merged.quantile(0.0), Some(1.0));
+        assert_eq!(merged.quantile(1.0), Some(100_000.0));
+
+        let mut empty = TDigest::default();
+        empty.merge(&merged);
+        assert_eq!(empty.quantile(0.95), merged.quantile(0.95));
+    }
+
+    #[test]
+    fn test_approx_percentile() {
+        let mut f = APPROX_PERCENTILE.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_opt_vec(vec![Some(1.0), None, Some(3.0)])) as ArrayRef,
+                Arc::new(Float64Array::new_scalar(3, Some(0.5))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1.0, 1.0, 2.0]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = APPROX_PERCENTILE.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        assert_eq!(f.current(DataType::Float64).unwrap(), Scalar::Float64(2.0));
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![5.0])) as ArrayRef,
+                Arc::new(Float64Array::new_scalar(1, Some(1.0))),
+            ])
+            .unwrap();
+        assert_eq!(array.downcast_ref::<Float64Array>().value(0), 5.0);
+
+        assert!(f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![5.0])) as ArrayRef,
+                Arc::new(Float64Array::new_scalar(1, Some(95.0))),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_rolling_digest() {
+        let mut rolling = RollingDigest::default();
+        for value in 1..=10 {
+            rolling.push(f64::from(value), 2);
+        }
+        assert_eq!(rolling.quantile(0.0), Some(9.0));
+        assert_eq!(rolling.quantile(1.0), Some(10.0));
+
+        let mut rolling = RollingDigest::default();
+        for value in 1..=1000 {
+            rolling.push(f64::from(value), 100);
+        }
+        assert_eq!(rolling.panes.len(), 4);
+        assert_eq!(rolling.quantile(0.0), Some(901.0));
+        assert_eq!(rolling.quantile(1.0), Some(1000.0));
+    }
+
+    #[test]
+    fn test_rolling_percentile() {
+        let mut f = ROLLING_PERCENTILE.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_opt_vec(vec![
+                    Some(1.0),
+                    Some(5.0),
+                    None,
+                    Some(3.0),
+                    Some(7.0),
+                ])) as ArrayRef,
+                Arc::new(Float64Array::new_scalar(5, Some(1.0))),
+                Arc::new(Int64Array::new_scalar(5, Some(2))),
+                Arc::new(StringArray::from_vec(vec!["a", "a", "a", "b", "a"])),
+            ])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![None, Some(1.0), Some(5.0), None, Some(5.0)]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = ROLLING_PERCENTILE.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![0.0])) as ArrayRef,
+                Arc::new(Float64Array::new_scalar(1, Some(0.0))),
+                Arc::new(Int64Array::new_scalar(1, Some(2))),
+                Arc::new(StringArray::from_vec(vec!["a"])),
+            ])
+            .unwrap();
+        assert_eq!(array.downcast_ref::<Float64Array>().value(0), 5.0);
+
+        assert!(f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![1.0])) as ArrayRef,
+                Arc::new(Float64Array::new_scalar(1, Some(0.5))),
+                Arc::new(Int64Array::new_scalar(1, Some(0))),
+            ])
+            .is_err());
+    }
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+
+use crate::array::{Array, ArrayExt, ArrayRef, StringArray, StructArray};
+
+/// Returns the field of a struct, used for `value.field`.
+///
+/// The name of the field is the second argument, the result is null if the struct is null.
+pub(crate) fn struct_field(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let structs = args[0].downcast_ref::<StructArray>();
+    let names = args[1].downcast_ref::<StringArray>();
+    anyhow::ensure!(!names.is_empty(), "the field name is empty");
+    let name = names.value(0);
+    match structs.field(name) {
+        Some(array) => Ok(array),
+        None => anyhow::bail!(
+            "the field '{}' does not exist in '{}'",
+            name,
+            structs.data_type()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::Int64Array;
+
+    #[test]
+    fn test_struct_field() {
+        let structs = StructArray::new(
+            2,
+            vec![(
+                "a".to_string(),
+                Arc::new(Int64Array::from_vec(vec![1, 2])) as ArrayRef,
+            )],
+            Some(vec![false, true]),
+        );
+        let names = StringArray::new_scalar(2, Some("a"));
+        let array = struct_field(&[Arc::new(structs.clone()), Arc::new(names)]).unwrap();
+        assert_eq!(
+            &*array,
+            &Int64Array::from_opt_vec(vec![None, Some(2)]) as &dyn Array
+        );
+
+        let names = StringArray::new_scalar(2, Some("b"));
+        assert!(struct_field(&[Arc::new(structs), Arc::new(names)]).is_err());
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use regex::Regex;
+
+use crate::array::{
+    Array, ArrayExt, ArrayRef, BooleanArray, BooleanBuilder, DataType, Int64Array, StringArray,
+    StringBuilder, StructArray, StructField,
+};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+
+#[rustfmt::skip]
+static GROK_PATTERNS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    vec![
+        ("WORD", r"\b\w+\b"),
+        ("NOTSPACE", r"\S+"),
+        ("SPACE", r"\s*"),
+        ("DATA", r".*?"),
+        ("GREEDYDATA", r".*"),
+        ("INT", r"[+-]?\d+"),
+        ("NUMBER", r"[+-]?(?:\d+(?:\.\d*)?|\.\d+)"),
+        ("QS", r#""(?:[^"\\]|\\.)*""#),
+        ("UUID", r"[A-Fa-f0-9]{8}-(?:[A-Fa-f0-9]{4}-){3}[A-Fa-f0-9]{12}"),
+        ("IPV4", r"(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)"),
+        ("IPV6", r"[0-9A-Fa-f:]*:[0-9A-Fa-f:.]+"),
+        ("IP", r"(?:%{IPV6}|%{IPV4})"),
+        ("HOSTNAME", r"\b[0-9A-Za-z][0-9A-Za-z-]{0,62}(?:\.[0-9A-Za-z][0-9A-Za-z-]{0,62})*\.?\b"),
+        ("IPORHOST", r"(?:%{IP}|%{HOSTNAME})"),
+        ("USER", r"[a-zA-Z0-9._-]+"),
+        ("URIPATH", r"(?:/[A-Za-z0-9$.+!*'(){},~:;=@#%&_\-]*)+"),
+        ("URIPARAM", r"\?[A-Za-z0-9$.+!*'|(){},~@#%&/=:;_?\-\[\]<>]*"),
+        ("URIPATHPARAM", r"%{URIPATH}(?:%{URIPARAM})?"),
+        ("LOGLEVEL", r"(?i:trace|debug|info|notice|warn(?:ing)?|err(?:or)?|crit(?:ical)?|fatal|severe|emerg(?:ency)?|alert)"),
+        ("MONTH", r"\b(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)[a-z]*\b"),
+        ("HTTPDATE", r"\d{2}/%{MONTH}/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}"),
+        ("TIMESTAMP_ISO8601", r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?"),
+        ("COMMONAPACHELOG", r#"%{IPORHOST:clientip} %{USER:ident} %{USER:auth} \[%{HTTPDATE:timestamp}\] "(?:%{WORD:verb} %{NOTSPACE:request}(?: HTTP/%{NUMBER:httpversion})?|%{DATA:rawrequest})" %{NUMBER:response} (?:%{NUMBER:bytes}|-)"#),
+    ]
+    .into_iter()
+    .collect()
+});
+
+/// Maximum number of compiled patterns kept in the cache.
+const MAX_CACHED_REGEXES: usize = 256;
+
+/// The compiled patterns by their source, so that each batch doesn't compile them again.
+static REGEX_CACHE: Lazy<Mutex<HashMap<String, Regex>>> = Lazy::new(Default::default);
+
+/// Returns the compiled `pattern`, from the cache if it was compiled before.
+fn cached_regex(pattern: &str) -> Result<Regex> {
+    let mut cache = REGEX_CACHE.lock();
+    if let Some(regex) = cache.get(pattern) {
+        return Ok(regex.clone());
+    }
+    let regex = Regex::new(pattern)?;
+    if cache.len() >= MAX_CACHED_REGEXES {
+        cache.clear();
+    }
+    cache.insert(pattern.to_string(), regex.clone());
+    Ok(regex)
+}
+
+static GROK_REFERENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"%\{(\w+)(?::(\w+))?\}").unwrap());
+
+/// Maximum nesting depth of grok pattern references.
+const MAX_GROK_DEPTH: usize = 16;
+
+/// Expands the `%{PATTERN}` and `%{PATTERN:field}` references of a grok pattern into a regular
+/// expression, where each `field` becomes a named capture group.
+fn expand_grok(pattern: &str, depth: usize) -> Result<String> {
+    anyhow::ensure!(
+        depth < MAX_GROK_DEPTH,
+        "grok patterns are nested too deeply"
+    );
+
+    let mut res = String::new();
+    let mut last = 0;
+    for captures in GROK_REFERENCE.captures_iter(pattern) {
+        let m = captures.get(0).unwrap();
+        let name = &captures[1];
+        let definition = GROK_PATTERNS
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("unknown grok pattern: '{}'", name))?;
+        let definition = expand_grok(definition, depth + 1)?;
+
+        res.push_str(&pattern[last..m.start()]);
+        match captures.get(2) {
+            Some(field) => res.push_str(&format!("(?P<{}>{})", field.as_str(), definition)),
+            None => res.push_str(&format!("(?:{})", definition)),
+        }
+        last = m.end();
+    }
+    res.push_str(&pattern[last..]);
+    Ok(res)
+}
+
+pub(super) fn constant_string_arg<'a>(
+    args: &'a [ArrayRef],
+    idx: usize,
+    name: &str,
+) -> Result<&'a str> {
+    args[idx]
+        .downcast_ref::<StringArray>()
+        .to_scalar()
+        .flatten()
+        .ok_or_else(|| anyhow::anyhow!("the {} argument must be a constant string", name))
+}
+
+/// Extracts a capture group, by name or by index, from each string of `array`.
+fn extract_group(array: &ArrayRef, regex: &Regex, group: &str) -> Result<ArrayRef> {
+    let index = group.parse::<usize>().ok();
+    anyhow::ensure!(
+        match index {
+            Some(index) => index < regex.captures_len(),
+            None => regex.capture_names().any(|name| name == Some(group)),
+        },
+        "capture group '{}' not found",
+        group
+    );
+
+    let extract = |s: &str| {
+        regex.captures(s).and_then(|captures| {
+            match index {
+                Some(index) => captures.get(index),
+                None => captures.name(group),
+            }
+            .map(|m| m.as_str().to_string())
+        })
+    };
+
+    let array = array.downcast_ref::<StringArray>();
+    if let Some(scalar) = array.to_scalar() {
+        return Ok(Arc::new(StringArray::new_scalar(
+            array.len(),
+            scalar.and_then(extract),
+        )));
+    }
+    let mut builder = StringBuilder::with_capacity(array.len());
+    for value in array.iter_opt() {
+        builder.append_opt(value.and_then(extract).as_deref());
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Compiles the pattern of `regexp_groups`, or of `grok` if `grok` is true.
+fn groups_regex(pattern: &str, grok: bool) -> Result<Regex> {
+    let regex = if grok {
+        cached_regex(&expand_grok(pattern, 0)?)?
+    } else {
+        cached_regex(pattern)?
+    };
+    anyhow::ensure!(
+        regex.capture_names().flatten().next().is_some(),
+        "the pattern has no named capture groups: '{}'",
+        pattern
+    );
+    Ok(regex)
+}
+
+/// Returns the type of the results of `func` called with the constant `pattern`, if it is
+/// `regexp_groups` or `grok`, a struct with a string field for each named capture group of the
+/// pattern.
+pub(crate) fn groups_type(func: &Function, pattern: Option<&str>) -> Result<Option<DataType>> {
+    let grok = match (func.namespace, func.name) {
+        (None, name) if name == REGEXP_GROUPS.name => false,
+        (None, name) if name == GROK.name => true,
+        _ => return Ok(None),
+    };
+    let pattern = pattern.ok_or_else(|| {
+        anyhow::anyhow!(
+            "the pattern argument of '{}' must be a constant string",
+            func.name
+        )
+    })?;
+    let fields = groups_regex(pattern, grok)?
+        .capture_names()
+        .flatten()
+        .map(|name| StructField::new(name, DataType::String))
+        .collect();
+    Ok(Some(DataType::struct_type(fields)))
+}
+
+/// Extracts the named capture groups of `regex` from each string of `array`, into a struct with
+/// a field for each group.
+fn extract_groups(array: &ArrayRef, regex: &Regex) -> ArrayRef {
+    let array = array.downcast_ref::<StringArray>();
+    let names = regex.capture_names().flatten().collect::<Vec<_>>();
+    let mut builders = names
+        .iter()
+        .map(|_| StringBuilder::with_capacity(array.len()))
+        .collect::<Vec<_>>();
+    for value in array.iter_opt() {
+        let captures = value.and_then(|s| regex.captures(s));
+        for (builder, name) in builders.iter_mut().zip(&names) {
+            let group = captures.as_ref().and_then(|captures| captures.name(name));
+            builder.append_opt(group.map(|m| m.as_str()));
+        }
+    }
+
+    let fields = names
+        .iter()
+        .zip(builders)
+        .map(|(name, builder)| (name.to_string(), Arc::new(builder.finish()) as ArrayRef))
+        .collect();
+    let validity = (0..array.len()).map(|idx| array.is_valid(idx)).collect();
+    Arc::new(StructArray::new(array.len(), fields, Some(validity)))
+}
+
+/// `regexp_groups(s, pattern)`, the named capture groups of the first match of `pattern`, as a
+/// struct with a field for each group.
+pub const REGEXP_GROUPS: Function = Function {
+    namespace: None,
+    name: "regexp_groups",
+    signature: &Signature::Exact(&[DataType::String, DataType::String]),
+    // The fields depend on the pattern, see `groups_type`.
+    return_type: |_| DataType::String,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let regex = groups_regex(constant_string_arg(args, 1, "pattern")?, false)?;
+        Ok(extract_groups(&args[0], &regex))
+    }),
+};
+
+/// `grok(s, pattern)`, the fields of a grok pattern, as a struct with a field for each
+/// `%{PATTERN:field}` reference.
+pub const GROK: Function = Function {
+    namespace: None,
+    name: "grok",
+    signature: &Signature::Exact(&[DataType::String, DataType::String]),
+    // The fields depend on the pattern, see `groups_type`.
+    return_type: |_| DataType::String,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let regex = groups_regex(constant_string_arg(args, 1, "pattern")?, true)?;
+        Ok(extract_groups(&args[0], &regex))
+    }),
+};
+
+/// `regexp_match(s, pattern)`, whether `pattern` matches any part of `s`.
+pub const REGEXP_MATCH: Function = Function {
+    namespace: None,
+    name: "regexp_match",
+    signature: &Signature::Exact(&[DataType::String, DataType::String]),
+    return_type: |_| DataType::Boolean,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let regex = cached_regex(constant_string_arg(args, 1, "pattern")?)?;
+        let array = args[0].downcast_ref::<StringArray>();
+        if let Some(scalar) = array.to_scalar() {
+            return Ok(Arc::new(BooleanArray::new_scalar(
+                array.len(),
+                scalar.map(|s| regex.is_match(s)),
+            )));
+        }
+        let mut builder = BooleanBuilder::with_capacity(array.len());
+        for value in array.iter_opt() {
+            builder.append_opt(value.map(|s| regex.is_match(s)));
+        }
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+/// `regexp_extract(s, pattern[, group])`, the capture group `group` of the first match, the
+/// whole match if it is omitted.
+pub const REGEXP_EXTRACT: Function = Function {
+    namespace: None,
+    name: "regexp_extract",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::String, DataType::String]),
+        Signature::Exact(&[DataType::String, DataType::String, DataType::Int64]),
+    ]),
+    return_type: |_| DataType::String,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let regex = cached_regex(constant_string_arg(args, 1, "pattern")?)?;
+        let group = match args.get(2) {
+            Some(group) => group
+                .downcast_ref::<Int64Array>()
+                .to_scalar()
+                .flatten()
+                .filter(|group| *group >= 0)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("the group argument must be a constant non-negative integer")
+                })?,
+            None => 0,
+        };
+        extract_group(&args[0], &regex, &group.to_string())
+    }),
+};
+
+/// `regexp_replace(s, pattern, replacement)`, replaces all matches of `pattern`, the
+/// replacement can refer to the capture groups with `$1` or `${name}`.
+pub const REGEXP_REPLACE: Function = Function {
+    namespace: None,
+    name: "regexp_replace",
+    signature: &Signature::Exact(&[DataType::String, DataType::String, DataType::String]),
+    return_type: |_| DataType::String,
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let regex = cached_regex(constant_string_arg(args, 1, "pattern")?)?;
+        let replacement = constant_string_arg(args, 2, "replacement")?;
+        let array = args[0].downcast_ref::<StringArray>();
+        if let Some(scalar) = array.to_scalar() {
+            return Ok(Arc::new(StringArray::new_scalar(
+                array.len(),
+                scalar.map(|s| regex.replace_all(s, replacement).into_owned()),
+            )));
+        }
+        let mut builder = StringBuilder::with_capacity(array.len());
+        for value in array.iter_opt() {
+            builder.append_opt(value.map(|s| regex.replace_all(s, replacement)).as_deref());
+        }
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::funcs::call;
+
+    fn call_groups(func: &Function, values: Vec<&str>, pattern: &str) -> StructArray {
+        let array = call(
+            func,
+            &[
+                Arc::new(StringArray::from_vec(values)),
+                Arc::new(StringArray::new_scalar(1, Some(pattern))),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            Some(array.data_type()),
+            groups_type(func, Some(pattern)).unwrap()
+        );
+        array.downcast_ref::<StructArray>().clone()
+    }
+
+    fn field(array: &StructArray, name: &str) -> Vec<Option<String>> {
+        array
+            .field(name)
+            .unwrap()
+            .downcast_ref::<StringArray>()
+            .iter_opt()
+            .map(|value| value.map(ToString::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn test_regexp_groups() {
+        let array = call_groups(
+            &REGEXP_GROUPS,
+            vec!["a=1", "b=2", "c"],
+            r"(?P<key>\w)=(?P<value>\d)",
+        );
+        assert_eq!(
+            array
+                .fields()
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["key", "value"]
+        );
+        assert_eq!(
+            field(&array, "key"),
+            vec![Some("a".to_string()), Some("b".to_string()), None]
+        );
+        assert_eq!(
+            field(&array, "value"),
+            vec![Some("1".to_string()), Some("2".to_string()), None]
+        );
+
+        assert!(groups_type(&REGEXP_GROUPS, Some(r"(\w)=(\d)")).is_err());
+        assert!(groups_type(&REGEXP_GROUPS, None).is_err());
+        assert_eq!(groups_type(&REGEXP_MATCH, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_grok() {
+        let line = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#;
+        let array = call_groups(&GROK, vec![line], "%{COMMONAPACHELOG}");
+        assert_eq!(
+            field(&array, "request"),
+            vec![Some("/apache_pb.gif".to_string())]
+        );
+        assert_eq!(field(&array, "response"), vec![Some("200".to_string())]);
+
+        let array = call_groups(&GROK, vec!["level=WARN", "none"], "level=%{LOGLEVEL:level}");
+        assert_eq!(field(&array, "level"), vec![Some("WARN".to_string()), None]);
+        assert!(expand_grok("%{UNKNOWN}", 0).is_err());
+    }
+
+    fn strings(values: Vec<Option<&str>>) -> ArrayRef {
+        Arc::new(StringArray::from_opt_vec(values))
+    }
+
+    fn string(value: &str) -> ArrayRef {
+        Arc::new(StringArray::new_scalar(3, Some(value)))
+    }
+
+    #[test]
+    fn test_regexp_match() {
+        let array = call(
+            &REGEXP_MATCH,
+            &[
+                strings(vec![Some("ERROR: disk"), Some("ok"), None]),
+                string("^ERROR"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<BooleanArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(true), Some(false), None]
+        );
+        assert!(call(&REGEXP_MATCH, &[string("a"), string("(")]).is_err());
+    }
+
+    #[test]
+    fn test_regexp_extract() {
+        let values = strings(vec![Some("took 15ms"), Some("took 7s"), Some("failed")]);
+        let array = call(&REGEXP_EXTRACT, &[values.clone(), string(r"\d+(ms|s)")]).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some("15ms"), Some("7s"), None]
+        );
+
+        let array = call(
+            &REGEXP_EXTRACT,
+            &[
+                values.clone(),
+                string(r"(\d+)(ms|s)"),
+                Arc::new(Int64Array::new_scalar(3, Some(2))),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some("ms"), Some("s"), None]
+        );
+
+        assert!(call(
+            &REGEXP_EXTRACT,
+            &[
+                values,
+                string(r"(\d+)"),
+                Arc::new(Int64Array::new_scalar(3, Some(2))),
+            ],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_regexp_replace() {
+        let array = call(
+            &REGEXP_REPLACE,
+            &[
+                strings(vec![Some("user=alice id=1"), None, Some("none")]),
+                string(r"(\w+)=(\w+)"),
+                string("$2:$1"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some("alice:user 1:id"), None, Some("none")]
+        );
+    }
+
+    #[test]
+    fn test_regex_cache() {
+        let a = cached_regex("cache-test-[0-9]+").unwrap();
+        let b = cached_regex("cache-test-[0-9]+").unwrap();
+        assert_eq!(a.as_str(), b.as_str());
+        assert!(REGEX_CACHE.lock().contains_key("cache-test-[0-9]+"));
+        assert!(cached_regex("[").is_err());
+    }
+}
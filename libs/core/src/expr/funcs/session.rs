@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::array::{
+    Array, ArrayExt, DataType, Int64Array, Int64Builder, StringArray, TimestampArray,
+};
+use crate::expr::func::{AggregateFunction, Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// The current session of a key.
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct Session {
+    id: i64,
+    last_time: i64,
+}
+
+/// `sessionize(ts, gap[, key])`
+///
+/// Returns the number of the session of the row within its key, starting at 1. A new session
+/// starts when `ts` is more than `gap` milliseconds after the latest time of the current
+/// session, rows that are out of order stay in the current session.
+///
+/// Unlike a session window, the rows are not aggregated, so that the queries can group by the
+/// session and the key as they need.
+pub const SESSIONIZE: Function = Function {
+    namespace: None,
+    name: "sessionize",
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::Timestamp(None), DataType::Int64]),
+        Signature::Exact(&[DataType::Timestamp(None), DataType::Int64, DataType::String]),
+    ]),
+    return_type: |_| DataType::Int64,
+    deterministic: false,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(AggregateFunction::<HashMap<String, Session>>::new(
+            |state, args| {
+                let times = args[0].downcast_ref::<TimestampArray>();
+                let gaps = args[1].downcast_ref::<Int64Array>();
+                let keys = args.get(2).map(|array| array.downcast_ref::<StringArray>());
+                let mut builder = Int64Builder::with_capacity(times.len());
+
+                for (idx, (time, gap)) in times.iter_opt().zip(gaps.iter_opt()).enumerate() {
+                    let (time, gap) = match (time, gap) {
+                        (Some(time), Some(gap)) => (time, gap),
+                        _ => {
+                            builder.append_null();
+                            continue;
+                        }
+                    };
+                    anyhow::ensure!(gap >= 0, "the session gap must not be negative");
+                    let key = keys
+                        .and_then(|keys| keys.value_opt(idx))
+                        .unwrap_or_default();
+
+                    let session = match state.get_mut(key) {
+                        Some(session) => session,
+                        None => state.entry(key.to_string()).or_default(),
+                    };
+                    if session.id == 0 || time - session.last_time > gap {
+                        session.id += 1;
+                        session.last_time = time;
+                    } else {
+                        session.last_time = session.last_time.max(time);
+                    }
+                    builder.append(session.id);
+                }
+
+                Ok(Arc::new(builder.finish()))
+            },
+        ))
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayRef;
+
+    fn sessions(array: ArrayRef) -> Vec<Option<i64>> {
+        array.downcast_ref::<Int64Array>().iter_opt().collect()
+    }
+
+    #[test]
+    fn test_sessionize() {
+        let mut f = SESSIONIZE.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(TimestampArray::from_opt_vec(vec![
+                    Some(1000),
+                    Some(2000),
+                    None,
+                    Some(1500),
+                    Some(5000),
+                ])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(5, Some(2000))),
+            ])
+            .unwrap();
+        assert_eq!(
+            sessions(array),
+            vec![Some(1), Some(1), None, Some(1), Some(2)]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = SESSIONIZE.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+
+        let array = f
+            .call(&[
+                Arc::new(TimestampArray::from_vec(vec![7000, 9001])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(2, Some(2000))),
+            ])
+            .unwrap();
+        assert_eq!(sessions(array), vec![Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_sessionize_keyed() {
+        let mut f = SESSIONIZE.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(TimestampArray::from_vec(vec![1000, 1000, 4000, 2000])) as ArrayRef,
+                Arc::new(Int64Array::new_scalar(4, Some(1500))),
+                Arc::new(StringArray::from_vec(vec!["a", "b", "a", "b"])),
+            ])
+            .unwrap();
+        assert_eq!(sessions(array), vec![Some(1), Some(1), Some(2), Some(1)]);
+    }
+}
@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::url::map_strings;
+use crate::array::{Array, DataType, StructArray, StructField};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// A list of `(pattern, name)` pairs, the first matching pattern wins.
+///
+/// A `$1` in the name is replaced with the first capture group of the pattern.
+type Rules = Lazy<Vec<(Regex, &'static str)>>;
+
+fn rules(rules: &[(&str, &'static str)]) -> Vec<(Regex, &'static str)> {
+    rules
+        .iter()
+        .map(|(pattern, name)| (Regex::new(pattern).unwrap(), *name))
+        .collect()
+}
+
+#[rustfmt::skip]
+static BROWSERS: Rules = Lazy::new(|| rules(&[
+    (r"(?i)bot|crawler|spider|slurp|curl/|wget/", "Bot"),
+    (r"Edg(?:e|A|iOS)?/", "Edge"),
+    (r"OPR/|Opera", "Opera"),
+    (r"SamsungBrowser/", "Samsung Internet"),
+    (r"YaBrowser/", "Yandex Browser"),
+    (r"UCBrowser/", "UC Browser"),
+    (r"MicroMessenger/", "WeChat"),
+    (r"FxiOS/|Firefox/", "Firefox"),
+    (r"CriOS/|Chromium/|Chrome/", "Chrome"),
+    (r"MSIE |Trident/", "Internet Explorer"),
+    (r"Version/[\d.]+.*Safari/", "Safari"),
+]));
+
+#[rustfmt::skip]
+static OPERATING_SYSTEMS: Rules = Lazy::new(|| rules(&[
+    (r"Windows Phone", "Windows Phone"),
+    (r"Windows", "Windows"),
+    (r"iPhone|iPad|iPod", "iOS"),
+    (r"Android", "Android"),
+    (r"CrOS", "Chrome OS"),
+    (r"Mac OS X|Macintosh", "Mac OS X"),
+    (r"Linux", "Linux"),
+]));
+
+#[rustfmt::skip]
+static DEVICES: Rules = Lazy::new(|| rules(&[
+    (r"(?i)bot|crawler|spider|slurp|curl/|wget/", "Spider"),
+    (r"(iPhone|iPad|iPod)", "$1"),
+    (r"Android.*Mobile|Windows Phone|Mobile", "Smartphone"),
+    (r"Android|Tablet", "Tablet"),
+    (r"Windows|Macintosh|CrOS|X11|Linux", "Desktop"),
+]));
+
+fn find(rules: &[(Regex, &'static str)], user_agent: &str) -> Option<String> {
+    rules.iter().find_map(|(regex, name)| {
+        regex.captures(user_agent).map(|captures| {
+            let mut res = String::new();
+            captures.expand(name, &mut res);
+            res
+        })
+    })
+}
+
+fn user_agent_type() -> DataType {
+    DataType::struct_type(vec![
+        StructField::new("browser", DataType::String),
+        StructField::new("os", DataType::String),
+        StructField::new("device", DataType::String),
+    ])
+}
+
+/// `parse_user_agent(s)`, the browser, operating system and device of a `User-Agent` header, as
+/// a struct with the fields `browser`, `os` and `device`.
+pub const PARSE_USER_AGENT: Function = Function {
+    namespace: None,
+    name: "parse_user_agent",
+    signature: &Signature::Exact(&[DataType::String]),
+    return_type: |_| user_agent_type(),
+    deterministic: true,
+    function_type: FunctionType::Stateless(|args| {
+        let array = &args[0];
+        let fields = [
+            ("browser", &BROWSERS),
+            ("os", &OPERATING_SYSTEMS),
+            ("device", &DEVICES),
+        ]
+        .iter()
+        .map(|(name, rules)| {
+            let values = map_strings(array, |user_agent| find(rules, user_agent));
+            (name.to_string(), values)
+        })
+        .collect();
+        let validity = (0..array.len()).map(|idx| array.is_valid(idx)).collect();
+        Ok(Arc::new(StructArray::new(
+            array.len(),
+            fields,
+            Some(validity),
+        )))
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayExt, StringArray};
+    use crate::expr::funcs::call;
+
+    const CHROME_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+        (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 14_6 like Mac OS X) \
+        AppleWebKit/605.1.15 (KHTML, like Gecko) Version/14.1.1 Mobile/15E148 Safari/604.1";
+    const FIREFOX_ANDROID: &str =
+        "Mozilla/5.0 (Android 11; Mobile; rv:89.0) Gecko/89.0 Firefox/89.0";
+    const GOOGLEBOT: &str =
+        "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+
+    fn parse_user_agents() -> StructArray {
+        let array = call(
+            &PARSE_USER_AGENT,
+            &[Arc::new(StringArray::from_opt_vec(vec![
+                Some(CHROME_WINDOWS),
+                Some(SAFARI_IPHONE),
+                Some(FIREFOX_ANDROID),
+                Some(GOOGLEBOT),
+                Some(""),
+                None,
+            ]))],
+        )
+        .unwrap();
+        assert_eq!(array.data_type(), (PARSE_USER_AGENT.return_type)(&[]));
+        array.downcast_ref::<StructArray>().clone()
+    }
+
+    fn field(array: &StructArray, name: &str) -> Vec<Option<String>> {
+        array
+            .field(name)
+            .unwrap()
+            .downcast_ref::<StringArray>()
+            .iter_opt()
+            .map(|value| value.map(ToString::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_user_agent() {
+        let array = parse_user_agents();
+        assert!(array.is_valid(4));
+        assert!(!array.is_valid(5));
+
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            field(&array, "browser"),
+            vec![
+                some("Chrome"),
+                some("Safari"),
+                some("Firefox"),
+                some("Bot"),
+                None,
+                None
+            ]
+        );
+        assert_eq!(
+            field(&array, "os"),
+            vec![
+                some("Windows"),
+                some("iOS"),
+                some("Android"),
+                None,
+                None,
+                None
+            ]
+        );
+        assert_eq!(
+            field(&array, "device"),
+            vec![
+                some("Desktop"),
+                some("iPhone"),
+                some("Smartphone"),
+                some("Spider"),
+                None,
+                None
+            ]
+        );
+    }
+}
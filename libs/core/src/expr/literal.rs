@@ -1,11 +1,17 @@
-use std::fmt::{self, Display, Formatter};
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter, Write};
 use std::sync::Arc;
 
+use anyhow::Error;
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
+use crate::array::temporal::{format_date, format_time};
 use crate::array::{
-    ArrayRef, BooleanArray, DataType, Float32Array, Float64Array, Int16Array, Int32Array,
-    Int64Array, Int8Array, StringArray,
+    ArrayRef, BooleanArray, DataType, DateArray, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, Scalar, StringArray, TimeArray, TimestampArray, UInt16Array,
+    UInt32Array, UInt64Array, UInt8Array,
 };
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -14,6 +20,12 @@ pub enum Literal {
     Int(i64),
     Float(f64),
     String(String),
+    /// Milliseconds since the unix epoch in UTC.
+    Timestamp(i64),
+    /// Days since the unix epoch.
+    Date(i32),
+    /// Milliseconds since midnight.
+    Time(i32),
 }
 
 impl Display for Literal {
@@ -21,8 +33,115 @@ impl Display for Literal {
         match self {
             Literal::Boolean(n) => write!(f, "{}", n),
             Literal::Int(n) => write!(f, "{}", n),
-            Literal::Float(n) => write!(f, "{}", n),
-            Literal::String(n) => write!(f, "\"{}\"", n),
+            Literal::Float(n) => {
+                let s = n.to_string();
+                if s.contains(|c: char| !c.is_ascii_digit() && c != '-') {
+                    f.write_str(&s)
+                } else {
+                    // Keeps the literal a float when it is parsed again.
+                    write!(f, "{}.0", s)
+                }
+            }
+            Literal::String(s) => {
+                f.write_char('"')?;
+                write_escaped(f, s)?;
+                f.write_char('"')
+            }
+            Literal::Timestamp(n) => write!(
+                f,
+                "timestamp '{}'",
+                Utc.timestamp_millis(*n).format("%Y-%m-%d %H:%M:%S%.f")
+            ),
+            Literal::Date(n) => write!(f, "date '{}'", format_date(*n)),
+            Literal::Time(n) => write!(f, "time '{}'", format_time(*n)),
+        }
+    }
+}
+
+/// Writes `s` with the escape sequences of the SQL string literals.
+pub(crate) fn write_escaped(f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\0' => f.write_str("\\0")?,
+            c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Parses a string compared with timestamps of the time zone `tz` into milliseconds since the
+/// unix epoch.
+///
+/// RFC 3339 and `YYYY-MM-DD[ HH:MM[:SS[.fff]]]` with an optional offset are accepted, the times
+/// without an offset are in `tz`, or in UTC if the timestamps have no time zone.
+pub(crate) fn parse_timestamp_in(s: &str, tz: Option<Tz>) -> Option<i64> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Some(datetime.timestamp_millis());
+    }
+    for format in &["%Y-%m-%d %H:%M:%S%.f%:z", "%Y-%m-%d %H:%M:%S%.f%z"] {
+        if let Ok(datetime) = DateTime::parse_from_str(s, format) {
+            return Some(datetime.timestamp_millis());
+        }
+    }
+
+    let datetime = [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .map(|date| date.and_hms(0, 0, 0))
+    })?;
+    match tz {
+        Some(tz) => match tz.from_local_datetime(&datetime) {
+            LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => {
+                Some(datetime.timestamp_millis())
+            }
+            // The time is skipped by a daylight saving time transition.
+            LocalResult::None => None,
+        },
+        None => Some(datetime.timestamp_millis()),
+    }
+}
+
+impl TryFrom<Scalar> for Literal {
+    type Error = Error;
+
+    fn try_from(value: Scalar) -> Result<Self, Self::Error> {
+        match value {
+            Scalar::Null => anyhow::bail!("null cannot be used as a literal"),
+            Scalar::Int8(n) => Ok(Literal::Int(n as i64)),
+            Scalar::Int16(n) => Ok(Literal::Int(n as i64)),
+            Scalar::Int32(n) => Ok(Literal::Int(n as i64)),
+            Scalar::Int64(n) => Ok(Literal::Int(n)),
+            Scalar::UInt8(n) => Ok(Literal::Int(n as i64)),
+            Scalar::UInt16(n) => Ok(Literal::Int(n as i64)),
+            Scalar::UInt32(n) => Ok(Literal::Int(n as i64)),
+            Scalar::UInt64(n) => match i64::try_from(n) {
+                Ok(n) => Ok(Literal::Int(n)),
+                Err(_) => anyhow::bail!("{} is out of the range of an integer literal", n),
+            },
+            Scalar::Float32(n) => Ok(Literal::Float(n as f64)),
+            Scalar::Float64(n) => Ok(Literal::Float(n)),
+            Scalar::Boolean(b) => Ok(Literal::Boolean(b)),
+            Scalar::Timestamp(n) => Ok(Literal::Timestamp(n)),
+            Scalar::Date(n) => Ok(Literal::Date(n)),
+            Scalar::Time(n) => Ok(Literal::Time(n)),
+            Scalar::String(s) => Ok(Literal::String(s.to_string())),
+            Scalar::List(_, _) => anyhow::bail!("list cannot be used as a literal"),
+            Scalar::Struct(_, _) => anyhow::bail!("struct cannot be used as a literal"),
+            Scalar::Map(_, _) => anyhow::bail!("map cannot be used as a literal"),
         }
     }
 }
@@ -50,6 +169,118 @@ impl Literal {
                 }
             }
             Literal::String(_) => DataType::String,
+            Literal::Timestamp(_) => DataType::Timestamp(None),
+            Literal::Date(_) => DataType::Date,
+            Literal::Time(_) => DataType::Time,
+        }
+    }
+
+    /// Returns the type the literal takes when it is compared with a value of `data_type`.
+    ///
+    /// A numeric literal takes the type of the other operand when it is exactly representable in
+    /// it, so `int8_col = 1` compares `Int8` values. A literal out of the range of the type is not
+    /// narrowed, the comparison is performed on the widened values instead, and a float literal
+    /// that does not fit is kept as `Float64` so it is not rounded to `Float32`.
+    pub(crate) fn data_type_in_context(&self, data_type: DataType) -> Option<DataType> {
+        use DataType::*;
+
+        match (self, data_type) {
+            (Literal::Int(n), Int8) if i8::try_from(*n).is_ok() => Some(Int8),
+            (Literal::Int(n), Int16) if i16::try_from(*n).is_ok() => Some(Int16),
+            (Literal::Int(n), Int32) if i32::try_from(*n).is_ok() => Some(Int32),
+            (Literal::Int(_), Int64) => Some(Int64),
+            (Literal::Int(n), UInt8) if u8::try_from(*n).is_ok() => Some(UInt8),
+            (Literal::Int(n), UInt16) if u16::try_from(*n).is_ok() => Some(UInt16),
+            (Literal::Int(n), UInt32) if u32::try_from(*n).is_ok() => Some(UInt32),
+            (Literal::Int(n), UInt64) if *n >= 0 => Some(UInt64),
+            (Literal::Int(n), Float32) if (-(1 << 24)..=1 << 24).contains(n) => Some(Float32),
+            (Literal::Int(n), Float64) if (-(1 << 53)..=1 << 53).contains(n) => Some(Float64),
+            (Literal::Float(n), data_type) if data_type.is_integer() && n.fract() == 0.0 => {
+                let (min, end) = match data_type {
+                    Int8 => (i8::MIN as f64, -(i8::MIN as f64)),
+                    Int16 => (i16::MIN as f64, -(i16::MIN as f64)),
+                    Int32 => (i32::MIN as f64, -(i32::MIN as f64)),
+                    UInt8 => (0.0, u8::MAX as f64 + 1.0),
+                    UInt16 => (0.0, u16::MAX as f64 + 1.0),
+                    UInt32 => (0.0, u32::MAX as f64 + 1.0),
+                    UInt64 => (0.0, u64::MAX as f64),
+                    _ => (i64::MIN as f64, -(i64::MIN as f64)),
+                };
+                if *n >= min && *n < end {
+                    Some(data_type)
+                } else {
+                    Some(Float64)
+                }
+            }
+            (Literal::Float(n), Float32) if (*n as f32) as f64 == *n => Some(Float32),
+            (Literal::Float(_), data_type) if data_type.is_numeric() => Some(Float64),
+            _ => None,
+        }
+    }
+
+    /// Creates an array of `len` copies of the literal with the type `data_type`, the type must
+    /// be the type of the literal or one returned by [`Literal::data_type_in_context`].
+    pub(crate) fn to_array_of(&self, data_type: DataType, len: usize) -> ArrayRef {
+        match (self, data_type) {
+            (Literal::Int(n), DataType::Int8) => {
+                Arc::new(Int8Array::new_scalar(len, Some(*n as i8)))
+            }
+            (Literal::Int(n), DataType::Int16) => {
+                Arc::new(Int16Array::new_scalar(len, Some(*n as i16)))
+            }
+            (Literal::Int(n), DataType::Int32) => {
+                Arc::new(Int32Array::new_scalar(len, Some(*n as i32)))
+            }
+            (Literal::Int(n), DataType::Int64) => Arc::new(Int64Array::new_scalar(len, Some(*n))),
+            (Literal::Int(n), DataType::UInt8) => {
+                Arc::new(UInt8Array::new_scalar(len, Some(*n as u8)))
+            }
+            (Literal::Int(n), DataType::UInt16) => {
+                Arc::new(UInt16Array::new_scalar(len, Some(*n as u16)))
+            }
+            (Literal::Int(n), DataType::UInt32) => {
+                Arc::new(UInt32Array::new_scalar(len, Some(*n as u32)))
+            }
+            (Literal::Int(n), DataType::UInt64) => {
+                Arc::new(UInt64Array::new_scalar(len, Some(*n as u64)))
+            }
+            (Literal::Int(n), DataType::Float32) => {
+                Arc::new(Float32Array::new_scalar(len, Some(*n as f32)))
+            }
+            (Literal::Int(n), DataType::Float64) => {
+                Arc::new(Float64Array::new_scalar(len, Some(*n as f64)))
+            }
+            (Literal::Float(n), DataType::Int8) => {
+                Arc::new(Int8Array::new_scalar(len, Some(*n as i8)))
+            }
+            (Literal::Float(n), DataType::Int16) => {
+                Arc::new(Int16Array::new_scalar(len, Some(*n as i16)))
+            }
+            (Literal::Float(n), DataType::Int32) => {
+                Arc::new(Int32Array::new_scalar(len, Some(*n as i32)))
+            }
+            (Literal::Float(n), DataType::Int64) => {
+                Arc::new(Int64Array::new_scalar(len, Some(*n as i64)))
+            }
+            (Literal::Float(n), DataType::UInt8) => {
+                Arc::new(UInt8Array::new_scalar(len, Some(*n as u8)))
+            }
+            (Literal::Float(n), DataType::UInt16) => {
+                Arc::new(UInt16Array::new_scalar(len, Some(*n as u16)))
+            }
+            (Literal::Float(n), DataType::UInt32) => {
+                Arc::new(UInt32Array::new_scalar(len, Some(*n as u32)))
+            }
+            (Literal::Float(n), DataType::UInt64) => {
+                Arc::new(UInt64Array::new_scalar(len, Some(*n as u64)))
+            }
+            (Literal::Float(n), DataType::Float32) => {
+                Arc::new(Float32Array::new_scalar(len, Some(*n as f32)))
+            }
+            (Literal::Float(n), DataType::Float64) => {
+                Arc::new(Float64Array::new_scalar(len, Some(*n)))
+            }
+            _ => self.to_array(len),
         }
     }
 
@@ -75,6 +306,9 @@ impl Literal {
                 }
             }
             Literal::String(s) => Arc::new(StringArray::new_scalar(len, Some(s.as_str()))),
+            Literal::Timestamp(n) => Arc::new(TimestampArray::new_scalar(len, Some(*n))),
+            Literal::Date(n) => Arc::new(DateArray::new_scalar(len, Some(*n))),
+            Literal::Time(n) => Arc::new(TimeArray::new_scalar(len, Some(*n))),
         }
     }
 }
@@ -116,5 +350,104 @@ mod tests {
             Literal::String("abc".to_string()).data_type(),
             DataType::String
         );
+
+        assert_eq!(Literal::Timestamp(0).data_type(), DataType::Timestamp(None));
+        assert_eq!(Literal::Date(0).data_type(), DataType::Date);
+        assert_eq!(Literal::Time(0).data_type(), DataType::Time);
+    }
+
+    #[test]
+    fn test_data_type_in_context() {
+        assert_eq!(
+            Literal::Int(100).data_type_in_context(DataType::Int8),
+            Some(DataType::Int8)
+        );
+        assert_eq!(
+            Literal::Int(1000).data_type_in_context(DataType::Int8),
+            None
+        );
+        assert_eq!(
+            Literal::Int(1000).data_type_in_context(DataType::Int64),
+            Some(DataType::Int64)
+        );
+        assert_eq!(
+            Literal::Int(255).data_type_in_context(DataType::UInt8),
+            Some(DataType::UInt8)
+        );
+        assert_eq!(
+            Literal::Int(-1).data_type_in_context(DataType::UInt64),
+            None
+        );
+        assert_eq!(
+            Literal::Int(1 << 24).data_type_in_context(DataType::Float32),
+            Some(DataType::Float32)
+        );
+        assert_eq!(
+            Literal::Int((1 << 24) + 1).data_type_in_context(DataType::Float32),
+            None
+        );
+
+        assert_eq!(
+            Literal::Float(-128.0).data_type_in_context(DataType::Int8),
+            Some(DataType::Int8)
+        );
+        assert_eq!(
+            Literal::Float(128.0).data_type_in_context(DataType::Int8),
+            Some(DataType::Float64)
+        );
+        assert_eq!(
+            Literal::Float(256.0).data_type_in_context(DataType::UInt8),
+            Some(DataType::Float64)
+        );
+        assert_eq!(
+            Literal::Float(1.5).data_type_in_context(DataType::Int32),
+            Some(DataType::Float64)
+        );
+        assert_eq!(
+            Literal::Float(i64::MAX as f64).data_type_in_context(DataType::Int64),
+            Some(DataType::Float64)
+        );
+        assert_eq!(
+            Literal::Float(0.5).data_type_in_context(DataType::Float32),
+            Some(DataType::Float32)
+        );
+        assert_eq!(
+            Literal::Float(0.1).data_type_in_context(DataType::Float32),
+            Some(DataType::Float64)
+        );
+        assert_eq!(
+            Literal::Float(0.1).data_type_in_context(DataType::String),
+            None
+        );
+        assert_eq!(
+            Literal::Int(1).data_type_in_context(DataType::Timestamp(None)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp_in() {
+        let utc = Some(1704067200000);
+        assert_eq!(parse_timestamp_in("2024-01-01T00:00:00Z", None), utc);
+        assert_eq!(parse_timestamp_in("2024-01-01T08:00:00+08:00", None), utc);
+        assert_eq!(parse_timestamp_in("2024-01-01 08:00:00+08:00", None), utc);
+        assert_eq!(parse_timestamp_in("2024-01-01 00:00:00", None), utc);
+        assert_eq!(
+            parse_timestamp_in("2024-01-01 00:00:00.250", None),
+            Some(1704067200250)
+        );
+        assert_eq!(parse_timestamp_in("2024-01-01T00:00", None), utc);
+        assert_eq!(parse_timestamp_in("2024-01-01", None), utc);
+
+        let shanghai = Some(chrono_tz::Asia::Shanghai);
+        assert_eq!(parse_timestamp_in("2024-01-01 08:00:00", shanghai), utc);
+        assert_eq!(parse_timestamp_in("2024-01-01T00:00:00Z", shanghai), utc);
+        assert_eq!(
+            parse_timestamp_in("2024-03-10 02:30:00", Some(chrono_tz::America::New_York)),
+            None
+        );
+
+        assert_eq!(parse_timestamp_in("yesterday", None), None);
+        assert_eq!(parse_timestamp_in("2024-13-01", None), None);
     }
 }
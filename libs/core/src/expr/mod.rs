@@ -1,19 +1,27 @@
 mod binary_operator;
-mod cast;
+pub(crate) mod cast;
+mod coercion;
 mod display;
 #[allow(clippy::module_inception)]
 mod expr;
+mod fold;
 mod func;
 mod funcs;
 mod literal;
+mod registry;
 mod signature;
 mod to_physical;
 mod unary_operator;
+mod unknown_name;
 
 pub mod physical_expr;
 
 pub use binary_operator::BinaryOperator;
-pub use expr::Expr;
+pub(crate) use display::{DisplayName, DisplayOver};
+pub use expr::{Expr, OrderBy, Span};
+pub use func::Accumulator;
 pub use literal::Literal;
 pub use physical_expr::ExprState;
+pub use registry::{FunctionRegistry, ScalarFunction};
 pub use unary_operator::UnaryOperator;
+pub use unknown_name::{NameKind, UnknownNameError};
@@ -1,21 +1,34 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 
-use crate::array::{ArrayRef, DataType};
+use crate::array::compute;
+use crate::array::{ArrayExt, ArrayRef, BooleanArray, DataType, NullArray, TimestampArray};
 use crate::dataset::DataSet;
+use crate::execution::clock::Clock;
 use crate::expr::func::StatefulFunction;
-use crate::expr::{cast, BinaryOperator, Literal, UnaryOperator};
+use crate::expr::{cast, BinaryOperator, Literal, ScalarFunction, UnaryOperator};
 
 #[derive(Clone)]
 pub enum PhysicalFunction {
     Stateless(fn(&[ArrayRef]) -> Result<ArrayRef>),
-    Stateful { id: usize },
+    Stateful {
+        id: usize,
+        /// The return type, of the current result of the function.
+        data_type: DataType,
+    },
+    Udf(ScalarFunction),
+    /// `now()`, the current time of the clock of the stream.
+    Now(Arc<dyn Clock>),
 }
 
 #[derive(Clone)]
 pub enum PhysicalNode {
-    Literal(Literal),
+    Literal {
+        literal: Literal,
+        data_type: DataType,
+    },
     Column {
         index: usize,
     },
@@ -28,10 +41,16 @@ pub enum PhysicalNode {
         op: UnaryOperator,
         expr: Box<PhysicalNode>,
     },
+    /// An implicit cast, inserted by the planner.
+    Cast {
+        expr: Box<PhysicalNode>,
+        data_type: DataType,
+    },
     Call {
         input_data_types: Vec<DataType>,
         func: PhysicalFunction,
         args: Vec<PhysicalNode>,
+        filter: Option<Box<PhysicalNode>>,
     },
 }
 
@@ -51,7 +70,40 @@ impl PhysicalExpr {
     }
 
     pub fn eval(&mut self, dataset: &DataSet) -> Result<ArrayRef> {
-        internal_eval(&mut self.root, &mut self.stateful_funcs, dataset)
+        internal_eval(&mut self.root, &mut self.stateful_funcs, dataset, true)
+    }
+
+    /// Evaluates the expression without updating the states of the stateful functions, they
+    /// return their current values.
+    pub fn eval_current(&mut self, dataset: &DataSet) -> Result<ArrayRef> {
+        internal_eval(&mut self.root, &mut self.stateful_funcs, dataset, false)
+    }
+
+    /// Returns an upper bound of the size of the states of the stateful functions in bytes, or
+    /// `None` if any of them can grow without bound.
+    pub fn max_state_size(&self) -> Option<usize> {
+        self.stateful_funcs
+            .iter()
+            .map(|func| func.max_state_size())
+            .sum()
+    }
+
+    /// Returns `true` if the states of all stateful functions can be merged.
+    pub fn can_merge(&self) -> bool {
+        self.stateful_funcs.iter().all(|func| func.can_merge())
+    }
+
+    /// Merges the states of the stateful functions of `other`, an expression created from the
+    /// same plan, into this expression.
+    pub fn merge(&mut self, other: &PhysicalExpr) -> Result<()> {
+        anyhow::ensure!(
+            self.stateful_funcs.len() == other.stateful_funcs.len(),
+            "cannot merge the states of different expressions"
+        );
+        for (func, other_func) in self.stateful_funcs.iter_mut().zip(&other.stateful_funcs) {
+            func.merge(other_func.as_ref())?;
+        }
+        Ok(())
     }
 
     pub fn save_state(&self) -> Result<ExprState> {
@@ -81,34 +133,75 @@ fn internal_eval(
     op: &mut PhysicalNode,
     stateful_funcs: &mut [Box<dyn StatefulFunction>],
     dataset: &DataSet,
+    update: bool,
 ) -> Result<ArrayRef> {
     match op {
-        PhysicalNode::Literal(literal) => Ok(literal.to_array(dataset.len())),
+        PhysicalNode::Literal { literal, data_type } => {
+            Ok(literal.to_array_of(*data_type, dataset.len()))
+        }
         PhysicalNode::Column { index } => Ok(dataset.column(*index).context("internal error")?),
         PhysicalNode::Binary { op, lhs, rhs } => {
-            let left = internal_eval(lhs, stateful_funcs, dataset)?;
-            let right = internal_eval(rhs, stateful_funcs, dataset)?;
+            let left = internal_eval(lhs, stateful_funcs, dataset, update)?;
+            let right = internal_eval(rhs, stateful_funcs, dataset, update)?;
             op.eval_array(&*left, &*right)
         }
         PhysicalNode::Unary { op, expr } => {
-            let array = internal_eval(expr, stateful_funcs, dataset)?;
+            let array = internal_eval(expr, stateful_funcs, dataset, update)?;
             op.eval_array(&*array)
         }
+        PhysicalNode::Cast { expr, data_type } => cast::array_cast_to(
+            internal_eval(expr, stateful_funcs, dataset, update)?,
+            *data_type,
+        ),
         PhysicalNode::Call {
             input_data_types,
             func,
             args,
+            filter,
         } => {
             let mut arg_values = Vec::with_capacity(args.len());
             for (expr, data_type) in args.iter_mut().zip(input_data_types) {
                 arg_values.push(cast::array_cast_to(
-                    internal_eval(expr, stateful_funcs, dataset)?,
+                    internal_eval(expr, stateful_funcs, dataset, update)?,
                     *data_type,
                 )?);
             }
+            if arg_values.is_empty() {
+                // Functions without arguments still need to know the number of rows.
+                arg_values.push(Arc::new(NullArray::new(dataset.len())));
+            }
+            if let Some(filter) = filter {
+                let flags = internal_eval(filter, stateful_funcs, dataset, update)?;
+                let flags = flags.downcast_ref::<BooleanArray>();
+                arg_values = arg_values
+                    .into_iter()
+                    .map(|array| compute::mask(array, flags))
+                    .collect();
+            }
             match func {
                 PhysicalFunction::Stateless(func) => func(&arg_values),
-                PhysicalFunction::Stateful { id } => {
+                PhysicalFunction::Now(clock) => Ok(Arc::new(TimestampArray::new_scalar(
+                    dataset.len(),
+                    Some(clock.now()),
+                ))),
+                PhysicalFunction::Udf(func) => {
+                    let array = func(&arg_values)?;
+                    anyhow::ensure!(
+                        array.len() == dataset.len(),
+                        "the function returned {} rows, but {} are expected.",
+                        array.len(),
+                        dataset.len()
+                    );
+                    Ok(array)
+                }
+                PhysicalFunction::Stateful { id, data_type } if !update => {
+                    let value = stateful_funcs[*id].current(*data_type)?;
+                    Ok(compute::array_from_scalars(
+                        *data_type,
+                        vec![value; dataset.len()],
+                    ))
+                }
+                PhysicalFunction::Stateful { id, .. } => {
                     let func = &mut stateful_funcs[*id];
                     func.call(&arg_values)
                 }
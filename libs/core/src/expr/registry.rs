@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::array::{ArrayRef, DataType};
+use crate::expr::func::Accumulator;
+use crate::expr::funcs::find_function;
+
+/// The implementation of a scalar user-defined function, called with the arguments cast to the
+/// declared argument types.
+pub type ScalarFunction = Arc<dyn Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync>;
+
+#[derive(Clone)]
+pub(crate) enum UdfType {
+    Scalar(ScalarFunction),
+    /// The accumulator cloned for every aggregation.
+    Aggregate(Arc<dyn Accumulator>),
+}
+
+#[derive(Clone)]
+pub(crate) struct Udf {
+    pub(crate) arg_types: Vec<DataType>,
+    pub(crate) return_type: DataType,
+    pub(crate) udf_type: UdfType,
+}
+
+static UDFS: Lazy<RwLock<HashMap<String, Udf>>> = Lazy::new(Default::default);
+
+/// Registry of the functions added by the application embedding yql.
+///
+/// The functions are visible to all streams of the process, by their case-insensitive name,
+/// and are resolved when the streams are planned.
+pub struct FunctionRegistry;
+
+impl FunctionRegistry {
+    /// Registers the scalar function `name`, replacing any function of the same name previously
+    /// registered.
+    ///
+    /// The arguments are cast to `arg_types` before `f` is called, and `f` must return an array
+    /// of `return_type` with one value per row.
+    pub fn register_scalar<F>(
+        name: &str,
+        arg_types: &[DataType],
+        return_type: DataType,
+        f: F,
+    ) -> Result<()>
+    where
+        F: Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync + 'static,
+    {
+        register(name, arg_types, return_type, UdfType::Scalar(Arc::new(f)))
+    }
+
+    /// Registers the aggregate function `name`, replacing any function of the same name
+    /// previously registered.
+    ///
+    /// Every aggregation of the function starts with a clone of `accumulator` reset by
+    /// [`Accumulator::init`]. The arguments are cast to `arg_types`, and
+    /// [`Accumulator::finish`] must return a value of `return_type`.
+    pub fn register_aggregate(
+        name: &str,
+        arg_types: &[DataType],
+        return_type: DataType,
+        accumulator: impl Accumulator,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            !arg_types.is_empty(),
+            "the aggregate function '{}' must take at least one argument.",
+            name
+        );
+        register(
+            name,
+            arg_types,
+            return_type,
+            UdfType::Aggregate(Arc::new(accumulator)),
+        )
+    }
+
+    /// Removes the function `name`, returns `false` if it was not registered.
+    pub fn deregister(name: &str) -> bool {
+        UDFS.write().remove(&name.to_ascii_lowercase()).is_some()
+    }
+}
+
+fn register(
+    name: &str,
+    arg_types: &[DataType],
+    return_type: DataType,
+    udf_type: UdfType,
+) -> Result<()> {
+    anyhow::ensure!(
+        find_function(None, name).is_none(),
+        "the function '{}' is a builtin function.",
+        name
+    );
+    UDFS.write().insert(
+        name.to_ascii_lowercase(),
+        Udf {
+            arg_types: arg_types.to_vec(),
+            return_type,
+            udf_type,
+        },
+    );
+    Ok(())
+}
+
+pub(crate) fn find_udf(name: &str) -> Option<Udf> {
+    UDFS.read().get(&name.to_ascii_lowercase()).cloned()
+}
+
+pub(crate) fn udf_names() -> Vec<String> {
+    UDFS.read().keys().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::Any;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::array::{
+        Array, ArrayExt, Float64Array, Int64Array, Int64Type, Int8Array, PrimitiveBuilder, Scalar,
+    };
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::*;
+
+    #[test]
+    fn test_register_scalar() {
+        FunctionRegistry::register_scalar(
+            "test_double",
+            &[DataType::Int64],
+            DataType::Int64,
+            |args: &[ArrayRef]| {
+                let values = args[0].downcast_ref::<Int64Array>();
+                let mut builder = PrimitiveBuilder::<Int64Type>::with_capacity(values.len());
+                for value in values.iter_opt() {
+                    match value {
+                        Some(value) => builder.append(value * 2),
+                        None => builder.append_null(),
+                    }
+                }
+                Ok(Arc::new(builder.finish()) as ArrayRef)
+            },
+        )
+        .unwrap();
+        assert!(
+            FunctionRegistry::register_scalar("sqrt", &[], DataType::Int64, |args| {
+                Ok(args[0].clone())
+            })
+            .is_err()
+        );
+
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int8)]).unwrap());
+        let dataset = DataSet::try_new(
+            schema.clone(),
+            vec![Arc::new(Int8Array::from_vec(vec![1, 2, 3])) as ArrayRef],
+        )
+        .unwrap();
+        let mut expr = call("TEST_DOUBLE", vec![col("a")])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Int64);
+        let array = expr.eval(&dataset).unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![2, 4, 6]
+        );
+
+        assert!(FunctionRegistry::deregister("test_double"));
+        assert!(call("test_double", vec![col("a")])
+            .into_physical(schema)
+            .is_err());
+    }
+
+    #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+    struct GeometricMean {
+        log_sum: f64,
+        count: u64,
+    }
+
+    impl Accumulator for GeometricMean {
+        fn init(&mut self) {
+            *self = Self::default();
+        }
+
+        fn update(&mut self, args: &[ArrayRef]) -> Result<()> {
+            for value in args[0].downcast_ref::<Float64Array>().iter() {
+                self.log_sum += value.ln();
+                self.count += 1;
+            }
+            Ok(())
+        }
+
+        fn merge(&mut self, other: &dyn Accumulator) -> Result<()> {
+            let other = other.as_any().downcast_ref::<Self>().unwrap();
+            self.log_sum += other.log_sum;
+            self.count += other.count;
+            Ok(())
+        }
+
+        fn finish(&self) -> Result<Scalar> {
+            Ok(match self.count {
+                0 => Scalar::Null,
+                count => Scalar::Float64((self.log_sum / count as f64).exp()),
+            })
+        }
+
+        fn save_state(&self) -> Result<Vec<u8>> {
+            Ok(bincode::serialize(self)?)
+        }
+
+        fn load_state(&mut self, state: &[u8]) -> Result<()> {
+            *self = bincode::deserialize(state)?;
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_register_aggregate() {
+        FunctionRegistry::register_aggregate(
+            "test_geomean",
+            &[DataType::Float64],
+            DataType::Float64,
+            GeometricMean {
+                log_sum: 100.0,
+                count: 1,
+            },
+        )
+        .unwrap();
+
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Float64)]).unwrap());
+        let dataset = |values: Vec<Option<f64>>| {
+            DataSet::try_new(
+                schema.clone(),
+                vec![Arc::new(Float64Array::from_opt_vec(values)) as ArrayRef],
+            )
+            .unwrap()
+        };
+        let result = |array: ArrayRef| array.scalar_value(array.len() - 1);
+
+        let mut expr = call("test_geomean", vec![col("a")])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Float64);
+        assert_eq!(
+            result(expr.eval(&dataset(vec![Some(2.0), None])).unwrap()),
+            Scalar::Float64(2.0)
+        );
+        let state = expr.save_state().unwrap();
+
+        let mut other = call("test_geomean", vec![col("a")])
+            .into_physical(schema.clone())
+            .unwrap();
+        other.eval(&dataset(vec![Some(8.0)])).unwrap();
+        expr.merge(&other).unwrap();
+        assert_eq!(
+            result(expr.eval_current(&dataset(vec![None])).unwrap()),
+            Scalar::Float64(4.0)
+        );
+
+        expr.load_state(state).unwrap();
+        assert_eq!(
+            result(expr.eval_current(&dataset(vec![None])).unwrap()),
+            Scalar::Float64(2.0)
+        );
+
+        assert!(FunctionRegistry::deregister("test_geomean"));
+    }
+}
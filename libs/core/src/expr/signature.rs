@@ -10,9 +10,23 @@ pub enum Signature {
     Uniform(usize, &'static [DataType]),
     Exact(&'static [DataType]),
     Any(usize),
+    /// An argument of any type followed by arguments of the given types.
+    AnyThen(&'static [DataType]),
     OneOf(&'static [Signature]),
 }
 
+/// Returns the type that all of `types` can be cast to, the widest of them.
+///
+/// Other types are converted to strings only if all of them are strings.
+fn common_type(types: &[DataType]) -> Option<DataType> {
+    types.iter().copied().find(|candidate| {
+        types.iter().all(|data_type| {
+            data_type.can_cast_to(*candidate)
+                && (*candidate != DataType::String || *data_type == DataType::String)
+        })
+    })
+}
+
 impl Signature {
     fn get_valid_types(&self, current_types: &[DataType]) -> Result<Vec<Vec<DataType>>> {
         let valid_types: Vec<Vec<DataType>> = match self {
@@ -26,9 +40,10 @@ impl Signature {
                 .copied()
                 .map(|valid_type| (0..*number).map(|_| valid_type).collect())
                 .collect(),
-            Signature::VariadicEqual => {
-                vec![current_types.iter().map(|_| current_types[0]).collect()]
-            }
+            Signature::VariadicEqual => match common_type(current_types) {
+                Some(common_type) => vec![current_types.iter().map(|_| common_type).collect()],
+                None => Vec::new(),
+            },
             Signature::Exact(valid_types) => vec![valid_types.to_vec()],
             Signature::Any(number) => {
                 anyhow::ensure!(
@@ -39,6 +54,12 @@ impl Signature {
                 );
                 vec![(0..*number).map(|i| current_types[i]).collect()]
             }
+            Signature::AnyThen(valid_types) => match current_types.first() {
+                Some(first) => vec![std::iter::once(*first)
+                    .chain(valid_types.iter().copied())
+                    .collect()],
+                None => Vec::new(),
+            },
             Signature::OneOf(types) => {
                 let mut r = Vec::new();
                 for s in *types {
@@ -51,7 +72,7 @@ impl Signature {
         Ok(valid_types)
     }
 
-    fn maybe_data_types(
+    pub(crate) fn maybe_data_types(
         valid_types: &[DataType],
         current_types: &[DataType],
     ) -> Option<Vec<DataType>> {
@@ -63,7 +84,12 @@ impl Signature {
         for (i, valid_type) in valid_types.iter().enumerate() {
             let current_type = &current_types[i];
 
-            if current_type == valid_type {
+            let same_type = match (current_type, valid_type) {
+                // Any time zone matches a timestamp argument.
+                (DataType::Timestamp(_), DataType::Timestamp(_)) => true,
+                _ => current_type == valid_type,
+            };
+            if same_type {
                 new_type.push(*current_type)
             } else if current_type.can_cast_to(*valid_type) {
                 new_type.push(*valid_type)
@@ -1,35 +1,79 @@
+use std::sync::Arc;
+
 use anyhow::Error;
 
+use crate::array::temporal::{parse_date, parse_time};
 use crate::array::DataType;
-use crate::dataset::SchemaRef;
-use crate::expr::func::{FunctionType, StatefulFunction};
-use crate::expr::funcs::find_function;
+use crate::dataset::{Schema, SchemaRef};
+use crate::execution::clock::{Clock, SystemClock};
+use crate::expr::coercion::{coerce_binary, swap_operands};
+use crate::expr::func::{AccumulatorFunction, FunctionType, StatefulFunction};
+use crate::expr::funcs::{
+    element_at, find_function, function_names, groups_type, make_array, map_value, struct_field,
+    MAKE_ARRAY, NOW,
+};
+use crate::expr::literal::parse_timestamp_in;
 use crate::expr::physical_expr::{PhysicalExpr, PhysicalFunction, PhysicalNode};
-use crate::expr::Expr;
+use crate::expr::registry::{find_udf, udf_names, Udf, UdfType};
+use crate::expr::signature::Signature;
+use crate::expr::{Expr, Literal, NameKind, Span, UnknownNameError};
 
 pub type Result<T, E = Error> = std::result::Result<(T, DataType), E>;
 
 struct Context {
     schema: SchemaRef,
     stateful_funcs: Vec<Box<dyn StatefulFunction>>,
+    clock: Arc<dyn Clock>,
 }
 
 fn to_physical(ctx: &mut Context, expr: Expr) -> Result<PhysicalNode> {
     match expr {
         Expr::Literal(literal) => {
             let data_type = literal.data_type();
-            Ok((PhysicalNode::Literal(literal), data_type))
+            Ok((PhysicalNode::Literal { literal, data_type }, data_type))
         }
-        Expr::Column { qualifier, name } => match ctx.schema.field(qualifier.as_deref(), &name) {
+        Expr::Column {
+            qualifier,
+            name,
+            span,
+        } => match ctx.schema.field(qualifier.as_deref(), &name) {
             Some((index, field)) => Ok((PhysicalNode::Column { index }, field.data_type)),
             None => match qualifier {
-                Some(qualifier) => anyhow::bail!("not such column: '{}.{}'", qualifier, name),
-                None => anyhow::bail!("not such column: '{}'", name),
+                // `a.b` is the field `b` of the column `a` if there is no table `a`.
+                Some(qualifier) if ctx.schema.field(None, &qualifier).is_some() => to_physical(
+                    ctx,
+                    Expr::Field {
+                        expr: Box::new(Expr::Column {
+                            qualifier: None,
+                            name: qualifier,
+                            span,
+                        }),
+                        name,
+                    },
+                ),
+                qualifier => Err(unknown_column(&ctx.schema, qualifier, name, span).into()),
             },
         },
         Expr::Binary { op, lhs, rhs } => {
-            let (lhs, lhs_data_type) = to_physical(ctx, *lhs)?;
-            let (rhs, rhs_data_type) = to_physical(ctx, *rhs)?;
+            let (mut lhs, mut lhs_data_type) = to_physical(ctx, *lhs)?;
+            let (mut rhs, mut rhs_data_type) = to_physical(ctx, *rhs)?;
+            if op.is_comparison() {
+                coerce_literal(&mut lhs, &mut lhs_data_type, rhs_data_type)?;
+                coerce_literal(&mut rhs, &mut rhs_data_type, lhs_data_type)?;
+            }
+            let (lhs_cast, rhs_cast) = coerce_binary(op, lhs_data_type, rhs_data_type);
+            if let Some(data_type) = lhs_cast {
+                lhs = cast(lhs, data_type);
+                lhs_data_type = data_type;
+            }
+            if let Some(data_type) = rhs_cast {
+                rhs = cast(rhs, data_type);
+                rhs_data_type = data_type;
+            }
+            if swap_operands(op, lhs_data_type, rhs_data_type) {
+                std::mem::swap(&mut lhs, &mut rhs);
+                std::mem::swap(&mut lhs_data_type, &mut rhs_data_type);
+            }
             let data_type = op.data_type(lhs_data_type, rhs_data_type)?;
             Ok((
                 PhysicalNode::Binary {
@@ -55,50 +99,340 @@ fn to_physical(ctx: &mut Context, expr: Expr) -> Result<PhysicalNode> {
             namespace,
             name,
             args: arguments,
+            span,
         } => {
             let func = match find_function(namespace.as_deref(), &name) {
                 Some(func) => func,
-                None => anyhow::bail!("no such function: '{}'", name),
+                None if namespace.is_none() => match find_udf(&name) {
+                    Some(udf) => return udf_to_physical(ctx, &name, udf, arguments),
+                    None => return Err(unknown_function(None, name, span).into()),
+                },
+                None => return Err(unknown_function(namespace, name, span).into()),
             };
 
-            let mut arg_exprs = Vec::new();
-            let mut arg_data_types = Vec::new();
-            for arg in arguments {
-                let (expr, data_type) = to_physical(ctx, arg)?;
-                arg_exprs.push(expr);
-                arg_data_types.push(data_type);
-            }
+            // The fields of the structs returned by `regexp_groups` and `grok` are the named
+            // groups of their pattern.
+            let pattern = match arguments.get(1) {
+                Some(Expr::Literal(Literal::String(pattern))) => Some(pattern.clone()),
+                _ => None,
+            };
+            let (arg_exprs, arg_data_types) = args_to_physical(ctx, arguments)?;
 
             let input_data_types = func
                 .signature
                 .data_types(&arg_data_types)
                 .map_err(|_| anyhow::anyhow!("misuse function: {}", func.name))?;
-            let return_data_type = (func.return_type)(&input_data_types);
+            let return_data_type = match groups_type(func, pattern.as_deref())? {
+                Some(data_type) => data_type,
+                None => (func.return_type)(&input_data_types),
+            };
 
             let call = PhysicalNode::Call {
                 input_data_types,
                 func: match &func.function_type {
+                    // `now()` reads the clock of the stream.
+                    FunctionType::Stateless(_)
+                        if func.namespace.is_none() && func.name == NOW.name =>
+                    {
+                        PhysicalFunction::Now(ctx.clock.clone())
+                    }
                     FunctionType::Stateless(f) => PhysicalFunction::Stateless(*f),
                     FunctionType::Stateful(f) => {
                         let id = ctx.stateful_funcs.len() as usize;
                         ctx.stateful_funcs.push(f());
-                        PhysicalFunction::Stateful { id }
+                        PhysicalFunction::Stateful {
+                            id,
+                            data_type: return_data_type,
+                        }
                     }
                 },
                 args: arg_exprs,
+                filter: None,
             };
             Ok((call, return_data_type))
         }
+        Expr::AggregateFilter { expr, condition } => {
+            let (mut call, data_type) = to_physical(ctx, *expr)?;
+            let (condition, condition_data_type) = to_physical(ctx, *condition)?;
+            anyhow::ensure!(
+                condition_data_type == DataType::Boolean,
+                "filter condition must return a boolean type."
+            );
+            match &mut call {
+                PhysicalNode::Call {
+                    func: PhysicalFunction::Stateful { .. },
+                    filter,
+                    ..
+                } => *filter = Some(Box::new(condition)),
+                _ => anyhow::bail!("the filter clause can only be used with aggregate functions."),
+            }
+            Ok((call, data_type))
+        }
+        Expr::Array(elements) => {
+            anyhow::ensure!(
+                !elements.is_empty(),
+                "cannot infer the element type of an empty array."
+            );
+
+            let mut arg_exprs = Vec::new();
+            let mut arg_data_types = Vec::new();
+            for element in elements {
+                let (expr, data_type) = to_physical(ctx, element)?;
+                arg_exprs.push(expr);
+                arg_data_types.push(data_type);
+            }
+
+            let input_data_types = if arg_data_types.iter().all(|ty| *ty == arg_data_types[0]) {
+                arg_data_types
+            } else {
+                MAKE_ARRAY
+                    .signature
+                    .data_types(&arg_data_types)
+                    .map_err(|_| anyhow::anyhow!("array elements must have a common type."))?
+            };
+            let data_type = (MAKE_ARRAY.return_type)(&input_data_types);
+            Ok((
+                PhysicalNode::Call {
+                    input_data_types,
+                    func: PhysicalFunction::Stateless(make_array),
+                    args: arg_exprs,
+                    filter: None,
+                },
+                data_type,
+            ))
+        }
+        Expr::Index { expr, index } => {
+            let (expr, data_type) = to_physical(ctx, *expr)?;
+            if let Some((key_type, value_type)) = data_type.entry_types() {
+                let (mut key, mut key_data_type) = to_physical(ctx, *index)?;
+                coerce_literal(&mut key, &mut key_data_type, key_type)?;
+                if key_data_type != key_type {
+                    anyhow::ensure!(
+                        key_data_type.can_cast_to(key_type),
+                        "map key must be of type '{}', but got '{}'.",
+                        key_type,
+                        key_data_type
+                    );
+                    key = cast(key, key_type);
+                }
+                return Ok((
+                    PhysicalNode::Call {
+                        input_data_types: vec![data_type, key_type],
+                        func: PhysicalFunction::Stateless(map_value),
+                        args: vec![expr, key],
+                        filter: None,
+                    },
+                    value_type,
+                ));
+            }
+            let element_type = match data_type.element_type() {
+                Some(element_type) => element_type,
+                None => anyhow::bail!("cannot index into a value of type '{}'.", data_type),
+            };
+            let (index, index_data_type) = to_physical(ctx, *index)?;
+            anyhow::ensure!(
+                index_data_type.is_integer(),
+                "array index must be an integer type, but got '{}'.",
+                index_data_type
+            );
+            Ok((
+                PhysicalNode::Call {
+                    input_data_types: vec![data_type, DataType::Int64],
+                    func: PhysicalFunction::Stateless(element_at),
+                    args: vec![expr, index],
+                    filter: None,
+                },
+                element_type,
+            ))
+        }
+        Expr::Field { expr, name } => {
+            let (expr, data_type) = to_physical(ctx, *expr)?;
+            let fields = data_type.fields().unwrap_or_default();
+            if let Some(field) = fields.iter().find(|field| field.name == name) {
+                return Ok((
+                    PhysicalNode::Call {
+                        input_data_types: vec![data_type, DataType::String],
+                        func: PhysicalFunction::Stateless(struct_field),
+                        args: vec![
+                            expr,
+                            PhysicalNode::Literal {
+                                literal: Literal::String(name),
+                                data_type: DataType::String,
+                            },
+                        ],
+                        filter: None,
+                    },
+                    field.data_type,
+                ));
+            }
+            anyhow::bail!(
+                "cannot access the field '{}' of a value of type '{}'.",
+                name,
+                data_type
+            )
+        }
+        Expr::Over { expr, .. } => anyhow::bail!(
+            "the analytic function '{}' can only be used in the select list.",
+            expr
+        ),
+        Expr::OverWindow { window, .. } => anyhow::bail!("the window '{}' is not defined.", window),
         Expr::Alias(expr, _) => to_physical(ctx, *expr),
         Expr::Wildcard { .. } => anyhow::bail!("invalid wildcard position"),
+        Expr::Placeholder(Some(index)) => anyhow::bail!("unbound parameter: '${}'", index),
+        Expr::Placeholder(None) => anyhow::bail!("unbound parameter: '?'"),
+    }
+}
+
+fn unknown_column(
+    schema: &Schema,
+    qualifier: Option<String>,
+    name: String,
+    span: Span,
+) -> UnknownNameError {
+    // The internal columns, such as `@time`, are never suggested.
+    let candidates = schema
+        .fields()
+        .iter()
+        .filter(|field| {
+            !field.name.starts_with('@')
+                && !matches!(&field.qualifier, Some(qualifier) if qualifier.starts_with('@'))
+        })
+        .filter_map(|field| match (&qualifier, &field.qualifier) {
+            (Some(_), Some(_)) => Some(field.qualified_name()),
+            (Some(_), None) => None,
+            (None, _) => Some(field.name.clone()),
+        })
+        .collect::<Vec<_>>();
+    UnknownNameError::new(NameKind::Column, qualifier, name, span, candidates)
+}
+
+fn unknown_function(namespace: Option<String>, name: String, span: Span) -> UnknownNameError {
+    let mut candidates = function_names(namespace.as_deref())
+        .map(|func_name| match &namespace {
+            Some(namespace) => format!("{}.{}", namespace, func_name),
+            None => func_name.to_string(),
+        })
+        .collect::<Vec<_>>();
+    if namespace.is_none() {
+        candidates.extend(udf_names());
+    }
+    UnknownNameError::new(NameKind::Function, namespace, name, span, candidates)
+}
+
+fn args_to_physical(
+    ctx: &mut Context,
+    arguments: Vec<Expr>,
+) -> anyhow::Result<(Vec<PhysicalNode>, Vec<DataType>)> {
+    let mut arg_exprs = Vec::new();
+    let mut arg_data_types = Vec::new();
+    for arg in arguments {
+        let (expr, data_type) = to_physical(ctx, arg)?;
+        arg_exprs.push(expr);
+        arg_data_types.push(data_type);
+    }
+    Ok((arg_exprs, arg_data_types))
+}
+
+fn udf_to_physical(
+    ctx: &mut Context,
+    name: &str,
+    udf: Udf,
+    arguments: Vec<Expr>,
+) -> Result<PhysicalNode> {
+    let (arg_exprs, arg_data_types) = args_to_physical(ctx, arguments)?;
+    let input_data_types = Signature::maybe_data_types(&udf.arg_types, &arg_data_types)
+        .ok_or_else(|| anyhow::anyhow!("misuse function: {}", name))?;
+    Ok((
+        PhysicalNode::Call {
+            input_data_types,
+            func: match udf.udf_type {
+                UdfType::Scalar(f) => PhysicalFunction::Udf(f),
+                UdfType::Aggregate(accumulator) => {
+                    let id = ctx.stateful_funcs.len();
+                    ctx.stateful_funcs.push(Box::new(AccumulatorFunction::new(
+                        &*accumulator,
+                        udf.return_type,
+                    )));
+                    PhysicalFunction::Stateful {
+                        id,
+                        data_type: udf.return_type,
+                    }
+                }
+            },
+            args: arg_exprs,
+            filter: None,
+        },
+        udf.return_type,
+    ))
+}
+
+fn cast(node: PhysicalNode, data_type: DataType) -> PhysicalNode {
+    PhysicalNode::Cast {
+        expr: Box::new(node),
+        data_type,
     }
 }
 
+/// Gives a literal compared with a value of `other_data_type` the type of that value if it fits,
+/// a string compared with a timestamp is parsed as a timestamp.
+fn coerce_literal(
+    node: &mut PhysicalNode,
+    data_type: &mut DataType,
+    other_data_type: DataType,
+) -> anyhow::Result<()> {
+    if let PhysicalNode::Literal {
+        literal,
+        data_type: literal_data_type,
+    } = node
+    {
+        let new_data_type = match (&*literal, other_data_type) {
+            (Literal::String(s), DataType::Timestamp(tz)) => match parse_timestamp_in(s, tz) {
+                Some(timestamp) => {
+                    *literal = Literal::Timestamp(timestamp);
+                    other_data_type
+                }
+                None => anyhow::bail!("'{}' is not a valid timestamp.", s),
+            },
+            (Literal::String(s), DataType::Date) => match parse_date(s) {
+                Some(date) => {
+                    *literal = Literal::Date(date);
+                    other_data_type
+                }
+                None => anyhow::bail!("'{}' is not a valid date.", s),
+            },
+            (Literal::String(s), DataType::Time) => match parse_time(s) {
+                Some(time) => {
+                    *literal = Literal::Time(time);
+                    other_data_type
+                }
+                None => anyhow::bail!("'{}' is not a valid time.", s),
+            },
+            _ => match literal.data_type_in_context(other_data_type) {
+                Some(new_data_type) => new_data_type,
+                None => return Ok(()),
+            },
+        };
+        *literal_data_type = new_data_type;
+        *data_type = new_data_type;
+    }
+    Ok(())
+}
+
 impl Expr {
     pub fn into_physical(self, schema: SchemaRef) -> anyhow::Result<PhysicalExpr> {
+        self.into_physical_with_clock(schema, Arc::new(SystemClock))
+    }
+
+    /// Like [`Expr::into_physical`], but `now()` returns the time of `clock`.
+    pub(crate) fn into_physical_with_clock(
+        self,
+        schema: SchemaRef,
+        clock: Arc<dyn Clock>,
+    ) -> anyhow::Result<PhysicalExpr> {
         let mut ctx = Context {
             schema,
             stateful_funcs: Vec::new(),
+            clock,
         };
         let (root, data_type) = to_physical(&mut ctx, self)?;
         Ok(PhysicalExpr {
@@ -25,7 +25,7 @@ impl UnaryOperator {
 
         match self {
             Neg => {
-                if data_type.is_numeric() {
+                if data_type.is_numeric() && !data_type.is_unsigned() {
                     Ok(data_type)
                 } else {
                     Err(unary_error(*self, data_type))
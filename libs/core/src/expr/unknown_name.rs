@@ -0,0 +1,162 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::expr::Span;
+
+/// What an unknown name refers to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NameKind {
+    Column,
+    Function,
+}
+
+/// An expression refers to a column or a function that does not exist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownNameError {
+    pub kind: NameKind,
+    /// The table of a column, or the namespace of a function.
+    pub qualifier: Option<String>,
+    pub name: String,
+    /// Where the column or the function is referred to in the SQL text of the query.
+    pub span: Span,
+    /// The most similar existing name, if one is close enough.
+    pub suggestion: Option<String>,
+}
+
+impl UnknownNameError {
+    pub(crate) fn new<I>(
+        kind: NameKind,
+        qualifier: Option<String>,
+        name: String,
+        span: Span,
+        candidates: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let full_name = match &qualifier {
+            Some(qualifier) => format!("{}.{}", qualifier, name),
+            None => name.clone(),
+        };
+        let suggestion = did_you_mean(&full_name, candidates);
+        Self {
+            kind,
+            qualifier,
+            name,
+            span,
+            suggestion,
+        }
+    }
+}
+
+impl Display for UnknownNameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            NameKind::Column => "column",
+            NameKind::Function => "function",
+        };
+        match &self.qualifier {
+            Some(qualifier) => write!(f, "no such {}: '{}.{}'", kind, qualifier, self.name)?,
+            None => write!(f, "no such {}: '{}'", kind, self.name)?,
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownNameError {}
+
+/// Returns the candidate closest to `name`, ignoring case, if it differs by at most a third of
+/// the characters of `name`.
+fn did_you_mean<I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let name = name.to_lowercase().chars().collect::<Vec<_>>();
+    let max_distance = (name.len() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let distance =
+                edit_distance(&name, &candidate.to_lowercase().chars().collect::<Vec<_>>());
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The number of insertions, deletions, substitutions and transpositions of adjacent characters
+/// to turn `a` into `b`.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in rows[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<String> {
+        vec!["name".to_string(), "age".to_string(), "address".to_string()]
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        assert_eq!(did_you_mean("nmae", candidates()), Some("name".to_string()));
+        assert_eq!(
+            did_you_mean("NAMES", candidates()),
+            Some("name".to_string())
+        );
+        assert_eq!(
+            did_you_mean("adress", candidates()),
+            Some("address".to_string())
+        );
+        assert_eq!(did_you_mean("ag", candidates()), Some("age".to_string()));
+        assert_eq!(did_you_mean("id", candidates()), None);
+        assert_eq!(did_you_mean("value", candidates()), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let err = UnknownNameError::new(
+            NameKind::Column,
+            None,
+            "nmae".to_string(),
+            Span::default(),
+            candidates(),
+        );
+        assert_eq!(
+            err.to_string(),
+            "no such column: 'nmae', did you mean 'name'?"
+        );
+
+        let err = UnknownNameError::new(
+            NameKind::Function,
+            None,
+            "foo".to_string(),
+            Span::default(),
+            vec!["sum".to_string()],
+        );
+        assert_eq!(err.to_string(), "no such function: 'foo'");
+    }
+}
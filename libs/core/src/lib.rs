@@ -1,5 +1,16 @@
+//! Without the default `runtime` feature only the SQL parser, the planner and the expressions
+//! are built, for tools that analyze queries without running them.
+
+// The helpers of the streams are unused without the runtime.
+#![cfg_attr(not(feature = "runtime"), allow(dead_code))]
+
 pub mod expr;
+pub mod metrics;
+pub mod progress;
+pub mod rates;
+#[cfg(feature = "runtime")]
 pub mod sinks;
+#[cfg(feature = "runtime")]
 pub mod sources;
 pub mod sql;
 
@@ -9,12 +20,21 @@ mod planner;
 mod sink_provider;
 mod source_provider;
 
-pub use dataframe::{dsl, DataFrame};
-pub use execution::execution_context::ExecutionContext;
+pub use dataframe::{dsl, DataFrame, LogicalPlanBuilder};
+pub use execution::clock::{Clock, ManualClock, SystemClock};
+pub use execution::execution_context::{
+    AdaptiveBatchSize, CheckPointFailureAction, CheckPointFailureCallback, ExecutionContext,
+};
 pub use execution::storage::Storage;
-pub use planner::window::Window;
+pub use execution::watermark::{WatermarkSkew, WatermarkSkewCallback};
+pub use planner::logical_plan::{SourceRef, PLAN_VERSION};
+pub use planner::state_size::{AggregateStateSize, CardinalityHints, StateSizeReport};
+pub use planner::statistics::{
+    NodeStatistics, PlanStatistics, StatisticsHints, StatisticsProvider, DEFAULT_SELECTIVITY,
+};
+pub use planner::window::{Emit, Window, WindowColumnPlacement};
 pub use sink_provider::{BoxSink, Sink, SinkProvider};
 pub use source_provider::{
-    GenericSourceDataSet, GenericSourceProvider, SourceProvider, SourceProviderWrapper,
+    BatchSize, GenericSourceDataSet, GenericSourceProvider, SourceProvider, SourceProviderWrapper,
 };
 pub use yql_dataset::{array, dataset};
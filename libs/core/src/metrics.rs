@@ -0,0 +1,314 @@
+//! Metrics collected from the running streams.
+//!
+//! Every dataset is stamped with the time its rows were received by the source, when a sink
+//! finishes writing it the elapsed time is recorded in a histogram per stream and sink. The
+//! metrics of the process can be exported in the Prometheus text format with [`render`].
+//!
+//! The lag of the watermark of each source behind the maximum watermark of all sources of the
+//! stream is also recorded.
+//!
+//! The rows dropped by the sources and the aggregations are counted by reason, see
+//! [`DropReason`].
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+/// Upper bounds of the latency buckets, in seconds.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+static SINK_LATENCY: Lazy<RwLock<BTreeMap<(String, String), Histogram>>> =
+    Lazy::new(Default::default);
+
+/// Watermark lag and name of each source, by stream and source id.
+type WatermarkLags = BTreeMap<(String, usize), (&'static str, Duration)>;
+
+static WATERMARK_LAG: Lazy<RwLock<WatermarkLags>> = Lazy::new(Default::default);
+
+/// Dropped rows and name of each source, by stream, source id and reason.
+type DroppedRows = BTreeMap<(String, usize, DropReason), (&'static str, u64)>;
+
+static DROPPED_ROWS: Lazy<RwLock<DroppedRows>> = Lazy::new(Default::default);
+
+/// Why a source dropped rows instead of passing them to the rest of the stream.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum DropReason {
+    /// The time of the row is behind the watermark by more than the allowed lateness, or all the
+    /// windows of the row have been emitted.
+    Late,
+    /// The time expression returned null for the row.
+    NullTime,
+    /// The row was delivered again by the source after a restore.
+    Duplicate,
+}
+
+impl DropReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DropReason::Late => "late",
+            DropReason::NullTime => "null_time",
+            DropReason::Duplicate => "duplicate",
+        }
+    }
+}
+
+/// A histogram of observed values with cumulative buckets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len()],
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bound, count) in self.bounds.iter().zip(&mut self.counts) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+
+    /// Returns the number of observed values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the sum of the observed values.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Returns the upper bound of each bucket and the number of values less than or equal to it.
+    pub fn buckets(&self) -> impl Iterator<Item = (f64, u64)> + '_ {
+        self.bounds.iter().copied().zip(self.counts.iter().copied())
+    }
+}
+
+/// Records the end-to-end latency of a dataset written by the sink `sink` of the stream `stream`.
+pub fn observe_sink_latency(stream: &str, sink: &str, latency: Duration) {
+    SINK_LATENCY
+        .write()
+        .entry((stream.to_string(), sink.to_string()))
+        .or_insert_with(|| Histogram::new(LATENCY_BUCKETS))
+        .observe(latency.as_secs_f64());
+}
+
+/// Returns the end-to-end latency histogram, in seconds, of the sink `sink` of the stream `stream`.
+pub fn sink_latency(stream: &str, sink: &str) -> Option<Histogram> {
+    SINK_LATENCY
+        .read()
+        .get(&(stream.to_string(), sink.to_string()))
+        .cloned()
+}
+
+/// Records how far the watermark of the source `source_id` lags behind the maximum watermark of
+/// all sources of the stream `stream`.
+pub fn set_watermark_lag(stream: &str, source_id: usize, source: &'static str, lag: Duration) {
+    WATERMARK_LAG
+        .write()
+        .insert((stream.to_string(), source_id), (source, lag));
+}
+
+/// Returns the watermark lag of the source `source_id` of the stream `stream`.
+pub fn watermark_lag(stream: &str, source_id: usize) -> Option<Duration> {
+    WATERMARK_LAG
+        .read()
+        .get(&(stream.to_string(), source_id))
+        .map(|(_, lag)| *lag)
+}
+
+/// Counts `rows` rows dropped by the source `source_id` of the stream `stream`.
+pub fn add_dropped_rows(
+    stream: &str,
+    source_id: usize,
+    source: &'static str,
+    reason: DropReason,
+    rows: u64,
+) {
+    DROPPED_ROWS
+        .write()
+        .entry((stream.to_string(), source_id, reason))
+        .or_insert((source, 0))
+        .1 += rows;
+}
+
+/// Returns the number of rows the source `source_id` of the stream `stream` dropped for
+/// `reason`.
+pub fn dropped_rows(stream: &str, source_id: usize, reason: DropReason) -> u64 {
+    DROPPED_ROWS
+        .read()
+        .get(&(stream.to_string(), source_id, reason))
+        .map(|(_, rows)| *rows)
+        .unwrap_or_default()
+}
+
+/// Removes the metrics of the stream `stream`.
+pub fn remove_stream(stream: &str) {
+    SINK_LATENCY.write().retain(|(name, _), _| name != stream);
+    WATERMARK_LAG.write().retain(|(name, _), _| name != stream);
+    DROPPED_ROWS
+        .write()
+        .retain(|(name, _, _), _| name != stream);
+}
+
+/// Renders all metrics in the Prometheus text format.
+pub fn render() -> String {
+    let mut output = String::new();
+    let histograms = SINK_LATENCY.read();
+
+    if !histograms.is_empty() {
+        output.push_str(
+            "# HELP yql_sink_latency_seconds Time from the source receiving the rows to the sink writing them.\n",
+        );
+        output.push_str("# TYPE yql_sink_latency_seconds histogram\n");
+    }
+    for ((stream, sink), histogram) in histograms.iter() {
+        let labels = format!(
+            "stream=\"{}\",sink=\"{}\"",
+            escape_label(stream),
+            escape_label(sink)
+        );
+        for (bound, count) in histogram.buckets() {
+            let _ = writeln!(
+                output,
+                "yql_sink_latency_seconds_bucket{{{},le=\"{}\"}} {}",
+                labels, bound, count
+            );
+        }
+        let _ = writeln!(
+            output,
+            "yql_sink_latency_seconds_bucket{{{},le=\"+Inf\"}} {}",
+            labels, histogram.count
+        );
+        let _ = writeln!(
+            output,
+            "yql_sink_latency_seconds_sum{{{}}} {}",
+            labels, histogram.sum
+        );
+        let _ = writeln!(
+            output,
+            "yql_sink_latency_seconds_count{{{}}} {}",
+            labels, histogram.count
+        );
+    }
+
+    let lags = WATERMARK_LAG.read();
+    if !lags.is_empty() {
+        output.push_str(
+            "# HELP yql_watermark_lag_seconds How far the watermark of the source lags behind the other sources of the stream.\n",
+        );
+        output.push_str("# TYPE yql_watermark_lag_seconds gauge\n");
+    }
+    for ((stream, source_id), (source, lag)) in lags.iter() {
+        let _ = writeln!(
+            output,
+            "yql_watermark_lag_seconds{{stream=\"{}\",source=\"{}\",source_id=\"{}\"}} {}",
+            escape_label(stream),
+            source,
+            source_id,
+            lag.as_secs_f64()
+        );
+    }
+
+    let dropped_rows = DROPPED_ROWS.read();
+    if !dropped_rows.is_empty() {
+        output.push_str(
+            "# HELP yql_dropped_rows_total Rows dropped by the source, by the reason they were dropped.\n",
+        );
+        output.push_str("# TYPE yql_dropped_rows_total counter\n");
+    }
+    for ((stream, source_id, reason), (source, rows)) in dropped_rows.iter() {
+        let _ = writeln!(
+            output,
+            "yql_dropped_rows_total{{stream=\"{}\",source=\"{}\",source_id=\"{}\",reason=\"{}\"}} {}",
+            escape_label(stream),
+            source,
+            source_id,
+            reason.as_str(),
+            rows
+        );
+    }
+
+    output
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sink_latency() {
+        observe_sink_latency("test_metrics", "console", Duration::from_millis(3));
+        observe_sink_latency("test_metrics", "console", Duration::from_millis(200));
+        observe_sink_latency("test_metrics", "console", Duration::from_secs(100));
+
+        let histogram = sink_latency("test_metrics", "console").unwrap();
+        assert_eq!(histogram.count(), 3);
+        assert!((histogram.sum() - 100.203).abs() < 1e-9);
+        let buckets = histogram.buckets().collect::<Vec<_>>();
+        assert_eq!(buckets[0], (0.001, 0));
+        assert_eq!(buckets[1], (0.005, 1));
+        assert_eq!(buckets[6], (0.25, 2));
+        assert_eq!(buckets[13], (60.0, 2));
+
+        let output = render();
+        assert!(output.contains(
+            "yql_sink_latency_seconds_bucket{stream=\"test_metrics\",sink=\"console\",le=\"0.005\"} 1\n"
+        ));
+        assert!(output.contains(
+            "yql_sink_latency_seconds_bucket{stream=\"test_metrics\",sink=\"console\",le=\"+Inf\"} 3\n"
+        ));
+        assert!(output.contains(
+            "yql_sink_latency_seconds_count{stream=\"test_metrics\",sink=\"console\"} 3\n"
+        ));
+
+        remove_stream("test_metrics");
+        assert!(sink_latency("test_metrics", "console").is_none());
+    }
+
+    #[test]
+    fn test_dropped_rows() {
+        add_dropped_rows("test_dropped_rows", 1, "csv", DropReason::Late, 3);
+        add_dropped_rows("test_dropped_rows", 1, "csv", DropReason::Late, 2);
+        add_dropped_rows("test_dropped_rows", 1, "csv", DropReason::NullTime, 1);
+        assert_eq!(dropped_rows("test_dropped_rows", 1, DropReason::Late), 5);
+        assert_eq!(
+            dropped_rows("test_dropped_rows", 1, DropReason::NullTime),
+            1
+        );
+        assert_eq!(dropped_rows("test_dropped_rows", 2, DropReason::Late), 0);
+
+        assert!(render().contains(
+            "yql_dropped_rows_total{stream=\"test_dropped_rows\",source=\"csv\",source_id=\"1\",reason=\"late\"} 5\n"
+        ));
+
+        remove_stream("test_dropped_rows");
+        assert_eq!(dropped_rows("test_dropped_rows", 1, DropReason::Late), 0);
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use crate::dataset::Schema;
+use crate::planner::logical_plan::LogicalPlan;
+use crate::planner::physical_plan::{PhysicalNode, PhysicalPlan};
+
+/// Writes a graph in the Graphviz dot format, the rows flow from the sources to the output.
+struct DotWriter {
+    body: String,
+    node_count: usize,
+}
+
+impl DotWriter {
+    fn new() -> Self {
+        Self {
+            body: String::new(),
+            node_count: 0,
+        }
+    }
+
+    /// Adds a node with the lines of `label`, and returns its name.
+    fn node(&mut self, label: &[String]) -> String {
+        let name = format!("n{}", self.node_count);
+        self.node_count += 1;
+        let _ = writeln!(
+            self.body,
+            "  {} [label=\"{}\"];",
+            name,
+            label.iter().map(|line| escape(line)).join("\\n")
+        );
+        name
+    }
+
+    fn edge(&mut self, from: &str, to: &str, schema: Option<&Schema>) {
+        match schema {
+            Some(schema) => {
+                let label = schema
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        escape(&format!("{}: {}", field.qualified_name(), field.data_type))
+                    })
+                    .join("\\l");
+                let _ = writeln!(self.body, "  {} -> {} [label=\"{}\\l\"];", from, to, label);
+            }
+            None => {
+                let _ = writeln!(self.body, "  {} -> {};", from, to);
+            }
+        }
+    }
+
+    fn finish(self) -> String {
+        format!("digraph plan {{\n  node [shape=box];\n{}}}\n", self.body)
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn list(name: &str, items: impl IntoIterator<Item = impl ToString>) -> Option<String> {
+    let items = items.into_iter().map(|item| item.to_string()).join(", ");
+    if items.is_empty() {
+        None
+    } else {
+        Some(format!("{}: {}", name, items))
+    }
+}
+
+impl LogicalPlan {
+    /// Returns the graph of the operators of the plan in the Graphviz dot format.
+    ///
+    /// The schemas of a logical plan are only known at its sources, so only the edges from the
+    /// sources are labeled with their schemas.
+    pub fn to_dot(&self) -> String {
+        let mut writer = DotWriter::new();
+        let root = logical_node(&mut writer, self);
+        let output = writer.node(&["Output".to_string()]);
+        writer.edge(&root, &output, None);
+        writer.finish()
+    }
+}
+
+/// Adds the node of `plan` and its inputs, and returns the name of the node.
+fn logical_node(writer: &mut DotWriter, plan: &LogicalPlan) -> String {
+    let (label, input) = match plan {
+        LogicalPlan::Source(source) => {
+            let mut label = vec![format!("Source {}", source.source_provider.provider_name())];
+            label.extend(source.name.as_ref().map(|name| format!("name: {}", name)));
+            label.extend(
+                source
+                    .qualifier
+                    .as_ref()
+                    .map(|qualifier| format!("qualifier: {}", qualifier)),
+            );
+            label.extend(
+                source
+                    .time_expr
+                    .as_ref()
+                    .map(|expr| format!("time: {}", expr)),
+            );
+            label.extend(
+                source
+                    .watermark_expr
+                    .as_ref()
+                    .map(|expr| format!("watermark: {}", expr)),
+            );
+            label.extend(list("filters", &source.filters));
+            let node = writer.node(&label);
+            if let Ok(schema) = source.source_provider.schema() {
+                let input = writer.node(&["Input".to_string()]);
+                writer.edge(&input, &node, Some(&schema));
+            }
+            return node;
+        }
+        LogicalPlan::Projection(projection) => (
+            std::iter::once("Projection".to_string())
+                .chain(projection.exprs.iter().map(ToString::to_string))
+                .collect::<Vec<_>>(),
+            &projection.input,
+        ),
+        LogicalPlan::Filter(filter) => (
+            vec!["Filter".to_string(), filter.expr.to_string()],
+            &filter.input,
+        ),
+        LogicalPlan::Aggregate(aggregate) => {
+            let mut label = vec![format!("Aggregate {}", aggregate.window)];
+            label.extend(list("group by", &aggregate.group_exprs));
+            label.extend(aggregate.aggr_exprs.iter().map(ToString::to_string));
+            label.extend(
+                aggregate
+                    .time_zone_expr
+                    .as_ref()
+                    .map(|expr| format!("time zone: {}", expr)),
+            );
+            if aggregate.allowed_lateness != 0 {
+                label.push(format!(
+                    "allowed lateness: {}ms",
+                    aggregate.allowed_lateness
+                ));
+            }
+            label.push(format!("emit: {}", aggregate.emit));
+            (label, &aggregate.input)
+        }
+        LogicalPlan::Analytic(analytic) => (
+            std::iter::once("Analytic".to_string())
+                .chain(analytic.exprs.iter().map(ToString::to_string))
+                .collect(),
+            &analytic.input,
+        ),
+    };
+    let input = logical_node(writer, input);
+    let node = writer.node(&label);
+    writer.edge(&input, &node, None);
+    node
+}
+
+impl PhysicalPlan {
+    /// Returns the graph of the operators of the plan in the Graphviz dot format, each edge is
+    /// labeled with the schema of the rows that flow through it.
+    pub fn to_dot(&self) -> String {
+        let mut writer = DotWriter::new();
+        let root = physical_node(&mut writer, &self.root);
+        let output = writer.node(&["Output".to_string()]);
+        writer.edge(&root, &output, Some(&self.root.schema()));
+        writer.finish()
+    }
+}
+
+fn physical_node(writer: &mut DotWriter, node: &PhysicalNode) -> String {
+    let (label, input) = match node {
+        PhysicalNode::Source(source) => {
+            let mut label = vec![format!(
+                "Source {} #{}",
+                source.source_provider.provider_name(),
+                source.id
+            )];
+            if source.time_expr.is_some() {
+                label.push("with time".to_string());
+            }
+            if !source.filters.is_empty() {
+                label.push(format!("{} filters", source.filters.len()));
+            }
+            if source.allowed_lateness != 0 {
+                label.push(format!("allowed lateness: {}ms", source.allowed_lateness));
+            }
+            return writer.node(&label);
+        }
+        PhysicalNode::Projection(projection) => {
+            let mut label = vec![format!("Projection #{}", projection.id)];
+            if !projection.common_exprs.is_empty() {
+                label.push("with common sub-expressions".to_string());
+            }
+            (label, &projection.input)
+        }
+        PhysicalNode::Filter(filter) => {
+            let mut label = vec![format!("Filter #{}", filter.id)];
+            if !filter.common_exprs.is_empty() {
+                label.push("with common sub-expressions".to_string());
+            }
+            (label, &filter.input)
+        }
+        PhysicalNode::Aggregate(aggregate) => {
+            let mut label = vec![format!("Aggregate {} #{}", aggregate.window, aggregate.id)];
+            label.extend(list("group by", &aggregate.group_names));
+            if aggregate.allowed_lateness != 0 {
+                label.push(format!(
+                    "allowed lateness: {}ms",
+                    aggregate.allowed_lateness
+                ));
+            }
+            label.push(format!("emit: {}", aggregate.emit));
+            (label, &aggregate.input)
+        }
+        PhysicalNode::Analytic(analytic) => (
+            std::iter::once(format!("Analytic #{}", analytic.id))
+                .chain(
+                    analytic
+                        .exprs
+                        .iter()
+                        .map(|expr| format!("{:?}", expr.function)),
+                )
+                .collect(),
+            &analytic.input,
+        ),
+    };
+    let input_node = physical_node(writer, input);
+    let node_name = writer.node(&label);
+    writer.edge(&input_node, &node_name, Some(&input.schema()));
+    node_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a = \"b\"\n"), "a = \\\"b\\\"\\n");
+        assert_eq!(escape("\\"), "\\\\");
+    }
+
+    #[test]
+    fn test_writer() {
+        let mut writer = DotWriter::new();
+        let a = writer.node(&["A".to_string(), "x".to_string()]);
+        let b = writer.node(&["B".to_string()]);
+        writer.edge(&a, &b, None);
+        assert_eq!(
+            writer.finish(),
+            "digraph plan {\n  node [shape=box];\n  n0 [label=\"A\\nx\"];\n  n1 [label=\"B\"];\n  n0 -> n1;\n}\n"
+        );
+    }
+}
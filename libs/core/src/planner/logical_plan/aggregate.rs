@@ -1,10 +1,17 @@
+use serde::{Deserialize, Serialize};
+
 use crate::expr::Expr;
 use crate::planner::logical_plan::LogicalPlan;
-use crate::planner::window::Window;
+use crate::planner::window::{Emit, Window};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LogicalAggregatePlan {
     pub input: Box<LogicalPlan>,
     pub group_exprs: Vec<Expr>,
     pub aggr_exprs: Vec<Expr>,
     pub window: Window,
+    /// The time zone each row aligns its windows to, overriding the time zone of the time column.
+    pub time_zone_expr: Option<Expr>,
+    pub allowed_lateness: i64,
+    pub emit: Emit,
 }
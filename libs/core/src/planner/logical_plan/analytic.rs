@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use crate::expr::Expr;
+use crate::planner::logical_plan::LogicalPlan;
+
+/// Appends the results of analytic function calls (`Expr::Over`) to the input rows.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LogicalAnalyticPlan {
+    pub input: Box<LogicalPlan>,
+    pub exprs: Vec<Expr>,
+}
@@ -1,6 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 use crate::expr::Expr;
 use crate::planner::logical_plan::LogicalPlan;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LogicalFilterPlan {
     pub input: Box<LogicalPlan>,
     pub expr: Expr,
@@ -1,16 +1,24 @@
 mod aggregate;
+mod analytic;
 mod filter;
 mod projection;
+mod serialize;
 mod source;
 
+use serde::{Deserialize, Serialize};
+
 pub use aggregate::LogicalAggregatePlan;
+pub use analytic::LogicalAnalyticPlan;
 pub use filter::LogicalFilterPlan;
 pub use projection::LogicalProjectionPlan;
+pub use serialize::{SourceRef, PLAN_VERSION};
 pub use source::LogicalSourcePlan;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub enum LogicalPlan {
     Source(LogicalSourcePlan),
     Projection(LogicalProjectionPlan),
     Filter(LogicalFilterPlan),
     Aggregate(LogicalAggregatePlan),
+    Analytic(LogicalAnalyticPlan),
 }
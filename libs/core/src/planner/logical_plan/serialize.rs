@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use futures_util::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::SchemaRef;
+use crate::planner::logical_plan::{LogicalPlan, LogicalSourcePlan};
+use crate::source_provider::{GenericSourceDataSet, GenericSourceProvider, SourceProvider};
+
+/// The version of the format of serialized plans.
+///
+/// It is increased when plans serialized by this version can't be read by the previous ones.
+/// Fields added to a plan are ignored by the versions that don't know them.
+pub const PLAN_VERSION: u32 = 1;
+
+/// A source of a serialized plan.
+///
+/// The source providers are not serialized, they are resolved again from these references when
+/// the plan is loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceRef {
+    /// The name the source was created from, such as the source of a SQL query.
+    pub name: Option<String>,
+    pub qualifier: Option<String>,
+    /// The name of the source provider, such as `csv`.
+    pub provider: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlanEnvelope {
+    version: u32,
+    /// The sources in the order they appear in the plan.
+    sources: Vec<SourceRef>,
+    plan: LogicalPlan,
+}
+
+impl LogicalPlan {
+    /// Serializes the plan to JSON, in an envelope with the version of the format.
+    pub fn to_json(&self) -> Result<String> {
+        let mut sources = Vec::new();
+        let mut plan = self.clone();
+        for_each_source(&mut plan, &mut |source| {
+            sources.push(SourceRef {
+                name: source.name.clone(),
+                qualifier: source.qualifier.clone(),
+                provider: source.source_provider.provider_name().to_string(),
+            });
+            Ok(())
+        })?;
+        Ok(serde_json::to_string_pretty(&PlanEnvelope {
+            version: PLAN_VERSION,
+            sources,
+            plan,
+        })?)
+    }
+
+    /// Loads a plan serialized by [`LogicalPlan::to_json`], `resolve` creates the provider of
+    /// each source.
+    pub fn from_json<F>(json: &str, mut resolve: F) -> Result<LogicalPlan>
+    where
+        F: FnMut(&SourceRef) -> Result<SourceProvider>,
+    {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or_else(|| anyhow::anyhow!("the serialized plan has no version."))?;
+        anyhow::ensure!(
+            version <= PLAN_VERSION as u64,
+            "the plan was serialized with the version {}, but only versions up to {} are supported.",
+            version,
+            PLAN_VERSION
+        );
+
+        let PlanEnvelope {
+            sources, mut plan, ..
+        } = serde_json::from_value(value)?;
+        let mut sources = sources.into_iter();
+        for_each_source(&mut plan, &mut |source| {
+            let source_ref = sources
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("the serialized plan has too few sources."))?;
+            source.source_provider = resolve(&source_ref).with_context(|| {
+                format!(
+                    "failed to resolve the source '{}'",
+                    source_ref.name.as_deref().unwrap_or(&source_ref.provider)
+                )
+            })?;
+            Ok(())
+        })?;
+        anyhow::ensure!(
+            sources.next().is_none(),
+            "the serialized plan has too many sources."
+        );
+        Ok(plan)
+    }
+}
+
+fn for_each_source<F>(plan: &mut LogicalPlan, f: &mut F) -> Result<()>
+where
+    F: FnMut(&mut LogicalSourcePlan) -> Result<()>,
+{
+    match plan {
+        LogicalPlan::Source(source) => f(source),
+        LogicalPlan::Projection(projection) => for_each_source(&mut projection.input, f),
+        LogicalPlan::Filter(filter) => for_each_source(&mut filter.input, f),
+        LogicalPlan::Aggregate(aggregate) => for_each_source(&mut aggregate.input, f),
+        LogicalPlan::Analytic(analytic) => for_each_source(&mut analytic.input, f),
+    }
+}
+
+/// The provider of a deserialized source until it is resolved.
+struct UnresolvedSource;
+
+impl GenericSourceProvider for UnresolvedSource {
+    type State = Vec<u8>;
+
+    fn provider_name(&self) -> &'static str {
+        "unresolved"
+    }
+
+    fn schema(&self) -> Result<SchemaRef> {
+        anyhow::bail!("the source of a deserialized plan is not resolved.")
+    }
+
+    fn create_stream(
+        &self,
+        _state: Option<Self::State>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        anyhow::bail!("the source of a deserialized plan is not resolved.")
+    }
+}
+
+pub(super) fn unresolved_source() -> SourceProvider {
+    Arc::new(UnresolvedSource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::Field;
+    use crate::dsl::*;
+    use crate::expr::Literal;
+    use crate::planner::logical_plan::{LogicalFilterPlan, LogicalProjectionPlan};
+    use crate::source_provider::TestSource;
+
+    fn fields() -> SourceProvider {
+        TestSource::provider(vec![Field::new("a", DataType::Int32)])
+    }
+
+    fn plan() -> LogicalPlan {
+        LogicalPlan::Projection(LogicalProjectionPlan {
+            input: Box::new(LogicalPlan::Filter(LogicalFilterPlan {
+                input: Box::new(LogicalPlan::Source(LogicalSourcePlan {
+                    name: Some("t".to_string()),
+                    qualifier: None,
+                    source_provider: fields(),
+                    time_expr: None,
+                    watermark_expr: None,
+                    filters: Vec::new(),
+                })),
+                expr: col("a").gt(value(Literal::Int(1))),
+            })),
+            exprs: vec![col("a") + value(Literal::Int(2))],
+        })
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let json = plan().to_json().unwrap();
+        let mut resolved = Vec::new();
+        let plan = LogicalPlan::from_json(&json, |source| {
+            resolved.push(source.clone());
+            Ok(fields())
+        })
+        .unwrap();
+        assert_eq!(
+            resolved,
+            vec![SourceRef {
+                name: Some("t".to_string()),
+                qualifier: None,
+                provider: "test".to_string(),
+            }]
+        );
+        assert_eq!(plan.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn test_newer_version() {
+        let mut value: serde_json::Value =
+            serde_json::from_str(&plan().to_json().unwrap()).unwrap();
+        value["version"] = serde_json::json!(PLAN_VERSION + 1);
+        let err = LogicalPlan::from_json(&value.to_string(), |_| unreachable!())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "the plan was serialized with the version {}, but only versions up to {} are supported.",
+                PLAN_VERSION + 1,
+                PLAN_VERSION
+            )
+        );
+    }
+}
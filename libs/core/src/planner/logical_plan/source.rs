@@ -1,9 +1,19 @@
+use serde::{Deserialize, Serialize};
+
 use crate::expr::Expr;
+use crate::planner::logical_plan::serialize::unresolved_source;
 use crate::source_provider::SourceProvider;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LogicalSourcePlan {
+    /// The name of the source the plan was created from, if it was created by name.
+    pub name: Option<String>,
     pub qualifier: Option<String>,
+    /// A serialized plan only refers to its sources, see [`SourceRef`](super::SourceRef).
+    #[serde(skip, default = "unresolved_source")]
     pub source_provider: SourceProvider,
     pub time_expr: Option<Expr>,
     pub watermark_expr: Option<Expr>,
+    /// The filter conditions pushed down to the source, the rows must match all of them.
+    pub filters: Vec<Expr>,
 }
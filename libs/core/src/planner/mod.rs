@@ -1,3 +1,7 @@
+mod dot;
 pub mod logical_plan;
+pub mod optimizer;
 pub mod physical_plan;
+pub mod state_size;
+pub mod statistics;
 pub mod window;
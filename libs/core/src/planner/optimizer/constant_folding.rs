@@ -0,0 +1,70 @@
+use anyhow::Result;
+
+use crate::expr::Expr;
+use crate::planner::logical_plan::{
+    LogicalAggregatePlan, LogicalAnalyticPlan, LogicalFilterPlan, LogicalPlan,
+    LogicalProjectionPlan, LogicalSourcePlan,
+};
+use crate::planner::optimizer::OptimizerRule;
+
+/// Evaluates the constant sub-expressions once when the plan is created, instead of for each
+/// batch.
+///
+/// The expressions that produce columns keep their names and types. The analytic function calls
+/// are left as they are, the projections refer to their results by name.
+pub struct ConstantFolding;
+
+impl OptimizerRule for ConstantFolding {
+    fn name(&self) -> &'static str {
+        "constant_folding"
+    }
+
+    fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
+        Ok(fold_plan(plan))
+    }
+}
+
+fn fold_plan(plan: LogicalPlan) -> LogicalPlan {
+    let fold_outputs = |exprs: Vec<Expr>| -> Vec<Expr> {
+        exprs
+            .into_iter()
+            .map(Expr::fold_constants_in_output)
+            .collect()
+    };
+
+    match plan {
+        LogicalPlan::Source(source) => LogicalPlan::Source(LogicalSourcePlan {
+            time_expr: source.time_expr.map(Expr::fold_constants),
+            watermark_expr: source.watermark_expr.map(Expr::fold_constants),
+            filters: source
+                .filters
+                .into_iter()
+                .map(Expr::fold_constants)
+                .collect(),
+            ..source
+        }),
+        LogicalPlan::Projection(projection) => LogicalPlan::Projection(LogicalProjectionPlan {
+            input: Box::new(fold_plan(*projection.input)),
+            exprs: fold_outputs(projection.exprs),
+        }),
+        LogicalPlan::Filter(filter) => LogicalPlan::Filter(LogicalFilterPlan {
+            input: Box::new(fold_plan(*filter.input)),
+            expr: filter.expr.fold_constants(),
+        }),
+        LogicalPlan::Aggregate(aggregate) => LogicalPlan::Aggregate(LogicalAggregatePlan {
+            input: Box::new(fold_plan(*aggregate.input)),
+            group_exprs: aggregate
+                .group_exprs
+                .into_iter()
+                .map(Expr::fold_constants)
+                .collect(),
+            aggr_exprs: fold_outputs(aggregate.aggr_exprs),
+            time_zone_expr: aggregate.time_zone_expr.map(Expr::fold_constants),
+            ..aggregate
+        }),
+        LogicalPlan::Analytic(analytic) => LogicalPlan::Analytic(LogicalAnalyticPlan {
+            input: Box::new(fold_plan(*analytic.input)),
+            exprs: analytic.exprs,
+        }),
+    }
+}
@@ -0,0 +1,32 @@
+//! Rewrites of the logical plan, applied before it is converted to a physical plan.
+
+mod constant_folding;
+mod predicate_pushdown;
+mod projection_pushdown;
+
+use anyhow::{Context, Result};
+
+use crate::planner::logical_plan::LogicalPlan;
+
+/// A rewrite of the logical plan that doesn't change its results.
+pub trait OptimizerRule {
+    fn name(&self) -> &'static str;
+
+    fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan>;
+}
+
+/// The rules applied to every plan, in order.
+fn rules() -> Vec<Box<dyn OptimizerRule>> {
+    vec![
+        Box::new(constant_folding::ConstantFolding),
+        Box::new(predicate_pushdown::PredicatePushdown),
+        Box::new(projection_pushdown::ProjectionPushdown),
+    ]
+}
+
+pub fn optimize(plan: LogicalPlan) -> Result<LogicalPlan> {
+    rules().into_iter().try_fold(plan, |plan, rule| {
+        rule.optimize(plan)
+            .with_context(|| format!("failed to apply the optimizer rule '{}'", rule.name()))
+    })
+}
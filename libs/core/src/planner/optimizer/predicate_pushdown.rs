@@ -0,0 +1,209 @@
+use anyhow::Result;
+
+use crate::expr::Expr;
+use crate::planner::logical_plan::{
+    LogicalAggregatePlan, LogicalAnalyticPlan, LogicalFilterPlan, LogicalPlan,
+    LogicalProjectionPlan,
+};
+use crate::planner::optimizer::OptimizerRule;
+
+/// Moves the filters below the projections, and into the sources, so the rows they drop are not
+/// evaluated by the expressions in between.
+///
+/// Only the filters without aggregate or analytic function calls are moved, below the
+/// projections without them, so every expression still sees the same rows. The filters are never
+/// moved below the aggregations and analytic functions.
+pub struct PredicatePushdown;
+
+impl OptimizerRule for PredicatePushdown {
+    fn name(&self) -> &'static str {
+        "predicate_pushdown"
+    }
+
+    fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
+        Ok(push_down(plan))
+    }
+}
+
+fn push_down(plan: LogicalPlan) -> LogicalPlan {
+    match plan {
+        LogicalPlan::Source(source) => LogicalPlan::Source(source),
+        LogicalPlan::Filter(filter) => push_filter(filter.expr, push_down(*filter.input)),
+        LogicalPlan::Projection(projection) => LogicalPlan::Projection(LogicalProjectionPlan {
+            input: Box::new(push_down(*projection.input)),
+            exprs: projection.exprs,
+        }),
+        LogicalPlan::Aggregate(aggregate) => LogicalPlan::Aggregate(LogicalAggregatePlan {
+            input: Box::new(push_down(*aggregate.input)),
+            ..aggregate
+        }),
+        LogicalPlan::Analytic(analytic) => LogicalPlan::Analytic(LogicalAnalyticPlan {
+            input: Box::new(push_down(*analytic.input)),
+            exprs: analytic.exprs,
+        }),
+    }
+}
+
+/// Filters the rows of `input` with `predicate`, as far down as possible.
+fn push_filter(predicate: Expr, input: LogicalPlan) -> LogicalPlan {
+    if !predicate.is_stateless() {
+        return filter(predicate, input);
+    }
+
+    match input {
+        LogicalPlan::Projection(projection) if projection.exprs.iter().all(Expr::is_stateless) => {
+            match substitute_columns(&predicate, &projection.exprs) {
+                Some(predicate) => LogicalPlan::Projection(LogicalProjectionPlan {
+                    input: Box::new(push_filter(predicate, *projection.input)),
+                    exprs: projection.exprs,
+                }),
+                None => filter(predicate, LogicalPlan::Projection(projection)),
+            }
+        }
+        LogicalPlan::Source(mut source) => {
+            source.filters.push(predicate);
+            LogicalPlan::Source(source)
+        }
+        input => filter(predicate, input),
+    }
+}
+
+fn filter(predicate: Expr, input: LogicalPlan) -> LogicalPlan {
+    LogicalPlan::Filter(LogicalFilterPlan {
+        input: Box::new(input),
+        expr: predicate,
+    })
+}
+
+/// Rewrites `predicate`, a condition on the results of a projection of `exprs`, into a condition
+/// on the input of the projection.
+///
+/// Returns `None` if a column of the predicate cannot be resolved to exactly one expression of the
+/// projection.
+fn substitute_columns(predicate: &Expr, exprs: &[Expr]) -> Option<Expr> {
+    let wildcard = |name: &str| {
+        exprs.iter().any(|expr| {
+            matches!(expr, Expr::Wildcard { exclude, .. } if !exclude.iter().any(|n| n == name))
+        })
+    };
+
+    predicate
+        .clone()
+        .transform(&mut |expr| match expr {
+            Expr::Column {
+                qualifier: None,
+                name,
+                span,
+            } => {
+                let mut matches = exprs.iter().filter(|expr| {
+                    !matches!(expr, Expr::Wildcard { .. }) && expr.create_name() == name
+                });
+                match (matches.next(), matches.next()) {
+                    (Some(Expr::Alias(expr, _)), None) => Ok((**expr).clone()),
+                    (Some(expr), None) => Ok(expr.clone()),
+                    (None, _) if wildcard(&name) => Ok(Expr::Column {
+                        qualifier: None,
+                        name,
+                        span,
+                    }),
+                    _ => anyhow::bail!("the column '{}' cannot be resolved", name),
+                }
+            }
+            Expr::Column {
+                qualifier: Some(qualifier),
+                name,
+                span,
+            } if wildcard(&name) => Ok(Expr::Column {
+                qualifier: Some(qualifier),
+                name,
+                span,
+            }),
+            Expr::Column { name, .. } => {
+                anyhow::bail!("the column '{}' cannot be resolved", name)
+            }
+            expr => Ok(expr),
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsl::*;
+    use crate::expr::Literal;
+    use crate::planner::logical_plan::LogicalSourcePlan;
+    use crate::source_provider::TestSource;
+
+    fn source() -> LogicalPlan {
+        LogicalPlan::Source(LogicalSourcePlan {
+            name: None,
+            qualifier: None,
+            source_provider: TestSource::provider(vec![]),
+            time_expr: None,
+            watermark_expr: None,
+            filters: Vec::new(),
+        })
+    }
+
+    fn projection(input: LogicalPlan, exprs: Vec<Expr>) -> LogicalPlan {
+        LogicalPlan::Projection(LogicalProjectionPlan {
+            input: Box::new(input),
+            exprs,
+        })
+    }
+
+    fn int(n: i64) -> Expr {
+        value(Literal::Int(n))
+    }
+
+    #[test]
+    fn test_substitute_columns() {
+        let exprs = vec![col("a"), (col("b") + int(1)).alias("c")];
+        assert_eq!(
+            substitute_columns(&col("c").gt(col("a")), &exprs),
+            Some((col("b") + int(1)).gt(col("a")))
+        );
+        assert_eq!(substitute_columns(&col("b").gt(int(1)), &exprs), None);
+
+        let exprs = vec![
+            Expr::Wildcard {
+                qualifier: None,
+                exclude: vec!["d".to_string()],
+            },
+            (col("b") + int(1)).alias("c"),
+        ];
+        assert_eq!(
+            substitute_columns(&col("b").eq(col("c")), &exprs),
+            Some(col("b").eq(col("b") + int(1)))
+        );
+        assert_eq!(substitute_columns(&col("d").gt(int(1)), &exprs), None);
+    }
+
+    #[test]
+    fn test_push_filter() {
+        let plan = push_filter(
+            col("c").gt(int(1)),
+            projection(source(), vec![(col("a") + col("b")).alias("c")]),
+        );
+        match plan {
+            LogicalPlan::Projection(projection) => match *projection.input {
+                LogicalPlan::Source(source) => {
+                    assert_eq!(source.filters, vec![(col("a") + col("b")).gt(int(1))])
+                }
+                _ => panic!("the filter is not pushed into the source"),
+            },
+            _ => panic!("the filter is not pushed below the projection"),
+        }
+
+        // The projection calls an aggregate function.
+        let plan = push_filter(
+            col("a").gt(int(1)),
+            projection(source(), vec![col("a"), call("sum", vec![col("b")])]),
+        );
+        assert!(matches!(plan, LogicalPlan::Filter(_)));
+
+        // The filter calls an aggregate function.
+        let plan = push_filter(call("count", vec![col("a")]).gt(int(1)), source());
+        assert!(matches!(plan, LogicalPlan::Filter(_)));
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+
+use crate::expr::Expr;
+use crate::planner::logical_plan::{LogicalPlan, LogicalSourcePlan};
+use crate::planner::optimizer::OptimizerRule;
+
+/// Lets the source only read the fields the query references, if it supports it with
+/// [`GenericSourceProvider::with_projection`](crate::GenericSourceProvider::with_projection).
+///
+/// The columns are matched by name, a field is read if any expression of the plan references a
+/// column with its name. Nothing is pushed down if the plan selects a wildcard.
+pub struct ProjectionPushdown;
+
+impl OptimizerRule for ProjectionPushdown {
+    fn name(&self) -> &'static str {
+        "projection_pushdown"
+    }
+
+    fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
+        let mut columns = HashSet::new();
+        if !plan_columns(&plan, &mut columns) {
+            return Ok(plan);
+        }
+        project_source(plan, &columns)
+    }
+}
+
+/// Adds the names of the columns referenced by the plan to `columns`, returns `false` if it
+/// references a wildcard.
+fn plan_columns(plan: &LogicalPlan, columns: &mut HashSet<String>) -> bool {
+    let (exprs, input): (Vec<&Expr>, _) = match plan {
+        LogicalPlan::Source(source) => (
+            source
+                .time_expr
+                .iter()
+                .chain(&source.watermark_expr)
+                .chain(&source.filters)
+                .collect(),
+            None,
+        ),
+        LogicalPlan::Projection(projection) => {
+            (projection.exprs.iter().collect(), Some(&projection.input))
+        }
+        LogicalPlan::Filter(filter) => (vec![&filter.expr], Some(&filter.input)),
+        LogicalPlan::Aggregate(aggregate) => (
+            aggregate
+                .group_exprs
+                .iter()
+                .chain(&aggregate.aggr_exprs)
+                .chain(&aggregate.time_zone_expr)
+                .collect(),
+            Some(&aggregate.input),
+        ),
+        LogicalPlan::Analytic(analytic) => (analytic.exprs.iter().collect(), Some(&analytic.input)),
+    };
+
+    exprs.into_iter().all(|expr| expr_columns(expr, columns))
+        && input.map_or(true, |input| plan_columns(input, columns))
+}
+
+fn expr_columns(expr: &Expr, columns: &mut HashSet<String>) -> bool {
+    let mut wildcard = false;
+    let _ = expr.clone().transform(&mut |expr| {
+        match &expr {
+            Expr::Column {
+                qualifier, name, ..
+            } => {
+                // `a.b` is the field `b` of the column `a` if there is no table `a`.
+                if let Some(qualifier) = qualifier {
+                    columns.insert(qualifier.clone());
+                }
+                columns.insert(name.clone());
+            }
+            Expr::Wildcard { .. } => wildcard = true,
+            _ => {}
+        }
+        Ok(expr)
+    });
+    !wildcard
+}
+
+fn project_source(plan: LogicalPlan, columns: &HashSet<String>) -> Result<LogicalPlan> {
+    Ok(match plan {
+        LogicalPlan::Source(source) => LogicalPlan::Source(project(source, columns)?),
+        LogicalPlan::Projection(mut projection) => {
+            projection.input = Box::new(project_source(*projection.input, columns)?);
+            LogicalPlan::Projection(projection)
+        }
+        LogicalPlan::Filter(mut filter) => {
+            filter.input = Box::new(project_source(*filter.input, columns)?);
+            LogicalPlan::Filter(filter)
+        }
+        LogicalPlan::Aggregate(mut aggregate) => {
+            aggregate.input = Box::new(project_source(*aggregate.input, columns)?);
+            LogicalPlan::Aggregate(aggregate)
+        }
+        LogicalPlan::Analytic(mut analytic) => {
+            analytic.input = Box::new(project_source(*analytic.input, columns)?);
+            LogicalPlan::Analytic(analytic)
+        }
+    })
+}
+
+fn project(source: LogicalSourcePlan, columns: &HashSet<String>) -> Result<LogicalSourcePlan> {
+    let schema = source.source_provider.schema()?;
+    let fields = schema.fields();
+    let mut indices = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| columns.contains(&field.name))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    if indices.len() == fields.len() {
+        return Ok(source);
+    }
+    if indices.is_empty() {
+        // A dataset has at least one column, which gives the number of rows.
+        indices.push(0);
+    }
+
+    match source.source_provider.with_projection(&indices) {
+        Some(source_provider) => Ok(LogicalSourcePlan {
+            source_provider,
+            ..source
+        }),
+        None => Ok(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::Field;
+    use crate::dsl::*;
+    use crate::planner::logical_plan::LogicalProjectionPlan;
+    use crate::source_provider::TestSource;
+
+    fn plan(exprs: Vec<Expr>) -> LogicalPlan {
+        LogicalPlan::Projection(LogicalProjectionPlan {
+            input: Box::new(LogicalPlan::Source(LogicalSourcePlan {
+                name: None,
+                qualifier: None,
+                source_provider: TestSource::provider(vec![
+                    Field::new("a", DataType::Int32),
+                    Field::new("b", DataType::Int32),
+                    Field::new("c", DataType::Int32),
+                ]),
+                time_expr: None,
+                watermark_expr: Some(col("c")),
+                filters: Vec::new(),
+            })),
+            exprs,
+        })
+    }
+
+    fn source_fields(plan: LogicalPlan) -> Vec<String> {
+        match plan {
+            LogicalPlan::Projection(projection) => match *projection.input {
+                LogicalPlan::Source(source) => source
+                    .source_provider
+                    .schema()
+                    .unwrap()
+                    .fields()
+                    .iter()
+                    .map(|field| field.name.clone())
+                    .collect(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_projection_pushdown() {
+        let optimized = ProjectionPushdown
+            .optimize(plan(vec![(col("a") + col("a")).alias("b")]))
+            .unwrap();
+        assert_eq!(source_fields(optimized), vec!["a", "c"]);
+
+        let optimized = ProjectionPushdown
+            .optimize(plan(vec![col("b").alias("x")]))
+            .unwrap();
+        assert_eq!(source_fields(optimized), vec!["b", "c"]);
+
+        let optimized = ProjectionPushdown
+            .optimize(plan(vec![col("a"), wildcard()]))
+            .unwrap();
+        assert_eq!(source_fields(optimized), vec!["a", "b", "c"]);
+    }
+}
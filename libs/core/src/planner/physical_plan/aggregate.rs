@@ -1,14 +1,20 @@
 use crate::dataset::SchemaRef;
 use crate::expr::physical_expr::PhysicalExpr;
 use crate::planner::physical_plan::PhysicalNode;
-use crate::planner::window::Window;
+use crate::planner::window::{Emit, Window, WindowColumnPlacement};
 
 pub struct PhysicalAggregateNode {
     pub id: usize,
     pub schema: SchemaRef,
     pub group_exprs: Vec<PhysicalExpr>,
+    /// The names of `group_exprs`, as written in the query.
+    pub group_names: Vec<String>,
     pub aggr_exprs: Vec<PhysicalExpr>,
     pub window: Window,
+    pub time_zone_expr: Option<PhysicalExpr>,
+    pub allowed_lateness: i64,
+    pub emit: Emit,
     pub time_idx: usize,
+    pub window_column_placement: WindowColumnPlacement,
     pub input: Box<PhysicalNode>,
 }
@@ -0,0 +1,29 @@
+use crate::array::DataType;
+use crate::dataset::SchemaRef;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::planner::physical_plan::PhysicalNode;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AnalyticFunction {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag { offset: usize },
+}
+
+pub struct PhysicalAnalyticExpr {
+    pub function: AnalyticFunction,
+    /// The value and the default value of `lag`.
+    pub args: Vec<PhysicalExpr>,
+    pub partition_by: Vec<PhysicalExpr>,
+    /// The sort keys, and whether they are descending.
+    pub order_by: Vec<(PhysicalExpr, bool)>,
+    pub data_type: DataType,
+}
+
+pub struct PhysicalAnalyticNode {
+    pub id: usize,
+    pub schema: SchemaRef,
+    pub exprs: Vec<PhysicalAnalyticExpr>,
+    pub input: Box<PhysicalNode>,
+}
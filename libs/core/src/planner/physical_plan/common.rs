@@ -0,0 +1,194 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::dataset::{DataSet, Field, Schema, SchemaRef};
+use crate::execution::clock::Clock;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::expr::{Expr, Span};
+use crate::planner::physical_plan::QUALIFIER_COMMON;
+
+/// The sub-expressions that appear more than once in the expressions of a node.
+///
+/// They are evaluated once per batch, and their values are appended to the columns of the batch
+/// where the expressions of the node refer to them.
+pub struct CommonExprs {
+    /// The schema of the input followed by the columns of the common sub-expressions.
+    pub schema: SchemaRef,
+    /// The sub-expressions with the schema of the batch after their values are appended.
+    exprs: Vec<(PhysicalExpr, SchemaRef)>,
+}
+
+impl CommonExprs {
+    pub fn is_empty(&self) -> bool {
+        self.exprs.is_empty()
+    }
+
+    /// Appends the values of the common sub-expressions to the dataset, a sub-expression can
+    /// refer to the ones before it.
+    pub fn eval(&mut self, dataset: &DataSet) -> Result<DataSet> {
+        let mut dataset = dataset.clone();
+        for (expr, schema) in &mut self.exprs {
+            let mut columns = dataset.columns().to_vec();
+            columns.push(expr.eval(&dataset)?);
+            dataset = DataSet::try_new(schema.clone(), columns)?;
+        }
+        Ok(dataset)
+    }
+}
+
+/// Replaces the sub-expressions that appear more than once in `exprs` with columns, and returns
+/// the rewritten expressions and the sub-expressions.
+///
+/// The sub-expressions are found from the bottom up, so `f(a) + 1` repeated becomes a column
+/// computed from the column of `f(a)`. Only stateless sub-expressions that compute something
+/// are shared.
+pub(crate) fn extract_common_exprs(exprs: Vec<Expr>) -> (Vec<Expr>, Vec<Expr>) {
+    let mut counts: Vec<(Expr, usize)> = Vec::new();
+    for expr in &exprs {
+        let _ = expr.clone().transform(&mut |expr| {
+            if is_candidate(&expr) {
+                match counts.iter_mut().find(|(candidate, _)| candidate == &expr) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((expr.clone(), 1)),
+                }
+            }
+            Ok(expr)
+        });
+    }
+    if counts.iter().all(|(_, count)| *count < 2) {
+        return (exprs, vec![]);
+    }
+
+    // The original sub-expressions and their rewritten forms.
+    let mut commons: Vec<(Expr, Expr)> = Vec::new();
+    let exprs = exprs
+        .into_iter()
+        .map(|expr| {
+            expr.transform(&mut |expr| {
+                let original = expand(&commons, expr.clone());
+                let repeated = counts
+                    .iter()
+                    .any(|(candidate, count)| *count > 1 && candidate == &original);
+                if !repeated {
+                    return Ok(expr);
+                }
+                let index = match commons.iter().position(|(common, _)| common == &original) {
+                    Some(index) => index,
+                    None => {
+                        commons.push((original, expr));
+                        commons.len() - 1
+                    }
+                };
+                Ok(common_column(index))
+            })
+            .expect("extracting common sub-expressions never fails")
+        })
+        .collect();
+    (exprs, commons.into_iter().map(|(_, expr)| expr).collect())
+}
+
+/// Converts the common sub-expressions, the expressions of the node are evaluated with the
+/// returned schema.
+pub(crate) fn common_exprs_to_physical(
+    exprs: Vec<Expr>,
+    input_schema: SchemaRef,
+    clock: &Arc<dyn Clock>,
+) -> Result<CommonExprs> {
+    let mut schema = input_schema;
+    let mut physical_exprs = Vec::with_capacity(exprs.len());
+    for (index, expr) in exprs.into_iter().enumerate() {
+        let physical_expr = expr.into_physical_with_clock(schema.clone(), clock.clone())?;
+        let mut fields = schema.fields().to_vec();
+        fields.push(Field {
+            qualifier: Some(QUALIFIER_COMMON.to_string()),
+            name: common_name(index),
+            data_type: physical_expr.data_type(),
+        });
+        schema = Arc::new(Schema::try_new(fields)?);
+        physical_exprs.push((physical_expr, schema.clone()));
+    }
+    Ok(CommonExprs {
+        schema,
+        exprs: physical_exprs,
+    })
+}
+
+fn is_candidate(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Binary { .. }
+            | Expr::Unary { .. }
+            | Expr::Call { .. }
+            | Expr::Array(_)
+            | Expr::Index { .. }
+            | Expr::Field { .. }
+    ) && expr.is_stateless()
+}
+
+fn common_name(index: usize) -> String {
+    format!("#{}", index)
+}
+
+fn common_column(index: usize) -> Expr {
+    Expr::Column {
+        qualifier: Some(QUALIFIER_COMMON.to_string()),
+        name: common_name(index),
+        span: Span::default(),
+    }
+}
+
+/// Replaces the columns of the common sub-expressions with the sub-expressions.
+fn expand(commons: &[(Expr, Expr)], expr: Expr) -> Expr {
+    expr.transform(&mut |expr| match &expr {
+        Expr::Column {
+            qualifier: Some(qualifier),
+            name,
+            ..
+        } if qualifier == QUALIFIER_COMMON => Ok(commons
+            .iter()
+            .enumerate()
+            .find(|(index, _)| &common_name(*index) == name)
+            .map(|(_, (original, _))| original.clone())
+            .unwrap_or(expr)),
+        _ => Ok(expr),
+    })
+    .expect("expanding common sub-expressions never fails")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsl::*;
+    use crate::expr::Literal;
+
+    #[test]
+    fn test_extract_common_exprs() {
+        let (exprs, commons) = extract_common_exprs(vec![col("a") + col("b"), col("c")]);
+        assert_eq!(exprs, vec![col("a") + col("b"), col("c")]);
+        assert!(commons.is_empty());
+
+        let abs = call("abs", vec![col("a")]);
+        let (exprs, commons) = extract_common_exprs(vec![
+            abs.clone() + value(Literal::Int(1)),
+            (abs.clone() + value(Literal::Int(1))).alias("b"),
+            abs.clone() * col("c"),
+        ]);
+        assert_eq!(
+            exprs,
+            vec![
+                common_column(1),
+                common_column(1).alias("b"),
+                common_column(0) * col("c"),
+            ]
+        );
+        assert_eq!(
+            commons,
+            vec![abs.clone(), common_column(0) + value(Literal::Int(1))]
+        );
+
+        let sum = call("sum", vec![col("a")]);
+        let (_, commons) = extract_common_exprs(vec![sum.clone(), sum]);
+        assert!(commons.is_empty());
+    }
+}
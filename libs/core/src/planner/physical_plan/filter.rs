@@ -1,10 +1,13 @@
 use crate::dataset::SchemaRef;
 use crate::expr::physical_expr::PhysicalExpr;
-use crate::planner::physical_plan::PhysicalNode;
+use crate::planner::physical_plan::{CommonExprs, PhysicalNode};
 
 pub struct PhysicalFilterNode {
     pub id: usize,
     pub schema: SchemaRef,
+    pub common_exprs: CommonExprs,
     pub expr: PhysicalExpr,
+    /// The condition, as written in the query.
+    pub condition: String,
     pub input: Box<PhysicalNode>,
 }
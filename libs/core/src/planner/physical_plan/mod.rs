@@ -1,4 +1,6 @@
 mod aggregate;
+mod analytic;
+mod common;
 mod filter;
 mod projection;
 mod source;
@@ -7,17 +9,26 @@ mod to_physical;
 use crate::dataset::SchemaRef;
 
 pub use aggregate::PhysicalAggregateNode;
+pub use analytic::{AnalyticFunction, PhysicalAnalyticExpr, PhysicalAnalyticNode};
+pub use common::CommonExprs;
 pub use filter::PhysicalFilterNode;
 pub use projection::PhysicalProjectionNode;
 pub use source::PhysicalSourceNode;
 
 pub const FIELD_TIME: &str = "@time";
 
+/// The qualifier of the columns produced by analytic functions.
+pub const QUALIFIER_ANALYTIC: &str = "@analytic";
+
+/// The qualifier of the columns produced by the common sub-expressions of a node.
+pub const QUALIFIER_COMMON: &str = "@common";
+
 pub enum PhysicalNode {
     Source(PhysicalSourceNode),
     Projection(PhysicalProjectionNode),
     Filter(PhysicalFilterNode),
     Aggregate(PhysicalAggregateNode),
+    Analytic(PhysicalAnalyticNode),
 }
 
 impl PhysicalNode {
@@ -27,6 +38,7 @@ impl PhysicalNode {
             PhysicalNode::Projection(projection) => projection.schema.clone(),
             PhysicalNode::Filter(filter) => filter.schema.clone(),
             PhysicalNode::Aggregate(aggregate) => aggregate.schema.clone(),
+            PhysicalNode::Analytic(analytic) => analytic.schema.clone(),
         }
     }
 }
@@ -1,10 +1,11 @@
 use crate::dataset::SchemaRef;
 use crate::expr::physical_expr::PhysicalExpr;
-use crate::planner::physical_plan::PhysicalNode;
+use crate::planner::physical_plan::{CommonExprs, PhysicalNode};
 
 pub struct PhysicalProjectionNode {
     pub id: usize,
     pub schema: SchemaRef,
+    pub common_exprs: CommonExprs,
     pub exprs: Vec<PhysicalExpr>,
     pub input: Box<PhysicalNode>,
 }
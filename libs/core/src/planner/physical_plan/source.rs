@@ -4,8 +4,16 @@ use crate::source_provider::SourceProvider;
 
 pub struct PhysicalSourceNode {
     pub id: usize,
+    /// The name of the source, if it was created by name.
+    pub name: Option<String>,
     pub schema: SchemaRef,
     pub source_provider: SourceProvider,
     pub time_expr: Option<PhysicalExpr>,
     pub watermark_expr: Option<PhysicalExpr>,
+    /// The filter conditions pushed down to the source, evaluated after the late rows are
+    /// dropped.
+    pub filters: Vec<PhysicalExpr>,
+    /// The conditions of `filters`, as written in the query.
+    pub filter_conditions: Vec<String>,
+    pub allowed_lateness: i64,
 }
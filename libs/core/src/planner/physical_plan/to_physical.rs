@@ -5,20 +5,30 @@ use itertools::Itertools;
 
 use crate::array::DataType;
 use crate::dataset::{Field, Schema, SchemaRef};
+use crate::execution::clock::Clock;
+use crate::execution::execution_context::ExecutionContext;
 use crate::expr::physical_expr::PhysicalExpr;
-use crate::expr::Expr;
+use crate::expr::{Expr, Literal, OrderBy};
 use crate::planner::logical_plan::{
-    LogicalAggregatePlan, LogicalFilterPlan, LogicalPlan, LogicalProjectionPlan, LogicalSourcePlan,
+    LogicalAggregatePlan, LogicalAnalyticPlan, LogicalFilterPlan, LogicalPlan,
+    LogicalProjectionPlan, LogicalSourcePlan,
 };
+use crate::planner::optimizer::optimize;
+use crate::planner::physical_plan::common::{common_exprs_to_physical, extract_common_exprs};
 use crate::planner::physical_plan::{
-    PhysicalAggregateNode, PhysicalFilterNode, PhysicalNode, PhysicalPlan, PhysicalProjectionNode,
-    PhysicalSourceNode, FIELD_TIME,
+    AnalyticFunction, PhysicalAggregateNode, PhysicalAnalyticExpr, PhysicalAnalyticNode,
+    PhysicalFilterNode, PhysicalNode, PhysicalPlan, PhysicalProjectionNode, PhysicalSourceNode,
+    FIELD_TIME, QUALIFIER_ANALYTIC, QUALIFIER_COMMON,
 };
+use crate::planner::window::{Window, WindowColumnPlacement};
 
 struct Context {
     id: usize,
     node_count: usize,
     source_count: usize,
+    allowed_lateness: i64,
+    window_column_placement: WindowColumnPlacement,
+    clock: Arc<dyn Clock>,
 }
 
 impl Context {
@@ -40,6 +50,7 @@ fn to_physical(ctx: &mut Context, plan: LogicalPlan) -> Result<PhysicalNode> {
         LogicalPlan::Projection(projection) => projection_to_physical(ctx, projection),
         LogicalPlan::Filter(filter) => filter_to_physical(ctx, filter),
         LogicalPlan::Aggregate(aggregate) => aggregate_to_physical(ctx, aggregate),
+        LogicalPlan::Analytic(analytic) => analytic_to_physical(ctx, analytic),
     }
 }
 
@@ -61,18 +72,30 @@ fn source_to_physical(ctx: &mut Context, source: LogicalSourcePlan) -> Result<Ph
             })
             .collect(),
     )?);
+    let filter_conditions = source.filters.iter().map(ToString::to_string).collect();
+    let filters = source
+        .filters
+        .into_iter()
+        .map(|expr| filter_condition(expr, schema.clone(), &ctx.clock))
+        .try_collect()?;
     Ok(PhysicalNode::Source(PhysicalSourceNode {
         id: ctx.take_id(),
+        name: source.name,
         schema,
         source_provider: source.source_provider,
         time_expr: match source.time_expr {
-            Some(expr) => Some(expr.into_physical(source_schema.clone())?),
+            Some(expr) => {
+                Some(expr.into_physical_with_clock(source_schema.clone(), ctx.clock.clone())?)
+            }
             None => None,
         },
         watermark_expr: match source.watermark_expr {
-            Some(expr) => Some(expr.into_physical(source_schema)?),
+            Some(expr) => Some(expr.into_physical_with_clock(source_schema, ctx.clock.clone())?),
             None => None,
         },
+        filters,
+        filter_conditions,
+        allowed_lateness: ctx.allowed_lateness,
     }))
 }
 
@@ -81,10 +104,23 @@ fn projection_to_physical(
     projection: LogicalProjectionPlan,
 ) -> Result<PhysicalNode> {
     let input = to_physical(ctx, *projection.input)?;
-    let (exprs, schema) = select_expr(projection.exprs, input.schema(), vec![])?;
+    let (rewritten_exprs, common_exprs) = extract_common_exprs(projection.exprs.clone());
+    // The names and the errors come from the expressions as they were written.
+    let (mut exprs, schema) = select_expr(projection.exprs, input.schema(), vec![], &ctx.clock)?;
+    let common_exprs = common_exprs_to_physical(common_exprs, input.schema(), &ctx.clock)?;
+    if !common_exprs.is_empty() {
+        exprs = select_expr(
+            rewritten_exprs,
+            common_exprs.schema.clone(),
+            vec![],
+            &ctx.clock,
+        )?
+        .0;
+    }
     Ok(PhysicalNode::Projection(PhysicalProjectionNode {
         id: ctx.take_id(),
         schema,
+        common_exprs,
         exprs,
         input: Box::new(input),
     }))
@@ -92,25 +128,61 @@ fn projection_to_physical(
 
 fn filter_to_physical(ctx: &mut Context, filter: LogicalFilterPlan) -> Result<PhysicalNode> {
     let input = to_physical(ctx, *filter.input)?;
-    let expr = filter.expr.into_physical(input.schema())?;
-
-    anyhow::ensure!(
-        expr.data_type() == DataType::Boolean,
-        "filter expression must return a boolean type."
-    );
+    let condition = filter.expr.to_string();
+    let (mut rewritten_exprs, common_exprs) = extract_common_exprs(vec![filter.expr.clone()]);
+    let mut expr = filter_condition(filter.expr, input.schema(), &ctx.clock)?;
+    let common_exprs = common_exprs_to_physical(common_exprs, input.schema(), &ctx.clock)?;
+    if !common_exprs.is_empty() {
+        expr = filter_condition(
+            rewritten_exprs.remove(0),
+            common_exprs.schema.clone(),
+            &ctx.clock,
+        )?;
+    }
     Ok(PhysicalNode::Filter(PhysicalFilterNode {
         id: ctx.take_id(),
         schema: input.schema(),
+        common_exprs,
         expr,
+        condition,
         input: Box::new(input),
     }))
 }
 
+/// Converts the condition of a filter, a numeric condition is true if it is not zero.
+fn filter_condition(expr: Expr, schema: SchemaRef, clock: &Arc<dyn Clock>) -> Result<PhysicalExpr> {
+    let physical_expr = expr
+        .clone()
+        .into_physical_with_clock(schema.clone(), clock.clone())?;
+    match physical_expr.data_type() {
+        DataType::Boolean => Ok(physical_expr),
+        data_type if data_type.is_numeric() => expr
+            .not_eq(Expr::Literal(Literal::Int(0)))
+            .into_physical_with_clock(schema, clock.clone()),
+        data_type => anyhow::bail!(
+            "the filter condition '{}' must return a boolean type, but it returns '{}'.",
+            expr,
+            data_type
+        ),
+    }
+}
+
 fn aggregate_to_physical(
     ctx: &mut Context,
     aggregate: LogicalAggregatePlan,
 ) -> Result<PhysicalNode> {
-    let input = to_physical(ctx, *aggregate.input)?;
+    if let Some(provider_name) = changelog_source(&aggregate.input) {
+        anyhow::bail!(
+            "Cannot aggregate the changes of a '{}' source, its retracted rows are not supported yet.",
+            provider_name
+        );
+    }
+
+    // The source must keep the late rows that the aggregation still accepts.
+    let outer_lateness = std::mem::replace(&mut ctx.allowed_lateness, aggregate.allowed_lateness);
+    let input = to_physical(ctx, *aggregate.input);
+    ctx.allowed_lateness = outer_lateness;
+    let input = input?;
     let (time_idx, timezone) = match input.schema().field(None, FIELD_TIME) {
         Some((idx, Field { data_type:DataType::Timestamp(timezone), .. })) => {
             (idx, *timezone)
@@ -118,24 +190,190 @@ fn aggregate_to_physical(
         _ => anyhow::bail!("A column whose name is '@time' and type is 'timestamp' is required to perform aggregation operations."),
     };
 
+    let time_zone_expr = match aggregate.time_zone_expr {
+        Some(expr) => {
+            let expr = expr.into_physical_with_clock(input.schema(), ctx.clock.clone())?;
+            anyhow::ensure!(
+                expr.data_type() == DataType::String,
+                "time zone expression must return a string type."
+            );
+            Some(expr)
+        }
+        None => None,
+    };
+    let group_names = aggregate
+        .group_exprs
+        .iter()
+        .map(ToString::to_string)
+        .collect();
     let group_exprs = aggregate
         .group_exprs
         .into_iter()
-        .map(|expr| expr.into_physical(input.schema()))
+        .map(|expr| expr.into_physical_with_clock(input.schema(), ctx.clock.clone()))
         .try_collect()?;
-    let (aggr_exprs, schema) = select_expr(
-        aggregate.aggr_exprs,
-        input.schema(),
-        vec![Field::new(FIELD_TIME, DataType::Timestamp(timezone))],
-    )?;
+    let time_field = Field::new(FIELD_TIME, DataType::Timestamp(timezone));
+    let (aggr_exprs, schema) = match ctx.window_column_placement {
+        WindowColumnPlacement::First => {
+            let (aggr_exprs, schema) =
+                select_expr(aggregate.aggr_exprs, input.schema(), vec![], &ctx.clock)?;
+            let fields = std::iter::once(time_field)
+                .chain(schema.fields().iter().cloned())
+                .collect();
+            (aggr_exprs, Arc::new(Schema::try_new(fields)?))
+        }
+        WindowColumnPlacement::Last => select_expr(
+            aggregate.aggr_exprs,
+            input.schema(),
+            vec![time_field],
+            &ctx.clock,
+        )?,
+    };
+    if let Window::Session { .. } = aggregate.window {
+        // An event that bridges two sessions merges their states.
+        let first_field = match ctx.window_column_placement {
+            WindowColumnPlacement::First => 1,
+            WindowColumnPlacement::Last => 0,
+        };
+        for (expr, field) in aggr_exprs.iter().zip(&schema.fields()[first_field..]) {
+            anyhow::ensure!(
+                expr.can_merge(),
+                "'{}' cannot be aggregated in a session window, the states of its functions cannot be merged.",
+                field.name
+            );
+        }
+    }
 
     Ok(PhysicalNode::Aggregate(PhysicalAggregateNode {
         id: ctx.take_id(),
         schema,
         group_exprs,
+        group_names,
         aggr_exprs,
         window: aggregate.window,
+        time_zone_expr,
+        allowed_lateness: aggregate.allowed_lateness,
+        emit: aggregate.emit,
         time_idx,
+        window_column_placement: ctx.window_column_placement,
+        input: Box::new(input),
+    }))
+}
+
+/// Returns the provider name of a source of `plan` whose rows retract earlier rows.
+fn changelog_source(plan: &LogicalPlan) -> Option<&'static str> {
+    match plan {
+        LogicalPlan::Source(source) => Some(source.source_provider.as_ref())
+            .filter(|provider| provider.is_changelog())
+            .map(|provider| provider.provider_name()),
+        LogicalPlan::Projection(projection) => changelog_source(&projection.input),
+        LogicalPlan::Filter(filter) => changelog_source(&filter.input),
+        LogicalPlan::Aggregate(aggregate) => changelog_source(&aggregate.input),
+        LogicalPlan::Analytic(analytic) => changelog_source(&analytic.input),
+    }
+}
+
+fn analytic_to_physical(ctx: &mut Context, analytic: LogicalAnalyticPlan) -> Result<PhysicalNode> {
+    let input = to_physical(ctx, *analytic.input)?;
+    let input_schema = input.schema();
+    let mut fields = input_schema.fields().to_vec();
+    let mut exprs = Vec::new();
+
+    for expr in analytic.exprs {
+        let name = expr.to_string();
+        let (expr, partition_by, order_by) = match expr {
+            Expr::Over {
+                expr,
+                partition_by,
+                order_by,
+            } => (*expr, partition_by, order_by),
+            _ => anyhow::bail!("internal error: not an analytic function call"),
+        };
+        let (func_name, mut args) = match expr {
+            Expr::Call {
+                namespace: None,
+                name,
+                args,
+                ..
+            } => (name, args),
+            expr => anyhow::bail!("'{}' is not an analytic function.", expr),
+        };
+
+        let (function, arg_count) = match func_name.to_ascii_lowercase().as_str() {
+            "row_number" => (AnalyticFunction::RowNumber, 0..=0),
+            "rank" => (AnalyticFunction::Rank, 0..=0),
+            "dense_rank" => (AnalyticFunction::DenseRank, 0..=0),
+            "lag" => (AnalyticFunction::Lag { offset: 1 }, 1..=3),
+            // The value of `lead` depends on rows that have not arrived yet, and might never.
+            "lead" => anyhow::bail!(
+                "the analytic function 'lead' is not supported on streams, use 'lag' with the \
+                 reverse order."
+            ),
+            _ => anyhow::bail!("'{}' is not an analytic function.", func_name),
+        };
+        anyhow::ensure!(
+            arg_count.contains(&args.len()),
+            "misuse function: {}",
+            func_name
+        );
+
+        // `lag(value[, offset[, default]])`
+        let function = match function {
+            AnalyticFunction::Lag { .. } if args.len() >= 2 => {
+                let offset = match args.remove(1) {
+                    Expr::Literal(Literal::Int(offset)) if offset >= 0 => offset as usize,
+                    _ => anyhow::bail!(
+                        "the offset of '{}' must be a non-negative integer literal.",
+                        func_name
+                    ),
+                };
+                AnalyticFunction::Lag { offset }
+            }
+            function => function,
+        };
+        let args: Vec<PhysicalExpr> = args
+            .into_iter()
+            .map(|arg| arg.into_physical_with_clock(input_schema.clone(), ctx.clock.clone()))
+            .try_collect()?;
+        if let [value, default] = args.as_slice() {
+            anyhow::ensure!(
+                default.data_type().can_cast_to(value.data_type()),
+                "the default value of '{}' must have the same type as its value.",
+                func_name
+            );
+        }
+
+        let data_type = match function {
+            AnalyticFunction::Lag { .. } => args[0].data_type(),
+            _ => DataType::Int64,
+        };
+
+        fields.push(Field {
+            qualifier: Some(QUALIFIER_ANALYTIC.to_string()),
+            name,
+            data_type,
+        });
+        exprs.push(PhysicalAnalyticExpr {
+            function,
+            args,
+            partition_by: partition_by
+                .into_iter()
+                .map(|expr| expr.into_physical_with_clock(input_schema.clone(), ctx.clock.clone()))
+                .try_collect()?,
+            order_by: order_by
+                .into_iter()
+                .map(|OrderBy { expr, descending }| {
+                    expr.into_physical_with_clock(input_schema.clone(), ctx.clock.clone())
+                        .map(|expr| (expr, descending))
+                })
+                .try_collect()?,
+            data_type,
+        });
+    }
+
+    Ok(PhysicalNode::Analytic(PhysicalAnalyticNode {
+        id: ctx.take_id(),
+        schema: Arc::new(Schema::try_new(fields)?),
+        exprs,
         input: Box::new(input),
     }))
 }
@@ -144,35 +382,54 @@ fn select_expr(
     exprs: Vec<Expr>,
     schema: SchemaRef,
     extra_fields: Vec<Field>,
+    clock: &Arc<dyn Clock>,
 ) -> Result<(Vec<PhysicalExpr>, SchemaRef)> {
     let mut fields = Vec::new();
     let mut physical_exprs = Vec::new();
 
     for expr in exprs {
         match expr {
-            Expr::Wildcard { qualifier } => {
-                let select_fields = match qualifier.clone() {
+            Expr::Wildcard { qualifier, exclude } => {
+                let mut select_fields = match qualifier.clone() {
                     Some(qualifier) => schema
                         .fields()
                         .to_vec()
                         .into_iter()
                         .filter(|field| field.qualifier.as_ref() == Some(&qualifier))
                         .collect::<Vec<_>>(),
-                    None => schema.fields().to_vec().into_iter().collect(),
+                    None => schema
+                        .fields()
+                        .iter()
+                        .filter(|field| {
+                            !matches!(
+                                field.qualifier.as_deref(),
+                                Some(QUALIFIER_ANALYTIC) | Some(QUALIFIER_COMMON)
+                            )
+                        })
+                        .cloned()
+                        .collect(),
                 };
+                for name in &exclude {
+                    anyhow::ensure!(
+                        select_fields.iter().any(|field| &field.name == name),
+                        "the excluded column '{}' is not found.",
+                        name
+                    );
+                }
+                select_fields.retain(|field| !exclude.contains(&field.name));
                 for field in &select_fields {
                     let expr = Expr::Column {
                         qualifier: qualifier.clone(),
                         name: field.name.clone(),
                     }
-                    .into_physical(schema.clone())?;
+                    .into_physical_with_clock(schema.clone(), clock.clone())?;
                     physical_exprs.push(expr);
                 }
                 fields.extend(select_fields.into_iter());
             }
             _ => {
                 let field_name = expr.create_name();
-                let physical_expr = expr.into_physical(schema.clone())?;
+                let physical_expr = expr.into_physical_with_clock(schema.clone(), clock.clone())?;
                 fields.push(Field {
                     qualifier: None,
                     name: field_name,
@@ -189,13 +446,16 @@ fn select_expr(
 }
 
 impl PhysicalPlan {
-    pub fn try_new(plan: LogicalPlan) -> Result<PhysicalPlan> {
+    pub fn try_new(plan: LogicalPlan, exec_ctx: &ExecutionContext) -> Result<PhysicalPlan> {
         let mut ctx = Context {
             id: 0,
             node_count: 0,
             source_count: 0,
+            allowed_lateness: 0,
+            window_column_placement: exec_ctx.window_column_placement,
+            clock: exec_ctx.clock.clone(),
         };
-        let root = to_physical(&mut ctx, plan)?;
+        let root = to_physical(&mut ctx, optimize(plan)?)?;
         Ok(PhysicalPlan {
             root,
             source_count: ctx.source_count,
@@ -203,3 +463,38 @@ impl PhysicalPlan {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsl::*;
+    use crate::execution::clock::SystemClock;
+
+    #[test]
+    fn test_filter_condition() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int8),
+                Field::new("s", DataType::String),
+            ])
+            .unwrap(),
+        );
+
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let expr = filter_condition(
+            col("a").gt(Expr::Literal(Literal::Int(10))),
+            schema.clone(),
+            &clock,
+        )
+        .unwrap();
+        assert_eq!(expr.data_type(), DataType::Boolean);
+        let expr = filter_condition(col("a"), schema.clone(), &clock).unwrap();
+        assert_eq!(expr.data_type(), DataType::Boolean);
+
+        let err = filter_condition(col("s"), schema, &clock).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "the filter condition 's' must return a boolean type, but it returns 'string'."
+        );
+    }
+}
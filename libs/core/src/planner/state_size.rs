@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use crate::planner::physical_plan::{PhysicalAggregateNode, PhysicalNode, PhysicalPlan};
+use crate::planner::window::{Period, Window, WindowColumnPlacement};
+
+/// The expected number of distinct values of the group expressions, by the name of the
+/// expression as written in the query.
+#[derive(Debug, Default, Clone)]
+pub struct CardinalityHints {
+    cardinalities: HashMap<String, u64>,
+}
+
+impl CardinalityHints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_cardinality(mut self, expr: impl Into<String>, cardinality: u64) -> Self {
+        self.cardinalities.insert(expr.into(), cardinality);
+        self
+    }
+
+    /// Returns the number of groups of `group_names`, the product of their cardinalities.
+    pub(crate) fn groups(&self, group_names: &[String]) -> Option<u64> {
+        group_names.iter().try_fold(1u64, |groups, name| {
+            groups.checked_mul(*self.cardinalities.get(name)?)
+        })
+    }
+}
+
+/// The estimated worst-case state of an aggregation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateStateSize {
+    /// Id of the aggregation node in the plan.
+    pub id: usize,
+    pub window: Window,
+    pub group_exprs: Vec<String>,
+    /// The declared upper bound of the state of each aggregate expression in bytes, `None` if
+    /// the state can grow without bound.
+    pub aggr_exprs: Vec<(String, Option<usize>)>,
+    /// The number of groups, `None` if a group expression has no cardinality hint.
+    pub groups: Option<u64>,
+    /// The maximum number of windows of a group that are open at the same time.
+    pub open_windows: u64,
+}
+
+impl AggregateStateSize {
+    /// Returns the upper bound of the state of a window of a group in bytes.
+    pub fn state_size_per_group(&self) -> Option<u64> {
+        self.aggr_exprs
+            .iter()
+            .map(|(_, size)| size.map(|size| size as u64))
+            .sum()
+    }
+
+    /// Returns the upper bound of the whole state in bytes.
+    pub fn max_state_size(&self) -> Option<u64> {
+        self.state_size_per_group()?
+            .checked_mul(self.groups?)?
+            .checked_mul(self.open_windows)
+    }
+}
+
+/// Estimates the worst-case state of each aggregation of a plan, to size the deployment of a
+/// stream before running it.
+///
+/// Only the states of the aggregate functions are counted, not the group keys or the
+/// bookkeeping of the windows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateSizeReport {
+    pub aggregates: Vec<AggregateStateSize>,
+}
+
+impl StateSizeReport {
+    pub(crate) fn new(plan: &PhysicalPlan, hints: &CardinalityHints) -> Self {
+        let mut aggregates = Vec::new();
+        collect_aggregates(&plan.root, hints, &mut aggregates);
+        aggregates.sort_by_key(|aggregate| aggregate.id);
+        Self { aggregates }
+    }
+
+    /// Returns the upper bound of the state of all aggregations in bytes, `None` if any of them
+    /// is unbounded or unknown.
+    pub fn max_state_size(&self) -> Option<u64> {
+        self.aggregates
+            .iter()
+            .map(|aggregate| aggregate.max_state_size())
+            .sum()
+    }
+}
+
+fn collect_aggregates(
+    node: &PhysicalNode,
+    hints: &CardinalityHints,
+    aggregates: &mut Vec<AggregateStateSize>,
+) {
+    let input = match node {
+        PhysicalNode::Source(_) => return,
+        PhysicalNode::Projection(projection) => &projection.input,
+        PhysicalNode::Filter(filter) => &filter.input,
+        PhysicalNode::Analytic(analytic) => &analytic.input,
+        PhysicalNode::Aggregate(aggregate) => {
+            aggregates.push(aggregate_state_size(aggregate, hints));
+            &aggregate.input
+        }
+    };
+    collect_aggregates(input, hints, aggregates);
+}
+
+fn aggregate_state_size(
+    aggregate: &PhysicalAggregateNode,
+    hints: &CardinalityHints,
+) -> AggregateStateSize {
+    let first_field = match aggregate.window_column_placement {
+        WindowColumnPlacement::First => 1,
+        WindowColumnPlacement::Last => 0,
+    };
+    let aggr_exprs = aggregate.schema.fields()[first_field..]
+        .iter()
+        .zip(&aggregate.aggr_exprs)
+        .map(|(field, expr)| (field.name.clone(), expr.max_state_size()))
+        .collect();
+
+    AggregateStateSize {
+        id: aggregate.id,
+        window: aggregate.window,
+        group_exprs: aggregate.group_names.clone(),
+        aggr_exprs,
+        groups: hints.groups(&aggregate.group_names),
+        open_windows: open_windows(aggregate.window, aggregate.allowed_lateness),
+    }
+}
+
+/// Returns the maximum number of windows of a group that are open at the same time, windows
+/// stay open for `allowed_lateness` after they end.
+fn open_windows(window: Window, allowed_lateness: i64) -> u64 {
+    let ceil_div = |a: i64, b: i64| ((a + b - 1) / b) as u64;
+    match window {
+        Window::Fixed { length } => 1 + ceil_div(allowed_lateness, length),
+        Window::Sliding {
+            length, interval, ..
+        } => ceil_div(length, interval) + ceil_div(allowed_lateness, interval),
+        Window::Period { period } => {
+            const DAY: i64 = 24 * 60 * 60 * 1000;
+            // The shortest length of the period, so that the estimate is an upper bound.
+            let length = match period {
+                Period::Day => DAY - 60 * 60 * 1000,
+                Period::Week => 7 * DAY - 60 * 60 * 1000,
+                Period::Month => 28 * DAY,
+                Period::Year => 365 * DAY,
+            };
+            1 + ceil_div(allowed_lateness, length)
+        }
+        // Sessions of a group never overlap, but any number of them may be waiting for late
+        // rows when the gap is shorter than the allowed lateness.
+        Window::Session { gap } => 1 + ceil_div(allowed_lateness, gap),
+    }
+}
+
+fn display_size(f: &mut Formatter<'_>, size: Option<u64>, unknown: &str) -> fmt::Result {
+    match size {
+        Some(size) => write!(f, "{} bytes", size),
+        None => f.write_str(unknown),
+    }
+}
+
+impl Display for StateSizeReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for aggregate in &self.aggregates {
+            writeln!(f, "aggregate #{} window {}", aggregate.id, aggregate.window)?;
+            if !aggregate.group_exprs.is_empty() {
+                write!(f, "  group by {}", aggregate.group_exprs.join(", "))?;
+                match aggregate.groups {
+                    Some(groups) => writeln!(f, " ({} groups)", groups)?,
+                    None => writeln!(f, " (unknown cardinality)")?,
+                }
+            }
+            for (name, size) in &aggregate.aggr_exprs {
+                write!(f, "  {}: ", name)?;
+                display_size(f, size.map(|size| size as u64), "unbounded")?;
+                writeln!(f)?;
+            }
+            writeln!(f, "  open windows per group: {}", aggregate.open_windows)?;
+            f.write_str("  max state: ")?;
+            display_size(f, aggregate.max_state_size(), "unknown")?;
+            writeln!(f)?;
+        }
+        f.write_str("total max state: ")?;
+        display_size(f, self.max_state_size(), "unknown")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_windows() {
+        assert_eq!(open_windows(Window::Fixed { length: 60000 }, 0), 1);
+        assert_eq!(open_windows(Window::Fixed { length: 60000 }, 90000), 3);
+        assert_eq!(
+            open_windows(
+                Window::Sliding {
+                    length: 300000,
+                    interval: 60000,
+                    offset: 0
+                },
+                60000
+            ),
+            6
+        );
+        assert_eq!(open_windows(Window::Session { gap: 30000 }, 0), 1);
+    }
+
+    #[test]
+    fn test_aggregate_state_size() {
+        let hints = CardinalityHints::new()
+            .with_cardinality("user", 1000)
+            .with_cardinality("country", 200);
+        assert_eq!(
+            hints.groups(&["user".to_string(), "country".to_string()]),
+            Some(200000)
+        );
+        assert_eq!(hints.groups(&["device".to_string()]), None);
+        assert_eq!(hints.groups(&[]), Some(1));
+
+        let mut aggregate = AggregateStateSize {
+            id: 1,
+            window: Window::Fixed { length: 60000 },
+            group_exprs: vec!["user".to_string()],
+            aggr_exprs: vec![
+                ("count(*)".to_string(), Some(8)),
+                ("avg(a)".to_string(), Some(16)),
+            ],
+            groups: Some(1000),
+            open_windows: 2,
+        };
+        assert_eq!(aggregate.state_size_per_group(), Some(24));
+        assert_eq!(aggregate.max_state_size(), Some(48000));
+
+        aggregate.aggr_exprs.push(("first(s)".to_string(), None));
+        assert_eq!(aggregate.max_state_size(), None);
+    }
+}
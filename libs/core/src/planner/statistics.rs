@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use crate::planner::physical_plan::{PhysicalNode, PhysicalPlan};
+use crate::planner::state_size::CardinalityHints;
+use crate::planner::window::{Emit, Period, Window};
+
+/// The fraction of the rows that match a filter without statistics.
+pub const DEFAULT_SELECTIVITY: f64 = 0.2;
+
+/// Provides the statistics the rows of the nodes of a plan are estimated from.
+///
+/// A statistic that is not known returns `None`, and the estimates of the nodes that depend on
+/// it are unknown or use [`DEFAULT_SELECTIVITY`].
+pub trait StatisticsProvider {
+    /// Returns the number of rows per second read from a source, by its name if it was created
+    /// by name, such as the sources of SQL queries, or else by the name of its provider.
+    fn source_rows(&self, _name: Option<&str>, _provider_name: &str) -> Option<f64> {
+        None
+    }
+
+    /// Returns the fraction of the rows that match a filter, by its condition as written in the
+    /// query.
+    fn selectivity(&self, _condition: &str) -> Option<f64> {
+        None
+    }
+
+    /// Returns the number of groups of an aggregation, by the names of its group expressions.
+    fn groups(&self, _group_names: &[String]) -> Option<f64> {
+        None
+    }
+}
+
+/// Statistics given by hand, by the names of the sources, the filter conditions and the group
+/// expressions.
+#[derive(Debug, Default, Clone)]
+pub struct StatisticsHints {
+    source_rows: HashMap<String, f64>,
+    selectivities: HashMap<String, f64>,
+    cardinalities: CardinalityHints,
+}
+
+impl StatisticsHints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_source_rows(mut self, source: impl Into<String>, rows_per_second: f64) -> Self {
+        self.source_rows.insert(source.into(), rows_per_second);
+        self
+    }
+
+    pub fn with_selectivity(mut self, condition: impl Into<String>, selectivity: f64) -> Self {
+        self.selectivities
+            .insert(condition.into(), selectivity.clamp(0.0, 1.0));
+        self
+    }
+
+    pub fn with_cardinality(mut self, expr: impl Into<String>, cardinality: u64) -> Self {
+        self.cardinalities = self.cardinalities.with_cardinality(expr, cardinality);
+        self
+    }
+}
+
+impl StatisticsProvider for StatisticsHints {
+    fn source_rows(&self, name: Option<&str>, provider_name: &str) -> Option<f64> {
+        self.source_rows.get(name.unwrap_or(provider_name)).copied()
+    }
+
+    fn selectivity(&self, condition: &str) -> Option<f64> {
+        self.selectivities.get(condition).copied()
+    }
+
+    fn groups(&self, group_names: &[String]) -> Option<f64> {
+        self.cardinalities
+            .groups(group_names)
+            .map(|groups| groups as f64)
+    }
+}
+
+/// The estimated output of a node of a plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStatistics {
+    /// Id of the node in the plan.
+    pub id: usize,
+    /// The kind of the node, such as `filter`.
+    pub kind: &'static str,
+    /// The number of rows per second the node outputs, `None` if a statistic it depends on is
+    /// unknown.
+    pub rows: Option<f64>,
+    /// The fraction of the input rows the node outputs.
+    pub selectivity: Option<f64>,
+}
+
+/// The estimated rows of each node of a plan, the groundwork for the cost-based decisions of
+/// the planner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStatistics {
+    /// The nodes by id, from the sources to the output.
+    pub nodes: Vec<NodeStatistics>,
+}
+
+impl PlanStatistics {
+    pub fn node(&self, id: usize) -> Option<&NodeStatistics> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+}
+
+impl PhysicalPlan {
+    pub fn statistics(&self, provider: &dyn StatisticsProvider) -> PlanStatistics {
+        let mut nodes = Vec::new();
+        estimate(&self.root, provider, &mut nodes);
+        nodes.sort_by_key(|node| node.id);
+        PlanStatistics { nodes }
+    }
+}
+
+/// Estimates the node and its inputs, and returns the rows per second of the node.
+fn estimate(
+    node: &PhysicalNode,
+    provider: &dyn StatisticsProvider,
+    nodes: &mut Vec<NodeStatistics>,
+) -> Option<f64> {
+    let selectivity_of = |conditions: &[String]| {
+        conditions
+            .iter()
+            .map(|condition| {
+                provider
+                    .selectivity(condition)
+                    .unwrap_or(DEFAULT_SELECTIVITY)
+            })
+            .product::<f64>()
+    };
+
+    let (id, kind, rows, selectivity) = match node {
+        PhysicalNode::Source(source) => {
+            let selectivity = selectivity_of(&source.filter_conditions);
+            let rows = provider
+                .source_rows(
+                    source.name.as_deref(),
+                    source.source_provider.provider_name(),
+                )
+                .map(|rows| rows * selectivity);
+            (source.id, "source", rows, Some(selectivity))
+        }
+        PhysicalNode::Projection(projection) => {
+            let rows = estimate(&projection.input, provider, nodes);
+            (projection.id, "projection", rows, Some(1.0))
+        }
+        PhysicalNode::Analytic(analytic) => {
+            let rows = estimate(&analytic.input, provider, nodes);
+            (analytic.id, "analytic", rows, Some(1.0))
+        }
+        PhysicalNode::Filter(filter) => {
+            let selectivity = selectivity_of(std::slice::from_ref(&filter.condition));
+            let rows = estimate(&filter.input, provider, nodes).map(|rows| rows * selectivity);
+            (filter.id, "filter", rows, Some(selectivity))
+        }
+        PhysicalNode::Aggregate(aggregate) => {
+            let input_rows = estimate(&aggregate.input, provider, nodes);
+            let rows = match aggregate.emit {
+                // Every row updates a group of each of its windows.
+                Emit::OnUpdate => input_rows,
+                Emit::Final | Emit::Every { .. } => {
+                    let emit_interval = match aggregate.emit {
+                        Emit::Every { interval } => Some(interval),
+                        _ => emit_interval(aggregate.window),
+                    };
+                    let groups = provider.groups(&aggregate.group_names);
+                    match (input_rows, groups, emit_interval) {
+                        (Some(input_rows), Some(groups), Some(interval)) => {
+                            Some(input_rows.min(groups * 1000.0 / interval as f64))
+                        }
+                        _ => None,
+                    }
+                }
+            };
+            let selectivity = match (rows, input_rows) {
+                (Some(rows), Some(input_rows)) if input_rows > 0.0 => Some(rows / input_rows),
+                _ => None,
+            };
+            (aggregate.id, "aggregate", rows, selectivity)
+        }
+    };
+
+    nodes.push(NodeStatistics {
+        id,
+        kind,
+        rows,
+        selectivity,
+    });
+    rows
+}
+
+/// Returns the interval in milliseconds between the ends of consecutive windows, `None` for
+/// sessions which end with the rows.
+fn emit_interval(window: Window) -> Option<i64> {
+    const DAY: i64 = 24 * 60 * 60 * 1000;
+    match window {
+        Window::Fixed { length } => Some(length),
+        Window::Sliding { interval, .. } => Some(interval),
+        Window::Period { period } => Some(match period {
+            Period::Day => DAY,
+            Period::Week => 7 * DAY,
+            Period::Month => 30 * DAY,
+            Period::Year => 365 * DAY,
+        }),
+        Window::Session { .. } => None,
+    }
+}
+
+impl Display for PlanStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            write!(f, "{} #{}: ", node.kind, node.id)?;
+            match node.rows {
+                Some(rows) => write!(f, "{:.2} rows/s", rows)?,
+                None => f.write_str("unknown rows")?,
+            }
+            if let Some(selectivity) = node.selectivity {
+                write!(f, ", selectivity {:.3}", selectivity)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints() {
+        let hints = StatisticsHints::new()
+            .with_source_rows("t", 1000.0)
+            .with_selectivity("(a > 1)", 1.5)
+            .with_cardinality("k", 10);
+        assert_eq!(hints.source_rows(Some("t"), "csv"), Some(1000.0));
+        assert_eq!(hints.source_rows(None, "csv"), None);
+        assert_eq!(hints.selectivity("(a > 1)"), Some(1.0));
+        assert_eq!(hints.groups(&["k".to_string()]), Some(10.0));
+        assert_eq!(hints.groups(&["k".to_string(), "j".to_string()]), None);
+    }
+
+    #[test]
+    fn test_emit_interval() {
+        assert_eq!(emit_interval(Window::Fixed { length: 60000 }), Some(60000));
+        assert_eq!(
+            emit_interval(Window::Sliding {
+                length: 60000,
+                interval: 10000,
+                offset: 0
+            }),
+            Some(10000)
+        );
+        assert_eq!(emit_interval(Window::Session { gap: 1000 }), None);
+    }
+}
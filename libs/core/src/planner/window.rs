@@ -1,3 +1,5 @@
+use std::fmt::{self, Display, Formatter};
+
 use chrono::TimeZone;
 use chrono::{DateTime, Datelike, Duration};
 use chrono_tz::Tz;
@@ -49,10 +51,132 @@ impl Period {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum Window {
-    Fixed { length: i64 },
-    Sliding { length: i64, interval: i64 },
-    Period { period: Period },
+    Fixed {
+        length: i64,
+    },
+    Sliding {
+        length: i64,
+        interval: i64,
+        offset: i64,
+    },
+    Period {
+        period: Period,
+    },
+    Session {
+        gap: i64,
+    },
+}
+
+/// When an aggregation emits the results of a window.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Emit {
+    /// Emits the results once, when the window is completed.
+    Final,
+    /// Also emits the current results of all open windows every `interval` milliseconds.
+    Every { interval: i64 },
+    /// Also emits the current results of a group whenever it is updated.
+    OnUpdate,
+}
+
+impl Default for Emit {
+    fn default() -> Self {
+        Emit::Final
+    }
+}
+
+/// Formats a duration in milliseconds with the largest unit that represents it exactly, e.g.
+/// `90s` or `2h`.
+pub(crate) struct DisplayDuration(pub i64);
+
+impl Display for DisplayDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const UNITS: &[(i64, &str)] = &[
+            (24 * 60 * 60 * 1000, "d"),
+            (60 * 60 * 1000, "h"),
+            (60 * 1000, "m"),
+            (1000, "s"),
+        ];
+        for (millis, unit) in UNITS {
+            if self.0 != 0 && self.0 % millis == 0 {
+                return write!(f, "{}{}", self.0 / millis, unit);
+            }
+        }
+        write!(f, "{}ms", self.0)
+    }
+}
+
+/// Where the results of an aggregation place the window column, `@time` with the start time of
+/// the window of each row.
+///
+/// The other columns always follow the order of the select list, group keys only appear if
+/// they are selected, so sinks that map columns by position keep working as long as the select
+/// list does not change.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WindowColumnPlacement {
+    /// Before the columns of the select list.
+    First,
+    /// After the columns of the select list.
+    Last,
+}
+
+impl Default for WindowColumnPlacement {
+    fn default() -> Self {
+        WindowColumnPlacement::Last
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Period::Day => f.write_str("day"),
+            Period::Week => f.write_str("week"),
+            Period::Month => f.write_str("month"),
+            Period::Year => f.write_str("year"),
+        }
+    }
+}
+
+impl Display for Window {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Window::Fixed { length } => write!(f, "fixed({})", DisplayDuration(*length)),
+            Window::Sliding {
+                length,
+                interval,
+                offset: 0,
+            } => write!(
+                f,
+                "sliding({}, {})",
+                DisplayDuration(*length),
+                DisplayDuration(*interval)
+            ),
+            Window::Sliding {
+                length,
+                interval,
+                offset,
+            } => write!(
+                f,
+                "sliding({}, {}, {})",
+                DisplayDuration(*length),
+                DisplayDuration(*interval),
+                DisplayDuration(*offset)
+            ),
+            Window::Period { period } => write!(f, "{}", period),
+            Window::Session { gap } => write!(f, "session({})", DisplayDuration(*gap)),
+        }
+    }
+}
+
+impl Display for Emit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Emit::Final => f.write_str("final"),
+            Emit::Every { interval } => write!(f, "every {}", DisplayDuration(*interval)),
+            Emit::OnUpdate => f.write_str("on update"),
+        }
+    }
 }
 
 impl Window {
@@ -62,13 +186,16 @@ impl Window {
                 let start = timestamp / length * length;
                 vec![(start, start + length)]
             }
-            Window::Sliding { length, interval } => {
+            Window::Sliding {
+                length,
+                interval,
+                offset,
+            } => {
                 let mut windows = Vec::new();
-                let mut time = timestamp / interval * interval;
-                let end_time = time + length;
-                while time < end_time {
+                let mut time = (timestamp - offset).div_euclid(interval) * interval + offset;
+                while time > timestamp - length {
                     windows.push((time, time + length));
-                    time += interval;
+                    time -= interval;
                 }
                 windows
             }
@@ -77,6 +204,7 @@ impl Window {
                 let (start, end) = period.window(datetime);
                 vec![(start.timestamp_millis(), end.timestamp_millis())]
             }
+            Window::Session { gap } => vec![(timestamp, timestamp + gap)],
         }
     }
 }
@@ -86,6 +214,33 @@ mod tests {
     use super::*;
     use chrono::NaiveDate;
 
+    #[test]
+    fn test_sliding_windows() {
+        let window = Window::Sliding {
+            length: 300,
+            interval: 100,
+            offset: 0,
+        };
+        assert_eq!(
+            window.windows(250, chrono_tz::UTC),
+            vec![(200, 500), (100, 400), (0, 300)]
+        );
+
+        let window = Window::Sliding {
+            length: 300,
+            interval: 100,
+            offset: 30,
+        };
+        assert_eq!(
+            window.windows(250, chrono_tz::UTC),
+            vec![(230, 530), (130, 430), (30, 330)]
+        );
+        assert_eq!(
+            window.windows(20, chrono_tz::UTC),
+            vec![(-70, 230), (-170, 130), (-270, 30)]
+        );
+    }
+
     #[test]
     fn test_period_day() {
         let tz = chrono_tz::Asia::Shanghai;
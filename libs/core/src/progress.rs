@@ -0,0 +1,173 @@
+//! Progress of the running streams.
+//!
+//! The position, watermark and number of rows read of every source are tracked per stream, a
+//! snapshot of them can be serialized for display with [`stream_progress`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+
+use crate::source_provider::SourceProvider;
+
+static STREAMS: Lazy<RwLock<HashMap<String, Arc<ProgressTracker>>>> = Lazy::new(Default::default);
+
+/// The progress of a source of a stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceProgress {
+    /// Id of the source node in the plan.
+    pub source_id: usize,
+    /// Name of the source provider.
+    pub source: String,
+    /// The position of the last rows read, as described by the source provider.
+    pub position: Option<String>,
+    pub watermark: Option<i64>,
+    /// The number of rows read since the stream started.
+    pub rows: u64,
+}
+
+/// The progress of a running stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamProgress {
+    pub stream: String,
+    pub sources: Vec<SourceProgress>,
+    /// The minimum watermark of the sources, `None` until every source has a watermark.
+    pub watermark: Option<i64>,
+    /// The number of rows read from all sources since the stream started.
+    pub rows_processed: u64,
+    pub uptime: Duration,
+}
+
+/// Returns the progress of the stream `stream`, or `None` if it was never started.
+///
+/// The progress of a stream that stopped is kept until [`remove_stream`] is called.
+pub fn stream_progress(stream: &str) -> Option<StreamProgress> {
+    let tracker = STREAMS.read().get(stream).cloned()?;
+    Some(tracker.progress(stream))
+}
+
+/// Removes the progress of the stream `stream`.
+pub fn remove_stream(stream: &str) {
+    STREAMS.write().remove(stream);
+}
+
+struct SourceState {
+    provider: SourceProvider,
+    state: Option<Vec<u8>>,
+    watermark: Option<i64>,
+    rows: u64,
+}
+
+/// Tracks the progress of the sources of a stream.
+pub(crate) struct ProgressTracker {
+    started_at: Instant,
+    sources: Mutex<BTreeMap<usize, SourceState>>,
+}
+
+impl ProgressTracker {
+    /// Creates the tracker of the stream `stream`, replacing the progress of its previous run.
+    pub(crate) fn start(stream: &str) -> Arc<Self> {
+        let tracker = Arc::new(Self {
+            started_at: Instant::now(),
+            sources: Default::default(),
+        });
+        STREAMS.write().insert(stream.to_string(), tracker.clone());
+        tracker
+    }
+
+    pub(crate) fn register(&self, id: usize, provider: SourceProvider, watermark: Option<i64>) {
+        self.sources.lock().insert(
+            id,
+            SourceState {
+                provider,
+                state: None,
+                watermark,
+                rows: 0,
+            },
+        );
+    }
+
+    /// Records that the source `id` read `rows` rows up to the position `state`.
+    pub(crate) fn update(&self, id: usize, state: &[u8], rows: usize, watermark: Option<i64>) {
+        if let Some(source) = self.sources.lock().get_mut(&id) {
+            source.state = Some(state.to_vec());
+            source.watermark = watermark;
+            source.rows += rows as u64;
+        }
+    }
+
+    fn progress(&self, stream: &str) -> StreamProgress {
+        let sources = self
+            .sources
+            .lock()
+            .iter()
+            .map(|(id, source)| SourceProgress {
+                source_id: *id,
+                source: source.provider.provider_name().to_string(),
+                position: source
+                    .state
+                    .as_ref()
+                    .and_then(|state| source.provider.position(state)),
+                watermark: source.watermark,
+                rows: source.rows,
+            })
+            .collect::<Vec<_>>();
+        let watermark = sources
+            .iter()
+            .map(|source| source.watermark)
+            .min()
+            .flatten();
+
+        StreamProgress {
+            stream: stream.to_string(),
+            watermark,
+            rows_processed: sources.iter().map(|source| source.rows).sum(),
+            sources,
+            uptime: self.started_at.elapsed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_provider::TestSource;
+
+    #[test]
+    fn test_stream_progress() {
+        let tracker = ProgressTracker::start("test_stream_progress");
+        tracker.register(0, TestSource::provider(vec![]), None);
+        tracker.register(2, TestSource::provider(vec![]), Some(500));
+
+        tracker.update(0, &bincode::serialize(&10u64).unwrap(), 10, Some(1000));
+        tracker.update(0, &bincode::serialize(&25u64).unwrap(), 15, Some(2000));
+        let progress = stream_progress("test_stream_progress").unwrap();
+        assert_eq!(progress.rows_processed, 25);
+        assert_eq!(progress.watermark, Some(500));
+        assert_eq!(
+            progress.sources,
+            vec![
+                SourceProgress {
+                    source_id: 0,
+                    source: "test".to_string(),
+                    position: Some("offset 25".to_string()),
+                    watermark: Some(2000),
+                    rows: 25,
+                },
+                SourceProgress {
+                    source_id: 2,
+                    source: "test".to_string(),
+                    position: None,
+                    watermark: Some(500),
+                    rows: 0,
+                },
+            ]
+        );
+
+        remove_stream("test_stream_progress");
+        assert!(stream_progress("test_stream_progress").is_none());
+    }
+}
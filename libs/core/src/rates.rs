@@ -0,0 +1,46 @@
+//! The conversion rates used by the `convert` function.
+//!
+//! Each unit (e.g. a currency code) has a rate relative to a common base unit, so
+//! `convert(amount, from, to)` returns `amount * rate(from) / rate(to)`. Units are case
+//! insensitive.
+//!
+//! The table is shared by all queries of the process and can be updated at any time, either
+//! directly or by sending a stream to a [`RateTable`](crate::sinks::RateTable) sink. Running
+//! queries use the new rates from the next dataset on.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+static RATES: Lazy<RwLock<HashMap<String, f64>>> = Lazy::new(Default::default);
+
+/// Sets the rate of `unit` relative to the base unit.
+pub fn set_rate(unit: &str, rate: f64) {
+    RATES.write().insert(unit.to_ascii_uppercase(), rate);
+}
+
+/// Removes the rate of `unit`.
+pub fn remove_rate(unit: &str) {
+    RATES.write().remove(&unit.to_ascii_uppercase());
+}
+
+/// Removes all rates.
+pub fn clear() {
+    RATES.write().clear();
+}
+
+/// Returns the rate of `unit` relative to the base unit.
+pub fn rate(unit: &str) -> Option<f64> {
+    RATES.read().get(&unit.to_ascii_uppercase()).copied()
+}
+
+/// Converts `amount` from the unit `from` to the unit `to`.
+///
+/// Returns `None` if the rate of either unit is unknown.
+pub fn convert(amount: f64, from: &str, to: &str) -> Option<f64> {
+    let rates = RATES.read();
+    let from = rates.get(&from.to_ascii_uppercase())?;
+    let to = rates.get(&to.to_ascii_uppercase())?;
+    Some(amount * from / to)
+}
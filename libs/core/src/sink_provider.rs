@@ -1,10 +1,25 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 
 use crate::dataset::DataSet;
+use crate::execution::clock::Clock;
 
 #[async_trait::async_trait]
 pub trait Sink {
     async fn send(&mut self, dataset: DataSet) -> Result<()>;
+
+    /// Sends `dataset` with the correlation ID of the source dataset its rows come from, so
+    /// that it can be forwarded to the downstream system, for example in a message header.
+    ///
+    /// Calls [`Sink::send`] by default.
+    async fn send_with_correlation_id(
+        &mut self,
+        dataset: DataSet,
+        _correlation_id: &str,
+    ) -> Result<()> {
+        self.send(dataset).await
+    }
 }
 
 pub type BoxSink = Box<dyn Sink + Send + 'static>;
@@ -13,6 +28,13 @@ pub trait SinkProvider: Send + 'static {
     fn provider_name(&self) -> &'static str;
 
     fn create(&self) -> Result<BoxSink>;
+
+    /// Creates a sink that reads the current time from `clock`, the clock of the stream.
+    ///
+    /// Sinks that don't read the time ignore the clock.
+    fn create_with_clock(&self, _clock: Arc<dyn Clock>) -> Result<BoxSink> {
+        self.create()
+    }
 }
 
 impl SinkProvider for Box<dyn SinkProvider> {
@@ -23,4 +45,8 @@ impl SinkProvider for Box<dyn SinkProvider> {
     fn create(&self) -> Result<BoxSink> {
         self.as_ref().create()
     }
+
+    fn create_with_clock(&self, clock: Arc<dyn Clock>) -> Result<BoxSink> {
+        self.as_ref().create_with_clock(clock)
+    }
 }
@@ -1,3 +1,7 @@
 mod console;
+mod prometheus;
+mod rates;
 
 pub use console::Console;
+pub use prometheus::PrometheusRemoteWrite;
+pub use rates::RateTable;
@@ -0,0 +1,311 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::array::{ArrayExt, DataType, Scalar};
+use crate::dataset::DataSet;
+use crate::execution::clock::{Clock, SystemClock};
+use crate::{BoxSink, Sink, SinkProvider};
+
+struct PrometheusRemoteWriteSink {
+    client: reqwest::Client,
+    url: String,
+    metric_name: String,
+    label_columns: Option<Vec<String>>,
+    clock: Arc<dyn Clock>,
+}
+
+#[async_trait::async_trait]
+impl Sink for PrometheusRemoteWriteSink {
+    async fn send(&mut self, dataset: DataSet) -> Result<()> {
+        self.write(dataset, None).await
+    }
+
+    async fn send_with_correlation_id(
+        &mut self,
+        dataset: DataSet,
+        correlation_id: &str,
+    ) -> Result<()> {
+        self.write(dataset, Some(correlation_id)).await
+    }
+}
+
+impl PrometheusRemoteWriteSink {
+    /// Sends the write request, with the correlation ID in the `X-Correlation-Id` header.
+    async fn write(&mut self, dataset: DataSet, correlation_id: Option<&str>) -> Result<()> {
+        let request = encode_write_request(
+            &self.metric_name,
+            self.label_columns.as_deref(),
+            &dataset,
+            self.clock.now(),
+        );
+        if request.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder = self
+            .client
+            .post(&self.url)
+            .header("Content-Encoding", "snappy")
+            .header("Content-Type", "application/x-protobuf")
+            .header("X-Prometheus-Remote-Write-Version", "0.1.0");
+        if let Some(correlation_id) = correlation_id {
+            builder = builder.header("X-Correlation-Id", correlation_id);
+        }
+        builder
+            .body(snappy_block(&request))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Publishes datasets to a Prometheus remote-write endpoint.
+///
+/// Every numeric column of a row becomes a sample, labeled with the label columns of that row.
+/// If no label columns are specified, all string columns are used. The sample timestamp comes
+/// from the first timestamp column (e.g. the window time of an aggregate), or the time of the
+/// stream clock if there isn't one.
+pub struct PrometheusRemoteWrite {
+    url: String,
+    metric_name: String,
+    label_columns: Option<Vec<String>>,
+}
+
+impl PrometheusRemoteWrite {
+    pub fn new(url: impl Into<String>, metric_name: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            metric_name: metric_name.into(),
+            label_columns: None,
+        }
+    }
+
+    pub fn with_label_columns(self, label_columns: Vec<String>) -> Self {
+        Self {
+            label_columns: Some(label_columns),
+            ..self
+        }
+    }
+}
+
+impl SinkProvider for PrometheusRemoteWrite {
+    fn provider_name(&self) -> &'static str {
+        "prometheus"
+    }
+
+    fn create(&self) -> Result<BoxSink> {
+        self.create_with_clock(Arc::new(SystemClock))
+    }
+
+    fn create_with_clock(&self, clock: Arc<dyn Clock>) -> Result<BoxSink> {
+        Ok(Box::new(PrometheusRemoteWriteSink {
+            client: reqwest::Client::new(),
+            url: self.url.clone(),
+            metric_name: self.metric_name.clone(),
+            label_columns: self.label_columns.clone(),
+            clock,
+        }))
+    }
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        buf.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+fn put_bytes_field(buf: &mut Vec<u8>, field: u64, data: &[u8]) {
+    put_varint(buf, (field << 3) | 2);
+    put_varint(buf, data.len() as u64);
+    buf.extend_from_slice(data);
+}
+
+pub(super) fn scalar_to_f64(value: Scalar) -> Option<f64> {
+    match value {
+        Scalar::Int8(n) => Some(n as f64),
+        Scalar::Int16(n) => Some(n as f64),
+        Scalar::Int32(n) => Some(n as f64),
+        Scalar::Int64(n) => Some(n as f64),
+        Scalar::UInt8(n) => Some(n as f64),
+        Scalar::UInt16(n) => Some(n as f64),
+        Scalar::UInt32(n) => Some(n as f64),
+        Scalar::UInt64(n) => Some(n as f64),
+        Scalar::Float32(n) => Some(n as f64),
+        Scalar::Float64(n) => Some(n),
+        _ => None,
+    }
+}
+
+/// Encodes the rows of `dataset` as a remote-write `WriteRequest` protobuf message, the rows
+/// without a timestamp are sampled at `now`.
+fn encode_write_request(
+    metric_name: &str,
+    label_columns: Option<&[String]>,
+    dataset: &DataSet,
+    now: i64,
+) -> Vec<u8> {
+    let schema = dataset.schema();
+    let fields = schema.fields();
+    let is_label = |idx: usize| match label_columns {
+        Some(label_columns) => label_columns
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(&fields[idx].name)),
+        None => fields[idx].data_type == DataType::String,
+    };
+    let labels = (0..fields.len())
+        .filter(|idx| is_label(*idx))
+        .collect::<Vec<_>>();
+    let values = (0..fields.len())
+        .filter(|idx| !is_label(*idx) && fields[*idx].data_type.is_numeric())
+        .collect::<Vec<_>>();
+    let time_idx = fields
+        .iter()
+        .position(|field| field.data_type.is_timestamp());
+
+    let mut buf = Vec::new();
+    for row in 0..dataset.len() {
+        let timestamp = match time_idx.map(|idx| dataset.columns()[idx].scalar_value(row)) {
+            Some(Scalar::Timestamp(timestamp)) => timestamp,
+            _ => now,
+        };
+
+        for value_idx in &values {
+            let value = match scalar_to_f64(dataset.columns()[*value_idx].scalar_value(row)) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let name = if values.len() == 1 {
+                metric_name.to_string()
+            } else {
+                format!("{}_{}", metric_name, fields[*value_idx].name)
+            };
+            let mut series_labels = vec![("__name__".to_string(), name)];
+            for label_idx in &labels {
+                match dataset.columns()[*label_idx].scalar_value(row) {
+                    Scalar::Null => {}
+                    label_value => series_labels
+                        .push((fields[*label_idx].name.clone(), label_value.to_string())),
+                }
+            }
+            series_labels.sort_by(|a, b| a.0.cmp(&b.0));
+
+            let mut series = Vec::new();
+            for (name, value) in series_labels {
+                let mut label = Vec::new();
+                put_bytes_field(&mut label, 1, name.as_bytes());
+                put_bytes_field(&mut label, 2, value.as_bytes());
+                put_bytes_field(&mut series, 1, &label);
+            }
+            let mut sample = Vec::new();
+            put_varint(&mut sample, 1 << 3 | 1);
+            sample.extend_from_slice(&value.to_le_bytes());
+            put_varint(&mut sample, 2 << 3);
+            put_varint(&mut sample, timestamp as u64);
+            put_bytes_field(&mut series, 2, &sample);
+
+            put_bytes_field(&mut buf, 1, &series);
+        }
+    }
+    buf
+}
+
+/// Frames `data` as a snappy block consisting only of literals, which every snappy decoder
+/// accepts.
+fn snappy_block(data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(data.len() + data.len() / 65536 * 3 + 13);
+    put_varint(&mut buf, data.len() as u64);
+    for chunk in data.chunks(65536) {
+        let n = chunk.len() - 1;
+        if n < 60 {
+            buf.push((n as u8) << 2);
+        } else {
+            buf.push(61 << 2);
+            buf.push(n as u8);
+            buf.push((n >> 8) as u8);
+        }
+        buf.extend_from_slice(chunk);
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{ArrayRef, Float64Array, StringArray, TimestampArray};
+    use crate::dataset::{Field, Schema};
+
+    #[test]
+    fn test_varint() {
+        let mut buf = Vec::new();
+        put_varint(&mut buf, 300);
+        assert_eq!(buf, vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn test_snappy_block() {
+        assert_eq!(snappy_block(b"abc"), vec![3, 2 << 2, b'a', b'b', b'c']);
+
+        let data = vec![7u8; 100];
+        let block = snappy_block(&data);
+        assert_eq!(&block[..4], &[100, 61 << 2, 99, 0]);
+        assert_eq!(&block[4..], data.as_slice());
+    }
+
+    #[test]
+    fn test_encode_write_request() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("host", DataType::String),
+                Field::new("value", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from_vec(vec!["a"])) as ArrayRef,
+                Arc::new(Float64Array::from_vec(vec![1.0])),
+                Arc::new(TimestampArray::from_vec(vec![1000])),
+            ],
+        )
+        .unwrap();
+
+        let mut expected = Vec::new();
+        let mut series = Vec::new();
+        for (name, value) in &[("__name__", "cpu"), ("host", "a")] {
+            let mut label = Vec::new();
+            put_bytes_field(&mut label, 1, name.as_bytes());
+            put_bytes_field(&mut label, 2, value.as_bytes());
+            put_bytes_field(&mut series, 1, &label);
+        }
+        let mut sample = vec![0x09];
+        sample.extend_from_slice(&1.0f64.to_le_bytes());
+        sample.extend_from_slice(&[0x10, 0xe8, 0x07]);
+        put_bytes_field(&mut series, 2, &sample);
+        put_bytes_field(&mut expected, 1, &series);
+
+        assert_eq!(encode_write_request("cpu", None, &dataset, 0), expected);
+
+        // Without a timestamp column, the sample is taken at `now`.
+        let dataset = DataSet::try_new(
+            Arc::new(
+                Schema::try_new(vec![
+                    Field::new("host", DataType::String),
+                    Field::new("value", DataType::Float64),
+                ])
+                .unwrap(),
+            ),
+            dataset.columns()[..2].to_vec(),
+        )
+        .unwrap();
+        assert_eq!(encode_write_request("cpu", None, &dataset, 1000), expected);
+    }
+}
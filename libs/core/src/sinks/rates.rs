@@ -0,0 +1,68 @@
+use anyhow::Result;
+
+use super::prometheus::scalar_to_f64;
+use crate::array::{ArrayExt, DataType, StringArray};
+use crate::dataset::DataSet;
+use crate::{rates, BoxSink, Sink, SinkProvider};
+
+struct RateTableSink {
+    unit_column: String,
+    rate_column: String,
+}
+
+#[async_trait::async_trait]
+impl Sink for RateTableSink {
+    async fn send(&mut self, dataset: DataSet) -> Result<()> {
+        let schema = dataset.schema();
+        let unit_idx = match schema.field(None, &self.unit_column) {
+            Some((idx, field)) if field.data_type == DataType::String => idx,
+            Some(_) => anyhow::bail!("the unit column '{}' must be a string", self.unit_column),
+            None => anyhow::bail!("the unit column '{}' not found", self.unit_column),
+        };
+        let rate_idx = match schema.field(None, &self.rate_column) {
+            Some((idx, field)) if field.data_type.is_numeric() => idx,
+            Some(_) => anyhow::bail!("the rate column '{}' must be numeric", self.rate_column),
+            None => anyhow::bail!("the rate column '{}' not found", self.rate_column),
+        };
+
+        let units = dataset.columns()[unit_idx].downcast_ref::<StringArray>();
+        let values = &dataset.columns()[rate_idx];
+        for (row, unit) in units.iter_opt().enumerate() {
+            if let (Some(unit), Some(rate)) = (unit, scalar_to_f64(values.scalar_value(row))) {
+                rates::set_rate(unit, rate);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Updates the rates used by the `convert` function.
+///
+/// Each row sets the rate of the unit in the unit column to the value of the rate column, so a
+/// stream of rate changes can be broadcast to all queries without restarting them.
+pub struct RateTable {
+    unit_column: String,
+    rate_column: String,
+}
+
+impl RateTable {
+    pub fn new(unit_column: impl Into<String>, rate_column: impl Into<String>) -> Self {
+        Self {
+            unit_column: unit_column.into(),
+            rate_column: rate_column.into(),
+        }
+    }
+}
+
+impl SinkProvider for RateTable {
+    fn provider_name(&self) -> &'static str {
+        "rates"
+    }
+
+    fn create(&self) -> Result<BoxSink> {
+        Ok(Box::new(RateTableSink {
+            unit_column: self.unit_column.clone(),
+            rate_column: self.rate_column.clone(),
+        }))
+    }
+}
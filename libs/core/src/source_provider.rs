@@ -1,20 +1,47 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use tokio_stream::StreamExt;
 
 use crate::dataset::{DataSet, SchemaRef};
+use crate::execution::clock::Clock;
 
 pub struct GenericSourceDataSet<T> {
     pub state: T,
     pub dataset: DataSet,
+    /// An ID the rows of the dataset carry from the upstream system, such as a trace ID, which
+    /// is passed to the sinks with the results of these rows.
+    ///
+    /// An ID is generated for the datasets without one.
+    pub correlation_id: Option<Arc<str>>,
 }
 
 pub type SourceDataSet = GenericSourceDataSet<Vec<u8>>;
 
+/// The number of rows a source reads per batch, which may change while the stream is running.
+#[derive(Clone)]
+pub struct BatchSize(Arc<AtomicUsize>);
+
+impl BatchSize {
+    pub fn new(batch_size: usize) -> Self {
+        assert!(batch_size > 0);
+        Self(Arc::new(AtomicUsize::new(batch_size)))
+    }
+
+    #[inline]
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set(&self, batch_size: usize) {
+        self.0.store(batch_size.max(1), Ordering::Relaxed);
+    }
+}
+
 pub trait GenericSourceProvider: Send + Sync + 'static {
     type State: Send + Sync + Serialize + DeserializeOwned + 'static;
 
@@ -26,6 +53,64 @@ pub trait GenericSourceProvider: Send + Sync + 'static {
         &self,
         state: Option<Self::State>,
     ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>>;
+
+    /// Creates a stream that reads `batch_size` rows per batch.
+    ///
+    /// Sources without a batch size ignore it.
+    fn create_stream_with_batch_size(
+        &self,
+        state: Option<Self::State>,
+        _batch_size: BatchSize,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        self.create_stream(state)
+    }
+
+    /// Creates a stream that reads the current time from `clock`, the clock of the stream, and
+    /// `batch_size` rows per batch if it is set.
+    ///
+    /// Sources that don't read the time ignore the clock.
+    fn create_stream_with_clock(
+        &self,
+        state: Option<Self::State>,
+        batch_size: Option<BatchSize>,
+        _clock: Arc<dyn Clock>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        match batch_size {
+            Some(batch_size) => self.create_stream_with_batch_size(state, batch_size),
+            None => self.create_stream(state),
+        }
+    }
+
+    /// Describes the position of the source saved in `state`, for progress reports.
+    fn position(&self, _state: &Self::State) -> Option<String> {
+        None
+    }
+
+    /// Returns the index of the first row of the batch read with `state`, counted from the start
+    /// of the source, for the sources whose rows have stable indices.
+    ///
+    /// It lets the stream drop the rows delivered again after a restore, see
+    /// [`ExecutionContext::with_source_deduplication`](crate::ExecutionContext::with_source_deduplication).
+    fn offset(&self, _state: &Self::State) -> Option<u64> {
+        None
+    }
+
+    /// Returns a provider that only reads the fields of the schema at `columns`, in that order,
+    /// or `None` if the source always reads all of its fields.
+    ///
+    /// The returned provider must accept the states of this provider, the planner uses it when a
+    /// query doesn't reference all of the fields.
+    fn with_projection(&self, _columns: &[usize]) -> Option<SourceProvider> {
+        None
+    }
+
+    /// Returns whether the rows of the source are changes to a table, some of which retract
+    /// earlier rows, like the rows of a change-data-capture source.
+    ///
+    /// The planner rejects aggregating such a source, the aggregations can't apply retractions.
+    fn is_changelog(&self) -> bool {
+        false
+    }
 }
 
 pub type SourceProvider = Arc<dyn GenericSourceProvider<State = Vec<u8>>>;
@@ -47,29 +132,125 @@ impl<T: GenericSourceProvider> GenericSourceProvider for SourceProviderWrapper<T
         &self,
         state: Option<Self::State>,
     ) -> Result<BoxStream<'static, Result<SourceDataSet>>> {
-        let state = match state {
-            Some(data) => Some(bincode::deserialize(&data).with_context(|| {
+        let state = self.deserialize_state(state)?;
+        Ok(self.serialize_states(self.0.create_stream(state)?))
+    }
+
+    fn create_stream_with_batch_size(
+        &self,
+        state: Option<Self::State>,
+        batch_size: BatchSize,
+    ) -> Result<BoxStream<'static, Result<SourceDataSet>>> {
+        let state = self.deserialize_state(state)?;
+        Ok(self.serialize_states(self.0.create_stream_with_batch_size(state, batch_size)?))
+    }
+
+    fn create_stream_with_clock(
+        &self,
+        state: Option<Self::State>,
+        batch_size: Option<BatchSize>,
+        clock: Arc<dyn Clock>,
+    ) -> Result<BoxStream<'static, Result<SourceDataSet>>> {
+        let state = self.deserialize_state(state)?;
+        Ok(self.serialize_states(self.0.create_stream_with_clock(state, batch_size, clock)?))
+    }
+
+    fn position(&self, state: &Self::State) -> Option<String> {
+        let state = bincode::deserialize(state).ok()?;
+        self.0.position(&state)
+    }
+
+    fn offset(&self, state: &Self::State) -> Option<u64> {
+        let state = bincode::deserialize(state).ok()?;
+        self.0.offset(&state)
+    }
+
+    fn with_projection(&self, columns: &[usize]) -> Option<SourceProvider> {
+        self.0.with_projection(columns)
+    }
+
+    fn is_changelog(&self) -> bool {
+        self.0.is_changelog()
+    }
+}
+
+impl<T: GenericSourceProvider> SourceProviderWrapper<T> {
+    fn deserialize_state(&self, state: Option<Vec<u8>>) -> Result<Option<T::State>> {
+        match state {
+            Some(data) => Ok(Some(bincode::deserialize(&data).with_context(|| {
                 format!(
                     "failed to deserialize state for source '{}'",
                     self.provider_name()
                 )
-            })?),
-            None => None,
-        };
-        let inner_stream = self.0.create_stream(state)?;
+            })?)),
+            None => Ok(None),
+        }
+    }
+
+    fn serialize_states(
+        &self,
+        mut inner_stream: BoxStream<'static, Result<GenericSourceDataSet<T::State>>>,
+    ) -> BoxStream<'static, Result<SourceDataSet>> {
         let provider_name = self.provider_name();
 
-        Ok(Box::pin(async_stream::try_stream! {
-            tokio::pin!(inner_stream);
-            while let Some(GenericSourceDataSet { state, dataset }) = inner_stream.next().await.transpose()? {
+        Box::pin(async_stream::try_stream! {
+            while let Some(GenericSourceDataSet { state, dataset, correlation_id }) = inner_stream.next().await.transpose()? {
                 let state = bincode::serialize(&state).with_context(|| {
                     format!("failed to serialize state for source '{}'", provider_name)
                 })?;
                 yield SourceDataSet {
                     state,
                     dataset,
+                    correlation_id,
                 };
             }
-        }))
+        })
+    }
+}
+
+/// A source with a fixed schema and no rows, for the tests.
+///
+/// The state is an offset, reported as the position of the source.
+#[cfg(test)]
+pub(crate) struct TestSource(pub(crate) SchemaRef);
+
+#[cfg(test)]
+impl TestSource {
+    /// Returns the provider of a source with `fields`.
+    pub(crate) fn provider(fields: Vec<crate::dataset::Field>) -> SourceProvider {
+        let schema = crate::dataset::Schema::try_new(fields).unwrap();
+        Arc::new(SourceProviderWrapper(TestSource(Arc::new(schema))))
+    }
+}
+
+#[cfg(test)]
+impl GenericSourceProvider for TestSource {
+    type State = u64;
+
+    fn provider_name(&self) -> &'static str {
+        "test"
+    }
+
+    fn schema(&self) -> Result<SchemaRef> {
+        Ok(self.0.clone())
+    }
+
+    fn create_stream(
+        &self,
+        _state: Option<Self::State>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        Ok(Box::pin(futures_util::stream::empty()))
+    }
+
+    fn position(&self, offset: &Self::State) -> Option<String> {
+        Some(format!("offset {}", offset))
+    }
+
+    fn with_projection(&self, columns: &[usize]) -> Option<SourceProvider> {
+        let fields = columns
+            .iter()
+            .map(|idx| self.0.fields()[*idx].clone())
+            .collect();
+        Some(TestSource::provider(fields))
     }
 }
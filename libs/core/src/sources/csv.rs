@@ -1,21 +1,34 @@
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::Result;
 use futures_util::stream::BoxStream;
 
-use crate::dataset::{CsvOptions, SchemaRef};
-use crate::{GenericSourceDataSet, GenericSourceProvider};
+use crate::dataset::{CsvOptions, Schema, SchemaRef};
+use crate::sources::schema_drift::{self, SchemaChangeCallback, SchemaDriftPolicy};
+use crate::{
+    BatchSize, GenericSourceDataSet, GenericSourceProvider, SourceProvider, SourceProviderWrapper,
+};
 
 const DEFAULT_BATCH_SIZE: usize = 10000;
 
 pub struct Csv {
     options: CsvOptions,
     schema: SchemaRef,
+    /// The column of each field of the schema, when the header of the file doesn't match it.
+    column_indices: Option<Vec<Option<usize>>>,
     batch_size: usize,
     path: PathBuf,
+    on_schema_change: Option<SchemaChangeCallback>,
 }
 
 impl Csv {
+    /// Creates a source that reads the file at `path`, with the schema inferred from the file if
+    /// `schema` is `None`.
+    ///
+    /// With [`CsvOptions::widen_on_overflow`], the batches read after a value out of the range of
+    /// its column have the widened schema, which is reported to the
+    /// [`SchemaChangeCallback`] of the source.
     pub fn new(
         options: CsvOptions,
         schema: Option<SchemaRef>,
@@ -29,10 +42,54 @@ impl Csv {
             options,
             batch_size: DEFAULT_BATCH_SIZE,
             schema,
+            column_indices: None,
             path: path.as_ref().to_path_buf(),
+            on_schema_change: None,
         })
     }
 
+    /// Calls `on_schema_change` with the new schema when the schema of the source changes.
+    ///
+    /// It must be set before [`Csv::with_schema_drift_policy`] to be called for the columns
+    /// added to the header.
+    pub fn with_schema_change_callback(self, on_schema_change: SchemaChangeCallback) -> Self {
+        Self {
+            on_schema_change: Some(on_schema_change),
+            ..self
+        }
+    }
+
+    /// Compares the header of the file with the schema and applies `policy` to the columns that
+    /// were added or removed.
+    ///
+    /// Files without a header are read by position.
+    pub fn with_schema_drift_policy(self, policy: SchemaDriftPolicy) -> Result<Self> {
+        let headers = match self.options.headers_from_path(&self.path)? {
+            Some(headers) => headers,
+            None => return Ok(self),
+        };
+        match schema_drift::map_columns(&self.schema, &headers, policy)? {
+            Some((schema, column_indices)) => {
+                if schema.fields().len() > self.schema.fields().len() {
+                    tracing::info!(
+                        path = %self.path.display(),
+                        columns = schema.fields().len() - self.schema.fields().len(),
+                        "schema evolved"
+                    );
+                    if let Some(on_schema_change) = &self.on_schema_change {
+                        on_schema_change(&schema)?;
+                    }
+                }
+                Ok(Self {
+                    schema,
+                    column_indices: Some(column_indices),
+                    ..self
+                })
+            }
+            None => Ok(self),
+        }
+    }
+
     pub fn with_batch_size(self, batch_size: usize) -> Self {
         assert!(batch_size > 0);
         Self { batch_size, ..self }
@@ -54,8 +111,19 @@ impl GenericSourceProvider for Csv {
     fn create_stream(
         &self,
         position: Option<Self::State>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        self.create_stream_with_batch_size(position, BatchSize::new(self.batch_size))
+    }
+
+    fn create_stream_with_batch_size(
+        &self,
+        position: Option<Self::State>,
+        batch_size: BatchSize,
     ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
         let mut reader = self.options.open_path(self.schema.clone(), &self.path)?;
+        if let Some(column_indices) = &self.column_indices {
+            reader = reader.with_column_indices(column_indices.clone());
+        }
         let mut position = if let Some(position) = position {
             reader.skip(position)?;
             position
@@ -63,20 +131,136 @@ impl GenericSourceProvider for Csv {
             0
         };
 
-        let batch_size = self.batch_size;
+        let on_schema_change = self.on_schema_change.clone();
+
         Ok(Box::pin(async_stream::try_stream! {
+            let mut schema = reader.schema();
             loop {
-                let dataset = reader.read_batch(Some(batch_size))?;
+                let dataset = reader.read_batch(Some(batch_size.get()))?;
                 if dataset.is_empty() {
                     break;
                 }
+                if dataset.schema() != schema {
+                    schema_drift::log_schema_change("csv", &schema, &dataset.schema());
+                    schema = dataset.schema();
+                    if let Some(on_schema_change) = &on_schema_change {
+                        on_schema_change(&schema)?;
+                    }
+                }
                 let count = dataset.len();
                 yield GenericSourceDataSet {
                     state: position,
                     dataset,
+                    correlation_id: None,
                 };
                 position += count;
             }
         }))
     }
+
+    fn position(&self, position: &Self::State) -> Option<String> {
+        Some(format!("row {}", position))
+    }
+
+    fn offset(&self, position: &Self::State) -> Option<u64> {
+        Some(*position as u64)
+    }
+
+    fn with_projection(&self, columns: &[usize]) -> Option<SourceProvider> {
+        let fields = self.schema.fields();
+        let schema = Schema::try_new(columns.iter().map(|idx| fields[*idx].clone()).collect());
+        let column_indices = columns
+            .iter()
+            .map(|idx| match &self.column_indices {
+                Some(column_indices) => column_indices[*idx],
+                None => Some(*idx),
+            })
+            .collect();
+        Some(Arc::new(SourceProviderWrapper(Csv {
+            options: self.options.clone(),
+            schema: Arc::new(schema.ok()?),
+            column_indices: Some(column_indices),
+            batch_size: self.batch_size,
+            path: self.path.clone(),
+            on_schema_change: self.on_schema_change.clone(),
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::Field;
+    use crate::dsl::*;
+    use crate::sources::SchemaChanged;
+    use crate::{DataFrame, ExecutionContext};
+
+    #[tokio::test]
+    async fn test_widen_on_overflow() {
+        let path = std::env::temp_dir().join(format!("yql-widen-{}.csv", std::process::id()));
+        std::fs::write(&path, "1,1\n3000000000,2\n3,18446744073709551615\n").unwrap();
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let options = CsvOptions {
+            widen_on_overflow: true,
+            ..CsvOptions::default()
+        };
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let csv = Csv::new(options.clone(), Some(schema.clone()), &path)
+            .unwrap()
+            .with_batch_size(1)
+            .with_schema_change_callback(Arc::new({
+                let changes = changes.clone();
+                move |schema: &Schema| -> Result<()> {
+                    changes.lock().push(
+                        schema
+                            .fields()
+                            .iter()
+                            .map(|field| field.data_type)
+                            .collect::<Vec<_>>(),
+                    );
+                    Ok(())
+                }
+            }));
+
+        // The columns are only widened from the batch with the value out of range.
+        let datasets = csv
+            .create_stream(None)
+            .unwrap()
+            .map(|res| res.unwrap().dataset.schema())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(datasets[0], schema);
+        assert_eq!(
+            *changes.lock(),
+            vec![
+                vec![DataType::Int64, DataType::Int64],
+                vec![DataType::Int64, DataType::Float64],
+            ]
+        );
+        assert_eq!(datasets[2].fields()[1].data_type, DataType::Float64);
+
+        // The stream planned with the declared types has to be planned again.
+        let csv = Csv::new(options, Some(schema), &path)
+            .unwrap()
+            .with_batch_size(1);
+        let datasets = DataFrame::new(Arc::new(SourceProviderWrapper(csv)), None, None, None)
+            .select(vec![col("a")])
+            .into_stream(ExecutionContext::new("test_widen_on_overflow"))
+            .collect::<Vec<_>>()
+            .await;
+        std::fs::remove_file(&path).unwrap();
+        assert!(datasets[0].is_ok());
+        let err = datasets.last().unwrap().as_ref().unwrap_err();
+        assert!(err.chain().any(|err| err.is::<SchemaChanged>()));
+    }
 }
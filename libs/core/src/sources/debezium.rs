@@ -0,0 +1,267 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use futures_util::stream::BoxStream;
+use serde_json::{Map, Value};
+
+use crate::array::{ArrayRef, DataType, StringBuilder};
+use crate::dataset::{DataSet, Field, Schema, SchemaRef};
+use crate::sources::json;
+use crate::{BatchSize, GenericSourceDataSet, GenericSourceProvider};
+
+const DEFAULT_BATCH_SIZE: usize = 10000;
+
+/// Name of the column that holds the [`RowKind`] of each row.
+pub const ROW_KIND_COLUMN: &str = "__row_kind";
+
+/// The kind of change a row of a change-data-capture source represents.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RowKind {
+    Insert,
+    UpdateBefore,
+    UpdateAfter,
+    Delete,
+}
+
+impl RowKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RowKind::Insert => "+I",
+            RowKind::UpdateBefore => "-U",
+            RowKind::UpdateAfter => "+U",
+            RowKind::Delete => "-D",
+        }
+    }
+}
+
+/// Reads a file of newline-delimited Debezium JSON change events.
+///
+/// Every event is turned into one or two rows (an update produces both the old and the new
+/// row), and the kind of change is stored in the [`ROW_KIND_COLUMN`] column.
+///
+/// The aggregations don't apply the deleted and the old rows yet, so the source can't be
+/// aggregated.
+pub struct Debezium {
+    schema: SchemaRef,
+    batch_size: usize,
+    path: PathBuf,
+}
+
+impl Debezium {
+    pub fn new(schema: SchemaRef, path: impl AsRef<Path>) -> Result<Self> {
+        let mut fields = schema.fields().to_vec();
+        fields.push(Field::new(ROW_KIND_COLUMN, DataType::String));
+        Ok(Self {
+            schema: Arc::new(Schema::try_new(fields)?),
+            batch_size: DEFAULT_BATCH_SIZE,
+            path: path.as_ref().to_path_buf(),
+        })
+    }
+
+    pub fn with_batch_size(self, batch_size: usize) -> Self {
+        assert!(batch_size > 0);
+        Self { batch_size, ..self }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl GenericSourceProvider for Debezium {
+    type State = usize;
+
+    fn provider_name(&self) -> &'static str {
+        "debezium"
+    }
+
+    fn schema(&self) -> Result<SchemaRef> {
+        Ok(self.schema.clone())
+    }
+
+    fn create_stream(
+        &self,
+        position: Option<Self::State>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        self.create_stream_with_batch_size(position, BatchSize::new(self.batch_size))
+    }
+
+    fn create_stream_with_batch_size(
+        &self,
+        position: Option<Self::State>,
+        batch_size: BatchSize,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        let file = File::open(&self.path)
+            .with_context(|| format!("failed to open file: {}", self.path.display()))?;
+        let mut lines = BufReader::new(file).lines();
+        let mut position = position.unwrap_or_default();
+        for _ in 0..position {
+            if lines.next().transpose()?.is_none() {
+                break;
+            }
+        }
+
+        let schema = self.schema.clone();
+        Ok(Box::pin(async_stream::try_stream! {
+            let mut rows = Vec::new();
+            let mut buf = Vec::new();
+            loop {
+                rows.clear();
+                let mut count = 0;
+                let batch_size = batch_size.get();
+                while count < batch_size {
+                    let line = match lines.next().transpose()? {
+                        Some(line) => line,
+                        None => break,
+                    };
+                    count += 1;
+                    parse_event(&line, &mut buf, &mut rows).with_context(|| {
+                        format!("invalid debezium event at line {}", position + count)
+                    })?;
+                }
+                if count == 0 {
+                    break;
+                }
+                let dataset = create_dataset(schema.clone(), &rows)?;
+                yield GenericSourceDataSet {
+                    state: position,
+                    dataset,
+                    correlation_id: None,
+                };
+                position += count;
+            }
+        }))
+    }
+
+    fn position(&self, position: &Self::State) -> Option<String> {
+        Some(format!("line {}", position))
+    }
+
+    fn is_changelog(&self) -> bool {
+        true
+    }
+}
+
+/// Parses a Debezium change event and appends the rows it produces to `rows`, `buf` is reused
+/// between the events to hold the bytes of `line` for the parser.
+fn parse_event(
+    line: &str,
+    buf: &mut Vec<u8>,
+    rows: &mut Vec<(RowKind, Map<String, Value>)>,
+) -> Result<()> {
+    if line.trim().is_empty() {
+        return Ok(());
+    }
+
+    buf.clear();
+    buf.extend_from_slice(line.as_bytes());
+    let value: Value = json::from_slice(buf)?;
+    // Events serialized with the JSON converter's schemas enabled wrap the envelope in `payload`.
+    let envelope = match value.get("payload") {
+        Some(payload) => payload,
+        None => &value,
+    };
+    if envelope.is_null() {
+        // Tombstone that follows a delete.
+        return Ok(());
+    }
+
+    let row = |name: &str| match envelope.get(name) {
+        Some(Value::Object(row)) => Ok(Some(row.clone())),
+        Some(Value::Null) | None => Ok(None),
+        Some(_) => anyhow::bail!("'{}' is not an object", name),
+    };
+    let op = envelope
+        .get("op")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("missing 'op'"))?;
+
+    match op {
+        "c" | "r" => {
+            let after = row("after")?.ok_or_else(|| anyhow::anyhow!("missing 'after'"))?;
+            rows.push((RowKind::Insert, after));
+        }
+        "u" => {
+            if let Some(before) = row("before")? {
+                rows.push((RowKind::UpdateBefore, before));
+            }
+            let after = row("after")?.ok_or_else(|| anyhow::anyhow!("missing 'after'"))?;
+            rows.push((RowKind::UpdateAfter, after));
+        }
+        "d" => {
+            let before = row("before")?.ok_or_else(|| anyhow::anyhow!("missing 'before'"))?;
+            rows.push((RowKind::Delete, before));
+        }
+        _ => anyhow::bail!("unknown op '{}'", op),
+    }
+    Ok(())
+}
+
+fn create_dataset(schema: SchemaRef, rows: &[(RowKind, Map<String, Value>)]) -> Result<DataSet> {
+    let fields = schema.fields();
+    let mut columns =
+        json::create_columns(&fields[..fields.len() - 1], rows.iter().map(|(_, row)| row))?;
+    let mut builder = StringBuilder::default();
+    for (kind, _) in rows {
+        builder.append(kind.as_str());
+    }
+    columns.push(Arc::new(builder.finish()) as ArrayRef);
+    DataSet::try_new(schema, columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Int32Array, StringArray};
+
+    #[test]
+    fn test_parse_event() {
+        let mut buf = Vec::new();
+        let mut rows = Vec::new();
+        parse_event(
+            r#"{"op":"c","before":null,"after":{"a":1,"b":"x"}}"#,
+            &mut buf,
+            &mut rows,
+        )
+        .unwrap();
+        parse_event(
+            r#"{"payload":{"op":"u","before":{"a":1,"b":"x"},"after":{"a":2,"b":"y"}}}"#,
+            &mut buf,
+            &mut rows,
+        )
+        .unwrap();
+        parse_event(
+            r#"{"op":"d","before":{"a":2,"b":"y"},"after":null}"#,
+            &mut buf,
+            &mut rows,
+        )
+        .unwrap();
+        parse_event(r#"{"payload":null}"#, &mut buf, &mut rows).unwrap();
+        assert!(parse_event(r#"{"op":"x"}"#, &mut buf, &mut rows).is_err());
+
+        let schema = Debezium::new(
+            Arc::new(
+                Schema::try_new(vec![
+                    Field::new("a", DataType::Int32),
+                    Field::new("b", DataType::String),
+                ])
+                .unwrap(),
+            ),
+            "test.json",
+        )
+        .unwrap()
+        .schema;
+        let dataset = create_dataset(schema.clone(), &rows).unwrap();
+        dataset.assert_eq(
+            &DataSet::try_new(
+                schema,
+                vec![
+                    Arc::new(Int32Array::from_vec(vec![1, 1, 2, 2])) as ArrayRef,
+                    Arc::new(StringArray::from_vec(vec!["x", "x", "y", "y"])),
+                    Arc::new(StringArray::from_vec(vec!["+I", "-U", "+U", "-D"])),
+                ],
+            )
+            .unwrap(),
+        );
+    }
+}
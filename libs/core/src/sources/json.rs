@@ -0,0 +1,230 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::array::temporal::{parse_date, parse_time};
+use crate::array::{
+    ArrayRef, BooleanType, DataType, DateType, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, MapArray, NullArray, PrimitiveBuilder, StringBuilder, StructArray,
+    TimeType, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use crate::dataset::Field;
+
+/// Deserializes a JSON document, `data` may be used as scratch space by the parser.
+#[cfg(feature = "simd")]
+pub(crate) fn from_slice<T: DeserializeOwned>(data: &mut [u8]) -> Result<T> {
+    Ok(simd_json::serde::from_slice(data)?)
+}
+
+/// Deserializes a JSON document, `data` may be used as scratch space by the parser.
+#[cfg(not(feature = "simd"))]
+pub(crate) fn from_slice<T: DeserializeOwned>(data: &mut [u8]) -> Result<T> {
+    Ok(serde_json::from_slice(data)?)
+}
+
+fn value_to_timestamp(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|datetime| datetime.timestamp_millis()),
+        _ => None,
+    }
+}
+
+macro_rules! create_array {
+    ($rows:expr, $field:expr, $ty:ty, $f:expr) => {{
+        let mut builder = PrimitiveBuilder::<$ty>::default();
+        for row in $rows {
+            match row.get(&$field.name) {
+                Some(Value::Null) | None => builder.append_null(),
+                Some(value) => builder.append($f(value).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "failed to parse json field '{}' as {}: {}",
+                        $field.name,
+                        $field.data_type,
+                        value
+                    )
+                })?),
+            }
+        }
+        Arc::new(builder.finish()) as ArrayRef
+    }};
+}
+
+/// Returns `fields` with the `Int32` fields widened to `Int64`, and the `Int64` fields widened to
+/// `Float64`, when they have values out of range in `rows`.
+///
+/// Returns `None` if all values are in range.
+pub(crate) fn widen_fields<'a>(
+    fields: &[Field],
+    rows: impl Iterator<Item = &'a Map<String, Value>> + Clone,
+) -> Option<Vec<Field>> {
+    let mut widened = None;
+    for (idx, field) in fields.iter().enumerate() {
+        let mut data_type = field.data_type;
+        for value in rows.clone().filter_map(|row| row.get(&field.name)) {
+            let n = match value {
+                Value::Number(n) => n,
+                _ => continue,
+            };
+            data_type = match data_type {
+                DataType::Int32 => match n.as_i64() {
+                    Some(n) if i32::try_from(n).is_ok() => continue,
+                    Some(_) => DataType::Int64,
+                    None if n.is_u64() => DataType::Float64,
+                    None => continue,
+                },
+                DataType::Int64 if n.is_u64() && n.as_i64().is_none() => DataType::Float64,
+                _ => continue,
+            };
+        }
+        if data_type != field.data_type {
+            widened.get_or_insert_with(|| fields.to_vec())[idx].data_type = data_type;
+        }
+    }
+    widened
+}
+
+/// Creates a column for each of `fields` from the values of JSON objects with the same name.
+///
+/// Missing values and `null` are converted to nulls.
+pub(crate) fn create_columns<'a>(
+    fields: &[Field],
+    rows: impl Iterator<Item = &'a Map<String, Value>> + Clone,
+) -> Result<Vec<ArrayRef>> {
+    let mut columns = Vec::with_capacity(fields.len());
+    for field in fields {
+        let rows = rows.clone();
+        columns.push(match field.data_type {
+            DataType::Null => Arc::new(NullArray::new(rows.count())) as ArrayRef,
+            DataType::Int8 => create_array!(rows, field, Int8Type, |v: &Value| v
+                .as_i64()
+                .map(|n| n as i8)),
+            DataType::Int16 => create_array!(rows, field, Int16Type, |v: &Value| v
+                .as_i64()
+                .map(|n| n as i16)),
+            DataType::Int32 => create_array!(rows, field, Int32Type, |v: &Value| v
+                .as_i64()
+                .and_then(|n| i32::try_from(n).ok())),
+            DataType::Int64 => create_array!(rows, field, Int64Type, Value::as_i64),
+            DataType::UInt8 => create_array!(rows, field, UInt8Type, |v: &Value| v
+                .as_u64()
+                .and_then(|n| u8::try_from(n).ok())),
+            DataType::UInt16 => create_array!(rows, field, UInt16Type, |v: &Value| v
+                .as_u64()
+                .and_then(|n| u16::try_from(n).ok())),
+            DataType::UInt32 => create_array!(rows, field, UInt32Type, |v: &Value| v
+                .as_u64()
+                .and_then(|n| u32::try_from(n).ok())),
+            DataType::UInt64 => create_array!(rows, field, UInt64Type, Value::as_u64),
+            DataType::Float32 => create_array!(rows, field, Float32Type, |v: &Value| v
+                .as_f64()
+                .map(|n| n as f32)),
+            DataType::Float64 => create_array!(rows, field, Float64Type, Value::as_f64),
+            DataType::Boolean => create_array!(rows, field, BooleanType, Value::as_bool),
+            DataType::Timestamp(_) => {
+                create_array!(rows, field, TimestampType, value_to_timestamp)
+            }
+            DataType::Date => create_array!(rows, field, DateType, |v: &Value| match v {
+                Value::Number(n) => n.as_i64().and_then(|n| i32::try_from(n).ok()),
+                Value::String(s) => parse_date(s),
+                _ => None,
+            }),
+            DataType::Time => create_array!(rows, field, TimeType, |v: &Value| match v {
+                Value::Number(n) => n.as_i64().and_then(|n| i32::try_from(n).ok()),
+                Value::String(s) => parse_time(s),
+                _ => None,
+            }),
+            DataType::String => {
+                let mut builder = StringBuilder::default();
+                for row in rows {
+                    match row.get(&field.name) {
+                        Some(Value::Null) | None => builder.append_null(),
+                        Some(Value::String(s)) => builder.append(s),
+                        Some(value) => builder.append(&value.to_string()),
+                    }
+                }
+                Arc::new(builder.finish()) as ArrayRef
+            }
+            DataType::Struct(struct_fields) => {
+                let mut objects = Vec::new();
+                for row in rows {
+                    objects.push(match row.get(&field.name) {
+                        Some(Value::Null) | None => None,
+                        Some(Value::Object(object)) => Some(object),
+                        Some(value) => anyhow::bail!(
+                            "expect an object for the field '{}', but got '{}'",
+                            field.name,
+                            value
+                        ),
+                    });
+                }
+
+                let child_fields = struct_fields
+                    .iter()
+                    .map(|child| Field::new(child.name.clone(), child.data_type))
+                    .collect::<Vec<_>>();
+                // The rows are collected so that the recursive call has a concrete iterator type.
+                let empty = Map::new();
+                let child_rows = objects
+                    .iter()
+                    .map(|object| object.unwrap_or(&empty))
+                    .collect::<Vec<_>>();
+                let children = create_columns(&child_fields, child_rows.iter().copied())?;
+                Arc::new(StructArray::new(
+                    objects.len(),
+                    child_fields
+                        .into_iter()
+                        .map(|field| field.name)
+                        .zip(children)
+                        .collect(),
+                    Some(objects.iter().map(Option::is_some).collect()),
+                )) as ArrayRef
+            }
+            DataType::Map(key_type, value_type) => {
+                anyhow::ensure!(
+                    key_type.is_string(),
+                    "the key of a map must be a string in json, but got '{}': '{}'",
+                    key_type,
+                    field.name
+                );
+                let mut lengths = Vec::new();
+                let mut keys = StringBuilder::default();
+                let mut entries = Vec::new();
+                for row in rows {
+                    match row.get(&field.name) {
+                        Some(Value::Null) | None => lengths.push(None),
+                        Some(Value::Object(object)) => {
+                            lengths.push(Some(object.len()));
+                            for (key, value) in object {
+                                keys.append(key);
+                                let mut entry = Map::with_capacity(1);
+                                entry.insert("value".to_string(), value.clone());
+                                entries.push(entry);
+                            }
+                        }
+                        Some(value) => anyhow::bail!(
+                            "expect an object for the field '{}', but got '{}'",
+                            field.name,
+                            value
+                        ),
+                    }
+                }
+
+                let values =
+                    create_columns(&[Field::new("value", *value_type)], entries.iter())?.remove(0);
+                Arc::new(MapArray::new(Arc::new(keys.finish()), values, lengths)) as ArrayRef
+            }
+            _ => anyhow::bail!(
+                "the type '{}' is not supported by json: '{}'",
+                field.data_type,
+                field.name
+            ),
+        });
+    }
+    Ok(columns)
+}
@@ -1,3 +1,11 @@
 mod csv;
+mod debezium;
+mod json;
+mod schema_drift;
+mod socket;
+mod syslog;
 
 pub use self::csv::Csv;
+pub use self::debezium::{Debezium, RowKind, ROW_KIND_COLUMN};
+pub use self::schema_drift::{SchemaChangeCallback, SchemaChanged, SchemaDriftPolicy};
+pub use self::socket::{LineFormat, Socket};
@@ -0,0 +1,371 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::array::DataType;
+use crate::dataset::{Field, Schema, SchemaRef};
+
+/// What a source does when its records stop matching the declared schema, because columns were
+/// added or removed upstream.
+///
+/// A value that can't be converted to the type of its column always fails the stream.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaDriftPolicy {
+    /// Fails the stream on new or missing columns.
+    Error,
+    /// Ignores the new columns, the missing columns are null.
+    IgnoreExtra,
+    /// Adds the new columns to the schema of the source as nullable columns, the missing
+    /// columns are null.
+    ///
+    /// A running stream keeps the schema it was planned with, the new columns are available to
+    /// the streams created afterwards. The evolved schema is reported to the
+    /// [`SchemaChangeCallback`] of the source, which can save it.
+    Evolve,
+}
+
+impl Default for SchemaDriftPolicy {
+    fn default() -> Self {
+        SchemaDriftPolicy::IgnoreExtra
+    }
+}
+
+impl FromStr for SchemaDriftPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(SchemaDriftPolicy::Error),
+            "ignore_extra" => Ok(SchemaDriftPolicy::IgnoreExtra),
+            "evolve" => Ok(SchemaDriftPolicy::Evolve),
+            _ => anyhow::bail!("invalid schema drift policy: '{}'", s),
+        }
+    }
+}
+
+/// Called with the new schema of a source when it changes, an error fails the stream.
+///
+/// The schema may be a projection of the schema of the source, with only the fields read by the
+/// stream.
+pub type SchemaChangeCallback = Arc<dyn Fn(&Schema) -> Result<()> + Send + Sync>;
+
+/// Returns the index in `columns` of each field of `schema`, and the schema of the source under
+/// `policy`.
+///
+/// Returns `None` if the columns match the schema.
+pub(crate) fn map_columns(
+    schema: &SchemaRef,
+    columns: &[String],
+    policy: SchemaDriftPolicy,
+) -> Result<Option<(SchemaRef, Vec<Option<usize>>)>> {
+    let names = schema
+        .fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect::<Vec<_>>();
+    if names.iter().copied().eq(columns.iter().map(String::as_str)) {
+        return Ok(None);
+    }
+
+    let extra = columns
+        .iter()
+        .filter(|column| !names.contains(&column.as_str()))
+        .collect::<Vec<_>>();
+    let missing = names
+        .iter()
+        .filter(|name| !columns.iter().any(|column| column == *name))
+        .collect::<Vec<_>>();
+    if policy == SchemaDriftPolicy::Error && (!extra.is_empty() || !missing.is_empty()) {
+        anyhow::bail!(
+            "the columns don't match the schema, new columns: {:?}, missing columns: {:?}",
+            extra,
+            missing
+        );
+    }
+
+    let schema = match policy {
+        SchemaDriftPolicy::Evolve if !extra.is_empty() => {
+            let mut fields = schema.fields().to_vec();
+            fields.extend(
+                extra
+                    .iter()
+                    .map(|column| Field::new(column.as_str(), DataType::String)),
+            );
+            Arc::new(Schema::try_new(fields)?)
+        }
+        _ => schema.clone(),
+    };
+    let column_indices = schema
+        .fields()
+        .iter()
+        .map(|field| columns.iter().position(|column| column == &field.name))
+        .collect();
+    Ok(Some((schema, column_indices)))
+}
+
+/// Logs the fields whose type differs between `from` and `to`, the schemas of two consecutive
+/// batches of a source.
+pub(crate) fn log_schema_change(source: &'static str, from: &Schema, to: &Schema) {
+    for (from, to) in from.fields().iter().zip(to.fields()) {
+        if from.data_type != to.data_type {
+            tracing::warn!(
+                source = source,
+                field = %from.name,
+                from = %from.data_type,
+                to = %to.data_type,
+                "schema changed"
+            );
+        }
+    }
+}
+
+/// The error of a stream whose source changed the types of its columns while it was running,
+/// e.g. by widening a column on overflow.
+///
+/// The stream was planned with the previous types, it has to be planned again with the new
+/// schema of the source to continue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaChanged {
+    /// The name of the source.
+    pub source: String,
+}
+
+impl Display for SchemaChanged {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the schema of the source '{}' changed while the stream was running",
+            self.source
+        )
+    }
+}
+
+impl std::error::Error for SchemaChanged {}
+
+fn json_data_type(value: &Value) -> DataType {
+    match value {
+        Value::Bool(_) => DataType::Boolean,
+        Value::Number(n) if n.is_i64() => DataType::Int64,
+        Value::Number(_) => DataType::Float64,
+        _ => DataType::String,
+    }
+}
+
+/// Checks the fields of JSON records against the schema of a source.
+pub(crate) struct JsonSchemaDrift {
+    policy: SchemaDriftPolicy,
+    /// The schema of the source with the columns added by [`SchemaDriftPolicy::Evolve`].
+    schema: Arc<RwLock<SchemaRef>>,
+    on_schema_change: Option<SchemaChangeCallback>,
+}
+
+impl JsonSchemaDrift {
+    pub(crate) fn new(schema: SchemaRef, policy: SchemaDriftPolicy) -> Self {
+        Self {
+            policy,
+            schema: Arc::new(RwLock::new(schema)),
+            on_schema_change: None,
+        }
+    }
+
+    pub(crate) fn with_schema_change_callback(
+        self,
+        on_schema_change: Option<SchemaChangeCallback>,
+    ) -> Self {
+        Self {
+            on_schema_change,
+            ..self
+        }
+    }
+
+    pub(crate) fn schema(&self) -> SchemaRef {
+        self.schema.read().clone()
+    }
+
+    pub(crate) fn policy(&self) -> SchemaDriftPolicy {
+        self.policy
+    }
+
+    /// Checks `rows` against `schema`, the schema the stream was planned with.
+    pub(crate) fn check(&self, schema: &Schema, rows: &[Map<String, Value>]) -> Result<()> {
+        if self.policy == SchemaDriftPolicy::IgnoreExtra {
+            return Ok(());
+        }
+
+        for row in rows {
+            if self.policy == SchemaDriftPolicy::Error {
+                for field in schema.fields() {
+                    anyhow::ensure!(
+                        row.contains_key(&field.name),
+                        "the field '{}' is missing from the record",
+                        field.name
+                    );
+                }
+            }
+
+            for (name, value) in row {
+                if schema.fields().iter().any(|field| &field.name == name) {
+                    continue;
+                }
+                match self.policy {
+                    SchemaDriftPolicy::Error => {
+                        anyhow::bail!("the field '{}' is not in the schema", name)
+                    }
+                    SchemaDriftPolicy::Evolve if !value.is_null() => self.evolve(name, value)?,
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn evolve(&self, name: &str, value: &Value) -> Result<()> {
+        if self
+            .schema
+            .read()
+            .fields()
+            .iter()
+            .any(|field| field.name == name)
+        {
+            return Ok(());
+        }
+
+        let data_type = json_data_type(value);
+        let evolved = {
+            let mut schema = self.schema.write();
+            if schema.fields().iter().any(|field| field.name == name) {
+                return Ok(());
+            }
+            let mut fields = schema.fields().to_vec();
+            fields.push(Field::new(name, data_type));
+            *schema = Arc::new(Schema::try_new(fields)?);
+            schema.clone()
+        };
+        tracing::info!(field = name, data_type = %data_type, "schema evolved");
+        if let Some(on_schema_change) = &self.on_schema_change {
+            on_schema_change(&evolved)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    fn schema() -> SchemaRef {
+        Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        )
+    }
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_map_columns() {
+        let schema = schema();
+        assert!(
+            map_columns(&schema, &columns(&["a", "b"]), SchemaDriftPolicy::Error)
+                .unwrap()
+                .is_none()
+        );
+
+        let (new_schema, indices) = map_columns(
+            &schema,
+            &columns(&["b", "c", "a"]),
+            SchemaDriftPolicy::IgnoreExtra,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(new_schema, schema);
+        assert_eq!(indices, vec![Some(2), Some(0)]);
+
+        let (new_schema, indices) =
+            map_columns(&schema, &columns(&["c", "a"]), SchemaDriftPolicy::Evolve)
+                .unwrap()
+                .unwrap();
+        assert_eq!(
+            new_schema
+                .fields()
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(new_schema.fields()[2].data_type, DataType::String);
+        assert_eq!(indices, vec![Some(1), None, Some(0)]);
+
+        assert!(map_columns(&schema, &columns(&["a"]), SchemaDriftPolicy::Error).is_err());
+        assert!(map_columns(
+            &schema,
+            &columns(&["a", "b", "c"]),
+            SchemaDriftPolicy::Error
+        )
+        .is_err());
+    }
+
+    fn rows(json: &str) -> Vec<Map<String, Value>> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_json_schema_drift() {
+        let schema = schema();
+
+        let drift = JsonSchemaDrift::new(schema.clone(), SchemaDriftPolicy::Error);
+        assert!(drift.check(&schema, &rows(r#"[{"a":1,"b":"x"}]"#)).is_ok());
+        assert!(drift.check(&schema, &rows(r#"[{"a":1}]"#)).is_err());
+        assert!(drift
+            .check(&schema, &rows(r#"[{"a":1,"b":"x","c":2}]"#))
+            .is_err());
+
+        let drift = JsonSchemaDrift::new(schema.clone(), SchemaDriftPolicy::IgnoreExtra);
+        assert!(drift.check(&schema, &rows(r#"[{"c":2}]"#)).is_ok());
+        assert_eq!(drift.schema(), schema);
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let drift = JsonSchemaDrift::new(schema.clone(), SchemaDriftPolicy::Evolve)
+            .with_schema_change_callback(Some(Arc::new({
+                let reported = reported.clone();
+                move |schema: &Schema| -> Result<()> {
+                    reported.lock().push(schema.fields().to_vec());
+                    Ok(())
+                }
+            })));
+        drift
+            .check(
+                &schema,
+                &rows(r#"[{"a":1,"c":2.5},{"d":true,"e":null},{"c":"x"}]"#),
+            )
+            .unwrap();
+        let evolved = drift.schema();
+        assert_eq!(reported.lock().last().unwrap(), evolved.fields());
+        assert_eq!(
+            evolved
+                .fields()
+                .iter()
+                .map(|field| (field.name.as_str(), field.data_type))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a", DataType::Int64),
+                ("b", DataType::String),
+                ("c", DataType::Float64),
+                ("d", DataType::Boolean),
+            ]
+        );
+    }
+}
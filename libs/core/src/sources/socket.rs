@@ -0,0 +1,490 @@
+use std::io::Cursor;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, TimeZone, Utc};
+use futures_util::future::FutureExt;
+use futures_util::stream::BoxStream;
+use serde_json::{Map, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::dataset::{CsvOptions, DataSet, Schema, SchemaRef};
+use crate::execution::clock::{Clock, SystemClock};
+use crate::sources::schema_drift::{self, JsonSchemaDrift, SchemaChangeCallback};
+use crate::sources::{json, syslog, SchemaDriftPolicy};
+use crate::{BatchSize, GenericSourceDataSet, GenericSourceProvider};
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// The format of the lines received by a [`Socket`] source.
+pub enum LineFormat {
+    Json,
+    Csv(CsvOptions),
+    /// RFC 5424 or RFC 3164 syslog messages, parsed into the `facility`, `severity`,
+    /// `timestamp`, `host`, `app`, `proc_id`, `msg_id`, `structured_data` and `msg` columns.
+    Syslog,
+}
+
+impl LineFormat {
+    /// Parses `lines` received at `now`, in milliseconds since the unix epoch.
+    ///
+    /// With `widen_on_overflow`, the JSON fields with values out of range are widened in the
+    /// schema of the dataset.
+    fn parse(
+        &self,
+        schema: SchemaRef,
+        schema_drift: &JsonSchemaDrift,
+        widen_on_overflow: bool,
+        lines: &[String],
+        now: i64,
+    ) -> Result<DataSet> {
+        match self {
+            LineFormat::Json => {
+                let mut buf = Vec::new();
+                let rows = lines
+                    .iter()
+                    .map(|line| {
+                        buf.clear();
+                        buf.extend_from_slice(line.as_bytes());
+                        json::from_slice::<Map<String, Value>>(&mut buf)
+                            .with_context(|| format!("invalid json line: {}", line))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                schema_drift.check(&schema, &rows)?;
+                let schema = match widen_on_overflow
+                    .then(|| json::widen_fields(schema.fields(), rows.iter()))
+                    .flatten()
+                {
+                    Some(fields) => Arc::new(Schema::try_new(fields)?),
+                    None => schema,
+                };
+                let columns = json::create_columns(schema.fields(), rows.iter())?;
+                DataSet::try_new(schema, columns)
+            }
+            LineFormat::Csv(options) => options
+                .open(schema, Cursor::new(lines.join("\n")))
+                .read_batch(None),
+            LineFormat::Syslog => {
+                let year = Utc.timestamp_millis(now).year();
+                let rows = lines
+                    .iter()
+                    .map(|line| syslog::parse_message(line, year))
+                    .collect::<Vec<_>>();
+                let columns = json::create_columns(schema.fields(), rows.iter())?;
+                DataSet::try_new(schema, columns)
+            }
+        }
+    }
+}
+
+enum Listen {
+    Tcp(SocketAddr),
+    Udp(SocketAddr),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn spawn_reader(stream: impl AsyncRead + Unpin + Send + 'static, tx: mpsc::Sender<String>) {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stream).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Waiting for the channel stops reading from the socket, so a slow query
+            // pushes back on the agents through TCP flow control.
+            if tx.send(line).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Listens on a TCP port or a unix domain socket and reads the lines pushed by the clients.
+///
+/// All connections are merged into a single stream. Since the received lines cannot be
+/// replayed, restarting a stream continues with the lines received after the restart.
+pub struct Socket {
+    listen: Arc<Listen>,
+    format: Arc<LineFormat>,
+    schema: SchemaRef,
+    schema_drift: Arc<JsonSchemaDrift>,
+    on_schema_change: Option<SchemaChangeCallback>,
+    widen_on_overflow: bool,
+    batch_size: usize,
+}
+
+impl Socket {
+    pub fn tcp(addr: SocketAddr, schema: SchemaRef, format: LineFormat) -> Self {
+        Self {
+            listen: Arc::new(Listen::Tcp(addr)),
+            format: Arc::new(format),
+            schema_drift: Arc::new(JsonSchemaDrift::new(
+                schema.clone(),
+                SchemaDriftPolicy::default(),
+            )),
+            schema,
+            on_schema_change: None,
+            widen_on_overflow: false,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Listens on a UDP port, every datagram is a line.
+    pub fn udp(addr: SocketAddr, schema: SchemaRef, format: LineFormat) -> Self {
+        Self {
+            listen: Arc::new(Listen::Udp(addr)),
+            format: Arc::new(format),
+            schema_drift: Arc::new(JsonSchemaDrift::new(
+                schema.clone(),
+                SchemaDriftPolicy::default(),
+            )),
+            schema,
+            on_schema_change: None,
+            widen_on_overflow: false,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    #[cfg(unix)]
+    pub fn unix(path: impl Into<PathBuf>, schema: SchemaRef, format: LineFormat) -> Self {
+        Self {
+            listen: Arc::new(Listen::Unix(path.into())),
+            format: Arc::new(format),
+            schema_drift: Arc::new(JsonSchemaDrift::new(
+                schema.clone(),
+                SchemaDriftPolicy::default(),
+            )),
+            schema,
+            on_schema_change: None,
+            widen_on_overflow: false,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    pub fn with_batch_size(self, batch_size: usize) -> Self {
+        assert!(batch_size > 0);
+        Self { batch_size, ..self }
+    }
+
+    /// Sets what happens when the JSON lines have fields that are not in the schema or miss
+    /// some of its fields, the other formats are read by position.
+    pub fn with_schema_drift_policy(self, policy: SchemaDriftPolicy) -> Self {
+        Self {
+            schema_drift: Arc::new(
+                JsonSchemaDrift::new(self.schema.clone(), policy)
+                    .with_schema_change_callback(self.on_schema_change.clone()),
+            ),
+            ..self
+        }
+    }
+
+    /// Calls `on_schema_change` with the new schema when the schema of the source changes.
+    pub fn with_schema_change_callback(self, on_schema_change: SchemaChangeCallback) -> Self {
+        let policy = self.schema_drift.policy();
+        Self {
+            on_schema_change: Some(on_schema_change),
+            ..self
+        }
+        .with_schema_drift_policy(policy)
+    }
+
+    /// Widens the `Int32` fields of JSON lines to `Int64`, and the `Int64` fields to `Float64`,
+    /// when a value is out of their range, instead of failing the stream.
+    ///
+    /// The batches received after the overflow have the widened schema, which is reported to
+    /// the [`SchemaChangeCallback`] of the source. CSV lines are widened by
+    /// [`CsvOptions::widen_on_overflow`].
+    pub fn with_widen_on_overflow(self, widen_on_overflow: bool) -> Self {
+        Self {
+            widen_on_overflow,
+            ..self
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl GenericSourceProvider for Socket {
+    type State = ();
+
+    fn provider_name(&self) -> &'static str {
+        "socket"
+    }
+
+    fn schema(&self) -> Result<SchemaRef> {
+        Ok(self.schema_drift.schema())
+    }
+
+    fn create_stream(
+        &self,
+        state: Option<Self::State>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        self.create_stream_with_clock(state, None, Arc::new(SystemClock))
+    }
+
+    fn create_stream_with_batch_size(
+        &self,
+        state: Option<Self::State>,
+        batch_size: BatchSize,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        self.create_stream_with_clock(state, Some(batch_size), Arc::new(SystemClock))
+    }
+
+    fn create_stream_with_clock(
+        &self,
+        _state: Option<Self::State>,
+        batch_size: Option<BatchSize>,
+        clock: Arc<dyn Clock>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        let batch_size = batch_size.unwrap_or_else(|| BatchSize::new(self.batch_size));
+        let listen = self.listen.clone();
+        let format = self.format.clone();
+        let schema_drift = self.schema_drift.clone();
+        let mut schema = schema_drift.schema();
+        let on_schema_change = self.on_schema_change.clone();
+        let widen_on_overflow = self.widen_on_overflow;
+        let channel_size = self.batch_size;
+
+        Ok(Box::pin(async_stream::try_stream! {
+            let (tx, mut rx) = mpsc::channel(channel_size);
+            let _accept = AbortOnDrop(match &*listen {
+                Listen::Tcp(addr) => {
+                    let listener = tokio::net::TcpListener::bind(addr).await?;
+                    tokio::spawn(async move {
+                        while let Ok((stream, _)) = listener.accept().await {
+                            spawn_reader(stream, tx.clone());
+                        }
+                    })
+                }
+                Listen::Udp(addr) => {
+                    let socket = tokio::net::UdpSocket::bind(addr).await?;
+                    tokio::spawn(async move {
+                        let mut buf = vec![0; 65536];
+                        while let Ok((len, _)) = socket.recv_from(&mut buf).await {
+                            let line = String::from_utf8_lossy(&buf[..len]);
+                            let line = line.trim_end_matches(|c| c == '\r' || c == '\n');
+                            if line.is_empty() {
+                                continue;
+                            }
+                            if tx.send(line.to_string()).await.is_err() {
+                                break;
+                            }
+                        }
+                    })
+                }
+                #[cfg(unix)]
+                Listen::Unix(path) => {
+                    let listener = tokio::net::UnixListener::bind(path)?;
+                    tokio::spawn(async move {
+                        while let Ok((stream, _)) = listener.accept().await {
+                            spawn_reader(stream, tx.clone());
+                        }
+                    })
+                }
+            });
+
+            let mut lines = Vec::with_capacity(batch_size.get());
+            while let Some(line) = rx.recv().await {
+                let batch_size = batch_size.get();
+                lines.clear();
+                lines.push(line);
+                while lines.len() < batch_size {
+                    match rx.recv().now_or_never() {
+                        Some(Some(line)) => lines.push(line),
+                        _ => break,
+                    }
+                }
+                let dataset = format.parse(
+                    schema.clone(),
+                    &schema_drift,
+                    widen_on_overflow,
+                    &lines,
+                    clock.now(),
+                )?;
+                if dataset.schema() != schema {
+                    schema_drift::log_schema_change("socket", &schema, &dataset.schema());
+                    schema = dataset.schema();
+                    if let Some(on_schema_change) = &on_schema_change {
+                        on_schema_change(&schema)?;
+                    }
+                }
+                yield GenericSourceDataSet {
+                    state: (),
+                    dataset,
+                    correlation_id: None,
+                };
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayRef, DataType, Float64Array, Int32Array, Int64Array, StringArray};
+    use crate::dataset::{Field, Schema};
+
+    #[test]
+    fn test_parse_lines() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let expected = DataSet::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_vec(vec![1, 2])) as ArrayRef,
+                Arc::new(StringArray::from_vec(vec!["x", "y"])),
+            ],
+        )
+        .unwrap();
+
+        let schema_drift = JsonSchemaDrift::new(schema.clone(), SchemaDriftPolicy::default());
+        LineFormat::Json
+            .parse(
+                schema.clone(),
+                &schema_drift,
+                false,
+                &[
+                    r#"{"a":1,"b":"x"}"#.to_string(),
+                    r#"{"a":2,"b":"y"}"#.to_string(),
+                ],
+                0,
+            )
+            .unwrap()
+            .assert_eq(&expected);
+
+        LineFormat::Csv(CsvOptions::default())
+            .parse(
+                schema.clone(),
+                &schema_drift,
+                false,
+                &["1,x".to_string(), "2,y".to_string()],
+                0,
+            )
+            .unwrap()
+            .assert_eq(&expected);
+
+        let schema_drift = JsonSchemaDrift::new(schema.clone(), SchemaDriftPolicy::Error);
+        assert!(LineFormat::Json
+            .parse(
+                schema,
+                &schema_drift,
+                false,
+                &[r#"{"a":1,"b":"x","c":true}"#.to_string()],
+                0,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_syslog_year() {
+        let schema =
+            Arc::new(Schema::try_new(vec![Field::new("timestamp", DataType::Int64)]).unwrap());
+        let schema_drift = JsonSchemaDrift::new(schema.clone(), SchemaDriftPolicy::default());
+
+        // The year of the RFC 3164 timestamps is the one of the time the line is received.
+        let received = Utc.ymd(2003, 12, 31).and_hms(0, 0, 0).timestamp_millis();
+        LineFormat::Syslog
+            .parse(
+                schema.clone(),
+                &schema_drift,
+                false,
+                &["<34>Oct  1 22:14:15 mymachine su: failed".to_string()],
+                received,
+            )
+            .unwrap()
+            .assert_eq(
+                &DataSet::try_new(
+                    schema,
+                    vec![Arc::new(Int64Array::from_vec(vec![1065046455000])) as ArrayRef],
+                )
+                .unwrap(),
+            );
+    }
+
+    #[test]
+    fn test_widen_on_overflow() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let schema_drift = JsonSchemaDrift::new(schema.clone(), SchemaDriftPolicy::default());
+        let lines = [
+            r#"{"a":1,"b":1}"#.to_string(),
+            r#"{"a":3000000000,"b":18446744073709551615}"#.to_string(),
+        ];
+
+        assert!(LineFormat::Json
+            .parse(schema.clone(), &schema_drift, false, &lines, 0)
+            .is_err());
+
+        // The types are only widened when a value is out of range.
+        LineFormat::Json
+            .parse(schema.clone(), &schema_drift, true, &lines[..1], 0)
+            .unwrap()
+            .assert_eq(
+                &DataSet::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from_vec(vec![1])) as ArrayRef,
+                        Arc::new(Int64Array::from_vec(vec![1])),
+                    ],
+                )
+                .unwrap(),
+            );
+
+        LineFormat::Json
+            .parse(schema.clone(), &schema_drift, true, &lines, 0)
+            .unwrap()
+            .assert_eq(
+                &DataSet::try_new(
+                    Arc::new(
+                        Schema::try_new(vec![
+                            Field::new("a", DataType::Int64),
+                            Field::new("b", DataType::Float64),
+                        ])
+                        .unwrap(),
+                    ),
+                    vec![
+                        Arc::new(Int64Array::from_vec(vec![1, 3000000000])) as ArrayRef,
+                        Arc::new(Float64Array::from_vec(vec![1.0, u64::MAX as f64])),
+                    ],
+                )
+                .unwrap(),
+            );
+
+        let dataset = LineFormat::Csv(CsvOptions {
+            widen_on_overflow: true,
+            ..CsvOptions::default()
+        })
+        .parse(
+            schema,
+            &schema_drift,
+            false,
+            &["3000000000,1".to_string()],
+            0,
+        )
+        .unwrap();
+        assert_eq!(dataset.schema().fields()[0].data_type, DataType::Int64);
+        assert_eq!(dataset.schema().fields()[1].data_type, DataType::Int64);
+    }
+}
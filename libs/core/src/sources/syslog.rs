@@ -0,0 +1,209 @@
+//! Parser of syslog messages in the RFC 5424 and RFC 3164 (BSD) formats.
+//!
+//! A message is parsed into the following fields, which become the columns of the same name:
+//!
+//! | Name              | Description                                    |
+//! |-------------------|------------------------------------------------|
+//! | `facility`        | Facility code                                  |
+//! | `severity`        | Severity code                                  |
+//! | `timestamp`       | Time of the message                            |
+//! | `host`            | Host name                                      |
+//! | `app`             | Application name (the tag of RFC 3164)         |
+//! | `proc_id`         | Process id                                     |
+//! | `msg_id`          | Message id (RFC 5424 only)                     |
+//! | `structured_data` | Raw structured data elements (RFC 5424 only)   |
+//! | `msg`             | Free-form message                              |
+
+use chrono::NaiveDateTime;
+use serde_json::{Map, Value};
+
+fn parse_pri(line: &str) -> Option<(i64, &str)> {
+    let line = line.strip_prefix('<')?;
+    let end = line.find('>')?;
+    let pri = line[..end].parse::<i64>().ok()?;
+    Some((pri, &line[end + 1..]))
+}
+
+/// Splits the first space-delimited token from `input`.
+fn token(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim_start_matches(' ');
+    if input.is_empty() {
+        return None;
+    }
+    match input.find(' ') {
+        Some(idx) => Some((&input[..idx], &input[idx + 1..])),
+        None => Some((input, "")),
+    }
+}
+
+fn nil_value(value: &str) -> Value {
+    match value {
+        "-" => Value::Null,
+        _ => Value::String(value.to_string()),
+    }
+}
+
+fn structured_data(input: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = input.strip_prefix('-') {
+        return Some(("-", rest.strip_prefix(' ').unwrap_or(rest)));
+    }
+
+    let mut in_element = false;
+    let mut escaped = false;
+    for (idx, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' if !in_element => in_element = true,
+            ']' if in_element => in_element = false,
+            ' ' if !in_element => return Some((&input[..idx], &input[idx + 1..])),
+            _ if !in_element => return None,
+            _ => {}
+        }
+    }
+    if in_element {
+        return None;
+    }
+    Some((input, ""))
+}
+
+fn parse_rfc5424(input: &str, row: &mut Map<String, Value>) -> Option<()> {
+    let (version, input) = token(input)?;
+    version.parse::<u32>().ok()?;
+    let (timestamp, input) = token(input)?;
+    let (host, input) = token(input)?;
+    let (app, input) = token(input)?;
+    let (proc_id, input) = token(input)?;
+    let (msg_id, input) = token(input)?;
+    let (sd, msg) = structured_data(input.trim_start_matches(' '))?;
+
+    let timestamp = match timestamp {
+        "-" => Value::Null,
+        _ => chrono::DateTime::parse_from_rfc3339(timestamp)
+            .ok()?
+            .timestamp_millis()
+            .into(),
+    };
+    row.insert("timestamp".to_string(), timestamp);
+    row.insert("host".to_string(), nil_value(host));
+    row.insert("app".to_string(), nil_value(app));
+    row.insert("proc_id".to_string(), nil_value(proc_id));
+    row.insert("msg_id".to_string(), nil_value(msg_id));
+    row.insert("structured_data".to_string(), nil_value(sd));
+    row.insert(
+        "msg".to_string(),
+        Value::String(msg.trim_start_matches('\u{feff}').to_string()),
+    );
+    Some(())
+}
+
+fn parse_rfc3164(input: &str, year: i32, row: &mut Map<String, Value>) -> Option<()> {
+    let (month, input) = token(input)?;
+    let (day, input) = token(input)?;
+    let (time, input) = token(input)?;
+    let timestamp = NaiveDateTime::parse_from_str(
+        &format!("{} {} {} {}", year, month, day, time),
+        "%Y %b %d %H:%M:%S",
+    )
+    .ok()?;
+    let (host, input) = token(input)?;
+
+    let (tag, msg) = match input.find(": ") {
+        Some(idx) if !input[..idx].contains(' ') => (&input[..idx], &input[idx + 2..]),
+        _ => ("", input),
+    };
+    let (app, proc_id) = match tag.find('[') {
+        Some(idx) if tag.ends_with(']') => (&tag[..idx], Some(&tag[idx + 1..tag.len() - 1])),
+        _ => (tag, None),
+    };
+
+    row.insert("timestamp".to_string(), timestamp.timestamp_millis().into());
+    row.insert("host".to_string(), host.into());
+    if !app.is_empty() {
+        row.insert("app".to_string(), app.into());
+    }
+    if let Some(proc_id) = proc_id {
+        row.insert("proc_id".to_string(), proc_id.into());
+    }
+    row.insert("msg".to_string(), msg.into());
+    Some(())
+}
+
+/// Parses a syslog message into a row.
+///
+/// A line that does not look like a syslog message is kept as the `msg` field. The RFC 3164
+/// timestamps don't have a year, they are read in `year`.
+pub(crate) fn parse_message(line: &str, year: i32) -> Map<String, Value> {
+    let line = line.trim_end_matches(&['\r', '\n'][..]);
+    let mut row = Map::new();
+
+    if let Some((pri, input)) = parse_pri(line) {
+        let mut fields = Map::new();
+        let starts_with_version = input.starts_with(|c: char| c.is_ascii_digit());
+        let res = if starts_with_version {
+            parse_rfc5424(input, &mut fields)
+        } else {
+            parse_rfc3164(input, year, &mut fields)
+        };
+        if res.is_some() {
+            row.insert("facility".to_string(), (pri / 8).into());
+            row.insert("severity".to_string(), (pri % 8).into());
+            row.extend(fields);
+            return row;
+        }
+    }
+
+    row.insert("msg".to_string(), line.into());
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc5424() {
+        let row = parse_message(
+            r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application"] An application event"#,
+            2020,
+        );
+        assert_eq!(row["facility"], 20);
+        assert_eq!(row["severity"], 5);
+        assert_eq!(row["timestamp"], 1065910455003i64);
+        assert_eq!(row["host"], "mymachine.example.com");
+        assert_eq!(row["app"], "evntslog");
+        assert_eq!(row["proc_id"], Value::Null);
+        assert_eq!(row["msg_id"], "ID47");
+        assert_eq!(
+            row["structured_data"],
+            r#"[exampleSDID@32473 iut="3" eventSource="Application"]"#
+        );
+        assert_eq!(row["msg"], "An application event");
+
+        let row = parse_message("<34>1 - - - - - -", 2020);
+        assert_eq!(row["timestamp"], Value::Null);
+        assert_eq!(row["msg"], "");
+    }
+
+    #[test]
+    fn test_rfc3164() {
+        let row = parse_message(
+            "<34>Oct  1 22:14:15 mymachine su[123]: 'su root' failed",
+            2003,
+        );
+        assert_eq!(row["facility"], 4);
+        assert_eq!(row["severity"], 2);
+        assert_eq!(row["timestamp"], 1065046455000i64);
+        assert_eq!(row["host"], "mymachine");
+        assert_eq!(row["app"], "su");
+        assert_eq!(row["proc_id"], "123");
+        assert_eq!(row["msg"], "'su root' failed");
+    }
+
+    #[test]
+    fn test_invalid() {
+        let row = parse_message("hello world", 2020);
+        assert_eq!(row.len(), 1);
+        assert_eq!(row["msg"], "hello world");
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::expr::Expr;
-use crate::Window;
+use crate::expr::{Expr, OrderBy};
+use crate::{Emit, Window};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum SourceFrom {
@@ -9,10 +9,18 @@ pub enum SourceFrom {
     SubQuery(Box<Select>),
 }
 
+/// The event time of a source and how long the watermark lags behind it.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Watermark {
+    pub time_expr: Expr,
+    pub delay: i64,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Source {
     pub from: SourceFrom,
     pub alias: Option<String>,
+    pub watermark: Option<Watermark>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -20,6 +28,27 @@ pub struct GroupBy {
     pub exprs: Vec<Expr>,
 }
 
+/// The definition of a window in the `WINDOW` clause.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum WindowDefinition {
+    /// A time window of aggregations, e.g. `w AS fixed(5m)`.
+    Time(Window),
+    /// The partitioning and ordering of analytic function calls, e.g.
+    /// `w AS (PARTITION BY k ORDER BY ts)`.
+    Over {
+        partition_by: Vec<Expr>,
+        order_by: Vec<OrderBy>,
+    },
+}
+
+/// A window defined by name, it can be referred to by the select that defines it and by its
+/// subqueries.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct NamedWindow {
+    pub name: String,
+    pub definition: WindowDefinition,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Select {
     pub projection: Vec<Expr>,
@@ -28,4 +57,11 @@ pub struct Select {
     pub having_clause: Option<Expr>,
     pub group_clause: Option<GroupBy>,
     pub window: Option<Window>,
+    /// The name of the window of the aggregation, used instead of `window`.
+    pub window_name: Option<String>,
+    pub named_windows: Vec<NamedWindow>,
+    /// The time zone each row aligns its windows to, `AT TIME ZONE expr`.
+    pub window_time_zone: Option<Expr>,
+    pub allowed_lateness: Option<i64>,
+    pub emit: Option<Emit>,
 }
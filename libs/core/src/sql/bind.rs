@@ -0,0 +1,196 @@
+use std::convert::TryFrom;
+
+use anyhow::Result;
+
+use crate::array::Scalar;
+use crate::expr::{Expr, Literal, OrderBy};
+use crate::sql::ast::{
+    GroupBy, NamedWindow, Select, Source, SourceFrom, Watermark, WindowDefinition,
+};
+
+struct Binder<'a> {
+    params: &'a [Scalar],
+    next: usize,
+}
+
+impl<'a> Binder<'a> {
+    fn bind_expr(&mut self, expr: Expr) -> Result<Expr> {
+        expr.transform(&mut |expr| match expr {
+            Expr::Placeholder(index) => {
+                let index = match index {
+                    Some(index) => index,
+                    None => {
+                        self.next += 1;
+                        self.next
+                    }
+                };
+                let param = index
+                    .checked_sub(1)
+                    .and_then(|idx| self.params.get(idx))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "parameter ${} is not provided, {} parameters are bound",
+                            index,
+                            self.params.len()
+                        )
+                    })?;
+                Ok(Expr::Literal(Literal::try_from(param.clone())?))
+            }
+            expr => Ok(expr),
+        })
+    }
+
+    fn bind_exprs(&mut self, exprs: Vec<Expr>) -> Result<Vec<Expr>> {
+        exprs.into_iter().map(|expr| self.bind_expr(expr)).collect()
+    }
+
+    fn bind_named_window(&mut self, named_window: NamedWindow) -> Result<NamedWindow> {
+        let definition = match named_window.definition {
+            WindowDefinition::Over {
+                partition_by,
+                order_by,
+            } => WindowDefinition::Over {
+                partition_by: self.bind_exprs(partition_by)?,
+                order_by: order_by
+                    .into_iter()
+                    .map(|order_by| {
+                        Ok(OrderBy {
+                            expr: self.bind_expr(order_by.expr)?,
+                            ..order_by
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            },
+            definition => definition,
+        };
+        Ok(NamedWindow {
+            definition,
+            ..named_window
+        })
+    }
+
+    fn bind_select(&mut self, select: Select) -> Result<Select> {
+        let projection = self.bind_exprs(select.projection)?;
+        let from = match select.source.from {
+            SourceFrom::SubQuery(sub_query) => {
+                SourceFrom::SubQuery(Box::new(self.bind_select(*sub_query)?))
+            }
+            from => from,
+        };
+        let watermark = match select.source.watermark {
+            Some(watermark) => Some(Watermark {
+                time_expr: self.bind_expr(watermark.time_expr)?,
+                ..watermark
+            }),
+            None => None,
+        };
+        let where_clause = select
+            .where_clause
+            .map(|expr| self.bind_expr(expr))
+            .transpose()?;
+        let group_clause = match select.group_clause {
+            Some(group_by) => Some(GroupBy {
+                exprs: self.bind_exprs(group_by.exprs)?,
+            }),
+            None => None,
+        };
+        let having_clause = select
+            .having_clause
+            .map(|expr| self.bind_expr(expr))
+            .transpose()?;
+        let named_windows = select
+            .named_windows
+            .into_iter()
+            .map(|named_window| self.bind_named_window(named_window))
+            .collect::<Result<Vec<_>>>()?;
+        let window_time_zone = select
+            .window_time_zone
+            .map(|expr| self.bind_expr(expr))
+            .transpose()?;
+
+        Ok(Select {
+            projection,
+            source: Source {
+                from,
+                watermark,
+                ..select.source
+            },
+            where_clause,
+            having_clause,
+            group_clause,
+            named_windows,
+            window_time_zone,
+            ..select
+        })
+    }
+}
+
+impl Select {
+    /// Replaces the `$n` and `?` placeholders of this query with literals of `params`.
+    ///
+    /// `$n` refers to the n-th parameter (starting from 1), and each `?` takes the parameter
+    /// after the one taken by the previous `?`, in the order they appear in the query.
+    pub fn bind(self, params: &[Scalar]) -> Result<Select> {
+        Binder { params, next: 0 }.bind_select(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsl::{col, value};
+    use crate::sql::parser::select;
+
+    #[test]
+    fn test_bind() {
+        let (_, stmt) = select("select a, $2 from t where a > ?").unwrap();
+        let stmt = stmt
+            .bind(&[Scalar::Int32(10), Scalar::String("x".into())])
+            .unwrap();
+        assert_eq!(
+            stmt.projection,
+            vec![col("a"), value(Literal::String("x".to_string()))]
+        );
+        assert_eq!(
+            stmt.where_clause,
+            Some(col("a").gt(value(Literal::Int(10))))
+        );
+
+        let (_, stmt) = select("select a from t where a > $3").unwrap();
+        assert!(stmt.bind(&[Scalar::Int32(10)]).is_err());
+    }
+
+    #[test]
+    fn test_bind_watermark() {
+        let (_, stmt) = select("select a from t watermark ts - ? where a > ?").unwrap();
+        let stmt = stmt.bind(&[Scalar::Int32(10), Scalar::Int32(20)]).unwrap();
+        assert_eq!(
+            stmt.source.watermark.map(|watermark| watermark.time_expr),
+            Some(col("ts") - value(Literal::Int(10)))
+        );
+        assert_eq!(
+            stmt.where_clause,
+            Some(col("a").gt(value(Literal::Int(20))))
+        );
+    }
+
+    #[test]
+    fn test_bind_named_window() {
+        let (_, stmt) =
+            select("select a from t window w as (partition by k + $1 order by ts - $2)").unwrap();
+        let stmt = stmt.bind(&[Scalar::Int32(1), Scalar::Int32(2)]).unwrap();
+        assert_eq!(
+            stmt.named_windows,
+            vec![NamedWindow {
+                name: "w".to_string(),
+                definition: WindowDefinition::Over {
+                    partition_by: vec![col("k") + value(Literal::Int(1))],
+                    order_by: vec![OrderBy {
+                        expr: col("ts") - value(Literal::Int(2)),
+                        descending: false,
+                    }],
+                },
+            }]
+        );
+    }
+}
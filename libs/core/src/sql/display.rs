@@ -0,0 +1,157 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::expr::{DisplayName, DisplayOver};
+use crate::planner::window::DisplayDuration;
+use crate::sql::ast::{
+    GroupBy, NamedWindow, Select, Source, SourceFrom, Watermark, WindowDefinition,
+};
+
+fn write_list<T: Display>(f: &mut Formatter<'_>, items: &[T]) -> fmt::Result {
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+impl Display for SourceFrom {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceFrom::Named(name) => write!(f, "{}", DisplayName(name)),
+            SourceFrom::SubQuery(select) => write!(f, "({})", select),
+        }
+    }
+}
+
+impl Display for Watermark {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "watermark {}", self.time_expr)?;
+        if self.delay != 0 {
+            write!(f, " delayed by {}", DisplayDuration(self.delay))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.from)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " as {}", DisplayName(alias))?;
+        }
+        if let Some(watermark) = &self.watermark {
+            write!(f, " {}", watermark)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for GroupBy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("group by ")?;
+        write_list(f, &self.exprs)
+    }
+}
+
+impl Display for NamedWindow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} as ", DisplayName(&self.name))?;
+        match &self.definition {
+            WindowDefinition::Time(window) => write!(f, "{}", window),
+            WindowDefinition::Over {
+                partition_by,
+                order_by,
+            } => write!(f, "{}", DisplayOver(partition_by, order_by)),
+        }
+    }
+}
+
+/// Formats the statement as SQL that parses back to the same statement.
+impl Display for Select {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("select ")?;
+        write_list(f, &self.projection)?;
+        write!(f, " from {}", self.source)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " where {}", where_clause)?;
+        }
+        if let Some(group_by) = &self.group_clause {
+            write!(f, " {}", group_by)?;
+        }
+        if let Some(having_clause) = &self.having_clause {
+            write!(f, " having {}", having_clause)?;
+        }
+        let mut window_items = Vec::new();
+        if let Some(window) = &self.window {
+            window_items.push(window.to_string());
+        }
+        if let Some(window_name) = &self.window_name {
+            window_items.push(DisplayName(window_name).to_string());
+        }
+        window_items.extend(self.named_windows.iter().map(ToString::to_string));
+        if !window_items.is_empty() {
+            f.write_str(" window ")?;
+            write_list(f, &window_items)?;
+        }
+        if let Some(time_zone) = &self.window_time_zone {
+            write!(f, " at time zone {}", time_zone)?;
+        }
+        if let Some(allowed_lateness) = self.allowed_lateness {
+            write!(f, " allow lateness {}", DisplayDuration(allowed_lateness))?;
+        }
+        if let Some(emit) = &self.emit {
+            write!(f, " emit {}", emit)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::parser::parse_select;
+
+    #[test]
+    fn test_round_trip() {
+        for (sql, expected) in &[
+            (
+                "SELECT a, b + 1.0 AS c FROM t",
+                "select a, (b + 1.0) as c from t",
+            ),
+            (
+                "select count(*) filter (where a > 0), t.* from \"my table\" as t watermark ts delayed by 90s",
+                "select count(*) filter (where (a > 0)), t.* from \"my table\" as t watermark ts delayed by 90s",
+            ),
+            (
+                "select k, sum(v) from (select * from t where not a) group by k having sum(v) > 10 window sliding(1h, 5m) allow lateness 1m emit every 30s",
+                "select k, sum(v) from (select * from t where not a) group by k having (sum(v) > 10) window sliding(1h, 5m) allow lateness 1m emit every 30s",
+            ),
+            (
+                "select lag(a, 2) over (partition by k order by ts desc), [1, 'x\\n\\u00e9'][2], a[1].b from t window day",
+                "select lag(a,2) over (partition by k order by ts desc), [1,\"x\\n\u{e9}\"][2], a[1].b from t window day",
+            ),
+            (
+                "select k, sum(v), lag(v) over o from (select * from t window w) window w, w as sliding(10m, 1m), o as (partition by k order by ts)",
+                "select k, sum(v), lag(v) over o from (select * from t window w) window w, w as sliding(10m, 1m), o as (partition by k order by ts)",
+            ),
+            (
+                "select * exclude (a, \"b c\"), t.* exclude d from t",
+                "select * exclude (a, \"b c\"), t.* exclude (d) from t",
+            ),
+            (
+                "select (a).b, $1, ? from t window session(1500ms) emit on update",
+                "select (a).b, $1, ? from t window session(1500ms) emit on update",
+            ),
+            (
+                "select user, count(*) from t group by user window day at time zone tz",
+                "select user, count(*) from t group by user window day at time zone tz",
+            ),
+        ] {
+            let select = parse_select(sql).unwrap();
+            let formatted = select.to_string();
+            assert_eq!(&formatted, expected);
+            assert_eq!(parse_select(&formatted).unwrap(), select);
+        }
+    }
+}
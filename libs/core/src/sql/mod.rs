@@ -2,6 +2,12 @@ pub mod ast;
 pub mod parser;
 pub(crate) mod planner;
 
+mod bind;
 mod context;
+mod display;
+mod parse_error;
+mod semantic_error;
 
 pub use context::{SqlContext, SqlSourceProvider};
+pub use parse_error::ParseError;
+pub use semantic_error::SemanticError;
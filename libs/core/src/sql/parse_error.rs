@@ -0,0 +1,99 @@
+use std::fmt::{self, Display, Formatter};
+
+use nom::error::{VerboseError, VerboseErrorKind};
+
+/// Maximum number of characters of the offending token shown in an error.
+const MAX_TOKEN_LENGTH: usize = 20;
+
+/// An error of the SQL parser, with the position where parsing failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number, in characters.
+    pub column: usize,
+    /// The token at the error position, or `None` at the end of the input.
+    pub token: Option<String>,
+    /// The alternatives that were expected at the error position.
+    pub expected: Vec<String>,
+    /// The innermost syntax element that failed to parse.
+    pub context: Option<&'static str>,
+}
+
+impl ParseError {
+    pub fn new(input: &str, err: nom::Err<VerboseError<&str>>) -> Self {
+        let errors = match err {
+            nom::Err::Error(err) | nom::Err::Failure(err) => err.errors,
+            nom::Err::Incomplete(_) => Vec::new(),
+        };
+        let remaining = errors
+            .first()
+            .map(|(remaining, _)| *remaining)
+            .unwrap_or("");
+
+        let (line, column) = position(input, input.len() - remaining.len());
+
+        let token = remaining
+            .split(|c: char| c.is_whitespace())
+            .next()
+            .filter(|token| !token.is_empty())
+            .map(|token| token.chars().take(MAX_TOKEN_LENGTH).collect());
+
+        let mut expected = Vec::new();
+        for (input, kind) in &errors {
+            if let VerboseErrorKind::Char(c) = kind {
+                let c = format!("'{}'", c);
+                if input.len() == remaining.len() && !expected.contains(&c) {
+                    expected.push(c);
+                }
+            }
+        }
+
+        let context = errors.iter().find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(context) => Some(*context),
+            _ => None,
+        });
+
+        Self {
+            line,
+            column,
+            token,
+            expected,
+            context,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "syntax error at line {}, column {}: ",
+            self.line, self.column
+        )?;
+        match &self.token {
+            Some(token) => write!(f, "unexpected '{}'", token)?,
+            None => write!(f, "unexpected end of input")?,
+        }
+        if !self.expected.is_empty() {
+            write!(f, ", expected {}", self.expected.join(" or "))?;
+        }
+        if let Some(context) = self.context {
+            write!(f, " (in {})", context)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Returns the 1-based line and column, in characters, of the byte `offset` of `input`.
+pub(crate) fn position(input: &str, offset: usize) -> (usize, usize) {
+    let consumed = &input[..offset];
+    let line = consumed.matches('\n').count() + 1;
+    let column = match consumed.rfind('\n') {
+        Some(idx) => consumed[idx + 1..].chars().count() + 1,
+        None => consumed.chars().count() + 1,
+    };
+    (line, column)
+}
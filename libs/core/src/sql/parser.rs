@@ -1,24 +1,118 @@
+use std::cell::Cell;
 use std::str::FromStr;
 
+use chrono::{DateTime, NaiveDateTime};
 use nom::branch::alt;
-use nom::bytes::complete::{is_not, tag, tag_no_case, take};
-use nom::character::complete::{alpha1, alphanumeric1, char, digit1, one_of};
-use nom::combinator::{cut, map, opt, recognize, value};
-use nom::error::context;
+use nom::bytes::complete::{is_not, tag, tag_no_case, take, take_while_m_n};
+use nom::character::complete::{alpha1, alphanumeric1, char, digit1, one_of, satisfy};
+use nom::combinator::{cut, eof, map, map_opt, map_res, not, opt, recognize, value, verify};
+use nom::error::{context, VerboseError, VerboseErrorKind};
 use nom::multi::{fold_many0, many0, separated_list0, separated_list1};
-use nom::sequence::{delimited, pair, preceded, separated_pair, tuple};
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
 use nom::IResult;
 
-use crate::expr::{BinaryOperator, Expr, Literal, UnaryOperator};
+use crate::array::temporal::{parse_date, parse_time};
+use crate::expr::{BinaryOperator, Expr, Literal, OrderBy, Span, UnaryOperator};
 use crate::planner::window::Period;
-use crate::sql::ast::{GroupBy, Select, Source, SourceFrom};
-use crate::Window;
+use crate::sql::ast::{
+    GroupBy, NamedWindow, Select, Source, SourceFrom, Watermark, WindowDefinition,
+};
+use crate::sql::ParseError;
+use crate::{Emit, Window};
 
-pub fn sp(input: &str) -> IResult<&str, ()> {
+/// The result type of the SQL parsers.
+pub type ParseResult<'a, O> = IResult<&'a str, O, VerboseError<&'a str>>;
+
+/// The default maximum nesting depth of expressions and subqueries.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 64;
+
+/// The options of a parse.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParserConfig {
+    /// The maximum nesting depth of expressions and subqueries.
+    ///
+    /// The parsers are recursive, deeper input fails with an error instead of overflowing the
+    /// stack.
+    pub max_nesting_depth: usize,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+        }
+    }
+}
+
+impl ParserConfig {
+    pub fn with_max_nesting_depth(self, depth: usize) -> Self {
+        Self {
+            max_nesting_depth: depth,
+        }
+    }
+}
+
+thread_local! {
+    /// The config of the parse running on the current thread.
+    static CONFIG: Cell<ParserConfig> = Cell::new(ParserConfig::default());
+    static NESTING_DEPTH: Cell<usize> = Cell::new(0);
+}
+
+/// Runs `f`, which applies the parsers of this module, with `config`.
+///
+/// The parsers use the default config outside of `with_config`.
+pub fn with_config<T>(config: ParserConfig, f: impl FnOnce() -> T) -> T {
+    /// Restores the config of the enclosing parse when it is dropped.
+    struct RestoreConfig(ParserConfig);
+
+    impl Drop for RestoreConfig {
+        fn drop(&mut self) {
+            CONFIG.with(|config| config.set(self.0));
+        }
+    }
+
+    let _restore = RestoreConfig(CONFIG.with(|current| current.replace(config)));
+    f()
+}
+
+/// Increases the nesting depth of the current thread until it is dropped.
+struct NestingGuard;
+
+impl NestingGuard {
+    fn enter(input: &str) -> Result<Self, nom::Err<VerboseError<&str>>> {
+        let depth = NESTING_DEPTH.with(|depth| depth.get());
+        let max_depth = CONFIG.with(|config| config.get().max_nesting_depth);
+        if depth >= max_depth {
+            return Err(nom::Err::Failure(VerboseError {
+                errors: vec![(input, VerboseErrorKind::Context("max_nesting_depth"))],
+            }));
+        }
+        NESTING_DEPTH.with(|nesting_depth| nesting_depth.set(depth + 1));
+        Ok(NestingGuard)
+    }
+}
+
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        NESTING_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Applies `parser` one nesting level deeper.
+fn nested<'a, O>(
+    mut parser: impl FnMut(&'a str) -> ParseResult<'a, O>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, O> {
+    move |input| {
+        let _guard = NestingGuard::enter(input)?;
+        parser(input)
+    }
+}
+
+pub fn sp(input: &str) -> ParseResult<()> {
     fold_many0(value((), one_of(" \t\n\r")), (), |_, _| ())(input)
 }
 
-pub fn ident(input: &str) -> IResult<&str, &str> {
+pub fn ident(input: &str) -> ParseResult<&str> {
     context(
         "ident",
         recognize(pair(
@@ -28,7 +122,7 @@ pub fn ident(input: &str) -> IResult<&str, &str> {
     )(input)
 }
 
-pub fn boolean(input: &str) -> IResult<&str, bool> {
+pub fn boolean(input: &str) -> ParseResult<bool> {
     context(
         "boolean",
         alt((
@@ -38,16 +132,14 @@ pub fn boolean(input: &str) -> IResult<&str, bool> {
     )(input)
 }
 
-pub fn integer(input: &str) -> IResult<&str, i64> {
+pub fn integer(input: &str) -> ParseResult<i64> {
     context(
         "integer",
-        map(recognize(tuple((opt(char('-')), digit1))), |s| {
-            i64::from_str(s).unwrap()
-        }),
+        map_res(recognize(tuple((opt(char('-')), digit1))), i64::from_str),
     )(input)
 }
 
-pub fn float(input: &str) -> IResult<&str, f64> {
+pub fn float(input: &str) -> ParseResult<f64> {
     context(
         "float",
         map(
@@ -68,34 +160,67 @@ pub fn float(input: &str) -> IResult<&str, f64> {
     )(input)
 }
 
-fn raw_string_quoted(input: &str, is_single_quote: bool) -> IResult<&str, String> {
+/// Parses `\uXXXX` or `\u{X}` with 1 to 6 hex digits into the character.
+fn unicode_escape(input: &str) -> ParseResult<char> {
+    let hex4 = take_while_m_n(4, 4, |c: char| c.is_ascii_hexdigit());
+    let braced = delimited(
+        char('{'),
+        take_while_m_n(1, 6, |c: char| c.is_ascii_hexdigit()),
+        char('}'),
+    );
+    context(
+        "unicode_escape",
+        preceded(
+            tag("\\u"),
+            cut(map_opt(alt((braced, hex4)), |hex| {
+                u32::from_str_radix(hex, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+            })),
+        ),
+    )(input)
+}
+
+enum StringFragment<'a> {
+    Str(&'a str),
+    Char(char),
+}
+
+fn raw_string_quoted(input: &str, is_single_quote: bool) -> ParseResult<String> {
     let quote_str = if is_single_quote { "\'" } else { "\"" };
     let double_quote_str = if is_single_quote { "\'\'" } else { "\"\"" };
     let backslash_quote = if is_single_quote { "\\\'" } else { "\\\"" };
+    let fragment = alt((
+        is_not(backslash_quote),
+        map(tag(double_quote_str), |_| -> &str {
+            if is_single_quote {
+                "\'"
+            } else {
+                "\""
+            }
+        }),
+        map(tag("\\\\"), |_| "\\"),
+        map(tag("\\b"), |_| "\x7f"),
+        map(tag("\\r"), |_| "\r"),
+        map(tag("\\n"), |_| "\n"),
+        map(tag("\\t"), |_| "\t"),
+        map(tag("\\0"), |_| "\0"),
+        map(tag("\\Z"), |_| "\x1A"),
+        preceded(tag("\\"), take(1usize)),
+    ));
     delimited(
         tag(quote_str),
         fold_many0(
             alt((
-                is_not(backslash_quote),
-                map(tag(double_quote_str), |_| -> &str {
-                    if is_single_quote {
-                        "\'"
-                    } else {
-                        "\""
-                    }
-                }),
-                map(tag("\\\\"), |_| "\\"),
-                map(tag("\\b"), |_| "\x7f"),
-                map(tag("\\r"), |_| "\r"),
-                map(tag("\\n"), |_| "\n"),
-                map(tag("\\t"), |_| "\t"),
-                map(tag("\\0"), |_| "\0"),
-                map(tag("\\Z"), |_| "\x1A"),
-                preceded(tag("\\"), take(1usize)),
+                map(unicode_escape, StringFragment::Char),
+                map(fragment, StringFragment::Str),
             )),
             String::new(),
-            |mut acc: String, s: &str| {
-                acc.push_str(s);
+            |mut acc: String, fragment| {
+                match fragment {
+                    StringFragment::Str(s) => acc.push_str(s),
+                    StringFragment::Char(c) => acc.push(c),
+                }
                 acc
             },
         ),
@@ -103,26 +228,66 @@ fn raw_string_quoted(input: &str, is_single_quote: bool) -> IResult<&str, String
     )(input)
 }
 
-fn raw_string_single_quoted(input: &str) -> IResult<&str, String> {
+fn raw_string_single_quoted(input: &str) -> ParseResult<String> {
     raw_string_quoted(input, true)
 }
 
-fn raw_string_double_quoted(input: &str) -> IResult<&str, String> {
+fn raw_string_double_quoted(input: &str) -> ParseResult<String> {
     raw_string_quoted(input, false)
 }
 
-pub fn string(input: &str) -> IResult<&str, String> {
+pub fn string(input: &str) -> ParseResult<String> {
     context(
         "string",
         alt((raw_string_single_quoted, raw_string_double_quoted)),
     )(input)
 }
 
-pub fn literal(input: &str) -> IResult<&str, Literal> {
+fn parse_timestamp(s: &str) -> Result<i64, chrono::ParseError> {
+    match DateTime::parse_from_rfc3339(s) {
+        Ok(datetime) => Ok(datetime.timestamp_millis()),
+        Err(_) => NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+            .map(|datetime| datetime.timestamp_millis()),
+    }
+}
+
+pub fn timestamp(input: &str) -> ParseResult<i64> {
+    context(
+        "timestamp",
+        map_res(preceded(pair(tag_no_case("timestamp"), sp), string), |s| {
+            parse_timestamp(&s)
+        }),
+    )(input)
+}
+
+/// A date literal such as `date '2024-01-01'`, in days since the unix epoch.
+pub fn date(input: &str) -> ParseResult<i32> {
+    context(
+        "date",
+        map_opt(preceded(pair(tag_no_case("date"), sp), string), |s| {
+            parse_date(&s)
+        }),
+    )(input)
+}
+
+/// A time literal such as `time '12:30:00'`, in milliseconds since midnight.
+pub fn time(input: &str) -> ParseResult<i32> {
+    context(
+        "time",
+        map_opt(preceded(pair(tag_no_case("time"), sp), string), |s| {
+            parse_time(&s)
+        }),
+    )(input)
+}
+
+pub fn literal(input: &str) -> ParseResult<Literal> {
     context(
         "literal",
         alt((
             map(boolean, Literal::Boolean),
+            map(timestamp, Literal::Timestamp),
+            map(date, Literal::Date),
+            map(time, Literal::Time),
             map(float, Literal::Float),
             map(integer, Literal::Int),
             map(string, Literal::String),
@@ -130,11 +295,12 @@ pub fn literal(input: &str) -> IResult<&str, Literal> {
     )(input)
 }
 
-pub fn name(input: &str) -> IResult<&str, String> {
+pub fn name(input: &str) -> ParseResult<String> {
     context("name", alt((string, map(ident, ToString::to_string))))(input)
 }
 
-pub fn column(input: &str) -> IResult<&str, Expr> {
+pub fn column(input: &str) -> ParseResult<Expr> {
+    let span = Span::new(input);
     context(
         "input",
         alt((
@@ -143,28 +309,101 @@ pub fn column(input: &str) -> IResult<&str, Expr> {
                 |(qualifier, name)| Expr::Column {
                     qualifier: Some(qualifier),
                     name,
+                    span,
                 },
             ),
-            map(name, |name| Expr::Column {
-                qualifier: None,
-                name,
-            }),
             map(
                 separated_pair(name, char('.'), char('*')),
                 |(qualifier, _)| Expr::Wildcard {
                     qualifier: Some(qualifier),
+                    exclude: vec![],
                 },
             ),
-            map(char('*'), |_| Expr::Wildcard { qualifier: None }),
+            map(name, |name| Expr::Column {
+                qualifier: None,
+                name,
+                span,
+            }),
+            map(char('*'), |_| Expr::Wildcard {
+                qualifier: None,
+                exclude: vec![],
+            }),
+        )),
+    )(input)
+}
+
+pub fn expr(input: &str) -> ParseResult<Expr> {
+    context("expr", nested(expr_or))(input)
+}
+
+fn order_by_item(input: &str) -> ParseResult<OrderBy> {
+    map(
+        pair(
+            expr,
+            opt(alt((
+                value(false, keyword("asc")),
+                value(true, keyword("desc")),
+            ))),
+        ),
+        |(expr, descending)| OrderBy {
+            expr,
+            descending: descending.unwrap_or_default(),
+        },
+    )(input)
+}
+
+/// Parses the partitioning and ordering of an `OVER` clause or of a window definition,
+/// `(PARTITION BY .. ORDER BY ..)`.
+fn over_spec(input: &str) -> ParseResult<(Vec<Expr>, Vec<OrderBy>)> {
+    let partition_by = preceded(
+        tuple((keyword("partition"), sp, keyword("by"))),
+        cut(separated_list1(char(','), delimited(sp, expr, sp))),
+    );
+    let order_by = preceded(
+        tuple((keyword("order"), sp, keyword("by"))),
+        cut(separated_list1(char(','), delimited(sp, order_by_item, sp))),
+    );
+    map(
+        tuple((
+            char('('),
+            sp,
+            opt(partition_by),
+            sp,
+            opt(order_by),
+            sp,
+            cut(char(')')),
         )),
+        |(_, _, partition_by, _, order_by, _, _)| {
+            (
+                partition_by.unwrap_or_default(),
+                order_by.unwrap_or_default(),
+            )
+        },
     )(input)
 }
 
-pub fn expr(input: &str) -> IResult<&str, Expr> {
-    context("expr", expr_a)(input)
+enum Over {
+    Spec(Vec<Expr>, Vec<OrderBy>),
+    Window(String),
+}
+
+fn over(input: &str) -> ParseResult<Over> {
+    context(
+        "over",
+        preceded(
+            pair(keyword("over"), sp),
+            cut(alt((
+                map(over_spec, |(partition_by, order_by)| {
+                    Over::Spec(partition_by, order_by)
+                }),
+                map(name, Over::Window),
+            ))),
+        ),
+    )(input)
 }
 
-fn expr_call(input: &str) -> IResult<&str, Expr> {
+fn expr_call(input: &str) -> ParseResult<Expr> {
+    let span = Span::new(input);
     let func_name = alt((
         map(tuple((ident, char('.'), ident)), |(namespace, _, name)| {
             (Some(namespace), name)
@@ -172,104 +411,237 @@ fn expr_call(input: &str) -> IResult<&str, Expr> {
         map(ident, |name| (None, name)),
     ));
     let arguments = separated_list0(char(','), delimited(sp, expr, sp));
+    let filter = preceded(
+        tuple((
+            sp,
+            keyword("filter"),
+            sp,
+            char('('),
+            sp,
+            tag_no_case("where"),
+            sp,
+        )),
+        cut(terminated(expr, pair(sp, char(')')))),
+    );
     context(
         "expr_call",
         map(
-            tuple((func_name, sp, char('('), sp, arguments, sp, char(')'))),
-            |((namespace, name), _, _, _, args, _, _)| Expr::Call {
-                namespace: namespace.map(ToString::to_string),
-                name: name.to_string(),
-                args,
+            tuple((
+                func_name,
+                sp,
+                char('('),
+                sp,
+                arguments,
+                sp,
+                char(')'),
+                opt(filter),
+                opt(preceded(sp, over)),
+            )),
+            |((namespace, name), _, _, _, args, _, _, condition, over)| {
+                let call = Expr::Call {
+                    namespace: namespace.map(ToString::to_string),
+                    name: name.to_string(),
+                    args,
+                    span,
+                };
+                let call = match condition {
+                    Some(condition) => Expr::AggregateFilter {
+                        expr: Box::new(call),
+                        condition: Box::new(condition),
+                    },
+                    None => call,
+                };
+                match over {
+                    Some(Over::Spec(partition_by, order_by)) => call.over(partition_by, order_by),
+                    Some(Over::Window(window)) => Expr::OverWindow {
+                        expr: Box::new(call),
+                        window,
+                    },
+                    None => call,
+                }
             },
         ),
     )(input)
 }
 
-fn expr_primitive(input: &str) -> IResult<&str, Expr> {
+fn expr_placeholder(input: &str) -> ParseResult<Expr> {
+    context(
+        "expr_placeholder",
+        alt((
+            map(preceded(char('$'), digit1), |n| {
+                Expr::Placeholder(Some(usize::from_str(n).unwrap()))
+            }),
+            value(Expr::Placeholder(None), char('?')),
+        )),
+    )(input)
+}
+
+fn expr_array(input: &str) -> ParseResult<Expr> {
+    context(
+        "expr_array",
+        map(
+            delimited(
+                char('['),
+                separated_list0(char(','), delimited(sp, expr, sp)),
+                pair(sp, cut(char(']'))),
+            ),
+            Expr::Array,
+        ),
+    )(input)
+}
+
+enum Postfix {
+    Index(Box<Expr>),
+    Field(String),
+}
+
+fn expr_primitive(input: &str) -> ParseResult<Expr> {
     let parens = map(
         tuple((char('('), sp, expr, sp, char(')'))),
         |(_, _, expr, _, _)| expr,
     );
     let p = alt((
         parens,
-        expr_unary,
+        expr_array,
         expr_call,
         map(literal, Expr::Literal),
+        expr_placeholder,
         column,
     ));
+    let subscript = map(
+        preceded(
+            pair(sp, char('[')),
+            cut(terminated(delimited(sp, expr, sp), char(']'))),
+        ),
+        |index| Postfix::Index(Box::new(index)),
+    );
+    let field = map(preceded(char('.'), name), Postfix::Field);
+    let p = map(
+        pair(p, many0(alt((subscript, field)))),
+        |(expr, postfixes)| {
+            postfixes
+                .into_iter()
+                .fold(expr, |expr, postfix| match postfix {
+                    Postfix::Index(index) => Expr::Index {
+                        expr: Box::new(expr),
+                        index,
+                    },
+                    Postfix::Field(name) => Expr::Field {
+                        expr: Box::new(expr),
+                        name,
+                    },
+                })
+        },
+    );
     context("expr_primitive", delimited(sp, p, sp))(input)
 }
 
-fn expr_unary(input: &str) -> IResult<&str, Expr> {
-    let op = alt((
-        value(UnaryOperator::Not, tag_no_case("not")),
-        value(UnaryOperator::Neg, char('-')),
-    ));
-    map(separated_pair(op, sp, expr), |(op, expr)| Expr::Unary {
-        op,
-        expr: Box::new(expr),
-    })(input)
+/// Matches a keyword that is not followed by other identifier characters.
+fn keyword<'a>(keyword: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    terminated(
+        tag_no_case(keyword),
+        not(satisfy(|c| c.is_alphanumeric() || c == '_')),
+    )
 }
 
-fn expr_a(input: &str) -> IResult<&str, Expr> {
-    let (input, lhs) = expr_b(input)?;
-    let (input, exprs) = many0(tuple((
-        value(BinaryOperator::Or, tag_no_case("or")),
-        expr_b,
-    )))(input)?;
+// Operators from the lowest to the highest precedence:
+//
+// OR
+// AND
+// NOT
+// =, !=, <>, <, <=, >, >=
+// ||
+// +, -
+// *, /, %
+// - (negation)
+
+fn expr_or(input: &str) -> ParseResult<Expr> {
+    let (input, lhs) = expr_and(input)?;
+    let (input, exprs) = many0(tuple((value(BinaryOperator::Or, keyword("or")), expr_and)))(input)?;
     Ok((input, parse_expr(lhs, exprs)))
 }
 
-fn expr_b(input: &str) -> IResult<&str, Expr> {
-    let (input, lhs) = expr_c(input)?;
+fn expr_and(input: &str) -> ParseResult<Expr> {
+    let (input, lhs) = expr_not(input)?;
     let (input, exprs) = many0(tuple((
-        value(BinaryOperator::Or, tag_no_case("and")),
-        expr_c,
+        value(BinaryOperator::And, keyword("and")),
+        expr_not,
     )))(input)?;
     Ok((input, parse_expr(lhs, exprs)))
 }
 
-fn expr_c(input: &str) -> IResult<&str, Expr> {
-    let (input, lhs) = expr_d(input)?;
+fn expr_not(input: &str) -> ParseResult<Expr> {
+    let not_expr = map(
+        preceded(pair(keyword("not"), sp), nested(expr_not)),
+        |expr| Expr::Unary {
+            op: UnaryOperator::Not,
+            expr: Box::new(expr),
+        },
+    );
+    preceded(sp, alt((not_expr, expr_comparison)))(input)
+}
+
+fn expr_comparison(input: &str) -> ParseResult<Expr> {
+    let (input, lhs) = expr_concat(input)?;
     let (input, exprs) = many0(tuple((
         alt((
-            value(BinaryOperator::Eq, tag("=")),
+            value(BinaryOperator::LtEq, tag("<=")),
+            value(BinaryOperator::GtEq, tag(">=")),
             value(BinaryOperator::NotEq, tag("!=")),
             value(BinaryOperator::NotEq, tag("<>")),
             value(BinaryOperator::Lt, tag("<")),
-            value(BinaryOperator::LtEq, tag("<")),
             value(BinaryOperator::Gt, tag(">")),
-            value(BinaryOperator::GtEq, tag(">=")),
+            value(BinaryOperator::Eq, tag("=")),
         )),
-        expr_d,
+        expr_concat,
+    )))(input)?;
+    Ok((input, parse_expr(lhs, exprs)))
+}
+
+fn expr_concat(input: &str) -> ParseResult<Expr> {
+    let (input, lhs) = expr_additive(input)?;
+    let (input, exprs) = many0(tuple((
+        value(BinaryOperator::Concat, tag("||")),
+        expr_additive,
     )))(input)?;
     Ok((input, parse_expr(lhs, exprs)))
 }
 
-fn expr_d(input: &str) -> IResult<&str, Expr> {
-    let (input, lhs) = expr_e(input)?;
+fn expr_additive(input: &str) -> ParseResult<Expr> {
+    let (input, lhs) = expr_multiplicative(input)?;
     let (input, exprs) = many0(tuple((
         alt((
             value(BinaryOperator::Plus, char('+')),
             value(BinaryOperator::Minus, char('-')),
         )),
-        expr_e,
+        expr_multiplicative,
     )))(input)?;
     Ok((input, parse_expr(lhs, exprs)))
 }
 
-fn expr_e(input: &str) -> IResult<&str, Expr> {
-    let (input, lhs) = expr_primitive(input)?;
+fn expr_multiplicative(input: &str) -> ParseResult<Expr> {
+    let (input, lhs) = expr_unary(input)?;
     let (input, exprs) = many0(tuple((
         alt((
             value(BinaryOperator::Multiply, char('*')),
             value(BinaryOperator::Divide, char('/')),
+            value(BinaryOperator::Modulo, char('%')),
         )),
-        expr_primitive,
+        expr_unary,
     )))(input)?;
     Ok((input, parse_expr(lhs, exprs)))
 }
 
+fn expr_unary(input: &str) -> ParseResult<Expr> {
+    let neg_expr = map(preceded(pair(char('-'), sp), nested(expr_unary)), |expr| {
+        Expr::Unary {
+            op: UnaryOperator::Neg,
+            expr: Box::new(expr),
+        }
+    });
+    preceded(sp, alt((neg_expr, expr_primitive)))(input)
+}
+
 fn parse_expr(expr: Expr, rem: Vec<(BinaryOperator, Expr)>) -> Expr {
     rem.into_iter().fold(expr, |lhs, (op, rhs)| Expr::Binary {
         op,
@@ -278,10 +650,25 @@ fn parse_expr(expr: Expr, rem: Vec<(BinaryOperator, Expr)>) -> Expr {
     })
 }
 
-fn projection_field(input: &str) -> IResult<&str, Expr> {
+fn projection_field(input: &str) -> ParseResult<Expr> {
+    let exclude = preceded(
+        tuple((sp, keyword("exclude"), sp)),
+        cut(alt((
+            delimited(
+                pair(char('('), sp),
+                separated_list1(char(','), delimited(sp, name, sp)),
+                char(')'),
+            ),
+            map(name, |name| vec![name]),
+        ))),
+    );
     context(
         "projection_field",
         alt((
+            map_opt(pair(column, exclude), |(expr, exclude)| match expr {
+                Expr::Wildcard { qualifier, .. } => Some(Expr::Wildcard { qualifier, exclude }),
+                _ => None,
+            }),
             map(
                 tuple((expr, sp, tag_no_case("as"), sp, name)),
                 |(expr, _, _, _, alias)| expr.alias(alias),
@@ -291,7 +678,7 @@ fn projection_field(input: &str) -> IResult<&str, Expr> {
     )(input)
 }
 
-fn source_from(input: &str) -> IResult<&str, SourceFrom> {
+fn source_from(input: &str) -> ParseResult<SourceFrom> {
     context(
         "source_from",
         alt((
@@ -304,23 +691,44 @@ fn source_from(input: &str) -> IResult<&str, SourceFrom> {
     )(input)
 }
 
-fn source(input: &str) -> IResult<&str, Source> {
+fn watermark(input: &str) -> ParseResult<Watermark> {
+    let delayed_by = tuple((sp, tag_no_case("delayed"), sp, tag_no_case("by"), sp));
+    context(
+        "watermark",
+        map(
+            tuple((
+                keyword("watermark"),
+                sp,
+                cut(expr),
+                opt(preceded(delayed_by, cut(duration))),
+            )),
+            |(_, _, time_expr, delay)| Watermark {
+                time_expr,
+                delay: delay.unwrap_or_default(),
+            },
+        ),
+    )(input)
+}
+
+fn source(input: &str) -> ParseResult<Source> {
     context(
         "source",
-        alt((
-            map(
-                tuple((source_from, sp, tag_no_case("as"), sp, name)),
-                |(from, _, _, _, alias)| Source {
-                    from,
-                    alias: Some(alias),
-                },
-            ),
-            map(source_from, |from| Source { from, alias: None }),
-        )),
+        map(
+            tuple((
+                source_from,
+                opt(preceded(tuple((sp, tag_no_case("as"), sp)), name)),
+                opt(preceded(sp, watermark)),
+            )),
+            |(from, alias, watermark)| Source {
+                from,
+                alias,
+                watermark,
+            },
+        ),
     )(input)
 }
 
-fn group_by(input: &str) -> IResult<&str, GroupBy> {
+fn group_by(input: &str) -> ParseResult<GroupBy> {
     context(
         "group_by",
         map(
@@ -336,21 +744,43 @@ fn group_by(input: &str) -> IResult<&str, GroupBy> {
     )(input)
 }
 
-fn duration(input: &str) -> IResult<&str, i64> {
-    let seconds = map(pair(integer, tag_no_case("s")), |(n, _)| n * 1000);
-    let milliseconds = map(pair(integer, tag_no_case("ms")), |(n, _)| n);
-    let minutes = map(pair(integer, tag_no_case("m")), |(n, _)| n * 1000 * 60);
-    context("duration", alt((seconds, milliseconds, minutes)))(input)
+/// Parses a duration in milliseconds, e.g. `500ms`, `30s`, `5m`, `1h` or `7d`.
+fn duration(input: &str) -> ParseResult<i64> {
+    let unit = alt((
+        value(1, keyword("ms")),
+        value(1000, keyword("s")),
+        value(60 * 1000, keyword("m")),
+        value(60 * 60 * 1000, keyword("h")),
+        value(24 * 60 * 60 * 1000, keyword("d")),
+    ));
+    context(
+        "duration",
+        map_opt(
+            pair(
+                map_res(digit1, i64::from_str),
+                cut(context("duration unit", unit)),
+            ),
+            |(n, unit)| n.checked_mul(unit),
+        ),
+    )(input)
+}
+
+/// Parses a duration greater than zero, the length of a window or the gap of a session.
+fn positive_duration(input: &str) -> ParseResult<i64> {
+    context(
+        "positive duration",
+        verify(duration, |duration: &i64| *duration > 0),
+    )(input)
 }
 
-fn window(input: &str) -> IResult<&str, Window> {
+fn window_spec(input: &str) -> ParseResult<Window> {
     let fixed_window = map(
         tuple((
             tag_no_case("fixed"),
             sp,
             char('('),
             sp,
-            duration,
+            positive_duration,
             sp,
             char(')'),
         )),
@@ -362,49 +792,185 @@ fn window(input: &str) -> IResult<&str, Window> {
             sp,
             char('('),
             sp,
-            duration,
+            positive_duration,
             sp,
             char(','),
             sp,
-            duration,
+            positive_duration,
+            sp,
+            opt(map(
+                tuple((char(','), sp, duration, sp)),
+                |(_, _, offset, _)| offset,
+            )),
+            char(')'),
+        )),
+        |(_, _, _, _, length, _, _, _, interval, _, offset, _)| Window::Sliding {
+            length,
+            interval,
+            offset: offset.unwrap_or_default(),
+        },
+    );
+    let session_window = map(
+        tuple((
+            tag_no_case("session"),
+            sp,
+            char('('),
+            sp,
+            positive_duration,
             sp,
             char(')'),
         )),
-        |(_, _, _, _, length, _, _, _, interval, _, _)| Window::Sliding { length, interval },
+        |(_, _, _, _, gap, _, _)| Window::Session { gap },
     );
     let period_window = map(
         alt((
-            value(Period::Day, tag_no_case("day")),
-            value(Period::Week, tag_no_case("week")),
-            value(Period::Month, tag_no_case("month")),
-            value(Period::Year, tag_no_case("year")),
+            value(Period::Day, keyword("day")),
+            value(Period::Week, keyword("week")),
+            value(Period::Month, keyword("month")),
+            value(Period::Year, keyword("year")),
         )),
         |period| Window::Period { period },
     );
 
+    context(
+        "window_spec",
+        alt((fixed_window, sliding_window, session_window, period_window)),
+    )(input)
+}
+
+enum WindowItem {
+    Window(Window),
+    Name(String),
+    Definition(NamedWindow),
+}
+
+/// The window of the aggregation, given inline or by name, and the windows defined by name.
+type WindowClause = (Option<Window>, Option<String>, Vec<NamedWindow>);
+
+/// Parses the `WINDOW` clause, a list of at most one window of the aggregation and any number
+/// of window definitions, e.g. `WINDOW fixed(5m), w AS (PARTITION BY k ORDER BY ts)`.
+fn window_clause(input: &str) -> ParseResult<WindowClause> {
+    let definition = map(
+        tuple((
+            name,
+            sp,
+            keyword("as"),
+            sp,
+            cut(alt((
+                map(window_spec, WindowDefinition::Time),
+                map(over_spec, |(partition_by, order_by)| {
+                    WindowDefinition::Over {
+                        partition_by,
+                        order_by,
+                    }
+                }),
+            ))),
+        )),
+        |(name, _, _, _, definition)| WindowItem::Definition(NamedWindow { name, definition }),
+    );
+    let item = alt((
+        definition,
+        map(window_spec, WindowItem::Window),
+        map(name, WindowItem::Name),
+    ));
+
     context(
         "window",
+        preceded(
+            pair(keyword("window"), sp),
+            cut(map_opt(
+                separated_list1(char(','), delimited(sp, item, sp)),
+                |items| {
+                    let mut window = None;
+                    let mut window_name = None;
+                    let mut named_windows = Vec::new();
+                    for item in items {
+                        match item {
+                            WindowItem::Window(item)
+                                if window.is_none() && window_name.is_none() =>
+                            {
+                                window = Some(item)
+                            }
+                            WindowItem::Name(name) if window.is_none() && window_name.is_none() => {
+                                window_name = Some(name)
+                            }
+                            WindowItem::Definition(definition) => named_windows.push(definition),
+                            _ => return None,
+                        }
+                    }
+                    Some((window, window_name, named_windows))
+                },
+            )),
+        ),
+    )(input)
+}
+
+fn window_time_zone(input: &str) -> ParseResult<Expr> {
+    context(
+        "window_time_zone",
+        preceded(
+            tuple((keyword("at"), sp, keyword("time"), sp, keyword("zone"), sp)),
+            cut(expr),
+        ),
+    )(input)
+}
+
+fn emit(input: &str) -> ParseResult<Emit> {
+    let every = map(
+        tuple((tag_no_case("every"), sp, cut(duration))),
+        |(_, _, interval)| Emit::Every { interval },
+    );
+    let on_update = value(
+        Emit::OnUpdate,
+        tuple((tag_no_case("on"), sp, tag_no_case("update"))),
+    );
+    context(
+        "emit",
         map(
             tuple((
-                tag_no_case("window"),
+                keyword("emit"),
                 sp,
-                alt((fixed_window, sliding_window, period_window)),
+                cut(alt((
+                    value(Emit::Final, keyword("final")),
+                    every,
+                    on_update,
+                ))),
             )),
-            |(_, _, window)| window,
+            |(_, _, emit)| emit,
         ),
     )(input)
 }
 
-pub fn select(input: &str) -> IResult<&str, Select> {
+fn allow_lateness(input: &str) -> ParseResult<i64> {
+    context(
+        "allow_lateness",
+        map(
+            tuple((
+                tag_no_case("allow"),
+                sp,
+                tag_no_case("lateness"),
+                sp,
+                cut(duration),
+            )),
+            |(_, _, _, _, lateness)| lateness,
+        ),
+    )(input)
+}
+
+pub fn select(input: &str) -> ParseResult<Select> {
     let projection = separated_list1(char(','), delimited(sp, projection_field, sp));
-    let where_clause = map(tuple((tag_no_case("where"), sp, expr)), |(_, _, expr)| expr);
-    let having_clause = map(tuple((tag_no_case("having"), sp, expr)), |(_, _, expr)| {
-        expr
-    });
+    let where_clause = map(
+        tuple((tag_no_case("where"), sp, cut(expr))),
+        |(_, _, expr)| expr,
+    );
+    let having_clause = map(
+        tuple((tag_no_case("having"), sp, cut(expr))),
+        |(_, _, expr)| expr,
+    );
 
     context(
         "select",
-        map(
+        nested(map(
             tuple((
                 tag_no_case("select"),
                 delimited(sp, projection, sp),
@@ -413,20 +979,57 @@ pub fn select(input: &str) -> IResult<&str, Select> {
                 opt(delimited(sp, where_clause, sp)),
                 opt(delimited(sp, group_by, sp)),
                 opt(delimited(sp, having_clause, sp)),
-                opt(delimited(sp, window, sp)),
+                opt(delimited(sp, window_clause, sp)),
+                opt(delimited(sp, window_time_zone, sp)),
+                opt(delimited(sp, allow_lateness, sp)),
+                opt(delimited(sp, emit, sp)),
             )),
-            |(_, projection, _, source, where_clause, group_by, having_clause, window)| Select {
+            |(
+                _,
                 projection,
+                _,
                 source,
                 where_clause,
+                group_by,
                 having_clause,
-                group_clause: group_by,
-                window,
+                window_clause,
+                window_time_zone,
+                allowed_lateness,
+                emit,
+            )| {
+                let (window, window_name, named_windows) = window_clause.unwrap_or_default();
+                Select {
+                    projection,
+                    source,
+                    where_clause,
+                    having_clause,
+                    group_clause: group_by,
+                    window,
+                    window_name,
+                    named_windows,
+                    window_time_zone,
+                    allowed_lateness,
+                    emit,
+                }
             },
-        ),
+        )),
     )(input)
 }
 
+/// Parses a complete `SELECT` statement.
+pub fn parse_select(sql: &str) -> Result<Select, ParseError> {
+    parse_select_with_config(sql, ParserConfig::default())
+}
+
+/// Parses a complete `SELECT` statement with `config`.
+pub fn parse_select_with_config(sql: &str, config: ParserConfig) -> Result<Select, ParseError> {
+    with_config(config, || {
+        delimited(sp, select, pair(sp, eof))(sql)
+            .map(|(_, select)| select)
+            .map_err(|err| ParseError::new(sql, err))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -477,6 +1080,13 @@ mod tests {
         assert_eq!(string(r#""abc""#), Ok(("", "abc".to_string())));
         assert_eq!(string(r#"'abc'"#), Ok(("", "abc".to_string())));
         assert_eq!(string(r#"'\nab\rc'"#), Ok(("", "\nab\rc".to_string())));
+        assert_eq!(
+            string(r#"'caf\u00e9 \u{1F600}\u{41}'"#),
+            Ok(("", "café 😀A".to_string()))
+        );
+        assert!(string(r#"'\u00'"#).is_err());
+        assert!(string(r#"'\u{110000}'"#).is_err());
+        assert!(string(r#"'\uD800'"#).is_err());
     }
 
     #[test]
@@ -489,6 +1099,28 @@ mod tests {
             literal(r#""abc""#),
             Ok(("", Literal::String("abc".to_string())))
         );
+        assert_eq!(
+            literal(r#"timestamp '2023-01-01 00:00:00'"#),
+            Ok(("", Literal::Timestamp(1672531200000)))
+        );
+        assert_eq!(
+            literal(r#"TIMESTAMP '2023-01-01 00:00:01.5'"#),
+            Ok(("", Literal::Timestamp(1672531201500)))
+        );
+        assert_eq!(
+            literal(r#"timestamp '2023-01-01T08:00:00+08:00'"#),
+            Ok(("", Literal::Timestamp(1672531200000)))
+        );
+        assert_eq!(
+            literal(r#"DATE '2023-01-01'"#),
+            Ok(("", Literal::Date(19358)))
+        );
+        assert!(literal(r#"date '2023-13-01'"#).is_err());
+        assert_eq!(
+            literal(r#"time '12:30:00.5'"#),
+            Ok(("", Literal::Time(45_000_500)))
+        );
+        assert!(literal(r#"time '25:00'"#).is_err());
     }
 
     #[test]
@@ -498,147 +1130,351 @@ mod tests {
     }
 
     #[test]
-    fn test_column() {
+    fn test_wildcard_exclude() {
+        use crate::dsl::{col, wildcard, wildcard_exclude};
+
         assert_eq!(
-            column(r#""abc".a"#),
+            projection_field("* exclude (a, \"b c\")"),
+            Ok(("", wildcard_exclude(vec!["a", "b c"])))
+        );
+        assert_eq!(
+            projection_field("t.* EXCLUDE a"),
             Ok((
                 "",
-                Expr::Column {
-                    qualifier: Some("abc".to_string()),
-                    name: "a".to_string()
+                Expr::Wildcard {
+                    qualifier: Some("t".to_string()),
+                    exclude: vec!["a".to_string()],
                 }
             ))
         );
+        assert_eq!(projection_field("*"), Ok(("", wildcard())));
+        assert_eq!(projection_field("a"), Ok(("", col("a"))));
+        assert!(parse_select("select * exclude () from t").is_err());
+        assert!(parse_select("select a exclude (b) from t").is_err());
+    }
+
+    #[test]
+    fn test_column() {
+        use crate::dsl::{col, qualified_col};
+
+        assert_eq!(column(r#""abc".a"#), Ok(("", qualified_col("abc", "a"))));
 
         assert_eq!(
             column(r#"abc.'123'"#),
+            Ok(("", qualified_col("abc", "123")))
+        );
+
+        assert_eq!(column(r#"abc"#), Ok(("", col("abc"))));
+
+        assert_eq!(column(r#"'123'"#), Ok(("", col("123"))));
+    }
+
+    #[test]
+    fn test_expr() {
+        use crate::dsl::col;
+
+        assert_eq!(
+            expr(r#"2000+4/2"#),
             Ok((
                 "",
-                Expr::Column {
-                    qualifier: Some("abc".to_string()),
-                    name: "123".to_string()
-                }
+                Expr::Literal(Literal::Int(2000))
+                    + Expr::Literal(Literal::Int(4)) / Expr::Literal(Literal::Int(2))
             ))
         );
 
         assert_eq!(
-            column(r#"abc"#),
+            expr(r#"(2000+4)/2"#),
             Ok((
                 "",
-                Expr::Column {
-                    qualifier: None,
-                    name: "abc".to_string()
-                }
+                (Expr::Literal(Literal::Int(2000)) + Expr::Literal(Literal::Int(4)))
+                    / Expr::Literal(Literal::Int(2))
             ))
         );
 
         assert_eq!(
-            column(r#"'123'"#),
+            expr(r#"a || 1+2 = "x""#),
             Ok((
                 "",
-                Expr::Column {
-                    qualifier: None,
-                    name: "123".to_string()
+                Expr::Binary {
+                    op: BinaryOperator::Concat,
+                    lhs: Box::new(col("a")),
+                    rhs: Box::new(Expr::Literal(Literal::Int(1)) + Expr::Literal(Literal::Int(2))),
                 }
+                .eq(Expr::Literal(Literal::String("x".to_string())))
+            ))
+        );
+
+        assert_eq!(
+            expr(r#"10%3*2"#),
+            Ok((
+                "",
+                Expr::Literal(Literal::Int(10)) % Expr::Literal(Literal::Int(3))
+                    * Expr::Literal(Literal::Int(2))
             ))
         );
     }
 
     #[test]
-    fn test_expr() {
+    fn test_expr_placeholder() {
+        use crate::dsl::col;
+
+        assert_eq!(expr("$1"), Ok(("", Expr::Placeholder(Some(1)))));
+        assert_eq!(expr("?"), Ok(("", Expr::Placeholder(None))));
         assert_eq!(
-            expr(r#"2000+4/2"#),
+            expr("a > $12"),
+            Ok(("", col("a").gt(Expr::Placeholder(Some(12)))))
+        );
+    }
+
+    #[test]
+    fn test_aggregate_filter() {
+        use crate::dsl::{call, col, value};
+
+        assert_eq!(
+            expr("sum(a) filter (where b > 0) + 1"),
             Ok((
                 "",
-                Expr::Literal(Literal::Int(2000))
-                    + Expr::Literal(Literal::Int(4)) / Expr::Literal(Literal::Int(2))
+                Expr::AggregateFilter {
+                    expr: Box::new(call("sum", vec![col("a")])),
+                    condition: Box::new(col("b").gt(value(Literal::Int(0)))),
+                } + value(Literal::Int(1))
             ))
         );
+        assert!(parse_select("select sum(a) filter (where b > 0 from t").is_err());
+    }
+
+    #[test]
+    fn test_over() {
+        use crate::dsl::{call, col, value};
 
         assert_eq!(
-            expr(r#"(2000+4)/2"#),
+            expr("row_number() over (partition by k order by ts)"),
             Ok((
                 "",
-                (Expr::Literal(Literal::Int(2000)) + Expr::Literal(Literal::Int(4)))
-                    / Expr::Literal(Literal::Int(2))
+                call("row_number", vec![]).over(
+                    vec![col("k")],
+                    vec![OrderBy {
+                        expr: col("ts"),
+                        descending: false
+                    }]
+                )
+            ))
+        );
+        assert_eq!(
+            expr("lag(a, 2) over (order by ts desc, b asc) + 1"),
+            Ok((
+                "",
+                call("lag", vec![col("a"), value(Literal::Int(2))]).over(
+                    vec![],
+                    vec![
+                        OrderBy {
+                            expr: col("ts"),
+                            descending: true
+                        },
+                        OrderBy {
+                            expr: col("b"),
+                            descending: false
+                        }
+                    ]
+                ) + value(Literal::Int(1))
+            ))
+        );
+        assert_eq!(
+            expr("rank() over ()"),
+            Ok(("", call("rank", vec![]).over(vec![], vec![])))
+        );
+        assert!(parse_select("select rank() over (order by) from t").is_err());
+        assert_eq!(
+            expr("rank() over w"),
+            Ok((
+                "",
+                Expr::OverWindow {
+                    expr: Box::new(call("rank", vec![])),
+                    window: "w".to_string(),
+                }
             ))
         );
     }
 
     #[test]
-    fn test_expr_call() {
+    fn test_array() {
+        use crate::dsl::{col, value};
+
         assert_eq!(
-            expr_call(r#"sum(a)"#),
+            expr("[1, a, [2]]"),
             Ok((
                 "",
-                Expr::Call {
-                    namespace: None,
-                    name: "sum".to_string(),
-                    args: vec![Expr::Column {
-                        qualifier: None,
-                        name: "a".to_string()
-                    }]
+                Expr::Array(vec![
+                    value(Literal::Int(1)),
+                    col("a"),
+                    Expr::Array(vec![value(Literal::Int(2))])
+                ])
+            ))
+        );
+        assert_eq!(expr("[ ]"), Ok(("", Expr::Array(vec![]))));
+        assert_eq!(
+            expr("a[1][b + 1] * 2"),
+            Ok((
+                "",
+                Expr::Index {
+                    expr: Box::new(Expr::Index {
+                        expr: Box::new(col("a")),
+                        index: Box::new(value(Literal::Int(1))),
+                    }),
+                    index: Box::new(col("b") + value(Literal::Int(1))),
+                } * value(Literal::Int(2))
+            ))
+        );
+        assert_eq!(
+            expr("[1, 2][2]"),
+            Ok((
+                "",
+                Expr::Index {
+                    expr: Box::new(Expr::Array(vec![
+                        value(Literal::Int(1)),
+                        value(Literal::Int(2))
+                    ])),
+                    index: Box::new(value(Literal::Int(2))),
                 }
             ))
         );
+        assert!(parse_select("select a[1 from t").is_err());
+    }
+
+    #[test]
+    fn test_field() {
+        use crate::dsl::{col, qualified_col, value};
 
         assert_eq!(
-            expr_call(r#"c(a, 1, b, 2)"#),
+            expr("a.b.c"),
             Ok((
                 "",
-                Expr::Call {
-                    namespace: None,
+                Expr::Field {
+                    expr: Box::new(qualified_col("a", "b")),
                     name: "c".to_string(),
-                    args: vec![
-                        Expr::Column {
-                            qualifier: None,
-                            name: "a".to_string()
-                        },
+                }
+            ))
+        );
+        assert_eq!(
+            expr("a[1].b.c + 1"),
+            Ok((
+                "",
+                Expr::Field {
+                    expr: Box::new(Expr::Field {
+                        expr: Box::new(Expr::Index {
+                            expr: Box::new(col("a")),
+                            index: Box::new(value(Literal::Int(1))),
+                        }),
+                        name: "b".to_string(),
+                    }),
+                    name: "c".to_string(),
+                } + value(Literal::Int(1))
+            ))
+        );
+    }
+
+    #[test]
+    fn test_expr_call() {
+        use crate::dsl::{call, call_with_namespace, col};
+
+        assert_eq!(
+            expr_call(r#"sum(a)"#),
+            Ok(("", call("sum", vec![col("a")])))
+        );
+
+        assert_eq!(
+            expr_call(r#"c(a, 1, b, 2)"#),
+            Ok((
+                "",
+                call(
+                    "c",
+                    vec![
+                        col("a"),
                         Expr::Literal(Literal::Int(1)),
-                        Expr::Column {
-                            qualifier: None,
-                            name: "b".to_string()
-                        },
+                        col("b"),
                         Expr::Literal(Literal::Int(2)),
                     ]
-                }
+                )
             ))
         );
 
         assert_eq!(
             expr_call(r#"abc.sum(a)"#),
+            Ok(("", call_with_namespace("abc", "sum", vec![col("a")])))
+        );
+    }
+
+    #[test]
+    fn test_source() {
+        assert_eq!(
+            source(r#"abc"#),
             Ok((
                 "",
-                Expr::Call {
-                    namespace: Some("abc".to_string()),
-                    name: "sum".to_string(),
-                    args: vec![Expr::Column {
-                        qualifier: None,
-                        name: "a".to_string()
-                    }]
+                Source {
+                    from: SourceFrom::Named("abc".to_string()),
+                    alias: None,
+                    watermark: None,
                 }
             ))
         );
     }
 
     #[test]
-    fn test_source() {
+    fn test_source_watermark() {
+        use crate::dsl::col;
+
         assert_eq!(
-            source(r#"abc"#),
+            source(r#"abc as a watermark ts delayed by 30s"#),
             Ok((
                 "",
                 Source {
                     from: SourceFrom::Named("abc".to_string()),
-                    alias: None
+                    alias: Some("a".to_string()),
+                    watermark: Some(Watermark {
+                        time_expr: col("ts"),
+                        delay: 30000,
+                    }),
                 }
             ))
         );
+
+        let (_, stmt) = select(r#"select a from abc watermark ts where a > 1"#).unwrap();
+        assert_eq!(
+            stmt.source.watermark,
+            Some(Watermark {
+                time_expr: col("ts"),
+                delay: 0,
+            })
+        );
+        assert!(stmt.where_clause.is_some());
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration("500ms"), Ok(("", 500)));
+        assert_eq!(duration("30s"), Ok(("", 30 * 1000)));
+        assert_eq!(duration("5M"), Ok(("", 5 * 60 * 1000)));
+        assert_eq!(duration("2h"), Ok(("", 2 * 60 * 60 * 1000)));
+        assert_eq!(duration("7d)"), Ok((")", 7 * 24 * 60 * 60 * 1000)));
+        assert!(duration("5").is_err());
+        assert!(duration("5min").is_err());
+        assert!(duration("1hour").is_err());
+        assert!(duration("9223372036854775807d").is_err());
+        assert!(duration("99999999999999999999s").is_err());
+        assert!(duration("-1h").is_err());
+        assert_eq!(duration("0s"), Ok(("", 0)));
+        assert!(positive_duration("0s").is_err());
+        assert_eq!(positive_duration("1ms"), Ok(("", 1)));
     }
 
     #[test]
     fn test_window() {
+        assert!(window_spec("fixed(0s)").is_err());
+        assert!(window_spec("sliding(1m, 0s)").is_err());
+        assert!(window_spec("sliding(1m, -1s)").is_err());
+        assert!(window_spec("session(0ms)").is_err());
         assert_eq!(
-            window(r#"window fixed(5m)"#),
+            window_spec(r#"fixed(5m)"#),
             Ok((
                 "",
                 Window::Fixed {
@@ -648,18 +1484,36 @@ mod tests {
         );
 
         assert_eq!(
-            window(r#"window sliding(5m, 1m)"#),
+            window_spec(r#"sliding(5m, 1m)"#),
+            Ok((
+                "",
+                Window::Sliding {
+                    length: 1000 * 5 * 60,
+                    interval: 1000 * 60,
+                    offset: 0,
+                },
+            ))
+        );
+
+        assert_eq!(
+            window_spec(r#"sliding(5m, 1m, 30s)"#),
             Ok((
                 "",
                 Window::Sliding {
                     length: 1000 * 5 * 60,
                     interval: 1000 * 60,
+                    offset: 1000 * 30,
                 },
             ))
         );
 
         assert_eq!(
-            window(r#"window day"#),
+            window_spec(r#"session(30s)"#),
+            Ok(("", Window::Session { gap: 1000 * 30 },))
+        );
+
+        assert_eq!(
+            window_spec(r#"day"#),
             Ok((
                 "",
                 Window::Period {
@@ -669,7 +1523,7 @@ mod tests {
         );
 
         assert_eq!(
-            window(r#"window week"#),
+            window_spec(r#"week"#),
             Ok((
                 "",
                 Window::Period {
@@ -679,7 +1533,7 @@ mod tests {
         );
 
         assert_eq!(
-            window(r#"window month"#),
+            window_spec(r#"month"#),
             Ok((
                 "",
                 Window::Period {
@@ -689,7 +1543,7 @@ mod tests {
         );
 
         assert_eq!(
-            window(r#"window year"#),
+            window_spec(r#"year"#),
             Ok((
                 "",
                 Window::Period {
@@ -701,44 +1555,33 @@ mod tests {
 
     #[test]
     fn test_select() {
+        use crate::dsl::{call, col};
+
         assert_eq!(
             select(r#"select a, b, a+b, sum(a) from t"#),
             Ok((
                 "",
                 Select {
                     projection: vec![
-                        Expr::Column {
-                            qualifier: None,
-                            name: "a".to_string()
-                        },
-                        Expr::Column {
-                            qualifier: None,
-                            name: "b".to_string()
-                        },
-                        Expr::Column {
-                            qualifier: None,
-                            name: "a".to_string()
-                        } + Expr::Column {
-                            qualifier: None,
-                            name: "b".to_string()
-                        },
-                        Expr::Call {
-                            namespace: None,
-                            name: "sum".to_string(),
-                            args: vec![Expr::Column {
-                                qualifier: None,
-                                name: "a".to_string()
-                            }]
-                        }
+                        col("a"),
+                        col("b"),
+                        col("a") + col("b"),
+                        call("sum", vec![col("a")])
                     ],
                     source: Source {
                         from: SourceFrom::Named("t".to_string()),
-                        alias: None
+                        alias: None,
+                        watermark: None,
                     },
                     where_clause: None,
                     having_clause: None,
                     group_clause: None,
                     window: None,
+                    window_name: None,
+                    named_windows: vec![],
+                    window_time_zone: None,
+                    allowed_lateness: None,
+                    emit: None,
                 },
             )),
         );
@@ -748,30 +1591,21 @@ mod tests {
             Ok((
                 "",
                 Select {
-                    projection: vec![
-                        Expr::Column {
-                            qualifier: None,
-                            name: "a".to_string()
-                        },
-                        Expr::Column {
-                            qualifier: None,
-                            name: "b".to_string()
-                        },
-                    ],
+                    projection: vec![col("a"), col("b"),],
                     source: Source {
                         from: SourceFrom::Named("t".to_string()),
-                        alias: None
+                        alias: None,
+                        watermark: None,
                     },
-                    where_clause: Some(
-                        Expr::Column {
-                            qualifier: None,
-                            name: "a".to_string()
-                        }
-                        .gt(Expr::Literal(Literal::Int(10)))
-                    ),
+                    where_clause: Some(col("a").gt(Expr::Literal(Literal::Int(10)))),
                     having_clause: None,
                     group_clause: None,
-                    window: None
+                    window: None,
+                    window_name: None,
+                    named_windows: vec![],
+                    window_time_zone: None,
+                    allowed_lateness: None,
+                    emit: None,
                 },
             )),
         );
@@ -781,39 +1615,209 @@ mod tests {
             Ok((
                 "",
                 Select {
-                    projection: vec![
-                        Expr::Column {
-                            qualifier: None,
-                            name: "a".to_string()
-                        },
-                        Expr::Column {
-                            qualifier: None,
-                            name: "b".to_string()
-                        },
-                    ],
+                    projection: vec![col("a"), col("b"),],
                     source: Source {
                         from: SourceFrom::Named("t".to_string()),
-                        alias: None
+                        alias: None,
+                        watermark: None,
                     },
-                    where_clause: Some(
-                        Expr::Column {
-                            qualifier: None,
-                            name: "a".to_string()
-                        }
-                        .gt(Expr::Literal(Literal::Int(10)))
-                    ),
+                    where_clause: Some(col("a").gt(Expr::Literal(Literal::Int(10)))),
                     having_clause: None,
                     group_clause: Some(GroupBy {
-                        exprs: vec![Expr::Column {
-                            qualifier: None,
-                            name: "b".to_string()
-                        }]
+                        exprs: vec![col("b")]
                     }),
                     window: Some(Window::Fixed {
                         length: 5 * 1000 * 60
-                    })
+                    }),
+                    window_name: None,
+                    named_windows: vec![],
+                    window_time_zone: None,
+                    allowed_lateness: None,
+                    emit: None,
                 },
             )),
         );
     }
+
+    #[test]
+    fn test_named_window() {
+        use crate::dsl::col;
+
+        let (_, stmt) = select(
+            "select lag(a) over o from t window w, w as fixed(5m), o as (partition by k order by ts)",
+        )
+        .unwrap();
+        assert_eq!(stmt.window, None);
+        assert_eq!(stmt.window_name.as_deref(), Some("w"));
+        assert_eq!(
+            stmt.named_windows,
+            vec![
+                NamedWindow {
+                    name: "w".to_string(),
+                    definition: WindowDefinition::Time(Window::Fixed { length: 300000 }),
+                },
+                NamedWindow {
+                    name: "o".to_string(),
+                    definition: WindowDefinition::Over {
+                        partition_by: vec![col("k")],
+                        order_by: vec![OrderBy {
+                            expr: col("ts"),
+                            descending: false,
+                        }],
+                    },
+                },
+            ]
+        );
+
+        let (_, stmt) = select("select a from t window daily as day").unwrap();
+        assert_eq!(stmt.window, None);
+        assert_eq!(stmt.named_windows[0].name, "daily");
+
+        assert!(parse_select("select a from t window fixed(5m), w").is_err());
+        assert!(parse_select("select a from t window w as").is_err());
+    }
+
+    #[test]
+    fn test_window_time_zone() {
+        use crate::dsl::col;
+
+        let (_, stmt) =
+            select("select count(*) from t group by user window day at time zone tz").unwrap();
+        assert_eq!(
+            stmt.window,
+            Some(Window::Period {
+                period: Period::Day
+            })
+        );
+        assert_eq!(stmt.window_time_zone, Some(col("tz")));
+
+        assert!(parse_select("select a from t window day at time zone").is_err());
+    }
+
+    #[test]
+    fn test_allow_lateness() {
+        let (_, stmt) = select("select a from t window fixed(5m) allow lateness 1m").unwrap();
+        assert_eq!(stmt.window, Some(Window::Fixed { length: 300000 }));
+        assert_eq!(stmt.allowed_lateness, Some(60000));
+
+        assert!(parse_select("select a from t window fixed(5m) allow lateness").is_err());
+    }
+
+    #[test]
+    fn test_emit() {
+        assert_eq!(emit("emit final"), Ok(("", Emit::Final)));
+        assert_eq!(
+            emit("emit every 10s"),
+            Ok(("", Emit::Every { interval: 10000 }))
+        );
+        assert_eq!(emit("EMIT ON UPDATE"), Ok(("", Emit::OnUpdate)));
+        assert!(emit("emit never").is_err());
+
+        let (_, stmt) =
+            select("select a from t window fixed(5m) allow lateness 1m emit on update").unwrap();
+        assert_eq!(stmt.emit, Some(Emit::OnUpdate));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_select("select a, b\nfrom t where (a > 1").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 14);
+        assert_eq!(err.token.as_deref(), Some("(a"));
+
+        let err = parse_select("select a from t abc").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 17));
+        assert_eq!(err.token.as_deref(), Some("abc"));
+        assert_eq!(
+            err.to_string(),
+            "syntax error at line 1, column 17: unexpected 'abc'"
+        );
+
+        let err = parse_select("select a from").unwrap_err();
+        assert_eq!(err.token, None);
+    }
+
+    #[test]
+    fn test_expr_precedence() {
+        use crate::dsl::{col, value};
+
+        let cases = vec![
+            ("not a = b and c", (!col("a").eq(col("b"))).and(col("c"))),
+            ("a or b and c", col("a").or(col("b").and(col("c")))),
+            ("a and b or c", col("a").and(col("b")).or(col("c"))),
+            (
+                "a = 1 or not b",
+                col("a").eq(value(Literal::Int(1))).or(!col("b")),
+            ),
+            ("not not a", !!col("a")),
+            ("not (a or b)", !(col("a").or(col("b")))),
+            ("-a * b", -col("a") * col("b")),
+            ("a + b * c - d", col("a") + col("b") * col("c") - col("d")),
+            (
+                "a || b = c",
+                Expr::Binary {
+                    op: BinaryOperator::Concat,
+                    lhs: Box::new(col("a")),
+                    rhs: Box::new(col("b")),
+                }
+                .eq(col("c")),
+            ),
+            (
+                "a <= 1 and b >= 2",
+                col("a")
+                    .lt_eq(value(Literal::Int(1)))
+                    .and(col("b").gt_eq(value(Literal::Int(2)))),
+            ),
+            (
+                "a < 1 or b > 2",
+                col("a")
+                    .lt(value(Literal::Int(1)))
+                    .or(col("b").gt(value(Literal::Int(2)))),
+            ),
+            ("a <> b", col("a").not_eq(col("b"))),
+            (
+                "a + 1 < b * 2",
+                (col("a") + value(Literal::Int(1))).lt(col("b") * value(Literal::Int(2))),
+            ),
+            ("nothing or android", col("nothing").or(col("android"))),
+        ];
+
+        for (sql, expected) in cases {
+            assert_eq!(expr(sql), Ok(("", expected)), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let sql = format!("select {}1{} from t", "(".repeat(10), ")".repeat(10));
+        assert!(parse_select(&sql).is_ok());
+
+        let sql = format!("select {}1{} from t", "(".repeat(10000), ")".repeat(10000));
+        let err = parse_select(&sql).unwrap_err();
+        assert_eq!(err.context, Some("max_nesting_depth"));
+
+        for prefix in &["- ", "not "] {
+            let sql = format!("select {}1 from t", prefix.repeat(10000));
+            let err = parse_select(&sql).unwrap_err();
+            assert_eq!(err.context, Some("max_nesting_depth"));
+        }
+
+        let sql = format!(
+            "select a from {}t{}",
+            "(select a from ".repeat(1000),
+            ")".repeat(1000)
+        );
+        let err = parse_select(&sql).unwrap_err();
+        assert_eq!(err.context, Some("max_nesting_depth"));
+
+        assert!(parse_select("select (1) from t").is_ok());
+
+        let sql = format!("select {}1{} from t", "(".repeat(10), ")".repeat(10));
+        let config = ParserConfig::default().with_max_nesting_depth(5);
+        let err = parse_select_with_config(&sql, config).unwrap_err();
+        assert_eq!(err.context, Some("max_nesting_depth"));
+        assert!(parse_select_with_config("select (1) from t", config).is_ok());
+        // The config only applies to its parse.
+        assert!(parse_select(&sql).is_ok());
+    }
 }
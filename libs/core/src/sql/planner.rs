@@ -1,62 +1,361 @@
+use std::convert::TryFrom;
+
 use anyhow::Result;
-use nom::combinator::{eof, map};
-use nom::sequence::tuple;
 
-use crate::sql::ast::{Select, Source, SourceFrom};
-use crate::sql::parser::sp;
-use crate::sql::SqlContext;
+use crate::expr::{Expr, Literal, Span};
+use crate::sql::ast::{NamedWindow, Select, Source, SourceFrom, Watermark, WindowDefinition};
+use crate::sql::parser::parse_select;
+use crate::sql::{SemanticError, SqlContext};
 use crate::DataFrame;
 
+/// Creates the data frame of a query and checks its columns and functions, an unknown name is
+/// reported with its position in `sql`.
 pub fn create_data_frame_with_sql(ctx: &dyn SqlContext, sql: &str) -> Result<DataFrame> {
-    let (_, select) = map(
-        tuple((sp, crate::sql::parser::select, sp, eof)),
-        |(_, select, _, _)| select,
-    )(sql)
-    .map_err(|err| anyhow::anyhow!("{}", err))?;
-    create_data_frame(ctx, select)
+    let df = create_data_frame(ctx, parse_select(sql)?)?;
+    df.validate()
+        .map_err(|err| SemanticError::locate(sql, err))?;
+    Ok(df)
 }
 
 pub fn create_data_frame(ctx: &dyn SqlContext, select: Select) -> Result<DataFrame> {
-    let mut df = create_source(ctx, select.source)?;
+    create_data_frame_with_windows(ctx, select, &[])
+}
+
+/// `outer_windows` are the windows defined by the enclosing selects, the innermost first.
+fn create_data_frame_with_windows(
+    ctx: &dyn SqlContext,
+    select: Select,
+    outer_windows: &[&NamedWindow],
+) -> Result<DataFrame> {
+    for (idx, named_window) in select.named_windows.iter().enumerate() {
+        anyhow::ensure!(
+            select.named_windows[..idx]
+                .iter()
+                .all(|prev| prev.name != named_window.name),
+            "the window '{}' is defined more than once.",
+            named_window.name
+        );
+    }
+    let windows = select
+        .named_windows
+        .iter()
+        .chain(outer_windows.iter().copied())
+        .collect::<Vec<_>>();
+
+    let mut df = create_source(ctx, select.source, &windows)?;
     if let Some(condition) = select.where_clause {
-        df = df.filter(condition);
+        df = df.filter(resolve_over_windows(condition, &windows)?);
     }
 
-    match (select.group_clause, select.window) {
+    let window = match (select.window, &select.window_name) {
+        (Some(window), _) => Some(window),
+        (None, Some(name)) => match find_window(&windows, name)? {
+            WindowDefinition::Time(window) => Some(*window),
+            WindowDefinition::Over { .. } => anyhow::bail!(
+                "the window '{}' cannot be used for aggregations, it is not a time window.",
+                name
+            ),
+        },
+        (None, None) => None,
+    };
+    let projection = select
+        .projection
+        .into_iter()
+        .map(|expr| resolve_over_windows(expr, &windows))
+        .collect::<Result<Vec<_>>>()?;
+    let having_clause = match select.having_clause {
+        Some(condition) => Some(resolve_having(
+            resolve_over_windows(condition, &windows)?,
+            &projection,
+        )?),
+        None => None,
+    };
+
+    match (select.group_clause, window) {
         (Some(group_by), Some(window)) => {
-            df = df.aggregate(group_by.exprs, select.projection, window);
+            let group_exprs = resolve_group_by(group_by.exprs, &projection)?;
+            df = df.aggregate(group_exprs, projection, window);
         }
         (None, Some(window)) => {
-            df = df.aggregate(vec![], select.projection, window);
+            df = df.aggregate(vec![], projection, window);
         }
         (Some(_), None) => {
             anyhow::bail!("the window clause is missing.");
         }
         (None, None) => {
-            df = df.select(select.projection);
+            df = df.select(projection);
         }
     }
 
-    if let Some(condition) = select.having_clause {
+    if let Some(time_zone) = select.window_time_zone {
+        anyhow::ensure!(
+            window.is_some(),
+            "the time zone of the windows can only be used with a window clause."
+        );
+        df = df.window_time_zone(time_zone);
+    }
+    if let Some(lateness) = select.allowed_lateness {
+        df = df.allow_lateness(lateness);
+    }
+    if let Some(emit) = select.emit {
+        df = df.emit(emit);
+    }
+
+    if let Some(condition) = having_clause {
         df = df.filter(condition);
     }
 
     Ok(df)
 }
 
-fn create_source(ctx: &dyn SqlContext, source: Source) -> Result<DataFrame> {
+/// Replaces the positions (`GROUP BY 1`) and the aliases of the select list in the `GROUP BY`
+/// clause with the expressions they refer to.
+///
+/// A name that is both an alias and a column of the source refers to the alias.
+fn resolve_group_by(exprs: Vec<Expr>, projection: &[Expr]) -> Result<Vec<Expr>> {
+    exprs
+        .into_iter()
+        .map(|expr| match expr {
+            Expr::Literal(Literal::Int(position)) => {
+                let idx = usize::try_from(position)
+                    .ok()
+                    .and_then(|position| position.checked_sub(1))
+                    .filter(|idx| *idx < projection.len())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "the GROUP BY position {} is not in the select list.",
+                            position
+                        )
+                    })?;
+                anyhow::ensure!(
+                    projection[..=idx]
+                        .iter()
+                        .all(|expr| !matches!(expr, Expr::Wildcard { .. })),
+                    "the GROUP BY position {} cannot refer to a wildcard.",
+                    position
+                );
+                Ok(match &projection[idx] {
+                    Expr::Alias(expr, _) => (**expr).clone(),
+                    expr => expr.clone(),
+                })
+            }
+            Expr::Column {
+                qualifier: None,
+                name,
+                span,
+            } => Ok(projection
+                .iter()
+                .find_map(|expr| match expr {
+                    Expr::Alias(expr, alias) if *alias == name => Some((**expr).clone()),
+                    _ => None,
+                })
+                .unwrap_or(Expr::Column {
+                    qualifier: None,
+                    name,
+                    span,
+                })),
+            expr => Ok(expr),
+        })
+        .collect()
+}
+
+/// Replaces the expressions of the select list in the `HAVING` clause with the columns they
+/// produce, so that `having count(*) > 5` refers to the column of `count(*) as c`.
+///
+/// A name that is both an alias and a column of the source refers to the alias, and the
+/// aggregate functions that are not in the select list are rejected.
+fn resolve_having(condition: Expr, projection: &[Expr]) -> Result<Expr> {
+    let outputs = projection
+        .iter()
+        .filter(|expr| !matches!(expr, Expr::Wildcard { .. }))
+        .map(|expr| {
+            let name = expr.create_name();
+            match expr {
+                Expr::Alias(expr, _) => ((**expr).clone(), name),
+                expr => (expr.clone(), name),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let condition = replace_outputs(condition, &outputs);
+    anyhow::ensure!(
+        condition.is_stateless(),
+        "the aggregate functions in the HAVING clause must be in the select list: '{}'.",
+        condition
+    );
+    Ok(condition)
+}
+
+/// Replaces the expressions of the select list in `expr` with references to their outputs.
+///
+/// Whole expressions are matched from the top down, so that the columns of a matched expression
+/// are not replaced by the outputs they are selected as.
+fn replace_outputs(expr: Expr, outputs: &[(Expr, String)]) -> Expr {
+    if let Expr::Column {
+        qualifier: None,
+        name,
+        ..
+    } = &expr
+    {
+        if outputs.iter().any(|(_, output)| output == name) {
+            return expr;
+        }
+    }
+    if let Some((_, name)) = outputs.iter().find(|(output_expr, _)| *output_expr == expr) {
+        return Expr::Column {
+            qualifier: None,
+            name: name.clone(),
+            span: Span::default(),
+        };
+    }
+    expr.map_children(&mut |expr| Ok(replace_outputs(expr, outputs)))
+        .expect("infallible")
+}
+
+fn find_window<'a>(windows: &[&'a NamedWindow], name: &str) -> Result<&'a WindowDefinition> {
+    windows
+        .iter()
+        .find(|named_window| named_window.name == name)
+        .map(|named_window| &named_window.definition)
+        .ok_or_else(|| anyhow::anyhow!("the window '{}' is not defined.", name))
+}
+
+/// Replaces the references to named windows in `OVER` clauses with their definitions.
+fn resolve_over_windows(expr: Expr, windows: &[&NamedWindow]) -> Result<Expr> {
+    expr.transform(&mut |expr| match expr {
+        Expr::OverWindow { expr, window } => match find_window(windows, &window)? {
+            WindowDefinition::Over {
+                partition_by,
+                order_by,
+            } => Ok((*expr).over(partition_by.clone(), order_by.clone())),
+            WindowDefinition::Time(_) => anyhow::bail!(
+                "the window '{}' cannot be used in an OVER clause, it is a time window.",
+                window
+            ),
+        },
+        expr => Ok(expr),
+    })
+}
+
+fn create_source(
+    ctx: &dyn SqlContext,
+    source: Source,
+    windows: &[&NamedWindow],
+) -> Result<DataFrame> {
     match source.from {
-        SourceFrom::Named(name) => {
-            let provider = ctx
-                .create_source_provider(&name)?
-                .ok_or_else(|| anyhow::anyhow!("source '{}' not found.", name))?;
-            Ok(DataFrame::new(
-                provider.source_provider,
-                source.alias,
-                provider.time_expr,
-                provider.watermark_expr,
-            ))
+        SourceFrom::Named(name) => create_named_source(ctx, name, source.alias, source.watermark),
+        SourceFrom::SubQuery(select) => {
+            anyhow::ensure!(
+                source.watermark.is_none(),
+                "the watermark clause can only be used with a named source."
+            );
+            create_data_frame_with_windows(ctx, *select, windows)
+        }
+    }
+}
+
+/// Creates the data frame of the source `name`, the watermark overrides the time column of the
+/// source.
+pub(crate) fn create_named_source(
+    ctx: &dyn SqlContext,
+    name: String,
+    alias: Option<String>,
+    watermark: Option<Watermark>,
+) -> Result<DataFrame> {
+    let provider = ctx
+        .create_source_provider(&name)?
+        .ok_or_else(|| anyhow::anyhow!("source '{}' not found.", name))?;
+    let (time_expr, watermark_expr) = match watermark {
+        Some(Watermark { time_expr, delay }) if delay != 0 => {
+            let watermark_expr = time_expr.clone() - Expr::Literal(Literal::Int(delay));
+            (Some(time_expr), Some(watermark_expr))
         }
-        SourceFrom::SubQuery(select) => create_data_frame(ctx, *select),
+        Some(Watermark { time_expr, .. }) => (Some(time_expr), None),
+        None => (provider.time_expr, provider.watermark_expr),
+    };
+    Ok(
+        DataFrame::new(provider.source_provider, alias, time_expr, watermark_expr)
+            .with_source_name(name),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsl::{call, col, value, wildcard};
+
+    #[test]
+    fn test_resolve_group_by() {
+        let projection = vec![
+            col("a"),
+            (col("b") + col("c")).alias("d"),
+            call("count", vec![col("a")]),
+        ];
+        assert_eq!(
+            resolve_group_by(
+                vec![value(Literal::Int(1)), value(Literal::Int(2)), col("d")],
+                &projection
+            )
+            .unwrap(),
+            vec![col("a"), col("b") + col("c"), col("b") + col("c")]
+        );
+        assert_eq!(
+            resolve_group_by(vec![col("e")], &projection).unwrap(),
+            vec![col("e")]
+        );
+        assert!(resolve_group_by(vec![value(Literal::Int(0))], &projection).is_err());
+        assert!(resolve_group_by(vec![value(Literal::Int(4))], &projection).is_err());
+        assert!(resolve_group_by(vec![value(Literal::Int(2))], &[wildcard(), col("a")]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_having() {
+        let count = call("count", vec![wildcard()]);
+        let projection = vec![
+            col("user").alias("u"),
+            count.clone().alias("c"),
+            call("sum", vec![col("v")]),
+            col("a").alias("user"),
+        ];
+        let int = |n| value(Literal::Int(n));
+
+        assert_eq!(
+            resolve_having(col("c").gt(int(5)), &projection).unwrap(),
+            col("c").gt(int(5))
+        );
+        assert_eq!(
+            resolve_having(count.gt(int(5)), &projection).unwrap(),
+            col("c").gt(int(5))
+        );
+        assert_eq!(
+            resolve_having(call("sum", vec![col("v")]).gt(int(1)), &projection).unwrap(),
+            col("sum(v)").gt(int(1))
+        );
+        assert_eq!(
+            resolve_having(col("a").gt(int(1)), &projection).unwrap(),
+            col("user").gt(int(1))
+        );
+        assert_eq!(
+            resolve_having(col("user").gt(int(1)), &projection).unwrap(),
+            col("user").gt(int(1))
+        );
+        assert_eq!(
+            resolve_having(call("max", vec![col("v")]).gt(int(1)), &projection)
+                .unwrap_err()
+                .to_string(),
+            "the aggregate functions in the HAVING clause must be in the select list: '(max(v) > 1)'."
+        );
+
+        // The argument of a selected aggregate isn't replaced by the output of its column.
+        let count_user = call("count", vec![col("user_id")]);
+        let projection = vec![col("user_id").alias("uid"), count_user.clone().alias("n")];
+        assert_eq!(
+            resolve_having(count_user.gt(int(5)), &projection).unwrap(),
+            col("n").gt(int(5))
+        );
+        assert_eq!(
+            resolve_having(col("user_id").gt(int(5)), &projection).unwrap(),
+            col("uid").gt(int(5))
+        );
     }
 }
@@ -0,0 +1,102 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::expr::UnknownNameError;
+use crate::sql::parse_error::position;
+
+/// An error of the semantic analysis of a query, with the position of the unknown name it
+/// refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number, in characters.
+    pub column: usize,
+    pub error: UnknownNameError,
+}
+
+impl SemanticError {
+    /// Adds the position in `sql`, the text the query was parsed from, to an error about an
+    /// unknown column or function.
+    ///
+    /// Any other error, or a name that was not parsed from `sql`, is returned as it is.
+    pub fn locate(sql: &str, err: anyhow::Error) -> anyhow::Error {
+        let error = match err
+            .chain()
+            .find_map(|err| err.downcast_ref::<UnknownNameError>())
+        {
+            Some(error) => error.clone(),
+            None => return err,
+        };
+        match error.span.offset(sql) {
+            Some(offset) => {
+                let (line, column) = position(sql, offset);
+                SemanticError {
+                    line,
+                    column,
+                    error,
+                }
+                .into()
+            }
+            None => err,
+        }
+    }
+}
+
+impl Display for SemanticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "semantic error at line {}, column {}: {}",
+            self.line, self.column, self.error
+        )
+    }
+}
+
+impl std::error::Error for SemanticError {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{Field, Schema};
+    use crate::expr::{NameKind, Span};
+    use crate::sql::parser::expr;
+
+    fn locate(sql: &str) -> String {
+        let (_, expr) = expr(sql).unwrap();
+        let schema = Schema::try_new(vec![Field::new("name", DataType::String)]).unwrap();
+        let err = expr.into_physical(Arc::new(schema)).err().unwrap();
+        SemanticError::locate(sql, err).to_string()
+    }
+
+    #[test]
+    fn test_locate() {
+        assert_eq!(
+            locate("coalesce('nmae', name,\n  nmae)"),
+            "semantic error at line 2, column 3: no such column: 'nmae', did you mean 'name'?"
+        );
+        assert_eq!(
+            locate("name || t.nmae"),
+            "semantic error at line 1, column 9: no such column: 't.nmae'"
+        );
+        assert_eq!(
+            locate("name || xyzzy (name)"),
+            "semantic error at line 1, column 9: no such function: 'xyzzy'"
+        );
+
+        let err = UnknownNameError {
+            kind: NameKind::Column,
+            qualifier: None,
+            name: "b".to_string(),
+            span: Span::default(),
+            suggestion: None,
+        };
+        let err = SemanticError::locate("select b from t", err.into());
+        assert_eq!(err.to_string(), "no such column: 'b'");
+
+        let err = SemanticError::locate("select b from t", anyhow::anyhow!("other"));
+        assert_eq!(err.to_string(), "other");
+    }
+}
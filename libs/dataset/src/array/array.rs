@@ -2,15 +2,25 @@ use std::any::Any;
 use std::fmt::Debug;
 use std::sync::Arc;
 
-use serde::{Serialize, Serializer};
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::array::list_array::ListArraySeed;
+use crate::array::map_array::MapArraySeed;
+use crate::array::sealed::Sealed;
+use crate::array::struct_array::StructArraySeed;
 use crate::array::{
-    ArrayExt, BooleanType, DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
-    Int8Type, NullArray, PrimitiveArray, StringArray, TimestampType,
+    ArrayExt, BooleanArray, BooleanType, DataType, DateArray, DateType, Float32Array, Float32Type,
+    Float64Array, Float64Type, Int16Array, Int16Type, Int32Array, Int32Type, Int64Array, Int64Type,
+    Int8Array, Int8Type, ListArray, MapArray, NullArray, PrimitiveArray, StringArray, StructArray,
+    TimeArray, TimeType, TimestampArray, TimestampType, UInt16Array, UInt16Type, UInt32Array,
+    UInt32Type, UInt64Array, UInt64Type, UInt8Array, UInt8Type,
 };
 
 /// Trait for dealing with different types of array at runtime when the type of the array is not known in advance.
-pub trait Array: Debug + Send + Sync {
+///
+/// The trait is sealed, the arrays are limited to the types of this crate.
+pub trait Array: Debug + Send + Sync + Sealed {
     /// Returns the array as Any so that it can be downcasted to a specific implementation.
     fn as_any(&self) -> &dyn Any;
 
@@ -88,14 +98,32 @@ impl PartialEq for dyn Array {
             DataType::Int16 => eq_primitive_array!(Int16Type, self, other),
             DataType::Int32 => eq_primitive_array!(Int32Type, self, other),
             DataType::Int64 => eq_primitive_array!(Int64Type, self, other),
+            DataType::UInt8 => eq_primitive_array!(UInt8Type, self, other),
+            DataType::UInt16 => eq_primitive_array!(UInt16Type, self, other),
+            DataType::UInt32 => eq_primitive_array!(UInt32Type, self, other),
+            DataType::UInt64 => eq_primitive_array!(UInt64Type, self, other),
             DataType::Float32 => eq_primitive_array!(Float32Type, self, other),
             DataType::Float64 => eq_primitive_array!(Float64Type, self, other),
             DataType::Boolean => eq_primitive_array!(BooleanType, self, other),
             DataType::Timestamp(_) => eq_primitive_array!(TimestampType, self, other),
+            DataType::Date => eq_primitive_array!(DateType, self, other),
+            DataType::Time => eq_primitive_array!(TimeType, self, other),
             DataType::String => {
                 self.as_any().downcast_ref::<StringArray>()
                     == other.as_any().downcast_ref::<StringArray>()
             }
+            DataType::List(_) => {
+                self.as_any().downcast_ref::<ListArray>()
+                    == other.as_any().downcast_ref::<ListArray>()
+            }
+            DataType::Struct(_) => {
+                self.as_any().downcast_ref::<StructArray>()
+                    == other.as_any().downcast_ref::<StructArray>()
+            }
+            DataType::Map(_, _) => {
+                self.as_any().downcast_ref::<MapArray>()
+                    == other.as_any().downcast_ref::<MapArray>()
+            }
         }
     }
 }
@@ -119,6 +147,18 @@ impl Serialize for dyn Array {
             DataType::Int64 => self
                 .downcast_ref::<PrimitiveArray<Int64Type>>()
                 .serialize(serializer),
+            DataType::UInt8 => self
+                .downcast_ref::<PrimitiveArray<UInt8Type>>()
+                .serialize(serializer),
+            DataType::UInt16 => self
+                .downcast_ref::<PrimitiveArray<UInt16Type>>()
+                .serialize(serializer),
+            DataType::UInt32 => self
+                .downcast_ref::<PrimitiveArray<UInt32Type>>()
+                .serialize(serializer),
+            DataType::UInt64 => self
+                .downcast_ref::<PrimitiveArray<UInt64Type>>()
+                .serialize(serializer),
             DataType::Float32 => self
                 .downcast_ref::<PrimitiveArray<Float32Type>>()
                 .serialize(serializer),
@@ -131,7 +171,51 @@ impl Serialize for dyn Array {
             DataType::Timestamp(_) => self
                 .downcast_ref::<PrimitiveArray<TimestampType>>()
                 .serialize(serializer),
+            DataType::Date => self
+                .downcast_ref::<PrimitiveArray<DateType>>()
+                .serialize(serializer),
+            DataType::Time => self
+                .downcast_ref::<PrimitiveArray<TimeType>>()
+                .serialize(serializer),
             DataType::String => self.downcast_ref::<StringArray>().serialize(serializer),
+            DataType::List(_) => self.downcast_ref::<ListArray>().serialize(serializer),
+            DataType::Struct(_) => self.downcast_ref::<StructArray>().serialize(serializer),
+            DataType::Map(_, _) => self.downcast_ref::<MapArray>().serialize(serializer),
         }
     }
 }
+
+/// Deserializes an array of the given data type.
+pub(crate) struct ArraySeed(pub DataType);
+
+impl<'de> DeserializeSeed<'de> for ArraySeed {
+    type Value = ArrayRef;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let array: ArrayRef = match self.0 {
+            DataType::Null => Arc::new(NullArray::deserialize(deserializer)?),
+            DataType::Int8 => Arc::new(Int8Array::deserialize(deserializer)?),
+            DataType::Int16 => Arc::new(Int16Array::deserialize(deserializer)?),
+            DataType::Int32 => Arc::new(Int32Array::deserialize(deserializer)?),
+            DataType::Int64 => Arc::new(Int64Array::deserialize(deserializer)?),
+            DataType::UInt8 => Arc::new(UInt8Array::deserialize(deserializer)?),
+            DataType::UInt16 => Arc::new(UInt16Array::deserialize(deserializer)?),
+            DataType::UInt32 => Arc::new(UInt32Array::deserialize(deserializer)?),
+            DataType::UInt64 => Arc::new(UInt64Array::deserialize(deserializer)?),
+            DataType::Float32 => Arc::new(Float32Array::deserialize(deserializer)?),
+            DataType::Float64 => Arc::new(Float64Array::deserialize(deserializer)?),
+            DataType::Boolean => Arc::new(BooleanArray::deserialize(deserializer)?),
+            DataType::Timestamp(_) => Arc::new(TimestampArray::deserialize(deserializer)?),
+            DataType::Date => Arc::new(DateArray::deserialize(deserializer)?),
+            DataType::Time => Arc::new(TimeArray::deserialize(deserializer)?),
+            DataType::String => Arc::new(StringArray::deserialize(deserializer)?),
+            DataType::List(_) => Arc::new(ListArraySeed(self.0).deserialize(deserializer)?),
+            DataType::Struct(_) => Arc::new(StructArraySeed(self.0).deserialize(deserializer)?),
+            DataType::Map(_, _) => Arc::new(MapArraySeed(self.0).deserialize(deserializer)?),
+        };
+        Ok(array)
+    }
+}
@@ -1,8 +1,9 @@
 use std::any::Any;
 
 use crate::array::{
-    Array, BooleanType, DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
-    Int8Type, PrimitiveArray, Scalar, StringArray, TimestampType,
+    Array, BooleanType, DataType, DateType, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, ListArray, MapArray, PrimitiveArray, Scalar, StringArray, StructArray,
+    TimeType, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 
 macro_rules! get_scalar_value {
@@ -39,15 +40,45 @@ pub trait ArrayExt: Array {
             DataType::Int16 => get_scalar_value!(self, index, Int16Type, Int16),
             DataType::Int32 => get_scalar_value!(self, index, Int32Type, Int32),
             DataType::Int64 => get_scalar_value!(self, index, Int64Type, Int64),
+            DataType::UInt8 => get_scalar_value!(self, index, UInt8Type, UInt8),
+            DataType::UInt16 => get_scalar_value!(self, index, UInt16Type, UInt16),
+            DataType::UInt32 => get_scalar_value!(self, index, UInt32Type, UInt32),
+            DataType::UInt64 => get_scalar_value!(self, index, UInt64Type, UInt64),
             DataType::Float32 => get_scalar_value!(self, index, Float32Type, Float32),
             DataType::Float64 => get_scalar_value!(self, index, Float64Type, Float64),
             DataType::Boolean => get_scalar_value!(self, index, BooleanType, Boolean),
             DataType::Timestamp(_) => get_scalar_value!(self, index, TimestampType, Timestamp),
+            DataType::Date => get_scalar_value!(self, index, DateType, Date),
+            DataType::Time => get_scalar_value!(self, index, TimeType, Time),
             DataType::String => self
                 .downcast_ref::<StringArray>()
                 .value_opt(index)
                 .map(|s| Scalar::String(s.into()))
                 .unwrap_or_default(),
+            DataType::List(element_type) => self
+                .downcast_ref::<ListArray>()
+                .value_opt(index)
+                .map(|list| {
+                    let values = (0..list.len()).map(|i| list.scalar_value(i)).collect();
+                    Scalar::List(*element_type, values)
+                })
+                .unwrap_or_default(),
+            DataType::Struct(_) => {
+                let array = self.downcast_ref::<StructArray>();
+                if array.is_valid(index) {
+                    Scalar::Struct(array.data_type(), array.value(index))
+                } else {
+                    Scalar::Null
+                }
+            }
+            DataType::Map(_, _) => {
+                let array = self.downcast_ref::<MapArray>();
+                if array.is_valid(index) {
+                    Scalar::Map(array.data_type(), array.value(index))
+                } else {
+                    Scalar::Null
+                }
+            }
         }
     }
 }
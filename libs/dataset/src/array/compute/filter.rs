@@ -1,9 +1,11 @@
 use std::sync::Arc;
 
+use crate::array::compute::take;
 use crate::array::{
-    Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, Float32Type, Float64Type,
-    Int16Type, Int32Type, Int64Type, Int8Type, NullArray, PrimitiveArray, PrimitiveBuilder,
-    StringArray, StringBuilder, TimestampType,
+    Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, DateType, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, NullArray, PrimitiveArray,
+    PrimitiveBuilder, StringArray, StringBuilder, TimeType, TimestampType, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
 };
 
 macro_rules! filter_primitive_array {
@@ -34,10 +36,16 @@ pub fn filter(array: ArrayRef, flags: &BooleanArray) -> ArrayRef {
         DataType::Int16 => filter_primitive_array!(array, flags, Int16Type),
         DataType::Int32 => filter_primitive_array!(array, flags, Int32Type),
         DataType::Int64 => filter_primitive_array!(array, flags, Int64Type),
+        DataType::UInt8 => filter_primitive_array!(array, flags, UInt8Type),
+        DataType::UInt16 => filter_primitive_array!(array, flags, UInt16Type),
+        DataType::UInt32 => filter_primitive_array!(array, flags, UInt32Type),
+        DataType::UInt64 => filter_primitive_array!(array, flags, UInt64Type),
         DataType::Float32 => filter_primitive_array!(array, flags, Float32Type),
         DataType::Float64 => filter_primitive_array!(array, flags, Float64Type),
         DataType::Boolean => filter_primitive_array!(array, flags, BooleanType),
         DataType::Timestamp(_) => filter_primitive_array!(array, flags, TimestampType),
+        DataType::Date => filter_primitive_array!(array, flags, DateType),
+        DataType::Time => filter_primitive_array!(array, flags, TimeType),
         DataType::String => {
             let input = array.downcast_ref::<StringArray>();
             if let Some(scalar) = input.to_scalar() {
@@ -54,6 +62,15 @@ pub fn filter(array: ArrayRef, flags: &BooleanArray) -> ArrayRef {
             }
             Arc::new(builder.finish())
         }
+        DataType::List(_) | DataType::Struct(_) | DataType::Map(_, _) => {
+            let indices = flags
+                .iter()
+                .enumerate()
+                .filter(|(_, flag)| *flag)
+                .map(|(idx, _)| Some(idx))
+                .collect::<Vec<_>>();
+            take(array, &indices)
+        }
     }
 }
 
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use crate::array::compute::take;
+use crate::array::{
+    Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, DateType, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, PrimitiveArray, PrimitiveBuilder,
+    StringArray, StringBuilder, TimeType, TimestampType, UInt16Type, UInt32Type, UInt64Type,
+    UInt8Type,
+};
+
+macro_rules! mask_primitive_array {
+    ($array:expr, $flags:expr, $ty:ty) => {{
+        let input = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity(input.len());
+        for (value, flag) in input.iter_opt().zip($flags.iter_opt()) {
+            builder.append_opt(value.filter(|_| flag == Some(true)));
+        }
+        Arc::new(builder.finish())
+    }};
+}
+
+/// Replaces the values of `array` whose flag is not `true` with nulls.
+pub fn mask(array: ArrayRef, flags: &BooleanArray) -> ArrayRef {
+    assert_eq!(array.len(), flags.len());
+
+    match array.data_type() {
+        DataType::Null => array,
+        DataType::Int8 => mask_primitive_array!(array, flags, Int8Type),
+        DataType::Int16 => mask_primitive_array!(array, flags, Int16Type),
+        DataType::Int32 => mask_primitive_array!(array, flags, Int32Type),
+        DataType::Int64 => mask_primitive_array!(array, flags, Int64Type),
+        DataType::UInt8 => mask_primitive_array!(array, flags, UInt8Type),
+        DataType::UInt16 => mask_primitive_array!(array, flags, UInt16Type),
+        DataType::UInt32 => mask_primitive_array!(array, flags, UInt32Type),
+        DataType::UInt64 => mask_primitive_array!(array, flags, UInt64Type),
+        DataType::Float32 => mask_primitive_array!(array, flags, Float32Type),
+        DataType::Float64 => mask_primitive_array!(array, flags, Float64Type),
+        DataType::Boolean => mask_primitive_array!(array, flags, BooleanType),
+        DataType::Timestamp(_) => mask_primitive_array!(array, flags, TimestampType),
+        DataType::Date => mask_primitive_array!(array, flags, DateType),
+        DataType::Time => mask_primitive_array!(array, flags, TimeType),
+        DataType::String => {
+            let input = array.downcast_ref::<StringArray>();
+            let mut builder = StringBuilder::with_capacity(input.len());
+            for (value, flag) in input.iter_opt().zip(flags.iter_opt()) {
+                builder.append_opt(value.filter(|_| flag == Some(true)));
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::List(_) | DataType::Struct(_) | DataType::Map(_, _) => {
+            let indices = flags
+                .iter_opt()
+                .enumerate()
+                .map(|(idx, flag)| Some(idx).filter(|_| flag == Some(true)))
+                .collect::<Vec<_>>();
+            take(array, &indices)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::Int32Array;
+
+    #[test]
+    fn test_mask() {
+        let flags = BooleanArray::from_opt_vec(vec![Some(true), Some(false), None, Some(true)]);
+
+        let array = mask(Arc::new(Int32Array::from_vec(vec![1, 2, 3, 4])), &flags);
+        assert_eq!(
+            array
+                .downcast_ref::<Int32Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(1), None, None, Some(4)]
+        );
+
+        let array = mask(Arc::new(StringArray::new_scalar(4, Some("a"))), &flags);
+        assert_eq!(
+            array
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some("a"), None, None, Some("a")]
+        );
+    }
+}
@@ -1,3 +1,11 @@
 mod filter;
+mod mask;
+mod scalars;
+mod stats;
+mod take;
 
 pub use filter::filter;
+pub use mask::mask;
+pub use scalars::{array_from_scalars, new_null_array};
+pub use stats::{max, mean, min, sum};
+pub use take::{interleave, take};
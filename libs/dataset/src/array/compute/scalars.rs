@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use crate::array::compute::interleave;
+use crate::array::{
+    ArrayRef, BooleanType, DataType, DateType, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, ListArray, MapArray, NullArray, PrimitiveBuilder, Scalar, StringBuilder,
+    StructArray, TimeType, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+
+macro_rules! primitive_array_from_scalars {
+    ($values:expr, $ty:ty, $scalar_ty:ident) => {{
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity($values.len());
+        for value in $values {
+            match value {
+                Scalar::$scalar_ty(value) => builder.append(value),
+                _ => builder.append_null(),
+            }
+        }
+        Arc::new(builder.finish()) as ArrayRef
+    }};
+}
+
+/// Creates an array of `data_type` from `values`, the values of other types are converted to
+/// nulls.
+pub fn array_from_scalars(data_type: DataType, values: Vec<Scalar>) -> ArrayRef {
+    match data_type {
+        DataType::Null => Arc::new(NullArray::new(values.len())),
+        DataType::Int8 => primitive_array_from_scalars!(values, Int8Type, Int8),
+        DataType::Int16 => primitive_array_from_scalars!(values, Int16Type, Int16),
+        DataType::Int32 => primitive_array_from_scalars!(values, Int32Type, Int32),
+        DataType::Int64 => primitive_array_from_scalars!(values, Int64Type, Int64),
+        DataType::UInt8 => primitive_array_from_scalars!(values, UInt8Type, UInt8),
+        DataType::UInt16 => primitive_array_from_scalars!(values, UInt16Type, UInt16),
+        DataType::UInt32 => primitive_array_from_scalars!(values, UInt32Type, UInt32),
+        DataType::UInt64 => primitive_array_from_scalars!(values, UInt64Type, UInt64),
+        DataType::Float32 => primitive_array_from_scalars!(values, Float32Type, Float32),
+        DataType::Float64 => primitive_array_from_scalars!(values, Float64Type, Float64),
+        DataType::Boolean => primitive_array_from_scalars!(values, BooleanType, Boolean),
+        DataType::Timestamp(_) => primitive_array_from_scalars!(values, TimestampType, Timestamp),
+        DataType::Date => primitive_array_from_scalars!(values, DateType, Date),
+        DataType::Time => primitive_array_from_scalars!(values, TimeType, Time),
+        DataType::String => {
+            let mut builder = StringBuilder::with_capacity(values.len());
+            for value in values {
+                match value {
+                    Scalar::String(value) => builder.append(&value),
+                    _ => builder.append_null(),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::List(element_type) => {
+            let mut lengths = Vec::with_capacity(values.len());
+            let mut elements = Vec::new();
+            for value in values {
+                match value {
+                    Scalar::List(_, list) => {
+                        lengths.push(Some(list.len()));
+                        elements.extend(list);
+                    }
+                    _ => lengths.push(None),
+                }
+            }
+            let elements = array_from_scalars(*element_type, elements);
+            Arc::new(ListArray::new(elements, lengths))
+        }
+        DataType::Struct(fields) => {
+            let len = values.len();
+            let mut validity = Vec::with_capacity(len);
+            let mut columns = vec![Vec::with_capacity(len); fields.len()];
+            for value in values {
+                let mut values = match value {
+                    Scalar::Struct(_, values) => {
+                        validity.push(true);
+                        values.into_iter()
+                    }
+                    _ => {
+                        validity.push(false);
+                        Vec::new().into_iter()
+                    }
+                };
+                for column in &mut columns {
+                    column.push(values.next().unwrap_or_default());
+                }
+            }
+            let children = fields
+                .iter()
+                .zip(columns)
+                .map(|(field, column)| {
+                    (
+                        field.name.clone(),
+                        array_from_scalars(field.data_type, column),
+                    )
+                })
+                .collect();
+            Arc::new(StructArray::new(len, children, Some(validity)))
+        }
+        DataType::Map(key_type, value_type) => {
+            let mut lengths = Vec::with_capacity(values.len());
+            let mut keys = Vec::new();
+            let mut map_values = Vec::new();
+            for value in values {
+                match value {
+                    Scalar::Map(_, entries) => {
+                        lengths.push(Some(entries.len()));
+                        for (key, value) in entries {
+                            keys.push(key);
+                            map_values.push(value);
+                        }
+                    }
+                    _ => lengths.push(None),
+                }
+            }
+            Arc::new(MapArray::new(
+                array_from_scalars(*key_type, keys),
+                array_from_scalars(*value_type, map_values),
+                lengths,
+            ))
+        }
+    }
+}
+
+/// Creates an array of `data_type` with `len` nulls.
+pub fn new_null_array(data_type: DataType, len: usize) -> ArrayRef {
+    interleave(data_type, &[], &vec![None; len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayExt;
+
+    #[test]
+    fn test_array_from_scalars() {
+        let values = vec![
+            Scalar::List(DataType::Int32, vec![Scalar::Int32(1), Scalar::Null]),
+            Scalar::Null,
+            Scalar::List(DataType::Int32, vec![]),
+        ];
+        let array = array_from_scalars(DataType::list(DataType::Int32), values.clone());
+        assert_eq!(
+            (0..array.len())
+                .map(|i| array.scalar_value(i))
+                .collect::<Vec<_>>(),
+            values
+        );
+    }
+}
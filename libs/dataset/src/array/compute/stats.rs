@@ -0,0 +1,193 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use anyhow::Result;
+
+use crate::array::{
+    ArrayExt, ArrayRef, BooleanType, DataType, DateType, Float32Type, Float64Type, Int16Type,
+    Int32Type, Int64Type, Int8Type, PrimitiveArray, Scalar, StringArray, TimeType, TimestampType,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+
+macro_rules! sum_integers {
+    ($array:expr, $ty:ty) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        let mut sum: Option<i64> = None;
+        for value in array.iter_opt().flatten() {
+            sum = Some(
+                i64::try_from(value)
+                    .ok()
+                    .and_then(|value| sum.unwrap_or_default().checked_add(value))
+                    .ok_or_else(|| anyhow::anyhow!("the sum overflows"))?,
+            );
+        }
+        sum.map(Scalar::Int64).unwrap_or_default()
+    }};
+}
+
+macro_rules! sum_floats {
+    ($array:expr, $ty:ty) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        let mut sum: Option<f64> = None;
+        for value in array.iter_opt().flatten() {
+            sum = Some(sum.unwrap_or_default() + value as f64);
+        }
+        sum.map(Scalar::Float64).unwrap_or_default()
+    }};
+}
+
+/// Returns the sum of the values of `array` that are not null, an `Int64` for integers and a
+/// `Float64` for floats, or null if there are no such values.
+pub fn sum(array: &ArrayRef) -> Result<Scalar> {
+    Ok(match array.data_type() {
+        DataType::Null => Scalar::Null,
+        DataType::Int8 => sum_integers!(array, Int8Type),
+        DataType::Int16 => sum_integers!(array, Int16Type),
+        DataType::Int32 => sum_integers!(array, Int32Type),
+        DataType::Int64 => sum_integers!(array, Int64Type),
+        DataType::UInt8 => sum_integers!(array, UInt8Type),
+        DataType::UInt16 => sum_integers!(array, UInt16Type),
+        DataType::UInt32 => sum_integers!(array, UInt32Type),
+        DataType::UInt64 => sum_integers!(array, UInt64Type),
+        DataType::Float32 => sum_floats!(array, Float32Type),
+        DataType::Float64 => sum_floats!(array, Float64Type),
+        data_type => anyhow::bail!("cannot compute the sum of {}", data_type),
+    })
+}
+
+macro_rules! mean_primitive {
+    ($array:expr, $ty:ty) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        let mut sum = 0.0;
+        let mut count = 0;
+        for value in array.iter_opt().flatten() {
+            sum += value as f64;
+            count += 1;
+        }
+        if count > 0 {
+            Some(sum / count as f64)
+        } else {
+            None
+        }
+    }};
+}
+
+/// Returns the mean of the values of `array` that are not null, or `None` if there are no such
+/// values.
+pub fn mean(array: &ArrayRef) -> Result<Option<f64>> {
+    Ok(match array.data_type() {
+        DataType::Null => None,
+        DataType::Int8 => mean_primitive!(array, Int8Type),
+        DataType::Int16 => mean_primitive!(array, Int16Type),
+        DataType::Int32 => mean_primitive!(array, Int32Type),
+        DataType::Int64 => mean_primitive!(array, Int64Type),
+        DataType::UInt8 => mean_primitive!(array, UInt8Type),
+        DataType::UInt16 => mean_primitive!(array, UInt16Type),
+        DataType::UInt32 => mean_primitive!(array, UInt32Type),
+        DataType::UInt64 => mean_primitive!(array, UInt64Type),
+        DataType::Float32 => mean_primitive!(array, Float32Type),
+        DataType::Float64 => mean_primitive!(array, Float64Type),
+        data_type => anyhow::bail!("cannot compute the mean of {}", data_type),
+    })
+}
+
+macro_rules! extreme_primitive {
+    ($array:expr, $ty:ty, $scalar_ty:ident, $ordering:expr) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        let mut result = None;
+        for value in array.iter_opt().flatten() {
+            match result {
+                Some(current) if value.partial_cmp(&current) != Some($ordering) => {}
+                _ => result = Some(value),
+            }
+        }
+        result.map(Scalar::$scalar_ty).unwrap_or_default()
+    }};
+}
+
+fn extreme(array: &ArrayRef, ordering: Ordering) -> Result<Scalar> {
+    Ok(match array.data_type() {
+        DataType::Null => Scalar::Null,
+        DataType::Int8 => extreme_primitive!(array, Int8Type, Int8, ordering),
+        DataType::Int16 => extreme_primitive!(array, Int16Type, Int16, ordering),
+        DataType::Int32 => extreme_primitive!(array, Int32Type, Int32, ordering),
+        DataType::Int64 => extreme_primitive!(array, Int64Type, Int64, ordering),
+        DataType::UInt8 => extreme_primitive!(array, UInt8Type, UInt8, ordering),
+        DataType::UInt16 => extreme_primitive!(array, UInt16Type, UInt16, ordering),
+        DataType::UInt32 => extreme_primitive!(array, UInt32Type, UInt32, ordering),
+        DataType::UInt64 => extreme_primitive!(array, UInt64Type, UInt64, ordering),
+        DataType::Float32 => extreme_primitive!(array, Float32Type, Float32, ordering),
+        DataType::Float64 => extreme_primitive!(array, Float64Type, Float64, ordering),
+        DataType::Boolean => extreme_primitive!(array, BooleanType, Boolean, ordering),
+        DataType::Timestamp(_) => extreme_primitive!(array, TimestampType, Timestamp, ordering),
+        DataType::Date => extreme_primitive!(array, DateType, Date, ordering),
+        DataType::Time => extreme_primitive!(array, TimeType, Time, ordering),
+        DataType::String => {
+            let array = array.downcast_ref::<StringArray>();
+            let mut result = None;
+            for value in array.iter_opt().flatten() {
+                match result {
+                    Some(current) if value.cmp(current) != ordering => {}
+                    _ => result = Some(value),
+                }
+            }
+            result.map(Scalar::from).unwrap_or_default()
+        }
+        data_type => anyhow::bail!("cannot compare the values of {}", data_type),
+    })
+}
+
+/// Returns the smallest value of `array` that is not null, or null if there are no such values.
+pub fn min(array: &ArrayRef) -> Result<Scalar> {
+    extreme(array, Ordering::Less)
+}
+
+/// Returns the largest value of `array` that is not null, or null if there are no such values.
+pub fn max(array: &ArrayRef) -> Result<Scalar> {
+    extreme(array, Ordering::Greater)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{Float64Array, Int32Array, Int64Array, NullArray, UInt64Array, UInt8Array};
+
+    #[test]
+    fn test_stats() {
+        let array: ArrayRef = Arc::new(Int32Array::from_opt_vec(vec![Some(3), None, Some(-1)]));
+        assert_eq!(sum(&array).unwrap(), Scalar::Int64(2));
+        assert_eq!(mean(&array).unwrap(), Some(1.0));
+        assert_eq!(min(&array).unwrap(), Scalar::Int32(-1));
+        assert_eq!(max(&array).unwrap(), Scalar::Int32(3));
+
+        let array: ArrayRef = Arc::new(Float64Array::from_vec(vec![1.5, 2.5]));
+        assert_eq!(sum(&array).unwrap(), Scalar::Float64(4.0));
+        assert_eq!(mean(&array).unwrap(), Some(2.0));
+
+        let array: ArrayRef = Arc::new(StringArray::from_vec(vec!["b", "c", "a"]));
+        assert_eq!(min(&array).unwrap(), Scalar::from("a"));
+        assert_eq!(max(&array).unwrap(), Scalar::from("c"));
+        assert!(sum(&array).is_err());
+        assert!(mean(&array).is_err());
+
+        let array: ArrayRef = Arc::new(Int64Array::from_opt_vec(vec![None]));
+        assert_eq!(sum(&array).unwrap(), Scalar::Null);
+        assert_eq!(mean(&array).unwrap(), None);
+        assert_eq!(min(&array).unwrap(), Scalar::Null);
+
+        let array: ArrayRef = Arc::new(NullArray::new(2));
+        assert_eq!(max(&array).unwrap(), Scalar::Null);
+
+        let array: ArrayRef = Arc::new(Int64Array::from_vec(vec![i64::MAX, 1]));
+        assert!(sum(&array).is_err());
+
+        let array: ArrayRef = Arc::new(UInt8Array::from_vec(vec![200, 100]));
+        assert_eq!(sum(&array).unwrap(), Scalar::Int64(300));
+        assert_eq!(max(&array).unwrap(), Scalar::UInt8(200));
+
+        let array: ArrayRef = Arc::new(UInt64Array::from_vec(vec![u64::MAX]));
+        assert!(sum(&array).is_err());
+    }
+}
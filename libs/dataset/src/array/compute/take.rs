@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use crate::array::{
+    Array, ArrayExt, ArrayRef, BooleanType, DataType, DateType, Float32Type, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, ListArray, MapArray, NullArray, PrimitiveArray,
+    PrimitiveBuilder, StringArray, StringBuilder, StructArray, TimeType, TimestampType, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
+};
+
+macro_rules! interleave_primitive_arrays {
+    ($arrays:expr, $indices:expr, $ty:ty) => {{
+        let arrays = $arrays
+            .iter()
+            .map(|array| array.downcast_ref::<PrimitiveArray<$ty>>())
+            .collect::<Vec<_>>();
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity($indices.len());
+        for index in $indices {
+            builder.append_opt(index.and_then(|(array, idx)| arrays[array].value_opt(idx)));
+        }
+        Arc::new(builder.finish())
+    }};
+}
+
+/// Creates an array of `data_type` whose element at position `i` is the element
+/// `indices[i] = (array, index)` of `arrays`, or null if the index is `None`.
+///
+/// # Panics
+///
+/// Panics if any of the arrays is not of `data_type` or an index is out of bounds.
+pub fn interleave(
+    data_type: DataType,
+    arrays: &[ArrayRef],
+    indices: &[Option<(usize, usize)>],
+) -> ArrayRef {
+    match data_type {
+        DataType::Null => Arc::new(NullArray::new(indices.len())),
+        DataType::Int8 => interleave_primitive_arrays!(arrays, indices, Int8Type),
+        DataType::Int16 => interleave_primitive_arrays!(arrays, indices, Int16Type),
+        DataType::Int32 => interleave_primitive_arrays!(arrays, indices, Int32Type),
+        DataType::Int64 => interleave_primitive_arrays!(arrays, indices, Int64Type),
+        DataType::UInt8 => interleave_primitive_arrays!(arrays, indices, UInt8Type),
+        DataType::UInt16 => interleave_primitive_arrays!(arrays, indices, UInt16Type),
+        DataType::UInt32 => interleave_primitive_arrays!(arrays, indices, UInt32Type),
+        DataType::UInt64 => interleave_primitive_arrays!(arrays, indices, UInt64Type),
+        DataType::Float32 => interleave_primitive_arrays!(arrays, indices, Float32Type),
+        DataType::Float64 => interleave_primitive_arrays!(arrays, indices, Float64Type),
+        DataType::Boolean => interleave_primitive_arrays!(arrays, indices, BooleanType),
+        DataType::Timestamp(_) => interleave_primitive_arrays!(arrays, indices, TimestampType),
+        DataType::Date => interleave_primitive_arrays!(arrays, indices, DateType),
+        DataType::Time => interleave_primitive_arrays!(arrays, indices, TimeType),
+        DataType::String => {
+            let arrays = arrays
+                .iter()
+                .map(|array| array.downcast_ref::<StringArray>())
+                .collect::<Vec<_>>();
+            let mut builder = StringBuilder::with_capacity(indices.len());
+            for index in indices {
+                builder.append_opt(index.and_then(|(array, idx)| arrays[array].value_opt(idx)));
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::List(element_type) => {
+            let lists = arrays
+                .iter()
+                .map(|array| array.downcast_ref::<ListArray>())
+                .collect::<Vec<_>>();
+            let values = lists
+                .iter()
+                .map(|list| list.values().clone())
+                .collect::<Vec<_>>();
+            let mut lengths = Vec::with_capacity(indices.len());
+            let mut value_indices = Vec::new();
+            for index in indices {
+                match index {
+                    Some((array, idx)) if lists[*array].is_valid(*idx) => {
+                        let range = lists[*array].value_range(*idx);
+                        lengths.push(Some(range.len()));
+                        value_indices.extend(range.map(|value_idx| Some((*array, value_idx))));
+                    }
+                    _ => lengths.push(None),
+                }
+            }
+            Arc::new(ListArray::new(
+                interleave(*element_type, &values, &value_indices),
+                lengths,
+            ))
+        }
+        DataType::Struct(fields) => {
+            let structs = arrays
+                .iter()
+                .map(|array| array.downcast_ref::<StructArray>())
+                .collect::<Vec<_>>();
+            let indices = indices
+                .iter()
+                .map(|index| index.filter(|(array, idx)| structs[*array].is_valid(*idx)))
+                .collect::<Vec<_>>();
+            let children = fields
+                .iter()
+                .enumerate()
+                .map(|(field_idx, field)| {
+                    let values = structs
+                        .iter()
+                        .map(|array| array.children()[field_idx].clone())
+                        .collect::<Vec<_>>();
+                    (
+                        field.name.clone(),
+                        interleave(field.data_type, &values, &indices),
+                    )
+                })
+                .collect();
+            let validity = indices.iter().map(Option::is_some).collect();
+            Arc::new(StructArray::new(indices.len(), children, Some(validity)))
+        }
+        DataType::Map(key_type, value_type) => {
+            let maps = arrays
+                .iter()
+                .map(|array| array.downcast_ref::<MapArray>())
+                .collect::<Vec<_>>();
+            let keys = maps
+                .iter()
+                .map(|map| map.keys().clone())
+                .collect::<Vec<_>>();
+            let values = maps
+                .iter()
+                .map(|map| map.values().clone())
+                .collect::<Vec<_>>();
+            let mut lengths = Vec::with_capacity(indices.len());
+            let mut entry_indices = Vec::new();
+            for index in indices {
+                match index {
+                    Some((array, idx)) if maps[*array].is_valid(*idx) => {
+                        let range = maps[*array].entries().value_range(*idx);
+                        lengths.push(Some(range.len()));
+                        entry_indices.extend(range.map(|entry_idx| Some((*array, entry_idx))));
+                    }
+                    _ => lengths.push(None),
+                }
+            }
+            Arc::new(MapArray::new(
+                interleave(*key_type, &keys, &entry_indices),
+                interleave(*value_type, &values, &entry_indices),
+                lengths,
+            ))
+        }
+    }
+}
+
+/// Creates an array whose element at position `i` is the element `indices[i]` of `array`, or
+/// null if the index is `None`.
+///
+/// # Panics
+///
+/// Panics if an index is out of bounds.
+pub fn take(array: ArrayRef, indices: &[Option<usize>]) -> ArrayRef {
+    let indices = indices
+        .iter()
+        .map(|index| index.map(|idx| (0, idx)))
+        .collect::<Vec<_>>();
+    interleave(array.data_type(), &[array], &indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Int32Array, Int64Array, Scalar};
+
+    #[test]
+    fn test_interleave() {
+        let arrays = vec![
+            Arc::new(Int32Array::from_vec(vec![1, 2, 3])) as ArrayRef,
+            Arc::new(Int32Array::from_opt_vec(vec![None, Some(5)])),
+        ];
+        let array = interleave(
+            DataType::Int32,
+            &arrays,
+            &[Some((1, 1)), Some((0, 0)), None, Some((1, 0)), Some((0, 2))],
+        );
+        assert_eq!(
+            array
+                .downcast_ref::<Int32Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(5), Some(1), None, None, Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_take_list() {
+        let array = Arc::new(ListArray::new(
+            Arc::new(StringArray::from_vec(vec!["a", "b", "c"])),
+            vec![Some(2), None, Some(1)],
+        ));
+        let array = take(array, &[Some(2), Some(1), None, Some(0)]);
+        let values = (0..array.len())
+            .map(|i| array.scalar_value(i))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Scalar::List(DataType::String, vec![Scalar::from("c")]),
+                Scalar::Null,
+                Scalar::Null,
+                Scalar::List(DataType::String, vec![Scalar::from("a"), Scalar::from("b")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_take_map() {
+        let array = Arc::new(MapArray::new(
+            Arc::new(StringArray::from_vec(vec!["a", "b", "c"])),
+            Arc::new(Int64Array::from_vec(vec![1, 2, 3])),
+            vec![Some(2), None, Some(1)],
+        ));
+        let array = take(array, &[Some(2), Some(1), None, Some(0)]);
+        let data_type = DataType::map(DataType::String, DataType::Int64);
+        let values = (0..array.len())
+            .map(|i| array.scalar_value(i))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Scalar::Map(data_type, vec![(Scalar::from("c"), Scalar::Int64(3))]),
+                Scalar::Null,
+                Scalar::Null,
+                Scalar::Map(
+                    data_type,
+                    vec![
+                        (Scalar::from("a"), Scalar::Int64(1)),
+                        (Scalar::from("b"), Scalar::Int64(2))
+                    ]
+                ),
+            ]
+        );
+    }
+}
@@ -1,9 +1,14 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
 use chrono_tz::Tz;
 use derive_more::Display;
-use serde::{Deserialize, Serialize};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// The sets of data types.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Display, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum DataType {
     /// Null type
     #[display(fmt = "null")]
@@ -25,6 +30,22 @@ pub enum DataType {
     #[display(fmt = "int64")]
     Int64,
 
+    /// An unsigned 8-bit integer.
+    #[display(fmt = "uint8")]
+    UInt8,
+
+    /// An unsigned 16-bit integer.
+    #[display(fmt = "uint16")]
+    UInt16,
+
+    /// An unsigned 32-bit integer.
+    #[display(fmt = "uint32")]
+    UInt32,
+
+    /// An unsigned 64-bit integer.
+    #[display(fmt = "uint64")]
+    UInt64,
+
     /// A 32-bit floating point number.
     #[display(fmt = "float32")]
     Float32,
@@ -41,12 +62,147 @@ pub enum DataType {
     #[display(fmt = "timestamp")]
     Timestamp(Option<Tz>),
 
+    /// A calendar date, the number of days since the unix epoch in a 32-bit integer.
+    #[display(fmt = "date")]
+    Date,
+
+    /// A time of day, the number of milliseconds since midnight in a 32-bit integer.
+    #[display(fmt = "time")]
+    Time,
+
     /// A variable-length string in Unicode with UTF-8 encoding.
     #[display(fmt = "string")]
     String,
+
+    /// A variable-length list of values of the element type, created with [`DataType::list`].
+    #[display(fmt = "list<{}>", _0)]
+    List(#[serde(deserialize_with = "deserialize_element_type")] &'static DataType),
+
+    /// A record of named fields, created with [`DataType::struct_type`].
+    #[display(fmt = "struct<{}>", "display_fields(_0)")]
+    Struct(#[serde(deserialize_with = "deserialize_struct_fields")] &'static [StructField]),
+
+    /// A variable-length map from keys of the key type to values of the value type, created with
+    /// [`DataType::map`].
+    #[display(fmt = "map<{}, {}>", _0, _1)]
+    Map(
+        #[serde(deserialize_with = "deserialize_element_type")] &'static DataType,
+        #[serde(deserialize_with = "deserialize_element_type")] &'static DataType,
+    ),
+}
+
+/// A named field of a struct type.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct StructField {
+    pub name: String,
+    pub data_type: DataType,
+}
+
+impl StructField {
+    pub fn new(name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+        }
+    }
+}
+
+fn display_fields(fields: &[StructField]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, field.data_type))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Element types are interned so that `DataType` stays `Copy`, each distinct type is only
+/// allocated once.
+fn intern(data_type: DataType) -> &'static DataType {
+    static TYPES: Lazy<Mutex<HashSet<&'static DataType>>> = Lazy::new(Default::default);
+
+    let mut types = TYPES.lock().unwrap();
+    match types.get(&data_type) {
+        Some(data_type) => data_type,
+        None => {
+            let data_type: &'static DataType = Box::leak(Box::new(data_type));
+            types.insert(data_type);
+            data_type
+        }
+    }
+}
+
+/// Struct fields are interned like element types.
+fn intern_fields(fields: Vec<StructField>) -> &'static [StructField] {
+    static FIELDS: Lazy<Mutex<HashSet<&'static [StructField]>>> = Lazy::new(Default::default);
+
+    let mut all_fields = FIELDS.lock().unwrap();
+    match all_fields.get(fields.as_slice()) {
+        Some(fields) => fields,
+        None => {
+            let fields: &'static [StructField] = Box::leak(fields.into_boxed_slice());
+            all_fields.insert(fields);
+            fields
+        }
+    }
+}
+
+fn deserialize_struct_fields<'de, D>(deserializer: D) -> Result<&'static [StructField], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<StructField>::deserialize(deserializer).map(intern_fields)
+}
+
+fn deserialize_element_type<'de, D>(deserializer: D) -> Result<&'static DataType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    DataType::deserialize(deserializer).map(intern)
 }
 
 impl DataType {
+    /// Returns the type of lists of `element_type` values.
+    pub fn list(element_type: DataType) -> DataType {
+        DataType::List(intern(element_type))
+    }
+
+    /// Returns the type of structs with `fields`.
+    pub fn struct_type(fields: Vec<StructField>) -> DataType {
+        DataType::Struct(intern_fields(fields))
+    }
+
+    /// Returns the type of maps from `key_type` keys to `value_type` values.
+    pub fn map(key_type: DataType, value_type: DataType) -> DataType {
+        DataType::Map(intern(key_type), intern(value_type))
+    }
+
+    /// Returns the fields if this type is a struct type.
+    #[inline]
+    pub fn fields(&self) -> Option<&'static [StructField]> {
+        match self {
+            DataType::Struct(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    /// Returns the element type if this type is a list type.
+    #[inline]
+    pub fn element_type(&self) -> Option<DataType> {
+        match self {
+            DataType::List(element_type) => Some(**element_type),
+            _ => None,
+        }
+    }
+
+    /// Returns the key type and the value type if this type is a map type.
+    #[inline]
+    pub fn entry_types(&self) -> Option<(DataType, DataType)> {
+        match self {
+            DataType::Map(key_type, value_type) => Some((**key_type, **value_type)),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if this type is a numeric type (integer or float).
     #[inline]
     pub fn is_numeric(&self) -> bool {
@@ -56,17 +212,30 @@ impl DataType {
                 | DataType::Int16
                 | DataType::Int32
                 | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
                 | DataType::Float32
                 | DataType::Float64
         )
     }
 
-    /// Returns `true` if this type is a integer type.
+    /// Returns `true` if this type is a integer type, signed or unsigned.
     #[inline]
     pub fn is_integer(&self) -> bool {
         matches!(
             self,
             DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64
+        ) || self.is_unsigned()
+    }
+
+    /// Returns `true` if this type is an unsigned integer type.
+    #[inline]
+    pub fn is_unsigned(&self) -> bool {
+        matches!(
+            self,
+            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64
         )
     }
 
@@ -94,6 +263,36 @@ impl DataType {
         matches!(self, DataType::Timestamp(_))
     }
 
+    /// Returns `true` if this type is a date type.
+    #[inline]
+    pub fn is_date(&self) -> bool {
+        matches!(self, DataType::Date)
+    }
+
+    /// Returns `true` if this type is a time type.
+    #[inline]
+    pub fn is_time(&self) -> bool {
+        matches!(self, DataType::Time)
+    }
+
+    /// Returns `true` if this type is a list type.
+    #[inline]
+    pub fn is_list(&self) -> bool {
+        matches!(self, DataType::List(_))
+    }
+
+    /// Returns `true` if this type is a struct type.
+    #[inline]
+    pub fn is_struct(&self) -> bool {
+        matches!(self, DataType::Struct(_))
+    }
+
+    /// Returns `true` if this type is a map type.
+    #[inline]
+    pub fn is_map(&self) -> bool {
+        matches!(self, DataType::Map(_, _))
+    }
+
     /// Returns `true` if this type can be cast to `to` type.
     #[inline]
     pub fn can_cast_to(&self, to: Self) -> bool {
@@ -106,14 +305,21 @@ impl DataType {
         match to {
             Null => matches!(self, Null),
             Int8 => matches!(self, Int8),
-            Int16 => matches!(self, Int8 | Int16),
-            Int32 => matches!(self, Int8 | Int16 | Int32),
-            Int64 => matches!(self, Int8 | Int16 | Int32 | Int64),
-            Float32 => matches!(self, Int8 | Int16 | Int32 | Int64 | Float32),
-            Float64 => matches!(self, Int8 | Int16 | Int32 | Int64 | Float32 | Float64),
+            Int16 => matches!(self, Int8 | Int16 | UInt8),
+            Int32 => matches!(self, Int8 | Int16 | Int32 | UInt8 | UInt16),
+            Int64 => matches!(self, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32),
+            UInt8 => matches!(self, UInt8),
+            UInt16 => matches!(self, UInt8 | UInt16),
+            UInt32 => matches!(self, UInt8 | UInt16 | UInt32),
+            UInt64 => matches!(self, UInt8 | UInt16 | UInt32 | UInt64),
+            Float32 => self.is_integer() || matches!(self, Float32),
+            Float64 => self.is_numeric(),
             Boolean => matches!(self, Boolean),
             Timestamp(_) => matches!(self, Timestamp(_)),
-            String => true,
+            Date => matches!(self, Date),
+            Time => matches!(self, Time),
+            String => !matches!(self, List(_) | Struct(_) | Map(_, _)),
+            List(_) | Struct(_) | Map(_, _) => false,
         }
     }
 }
@@ -140,6 +346,9 @@ mod tests {
         };
 
         (@check3 $t:expr =>)=>{};
+        (@check3 $t:expr => (List(_))) => {assert!($t.can_cast_to(List(&Int64)));};
+        (@check3 $t:expr => (Struct(_))) => {assert!($t.can_cast_to(Struct(&[])));};
+        (@check3 $t:expr => (Map(_, _))) => {assert!($t.can_cast_to(Map(&String, &Int64)));};
         (@check3 $t:expr => ($data:tt(_))) => {assert!($t.can_cast_to($data(None)));};
         (@check3 $t:expr => $data:tt) => {assert!($t.can_cast_to($data));};
 
@@ -149,57 +358,149 @@ mod tests {
         };
 
         (@check4 $t:expr =>)=>{};
+        (@check4 $t:expr => (List(_))) => {assert!(!$t.can_cast_to(List(&Int64)));};
+        (@check4 $t:expr => (Struct(_))) => {assert!(!$t.can_cast_to(Struct(&[])));};
+        (@check4 $t:expr => (Map(_, _))) => {assert!(!$t.can_cast_to(Map(&String, &Int64)));};
         (@check4 $t:expr => ($data:tt(_))) => {assert!(!$t.can_cast_to($data(None)));};
         (@check4 $t:expr => $data:tt) => {assert!(!$t.can_cast_to($data));};
 }
 
     #[test]
     fn test_null_can_cast() {
-        test_cast!(Null => Null, String | Int8, Int16, Int32, Int64, Float32, Float64, Boolean, (Timestamp(_)));
+        test_cast!(Null => Null, String | Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 
     #[test]
     fn test_int8_can_cast() {
-        test_cast!(Int8 => Int8, Int16, Int32, Int64, Float32, Float64, String | Null, Boolean, (Timestamp(_)));
+        test_cast!(Int8 => Int8, Int16, Int32, Int64, Float32, Float64, String | Null, UInt8, UInt16, UInt32, UInt64, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 
     #[test]
     fn test_int16_can_cast() {
-        test_cast!(Int16 => Int16, Int32, Int64, Float32, Float64, String | Null, Int8, Boolean, (Timestamp(_)));
+        test_cast!(Int16 => Int16, Int32, Int64, Float32, Float64, String | Null, Int8, UInt8, UInt16, UInt32, UInt64, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 
     #[test]
     fn test_int32_can_cast() {
-        test_cast!(Int32 => Int32, Int64, Float32, Float64, String | Null, Int8, Int16, Boolean, (Timestamp(_)));
+        test_cast!(Int32 => Int32, Int64, Float32, Float64, String | Null, Int8, Int16, UInt8, UInt16, UInt32, UInt64, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 
     #[test]
     fn test_int64_can_cast() {
-        test_cast!(Int64 => Int64, Float32, Float64, String | Null, Int8, Int16, Int32, Boolean, (Timestamp(_)));
+        test_cast!(Int64 => Int64, Float32, Float64, String | Null, Int8, Int16, Int32, UInt8, UInt16, UInt32, UInt64, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
+    }
+
+    #[test]
+    fn test_uint8_can_cast() {
+        test_cast!(UInt8 => Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, String | Null, Int8, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
+    }
+
+    #[test]
+    fn test_uint16_can_cast() {
+        test_cast!(UInt16 => Int32, Int64, UInt16, UInt32, UInt64, Float32, Float64, String | Null, Int8, Int16, UInt8, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
+    }
+
+    #[test]
+    fn test_uint32_can_cast() {
+        test_cast!(UInt32 => Int64, UInt32, UInt64, Float32, Float64, String | Null, Int8, Int16, Int32, UInt8, UInt16, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
+    }
+
+    #[test]
+    fn test_uint64_can_cast() {
+        test_cast!(UInt64 => UInt64, Float32, Float64, String | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 
     #[test]
     fn test_float32_can_cast() {
-        test_cast!(Float32 => Float32, Float64, String | Null, Int8, Int16, Int32, Int64, Boolean, (Timestamp(_)));
+        test_cast!(Float32 => Float32, Float64, String | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 
     #[test]
     fn test_float64_can_cast() {
-        test_cast!(Float64 => Float64, String | Null, Int8, Int16, Int32, Int64, Float32, Boolean, (Timestamp(_)));
+        test_cast!(Float64 => Float64, String | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 
     #[test]
     fn test_boolean_can_cast() {
-        test_cast!(Boolean => Boolean, String | Null, Int8, Int16, Int32, Int64, Float32, Float64, (Timestamp(_)));
+        test_cast!(Boolean => Boolean, String | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 
     #[test]
     fn test_timestamp_can_cast() {
-        test_cast!(Timestamp(None) => (Timestamp(_)), String | Null, Int8, Int16, Int32, Int64, Float32, Float64, Boolean);
+        test_cast!(Timestamp(None) => (Timestamp(_)), String | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean, Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
+    }
+
+    #[test]
+    fn test_date_can_cast() {
+        test_cast!(Date => Date, String | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean, (Timestamp(_)), Time, (List(_)), (Struct(_)), (Map(_, _)));
+    }
+
+    #[test]
+    fn test_time_can_cast() {
+        test_cast!(Time => Time, String | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean, (Timestamp(_)), Date, (List(_)), (Struct(_)), (Map(_, _)));
+    }
+
+    #[test]
+    fn test_list_can_cast() {
+        assert!(List(&Int64).can_cast_to(List(&Int64)));
+        assert!(!List(&Int8).can_cast_to(List(&Int64)));
+        assert!(!List(&Int64).can_cast_to(String));
+    }
+
+    #[test]
+    fn test_list_type() {
+        let list = super::DataType::list(Int64);
+        assert_eq!(list, List(&Int64));
+        assert_eq!(list.element_type(), Some(Int64));
+        assert_eq!(super::DataType::list(list).to_string(), "list<list<int64>>");
+
+        let data = bincode::serialize(&list).unwrap();
+        assert_eq!(
+            bincode::deserialize::<super::DataType>(&data).unwrap(),
+            list
+        );
+    }
+
+    #[test]
+    fn test_struct_type() {
+        let fields = vec![
+            super::StructField::new("a", Int64),
+            super::StructField::new("b", super::DataType::list(String)),
+        ];
+        let data_type = super::DataType::struct_type(fields.clone());
+        assert_eq!(data_type, super::DataType::struct_type(fields.clone()));
+        assert_eq!(data_type.fields(), Some(fields.as_slice()));
+        assert_eq!(data_type.to_string(), "struct<a: int64, b: list<string>>");
+        assert!(data_type.can_cast_to(data_type));
+        assert!(!data_type.can_cast_to(String));
+        assert!(!data_type.can_cast_to(super::DataType::struct_type(fields[..1].to_vec())));
+
+        let data = bincode::serialize(&data_type).unwrap();
+        assert_eq!(
+            bincode::deserialize::<super::DataType>(&data).unwrap(),
+            data_type
+        );
+    }
+
+    #[test]
+    fn test_map_type() {
+        let map = super::DataType::map(String, super::DataType::list(Int64));
+        assert_eq!(map, Map(&String, &List(&Int64)));
+        assert_eq!(
+            map.entry_types(),
+            Some((String, super::DataType::list(Int64)))
+        );
+        assert_eq!(map.to_string(), "map<string, list<int64>>");
+        assert!(map.can_cast_to(map));
+        assert!(!map.can_cast_to(String));
+        assert!(!Map(&String, &Int8).can_cast_to(Map(&String, &Int64)));
+
+        let data = bincode::serialize(&map).unwrap();
+        assert_eq!(bincode::deserialize::<super::DataType>(&data).unwrap(), map);
     }
 
     #[test]
     fn test_string_can_cast() {
-        test_cast!(String =>  String | Null, Int8, Int16, Int32, Int64, Float32, Float64, Boolean, (Timestamp(_)));
+        test_cast!(String => String | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean, (Timestamp(_)), Date, Time, (List(_)), (Struct(_)), (Map(_, _)));
     }
 }
@@ -0,0 +1,398 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::array::bitmap::{Bitmap, BitmapBuilder};
+use crate::array::compute::array_from_scalars;
+use crate::array::sealed::Sealed;
+use crate::array::{Array, ArrayBuilder, ArrayRef, ArraySeed, DataType, Scalar};
+
+/// An array where each element is a variable-length list of values of the element type.
+///
+/// The values of all lists are stored in one child array, the list at position `i` is the
+/// range `offsets[i]..offsets[i + 1]` of it.
+#[derive(Clone)]
+pub struct ListArray {
+    data_type: DataType,
+    offset: usize,
+    length: usize,
+    offsets: Arc<Vec<usize>>,
+    values: ArrayRef,
+    bitmap: Option<Bitmap>,
+}
+
+impl Debug for ListArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut ls = f.debug_list();
+        for i in 0..self.len() {
+            ls.entry(&self.value_opt(i));
+        }
+        ls.finish()
+    }
+}
+
+impl Sealed for ListArray {}
+
+impl Array for ListArray {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> ArrayRef {
+        if offset > self.len() {
+            panic!(
+                "offset (is {}) should be <= len (is {})",
+                offset,
+                self.len()
+            );
+        }
+
+        if offset + length > self.len() {
+            panic!(
+                "offset+length (is {}) should be <= len (is {})",
+                offset + length,
+                self.len()
+            );
+        }
+
+        Arc::new(ListArray {
+            data_type: self.data_type,
+            offset: self.offset + offset,
+            length,
+            offsets: self.offsets.clone(),
+            values: self.values.clone(),
+            bitmap: self.bitmap.as_ref().map(|bitmap| bitmap.offset(offset)),
+        })
+    }
+
+    fn is_valid(&self, index: usize) -> bool {
+        if index >= self.len() {
+            panic!("index (is {}) should be <= len (is {})", index, self.len());
+        }
+
+        match &self.bitmap {
+            Some(bitmap) => bitmap.is_valid(index),
+            None => true,
+        }
+    }
+
+    fn null_count(&self) -> usize {
+        (0..self.len()).filter(|i| self.is_null(*i)).count()
+    }
+}
+
+impl PartialEq for ListArray {
+    fn eq(&self, other: &Self) -> bool {
+        if self.data_type != other.data_type || self.len() != other.len() {
+            return false;
+        }
+        (0..self.len()).all(|i| match (self.value_opt(i), other.value_opt(i)) {
+            (Some(a), Some(b)) => *a == *b,
+            (None, None) => true,
+            _ => false,
+        })
+    }
+}
+
+impl ListArray {
+    /// Creates a list array from the concatenated `values` of all lists and the length of each
+    /// list, `None` means the list is null.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum of the lengths is not equal to the length of `values`.
+    pub fn new(values: ArrayRef, lengths: impl IntoIterator<Item = Option<usize>>) -> Self {
+        let lengths = lengths.into_iter();
+        let mut offsets = Vec::with_capacity(lengths.size_hint().0 + 1);
+        let mut bitmap = BitmapBuilder::default();
+        let mut end = 0;
+
+        offsets.push(0);
+        for (idx, length) in lengths.enumerate() {
+            bitmap.set(idx, length.is_some());
+            end += length.unwrap_or_default();
+            offsets.push(end);
+        }
+
+        if end != values.len() {
+            panic!(
+                "sum of lengths (is {}) should be == len of values (is {})",
+                end,
+                values.len()
+            );
+        }
+
+        Self {
+            data_type: DataType::list(values.data_type()),
+            offset: 0,
+            length: offsets.len() - 1,
+            offsets: Arc::new(offsets),
+            values,
+            bitmap: if !bitmap.is_empty() {
+                Some(bitmap.finish())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Returns the type of the values in the lists.
+    #[inline]
+    pub fn element_type(&self) -> DataType {
+        self.values.data_type()
+    }
+
+    /// Returns the child array that holds the values of all lists.
+    #[inline]
+    pub fn values(&self) -> &ArrayRef {
+        &self.values
+    }
+
+    /// Returns the range of the child array that holds the list at position `index`.
+    #[inline]
+    pub fn value_range(&self, index: usize) -> Range<usize> {
+        if index >= self.len() {
+            panic!("index (is {}) should be <= len (is {})", index, self.len());
+        }
+
+        let index = self.offset + index;
+        self.offsets[index]..self.offsets[index + 1]
+    }
+
+    /// Returns the list at position `index`.
+    #[inline]
+    pub fn value(&self, index: usize) -> ArrayRef {
+        let range = self.value_range(index);
+        self.values.slice(range.start, range.end - range.start)
+    }
+
+    #[inline]
+    pub fn value_opt(&self, index: usize) -> Option<ArrayRef> {
+        if self.is_valid(index) {
+            Some(self.value(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the length of the list at position `index`, or `None` if it is null.
+    #[inline]
+    pub fn value_len_opt(&self, index: usize) -> Option<usize> {
+        if self.is_valid(index) {
+            Some(self.value_range(index).len())
+        } else {
+            None
+        }
+    }
+}
+
+/// Array builder for lists, the values of each list are appended as scalars of the element type.
+pub struct ListBuilder {
+    element_type: DataType,
+    values: Vec<Scalar>,
+    lengths: Vec<Option<usize>>,
+}
+
+impl ArrayBuilder for ListBuilder {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+}
+
+impl ListBuilder {
+    /// Creates a builder of lists of `element_type` values.
+    pub fn with_capacity(element_type: DataType, size: usize) -> Self {
+        Self {
+            element_type,
+            values: Vec::new(),
+            lengths: Vec::with_capacity(size),
+        }
+    }
+
+    /// Appends a list of `values`, the values of other types than the element type become
+    /// nulls.
+    pub fn append(&mut self, values: impl IntoIterator<Item = Scalar>) {
+        let start = self.values.len();
+        self.values.extend(values);
+        self.lengths.push(Some(self.values.len() - start));
+    }
+
+    #[inline]
+    pub fn append_null(&mut self) {
+        self.lengths.push(None);
+    }
+
+    pub fn append_opt(&mut self, values: Option<impl IntoIterator<Item = Scalar>>) {
+        match values {
+            Some(values) => self.append(values),
+            None => self.append_null(),
+        }
+    }
+
+    pub fn finish(self) -> ListArray {
+        ListArray::new(
+            array_from_scalars(self.element_type, self.values),
+            self.lengths,
+        )
+    }
+}
+
+impl Serialize for ListArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (start, end) = match self.len() {
+            0 => (0, 0),
+            len => (self.value_range(0).start, self.value_range(len - 1).end),
+        };
+        let lengths = (0..self.len())
+            .map(|i| self.value_len_opt(i))
+            .collect::<Vec<_>>();
+
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&*self.values.slice(start, end - start))?;
+        tuple.serialize_element(&lengths)?;
+        tuple.end()
+    }
+}
+
+/// Deserializes a [`ListArray`] of the `list` data type.
+pub(crate) struct ListArraySeed(pub DataType);
+
+impl<'de> DeserializeSeed<'de> for ListArraySeed {
+    type Value = ListArray;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ArrayVisitor(DataType);
+
+        impl<'de> Visitor<'de> for ArrayVisitor {
+            type Value = ListArray;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("ListArray")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let values = seq
+                    .next_element_seed(ArraySeed(self.0))?
+                    .ok_or_else(|| Error::custom("expect values"))?;
+                let lengths = seq
+                    .next_element::<Vec<Option<usize>>>()?
+                    .ok_or_else(|| Error::custom("expect lengths"))?;
+                if lengths.iter().flatten().sum::<usize>() != values.len() {
+                    return Err(Error::custom("invalid list lengths"));
+                }
+                Ok(ListArray::new(values, lengths))
+            }
+        }
+
+        let element_type = self
+            .0
+            .element_type()
+            .ok_or_else(|| Error::custom(format!("'{}' is not a list type", self.0)))?;
+        deserializer.deserialize_tuple(2, ArrayVisitor(element_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayExt, Int64Array, StringArray};
+
+    fn create_array() -> ListArray {
+        ListArray::new(
+            Arc::new(Int64Array::from_vec(vec![1, 2, 3, 4, 5, 6])),
+            vec![Some(2), None, Some(0), Some(3), Some(1)],
+        )
+    }
+
+    #[test]
+    fn test_array() {
+        let array = create_array();
+        assert_eq!(array.data_type(), DataType::list(DataType::Int64));
+        assert_eq!(array.len(), 5);
+        assert_eq!(array.null_count(), 1);
+        assert_eq!(
+            &*array.value(0),
+            &Int64Array::from_vec(vec![1, 2]) as &dyn Array
+        );
+        assert!(array.value_opt(1).is_none());
+        assert!(array.value(2).is_empty());
+        assert_eq!(
+            &*array.value(3),
+            &Int64Array::from_vec(vec![3, 4, 5]) as &dyn Array
+        );
+    }
+
+    #[test]
+    fn test_slice() {
+        let array = create_array();
+        let slice = array.slice(1, 3);
+        let slice = slice.downcast_ref::<ListArray>();
+        assert_eq!(slice.len(), 3);
+        assert!(slice.is_null(0));
+        assert_eq!(slice.value_len_opt(1), Some(0));
+        assert_eq!(
+            slice.scalar_value(2),
+            Scalar::List(
+                DataType::Int64,
+                vec![Scalar::Int64(3), Scalar::Int64(4), Scalar::Int64(5)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let mut builder = ListBuilder::with_capacity(DataType::String, 3);
+        builder.append(vec![Scalar::from("a"), Scalar::from("b")]);
+        builder.append_null();
+        builder.append_opt(Some(vec![Scalar::Null]));
+        assert_eq!(builder.len(), 3);
+
+        let array = builder.finish();
+        assert_eq!(array.data_type(), DataType::list(DataType::String));
+        assert!(array.is_null(1));
+        assert_eq!(
+            &*array.value(0),
+            &StringArray::from_vec(vec!["a", "b"]) as &dyn Array
+        );
+        assert_eq!(
+            &*array.value(2),
+            &StringArray::from_opt_vec(vec![Option::<&str>::None]) as &dyn Array
+        );
+    }
+}
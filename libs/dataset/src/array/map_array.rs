@@ -0,0 +1,245 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use serde::de::{DeserializeSeed, Error};
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::array::list_array::ListArraySeed;
+use crate::array::sealed::Sealed;
+use crate::array::{
+    Array, ArrayExt, ArrayRef, DataType, ListArray, Scalar, StructArray, StructField,
+};
+
+/// An array where each element is a variable-length map from keys to values.
+///
+/// The entries of all maps are stored in one list array of structs with the fields `key` and
+/// `value`, the map at position `i` is the list at position `i`.
+#[derive(Clone)]
+pub struct MapArray {
+    data_type: DataType,
+    entries: ListArray,
+}
+
+impl Debug for MapArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut ls = f.debug_list();
+        for i in 0..self.len() {
+            ls.entry(&self.scalar_value(i));
+        }
+        ls.finish()
+    }
+}
+
+impl Sealed for MapArray {}
+
+impl Array for MapArray {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> ArrayRef {
+        Arc::new(MapArray {
+            data_type: self.data_type,
+            entries: self
+                .entries
+                .slice(offset, length)
+                .downcast_ref::<ListArray>()
+                .clone(),
+        })
+    }
+
+    #[inline]
+    fn is_valid(&self, index: usize) -> bool {
+        self.entries.is_valid(index)
+    }
+
+    #[inline]
+    fn null_count(&self) -> usize {
+        self.entries.null_count()
+    }
+}
+
+impl PartialEq for MapArray {
+    fn eq(&self, other: &Self) -> bool {
+        self.data_type == other.data_type && self.entries == other.entries
+    }
+}
+
+impl MapArray {
+    /// Creates a map array from the concatenated `keys` and `values` of all maps and the number
+    /// of entries of each map, `None` means the map is null.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` and `values` have different lengths, or the sum of the lengths is not
+    /// equal to them.
+    pub fn new(
+        keys: ArrayRef,
+        values: ArrayRef,
+        lengths: impl IntoIterator<Item = Option<usize>>,
+    ) -> Self {
+        let data_type = DataType::map(keys.data_type(), values.data_type());
+        let entries = StructArray::new(
+            keys.len(),
+            vec![("key".to_string(), keys), ("value".to_string(), values)],
+            None,
+        );
+        Self {
+            data_type,
+            entries: ListArray::new(Arc::new(entries), lengths),
+        }
+    }
+
+    /// Returns the type of the keys.
+    #[inline]
+    pub fn key_type(&self) -> DataType {
+        self.keys().data_type()
+    }
+
+    /// Returns the type of the values.
+    #[inline]
+    pub fn value_type(&self) -> DataType {
+        self.values().data_type()
+    }
+
+    /// Returns the entries of all maps, a list array of structs with the fields `key` and
+    /// `value`.
+    #[inline]
+    pub fn entries(&self) -> &ListArray {
+        &self.entries
+    }
+
+    /// Returns the child array that holds the keys of all maps.
+    #[inline]
+    pub fn keys(&self) -> &ArrayRef {
+        &self.entries_struct().children()[0]
+    }
+
+    /// Returns the child array that holds the values of all maps.
+    #[inline]
+    pub fn values(&self) -> &ArrayRef {
+        &self.entries_struct().children()[1]
+    }
+
+    /// Returns the keys and the values of the map at position `index`.
+    pub fn value(&self, index: usize) -> Vec<(Scalar, Scalar)> {
+        self.entries
+            .value_range(index)
+            .map(|idx| {
+                (
+                    self.keys().scalar_value(idx),
+                    self.values().scalar_value(idx),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the position in [`MapArray::values`] of the last entry of `key` in the map at
+    /// position `index`, or `None` if the map is null or has no such key.
+    pub fn find(&self, index: usize, key: &Scalar) -> Option<usize> {
+        if self.is_null(index) {
+            return None;
+        }
+        self.entries
+            .value_range(index)
+            .rev()
+            .find(|idx| &self.keys().scalar_value(*idx) == key)
+    }
+
+    #[inline]
+    fn entries_struct(&self) -> &StructArray {
+        self.entries.values().downcast_ref::<StructArray>()
+    }
+}
+
+impl Serialize for MapArray {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.entries.serialize(serializer)
+    }
+}
+
+/// Deserializes a [`MapArray`] of the `map` data type.
+pub(crate) struct MapArraySeed(pub DataType);
+
+impl<'de> DeserializeSeed<'de> for MapArraySeed {
+    type Value = MapArray;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (key_type, value_type) = self
+            .0
+            .entry_types()
+            .ok_or_else(|| Error::custom(format!("'{}' is not a map type", self.0)))?;
+        let entries_type = DataType::list(DataType::struct_type(vec![
+            StructField::new("key", key_type),
+            StructField::new("value", value_type),
+        ]));
+        let entries = ListArraySeed(entries_type).deserialize(deserializer)?;
+        if entries.values().null_count() > 0 {
+            return Err(Error::custom("invalid map entries"));
+        }
+        Ok(MapArray {
+            data_type: self.0,
+            entries,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Int64Array, StringArray};
+
+    fn create_array() -> MapArray {
+        MapArray::new(
+            Arc::new(StringArray::from_vec(vec!["a", "b", "a", "c"])),
+            Arc::new(Int64Array::from_vec(vec![1, 2, 3, 4])),
+            vec![Some(2), None, Some(2)],
+        )
+    }
+
+    #[test]
+    fn test_array() {
+        let array = create_array();
+        assert_eq!(array.data_type().to_string(), "map<string, int64>");
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.null_count(), 1);
+        assert_eq!(
+            array.value(2),
+            vec![
+                (Scalar::from("a"), Scalar::Int64(3)),
+                (Scalar::from("c"), Scalar::Int64(4))
+            ]
+        );
+        assert_eq!(array.find(0, &Scalar::from("b")), Some(1));
+        assert_eq!(array.find(1, &Scalar::from("b")), None);
+        assert_eq!(array.find(2, &Scalar::from("b")), None);
+    }
+
+    #[test]
+    fn test_slice() {
+        let array = create_array();
+        let slice = array.slice(1, 2);
+        let slice = slice.downcast_ref::<MapArray>();
+        assert!(slice.is_null(0));
+        assert_eq!(slice.find(1, &Scalar::from("a")), Some(2));
+    }
+}
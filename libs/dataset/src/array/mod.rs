@@ -4,24 +4,39 @@ mod array_ext;
 mod bitmap;
 mod builder;
 mod data_type;
+mod list_array;
+mod map_array;
 mod null_array;
 mod primitive_array;
 mod scalar;
 mod string_array;
+mod struct_array;
 
 pub mod compute;
+pub mod temporal;
 
+/// Prevents the traits of the array types from being implemented outside of this crate, so that
+/// they can gain methods without breaking downstream code.
+mod sealed {
+    pub trait Sealed {}
+}
+
+pub(crate) use array::ArraySeed;
 pub use array::{Array, ArrayRef};
 pub use array_ext::ArrayExt;
 pub use builder::ArrayBuilder;
-pub use data_type::DataType;
+pub use data_type::{DataType, StructField};
+pub use list_array::{ListArray, ListBuilder};
+pub use map_array::MapArray;
 pub use null_array::NullArray;
 pub use primitive_array::{
-    BooleanType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
-    PrimitiveArray, PrimitiveBuilder, PrimitiveType, TimestampType,
+    BooleanType, DateType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    PrimitiveArray, PrimitiveBuilder, PrimitiveType, TimeType, TimestampType, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
 };
 pub use scalar::Scalar;
 pub use string_array::{StringArray, StringBuilder};
+pub use struct_array::StructArray;
 
 macro_rules! impl_primitive_arrays {
     ($(($ty:ident, $native_ty:ty)),*) => {
@@ -36,10 +51,16 @@ impl_primitive_arrays!(
     (Int16Array, Int16Type),
     (Int32Array, Int32Type),
     (Int64Array, Int64Type),
+    (UInt8Array, UInt8Type),
+    (UInt16Array, UInt16Type),
+    (UInt32Array, UInt32Type),
+    (UInt64Array, UInt64Type),
     (Float32Array, Float32Type),
     (Float64Array, Float64Type),
     (BooleanArray, BooleanType),
-    (TimestampArray, TimestampType)
+    (TimestampArray, TimestampType),
+    (DateArray, DateType),
+    (TimeArray, TimeType)
 );
 
 macro_rules! impl_primitive_builder {
@@ -55,8 +76,14 @@ impl_primitive_builder!(
     (Int16Builder, Int16Type),
     (Int32Builder, Int32Type),
     (Int64Builder, Int64Type),
+    (UInt8Builder, UInt8Type),
+    (UInt16Builder, UInt16Type),
+    (UInt32Builder, UInt32Type),
+    (UInt64Builder, UInt64Type),
     (Float32Builder, Float32Type),
     (Float64Builder, Float64Type),
     (BooleanBuilder, BooleanType),
-    (TimestampBuilder, TimestampType)
+    (TimestampBuilder, TimestampType),
+    (DateBuilder, DateType),
+    (TimeBuilder, TimeType)
 );
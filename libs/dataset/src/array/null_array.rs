@@ -6,6 +6,7 @@ use serde::de::{SeqAccess, Visitor};
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::array::sealed::Sealed;
 use crate::array::{Array, ArrayRef, DataType};
 
 /// An Array where all elements are nulls.
@@ -24,6 +25,8 @@ impl Debug for NullArray {
     }
 }
 
+impl Sealed for NullArray {}
+
 impl Array for NullArray {
     #[inline]
     fn as_any(&self) -> &dyn Any {
@@ -10,14 +10,15 @@ use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::array::bitmap::{Bitmap, BitmapBuilder};
+use crate::array::sealed::Sealed;
 use crate::array::{Array, ArrayBuilder, ArrayRef, DataType};
 
 pub trait NativeType:
-    Debug + Copy + Send + Sync + Default + PartialEq + Serialize + DeserializeOwned + 'static
+    Debug + Copy + Send + Sync + Default + PartialEq + Serialize + DeserializeOwned + Sealed + 'static
 {
 }
 
-pub trait PrimitiveType: Copy + Send + Sync + 'static {
+pub trait PrimitiveType: Copy + Send + Sync + Sealed + 'static {
     const DATA_TYPE: DataType;
 
     type Native: NativeType;
@@ -30,6 +31,8 @@ pub trait PrimitiveType: Copy + Send + Sync + 'static {
 macro_rules! impl_native_types {
     ($($ty:ty),*) => {
         $(
+        impl Sealed for $ty {}
+
         impl NativeType for $ty {}
         )*
     };
@@ -41,6 +44,8 @@ macro_rules! impl_primitive_types {
         #[derive(Debug, Copy, Clone)]
         pub struct $pt;
 
+        impl Sealed for $pt {}
+
         impl PrimitiveType for $pt {
             const DATA_TYPE: DataType = $dt;
             type Native = $native_ty;
@@ -49,17 +54,23 @@ macro_rules! impl_primitive_types {
     };
 }
 
-impl_native_types!(i8, i16, i32, i64, f32, f64, bool);
+impl_native_types!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64, bool);
 
 impl_primitive_types!(
     (Int8Type, i8, DataType::Int8),
     (Int16Type, i16, DataType::Int16),
     (Int32Type, i32, DataType::Int32),
     (Int64Type, i64, DataType::Int64),
+    (UInt8Type, u8, DataType::UInt8),
+    (UInt16Type, u16, DataType::UInt16),
+    (UInt32Type, u32, DataType::UInt32),
+    (UInt64Type, u64, DataType::UInt64),
     (Float32Type, f32, DataType::Float32),
     (Float64Type, f64, DataType::Float64),
     (BooleanType, bool, DataType::Boolean),
-    (TimestampType, i64, DataType::Timestamp(None))
+    (TimestampType, i64, DataType::Timestamp(None)),
+    (DateType, i32, DataType::Date),
+    (TimeType, i32, DataType::Time)
 );
 
 /// Array builder for fixed-width primitive types.
@@ -166,6 +177,8 @@ impl<T: PrimitiveType> Debug for PrimitiveArray<T> {
     }
 }
 
+impl<T: PrimitiveType> Sealed for PrimitiveArray<T> {}
+
 impl<T: PrimitiveType> Array for PrimitiveArray<T> {
     #[inline]
     fn as_any(&self) -> &dyn Any {
@@ -1,9 +1,11 @@
+use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
 
 use chrono::TimeZone;
 use serde::{Deserialize, Serialize};
 
+use crate::array::temporal::{format_date, format_time};
 use crate::array::DataType;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -13,11 +15,25 @@ pub enum Scalar {
     Int16(i16),
     Int32(i32),
     Int64(i64),
+    UInt8(u8),
+    UInt16(u16),
+    UInt32(u32),
+    UInt64(u64),
     Float32(f32),
     Float64(f64),
     Boolean(bool),
     Timestamp(i64),
+    /// Days since the unix epoch.
+    Date(i32),
+    /// Milliseconds since midnight.
+    Time(i32),
     String(Arc<str>),
+    /// A list with its element type and values.
+    List(DataType, Vec<Scalar>),
+    /// A struct with its struct type and the values of its fields.
+    Struct(DataType, Vec<Scalar>),
+    /// A map with its map type and its entries.
+    Map(DataType, Vec<(Scalar, Scalar)>),
 }
 
 impl Default for Scalar {
@@ -44,6 +60,10 @@ impl_from_numerics!(
     (i16, Int16),
     (i32, Int32),
     (i64, Int64),
+    (u8, UInt8),
+    (u16, UInt16),
+    (u32, UInt32),
+    (u64, UInt64),
     (f32, Float32),
     (f64, Float64)
 );
@@ -81,11 +101,20 @@ impl Scalar {
             Scalar::Int16(_) => DataType::Int16,
             Scalar::Int32(_) => DataType::Int32,
             Scalar::Int64(_) => DataType::Int64,
+            Scalar::UInt8(_) => DataType::UInt8,
+            Scalar::UInt16(_) => DataType::UInt16,
+            Scalar::UInt32(_) => DataType::UInt32,
+            Scalar::UInt64(_) => DataType::UInt64,
             Scalar::Float32(_) => DataType::Float32,
             Scalar::Float64(_) => DataType::Float64,
             Scalar::Boolean(_) => DataType::Boolean,
             Scalar::Timestamp(_) => DataType::Timestamp(None),
+            Scalar::Date(_) => DataType::Date,
+            Scalar::Time(_) => DataType::Time,
             Scalar::String(_) => DataType::String,
+            Scalar::List(element_type, _) => DataType::list(*element_type),
+            Scalar::Struct(data_type, _) => *data_type,
+            Scalar::Map(data_type, _) => *data_type,
         }
     }
 
@@ -100,6 +129,40 @@ impl Scalar {
     }
 }
 
+/// Values of the same type are ordered, values of different types are not comparable.
+impl PartialOrd for Scalar {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Scalar::Null, Scalar::Null) => Some(Ordering::Equal),
+            (Scalar::Int8(a), Scalar::Int8(b)) => a.partial_cmp(b),
+            (Scalar::Int16(a), Scalar::Int16(b)) => a.partial_cmp(b),
+            (Scalar::Int32(a), Scalar::Int32(b)) => a.partial_cmp(b),
+            (Scalar::Int64(a), Scalar::Int64(b)) => a.partial_cmp(b),
+            (Scalar::UInt8(a), Scalar::UInt8(b)) => a.partial_cmp(b),
+            (Scalar::UInt16(a), Scalar::UInt16(b)) => a.partial_cmp(b),
+            (Scalar::UInt32(a), Scalar::UInt32(b)) => a.partial_cmp(b),
+            (Scalar::UInt64(a), Scalar::UInt64(b)) => a.partial_cmp(b),
+            (Scalar::Float32(a), Scalar::Float32(b)) => a.partial_cmp(b),
+            (Scalar::Float64(a), Scalar::Float64(b)) => a.partial_cmp(b),
+            (Scalar::Boolean(a), Scalar::Boolean(b)) => a.partial_cmp(b),
+            (Scalar::Timestamp(a), Scalar::Timestamp(b)) => a.partial_cmp(b),
+            (Scalar::Date(a), Scalar::Date(b)) => a.partial_cmp(b),
+            (Scalar::Time(a), Scalar::Time(b)) => a.partial_cmp(b),
+            (Scalar::String(a), Scalar::String(b)) => a.partial_cmp(b),
+            (Scalar::List(a_type, a), Scalar::List(b_type, b)) if a_type == b_type => {
+                a.partial_cmp(b)
+            }
+            (Scalar::Struct(a_type, a), Scalar::Struct(b_type, b)) if a_type == b_type => {
+                a.partial_cmp(b)
+            }
+            (Scalar::Map(a_type, a), Scalar::Map(b_type, b)) if a_type == b_type => {
+                a.partial_cmp(b)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl Display for Scalar {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -108,11 +171,48 @@ impl Display for Scalar {
             Scalar::Int16(n) => write!(f, "{}", n),
             Scalar::Int32(n) => write!(f, "{}", n),
             Scalar::Int64(n) => write!(f, "{}", n),
+            Scalar::UInt8(n) => write!(f, "{}", n),
+            Scalar::UInt16(n) => write!(f, "{}", n),
+            Scalar::UInt32(n) => write!(f, "{}", n),
+            Scalar::UInt64(n) => write!(f, "{}", n),
             Scalar::Float32(n) => write!(f, "{}", n),
             Scalar::Float64(n) => write!(f, "{}", n),
             Scalar::Boolean(n) => write!(f, "{}", n),
             Scalar::Timestamp(n) => write!(f, "{}", chrono::Local.timestamp_millis(*n)),
+            Scalar::Date(n) => f.write_str(&format_date(*n)),
+            Scalar::Time(n) => f.write_str(&format_time(*n)),
             Scalar::String(n) => f.write_str(n),
+            Scalar::List(_, values) => {
+                f.write_str("[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+            Scalar::Struct(data_type, values) => {
+                f.write_str("{")?;
+                let fields = data_type.fields().unwrap_or_default();
+                for (idx, (field, value)) in fields.iter().zip(values).enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", field.name, value)?;
+                }
+                f.write_str("}")
+            }
+            Scalar::Map(_, entries) => {
+                f.write_str("{")?;
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                f.write_str("}")
+            }
         }
     }
 }
@@ -8,6 +8,7 @@ use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::array::bitmap::{Bitmap, BitmapBuilder};
+use crate::array::sealed::Sealed;
 use crate::array::{Array, ArrayBuilder, ArrayRef, DataType};
 use serde::de::{SeqAccess, Visitor};
 
@@ -113,6 +114,8 @@ impl Debug for StringArray {
     }
 }
 
+impl Sealed for StringArray {}
+
 impl Array for StringArray {
     #[inline]
     fn as_any(&self) -> &dyn Any {
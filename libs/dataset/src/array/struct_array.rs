@@ -0,0 +1,309 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::array::bitmap::{Bitmap, BitmapBuilder};
+use crate::array::compute::mask;
+use crate::array::sealed::Sealed;
+use crate::array::{
+    Array, ArrayExt, ArrayRef, ArraySeed, BooleanArray, DataType, Scalar, StructField,
+};
+
+/// An array where each element is a record of named fields.
+///
+/// Each field is stored in a child array of the same length as the struct array, the value of a
+/// field of a null struct is unspecified.
+#[derive(Clone)]
+pub struct StructArray {
+    data_type: DataType,
+    length: usize,
+    children: Vec<ArrayRef>,
+    bitmap: Option<Bitmap>,
+}
+
+impl Debug for StructArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut ls = f.debug_list();
+        for i in 0..self.len() {
+            ls.entry(&self.scalar_value(i));
+        }
+        ls.finish()
+    }
+}
+
+impl Sealed for StructArray {}
+
+impl Array for StructArray {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> ArrayRef {
+        if offset > self.len() {
+            panic!(
+                "offset (is {}) should be <= len (is {})",
+                offset,
+                self.len()
+            );
+        }
+
+        if offset + length > self.len() {
+            panic!(
+                "offset+length (is {}) should be <= len (is {})",
+                offset + length,
+                self.len()
+            );
+        }
+
+        Arc::new(StructArray {
+            data_type: self.data_type,
+            length,
+            children: self
+                .children
+                .iter()
+                .map(|child| child.slice(offset, length))
+                .collect(),
+            bitmap: self.bitmap.as_ref().map(|bitmap| bitmap.offset(offset)),
+        })
+    }
+
+    fn is_valid(&self, index: usize) -> bool {
+        if index >= self.len() {
+            panic!("index (is {}) should be <= len (is {})", index, self.len());
+        }
+
+        match &self.bitmap {
+            Some(bitmap) => bitmap.is_valid(index),
+            None => true,
+        }
+    }
+
+    fn null_count(&self) -> usize {
+        (0..self.len()).filter(|i| self.is_null(*i)).count()
+    }
+}
+
+impl PartialEq for StructArray {
+    fn eq(&self, other: &Self) -> bool {
+        self.data_type == other.data_type
+            && self.len() == other.len()
+            && (0..self.len()).all(|i| self.scalar_value(i) == other.scalar_value(i))
+    }
+}
+
+impl StructArray {
+    /// Creates a struct array from the child array of each field, and whether each struct is
+    /// valid, `None` if all of them are.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the children or the validity have different lengths.
+    pub fn new(
+        length: usize,
+        fields: Vec<(String, ArrayRef)>,
+        validity: Option<Vec<bool>>,
+    ) -> Self {
+        for (name, child) in &fields {
+            if child.len() != length {
+                panic!(
+                    "len of field '{}' (is {}) should be == len (is {})",
+                    name,
+                    child.len(),
+                    length
+                );
+            }
+        }
+
+        let bitmap = validity.map(|validity| {
+            if validity.len() != length {
+                panic!(
+                    "len of validity (is {}) should be == len (is {})",
+                    validity.len(),
+                    length
+                );
+            }
+            let mut bitmap = BitmapBuilder::default();
+            for (idx, is_valid) in validity.into_iter().enumerate() {
+                bitmap.set(idx, is_valid);
+            }
+            bitmap
+        });
+
+        let (struct_fields, children) = fields
+            .into_iter()
+            .map(|(name, child)| (StructField::new(name, child.data_type()), child))
+            .unzip();
+        Self {
+            data_type: DataType::struct_type(struct_fields),
+            length,
+            children,
+            bitmap: bitmap
+                .filter(|bitmap| !bitmap.is_empty())
+                .map(BitmapBuilder::finish),
+        }
+    }
+
+    /// Returns the fields of the structs.
+    #[inline]
+    pub fn fields(&self) -> &'static [StructField] {
+        self.data_type.fields().unwrap_or_default()
+    }
+
+    /// Returns the child arrays, in the order of the fields.
+    #[inline]
+    pub fn children(&self) -> &[ArrayRef] {
+        &self.children
+    }
+
+    /// Returns the values of the field `name`, null for the null structs, or `None` if there is
+    /// no such field.
+    pub fn field(&self, name: &str) -> Option<ArrayRef> {
+        let idx = self.fields().iter().position(|field| field.name == name)?;
+        let child = self.children[idx].clone();
+        Some(match self.bitmap {
+            Some(_) => mask(
+                child,
+                &BooleanArray::from_vec((0..self.len()).map(|i| self.is_valid(i)).collect()),
+            ),
+            None => child,
+        })
+    }
+
+    /// Returns the values of the fields of the struct at position `index`.
+    pub fn value(&self, index: usize) -> Vec<Scalar> {
+        self.children
+            .iter()
+            .map(|child| child.scalar_value(index))
+            .collect()
+    }
+}
+
+impl Serialize for StructArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let validity = (0..self.len())
+            .map(|i| self.is_valid(i))
+            .collect::<Vec<_>>();
+
+        let mut tuple = serializer.serialize_tuple(self.children.len() + 1)?;
+        tuple.serialize_element(&validity)?;
+        for child in &self.children {
+            tuple.serialize_element(&**child)?;
+        }
+        tuple.end()
+    }
+}
+
+/// Deserializes a [`StructArray`] of the `struct` data type.
+pub(crate) struct StructArraySeed(pub DataType);
+
+impl<'de> DeserializeSeed<'de> for StructArraySeed {
+    type Value = StructArray;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ArrayVisitor(&'static [StructField]);
+
+        impl<'de> Visitor<'de> for ArrayVisitor {
+            type Value = StructArray;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("StructArray")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let validity = seq
+                    .next_element::<Vec<bool>>()?
+                    .ok_or_else(|| Error::custom("expect validity"))?;
+                let mut fields = Vec::with_capacity(self.0.len());
+                for field in self.0 {
+                    let child = seq
+                        .next_element_seed(ArraySeed(field.data_type))?
+                        .ok_or_else(|| Error::custom("expect children"))?;
+                    if child.len() != validity.len() {
+                        return Err(Error::custom("invalid child length"));
+                    }
+                    fields.push((field.name.clone(), child));
+                }
+                Ok(StructArray::new(validity.len(), fields, Some(validity)))
+            }
+        }
+
+        let fields = self
+            .0
+            .fields()
+            .ok_or_else(|| Error::custom(format!("'{}' is not a struct type", self.0)))?;
+        deserializer.deserialize_tuple(fields.len() + 1, ArrayVisitor(fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Int64Array, StringArray};
+
+    fn create_array() -> StructArray {
+        StructArray::new(
+            3,
+            vec![
+                (
+                    "a".to_string(),
+                    Arc::new(Int64Array::from_vec(vec![1, 2, 3])) as ArrayRef,
+                ),
+                (
+                    "b".to_string(),
+                    Arc::new(StringArray::from_vec(vec!["x", "y", "z"])),
+                ),
+            ],
+            Some(vec![true, false, true]),
+        )
+    }
+
+    #[test]
+    fn test_array() {
+        let array = create_array();
+        assert_eq!(array.data_type().to_string(), "struct<a: int64, b: string>");
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.null_count(), 1);
+        assert_eq!(array.value(2), vec![Scalar::Int64(3), Scalar::from("z")]);
+        assert_eq!(
+            &*array.field("a").unwrap(),
+            &Int64Array::from_opt_vec(vec![Some(1), None, Some(3)]) as &dyn Array
+        );
+        assert!(array.field("c").is_none());
+    }
+
+    #[test]
+    fn test_slice() {
+        let array = create_array();
+        let slice = array.slice(1, 2);
+        let slice = slice.downcast_ref::<StructArray>();
+        assert!(slice.is_null(0));
+        assert_eq!(
+            &*slice.field("b").unwrap(),
+            &StringArray::from_opt_vec(vec![None, Some("z")]) as &dyn Array
+        );
+    }
+}
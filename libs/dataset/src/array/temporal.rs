@@ -0,0 +1,116 @@
+//! Conversions of the values of the `Date` and `Time` types.
+//!
+//! A date is the number of days since 1970-01-01, a time is the number of milliseconds since
+//! midnight, both are stored in `i32`.
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+
+/// The number of milliseconds in a day.
+pub const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// The number of days from 0001-01-01 to 1970-01-01.
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Returns the calendar date of `days` since the unix epoch, `None` if it is out of range.
+pub fn date_to_naive(days: i32) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(days.checked_add(UNIX_EPOCH_DAYS_FROM_CE)?)
+}
+
+/// Returns the number of days since the unix epoch of `date`.
+pub fn naive_to_date(date: NaiveDate) -> i32 {
+    date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE
+}
+
+/// Returns the time of day of `millis` since midnight, `None` if it is not in a day.
+pub fn time_to_naive(millis: i32) -> Option<NaiveTime> {
+    if millis < 0 {
+        return None;
+    }
+    NaiveTime::from_num_seconds_from_midnight_opt(
+        millis as u32 / 1000,
+        millis as u32 % 1000 * 1_000_000,
+    )
+}
+
+/// Returns the number of milliseconds since midnight of `time`.
+pub fn naive_to_time(time: NaiveTime) -> i32 {
+    (time.num_seconds_from_midnight() * 1000 + time.nanosecond() / 1_000_000) as i32
+}
+
+/// Returns the date of the timestamp `millis` since the unix epoch in UTC.
+pub fn timestamp_to_date(millis: i64) -> i32 {
+    millis.div_euclid(MILLIS_PER_DAY) as i32
+}
+
+/// Returns the time of day of the timestamp `millis` since the unix epoch in UTC.
+pub fn timestamp_to_time(millis: i64) -> i32 {
+    millis.rem_euclid(MILLIS_PER_DAY) as i32
+}
+
+/// Returns the timestamp of the midnight of `date` in UTC.
+pub fn date_to_timestamp(date: i32) -> i64 {
+    date as i64 * MILLIS_PER_DAY
+}
+
+/// Parses a date in the format `YYYY-MM-DD`.
+pub fn parse_date(s: &str) -> Option<i32> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .map(naive_to_date)
+}
+
+/// Parses a time in the format `HH:MM[:SS[.fff]]`.
+pub fn parse_time(s: &str) -> Option<i32> {
+    ["%H:%M:%S%.f", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(s, format).ok())
+        .map(naive_to_time)
+}
+
+/// Formats a date as `YYYY-MM-DD`.
+pub fn format_date(date: i32) -> String {
+    match date_to_naive(date) {
+        Some(date) => date.format("%Y-%m-%d").to_string(),
+        None => date.to_string(),
+    }
+}
+
+/// Formats a time as `HH:MM:SS`, with the milliseconds if they are not zero.
+pub fn format_time(time: i32) -> String {
+    match time_to_naive(time) {
+        Some(time) => time.format("%H:%M:%S%.f").to_string(),
+        None => time.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2024-01-01"), Some(19723));
+        assert_eq!(parse_date("1969-12-31"), Some(-1));
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(format_date(19723), "2024-01-01");
+        assert_eq!(format_date(-1), "1969-12-31");
+
+        assert_eq!(timestamp_to_date(1704067200000), 19723);
+        assert_eq!(timestamp_to_date(-1), -1);
+        assert_eq!(date_to_timestamp(19723), 1704067200000);
+    }
+
+    #[test]
+    fn test_time() {
+        assert_eq!(parse_time("00:00:00"), Some(0));
+        assert_eq!(parse_time("12:30"), Some(45_000_000));
+        assert_eq!(parse_time("23:59:59.999"), Some(86_399_999));
+        assert_eq!(parse_time("24:00:00"), None);
+        assert_eq!(format_time(45_000_000), "12:30:00");
+        assert_eq!(format_time(86_399_999), "23:59:59.999");
+
+        assert_eq!(timestamp_to_time(1704067200000 + 1500), 1500);
+        assert_eq!(timestamp_to_time(-1), 86_399_999);
+    }
+}
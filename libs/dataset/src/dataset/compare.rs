@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use crate::array::{ArrayExt, Scalar};
+use crate::dataset::DataSet;
+
+/// Maximum number of rows listed in an order-insensitive diff.
+const MAX_DIFF_ROWS: usize = 10;
+
+impl DataSet {
+    fn row(&self, index: usize) -> Vec<Scalar> {
+        self.columns()
+            .iter()
+            .map(|column| column.scalar_value(index))
+            .collect()
+    }
+
+    fn diff_schema(&self, expected: &DataSet) -> Option<String> {
+        let actual_schema = self.schema();
+        let expected_schema = expected.schema();
+        let actual_fields = actual_schema.fields();
+        let expected_fields = expected_schema.fields();
+
+        if actual_fields.len() != expected_fields.len() {
+            return Some(format!(
+                "expect {} columns, actual {} columns",
+                expected_fields.len(),
+                actual_fields.len()
+            ));
+        }
+
+        for (idx, (actual, expected)) in actual_fields.iter().zip(expected_fields).enumerate() {
+            if actual.qualified_name() != expected.qualified_name() {
+                return Some(format!(
+                    "column {}: expect name '{}', actual name '{}'",
+                    idx,
+                    expected.qualified_name(),
+                    actual.qualified_name()
+                ));
+            }
+            if actual.data_type != expected.data_type {
+                return Some(format!(
+                    "column {} '{}': expect datatype {}, actual datatype {}",
+                    idx, actual.name, expected.data_type, actual.data_type
+                ));
+            }
+        }
+
+        None
+    }
+
+    /// Returns a description of the first difference between this dataset and `expected`,
+    /// or `None` if they are equal.
+    pub fn diff(&self, expected: &DataSet) -> Option<String> {
+        if let Some(diff) = self.diff_schema(expected) {
+            return Some(diff);
+        }
+
+        for row in 0..self.len().min(expected.len()) {
+            for (idx, field) in self.schema().fields().iter().enumerate() {
+                let actual_value = self.columns()[idx].scalar_value(row);
+                let expected_value = expected.columns()[idx].scalar_value(row);
+                if actual_value != expected_value {
+                    return Some(format!(
+                        "row {} column '{}': expect {:?}, actual {:?}\n  expect row: {}\n  actual row: {}",
+                        row,
+                        field.name,
+                        expected_value,
+                        actual_value,
+                        format_row(&expected.row(row)),
+                        format_row(&self.row(row)),
+                    ));
+                }
+            }
+        }
+
+        if self.len() != expected.len() {
+            return Some(format!(
+                "expect {} rows, actual {} rows",
+                expected.len(),
+                self.len()
+            ));
+        }
+
+        None
+    }
+
+    /// Like [`DataSet::diff`], but ignores the order of rows.
+    pub fn diff_unordered(&self, expected: &DataSet) -> Option<String> {
+        if let Some(diff) = self.diff_schema(expected) {
+            return Some(diff);
+        }
+
+        let mut counter: HashMap<String, (Vec<Scalar>, isize)> = HashMap::new();
+        for row in 0..expected.len() {
+            let values = expected.row(row);
+            counter
+                .entry(format!("{:?}", values))
+                .or_insert_with(|| (values, 0))
+                .1 += 1;
+        }
+        for row in 0..self.len() {
+            let values = self.row(row);
+            counter
+                .entry(format!("{:?}", values))
+                .or_insert_with(|| (values, 0))
+                .1 -= 1;
+        }
+
+        let mut counter = counter.into_iter().collect::<Vec<_>>();
+        counter.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut missing = Vec::new();
+        let mut unexpected = Vec::new();
+        for (_, (values, count)) in counter {
+            if count > 0 {
+                missing.extend(std::iter::repeat(values).take(count as usize));
+            } else if count < 0 {
+                unexpected.extend(std::iter::repeat(values).take(-count as usize));
+            }
+        }
+
+        if missing.is_empty() && unexpected.is_empty() {
+            return None;
+        }
+
+        let mut msg = format!(
+            "expect {} rows, actual {} rows, {} missing, {} unexpected",
+            expected.len(),
+            self.len(),
+            missing.len(),
+            unexpected.len()
+        );
+        for (title, rows) in [("missing", &missing), ("unexpected", &unexpected)].iter() {
+            for values in rows.iter().take(MAX_DIFF_ROWS) {
+                msg.push_str(&format!("\n  {}: {}", title, format_row(values)));
+            }
+            if rows.len() > MAX_DIFF_ROWS {
+                msg.push_str(&format!(
+                    "\n  ... and {} more {} rows",
+                    rows.len() - MAX_DIFF_ROWS,
+                    title
+                ));
+            }
+        }
+        Some(msg)
+    }
+
+    /// Asserts that this dataset is equal to `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a description of the first difference if they are not equal.
+    #[track_caller]
+    pub fn assert_eq(&self, expected: &DataSet) {
+        if let Some(diff) = self.diff(expected) {
+            panic!("datasets are not equal: {}", diff);
+        }
+    }
+
+    /// Asserts that this dataset contains the same rows as `expected`, in any order.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the missing and unexpected rows if they are not equal.
+    #[track_caller]
+    pub fn assert_eq_unordered(&self, expected: &DataSet) {
+        if let Some(diff) = self.diff_unordered(expected) {
+            panic!("datasets are not equal: {}", diff);
+        }
+    }
+}
+
+fn format_row(values: &[Scalar]) -> String {
+    let values = values
+        .iter()
+        .map(|value| format!("{:?}", value))
+        .collect::<Vec<_>>();
+    format!("({})", values.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{ArrayRef, DataType, Int32Array, Int64Array, StringArray};
+    use crate::dataset::{Field, Schema};
+
+    fn create_dataset(a: Vec<i32>, b: Vec<&str>) -> DataSet {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        DataSet::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from_vec(a)) as ArrayRef,
+                Arc::new(StringArray::from_vec(b)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff() {
+        let dataset = create_dataset(vec![1, 2, 3], vec!["a", "b", "c"]);
+        assert_eq!(dataset.diff(&dataset), None);
+        dataset.assert_eq(&dataset);
+
+        let other = create_dataset(vec![1, 2, 4], vec!["a", "b", "c"]);
+        let diff = dataset.diff(&other).unwrap();
+        assert!(diff.starts_with("row 2 column 'a': expect Int32(4), actual Int32(3)"));
+
+        let other = create_dataset(vec![1, 2], vec!["a", "b"]);
+        assert_eq!(
+            dataset.diff(&other).unwrap(),
+            "expect 2 rows, actual 3 rows"
+        );
+
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let other = DataSet::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from_vec(vec![1, 2, 3])) as ArrayRef,
+                Arc::new(StringArray::from_vec(vec!["a", "b", "c"])),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            dataset.diff(&other).unwrap(),
+            "column 0 'a': expect datatype int64, actual datatype int32"
+        );
+    }
+
+    #[test]
+    fn test_diff_unordered() {
+        let dataset = create_dataset(vec![1, 2, 3], vec!["a", "b", "c"]);
+        let other = create_dataset(vec![3, 1, 2], vec!["c", "a", "b"]);
+        assert!(dataset.diff(&other).is_some());
+        assert_eq!(dataset.diff_unordered(&other), None);
+        dataset.assert_eq_unordered(&other);
+
+        let other = create_dataset(vec![3, 1, 1], vec!["c", "a", "a"]);
+        assert_eq!(
+            dataset.diff_unordered(&other).unwrap(),
+            "expect 3 rows, actual 3 rows, 1 missing, 1 unexpected\n  \
+            missing: (Int32(1), String(\"a\"))\n  \
+            unexpected: (Int32(2), String(\"b\"))"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "datasets are not equal")]
+    fn test_assert_eq() {
+        let dataset = create_dataset(vec![1, 2, 3], vec!["a", "b", "c"]);
+        dataset.assert_eq(&create_dataset(vec![1, 2, 3], vec!["a", "b", "d"]));
+    }
+}
@@ -12,20 +12,29 @@ use once_cell::sync::Lazy;
 use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 
+use crate::array::compute::new_null_array;
+use crate::array::temporal::{parse_date, parse_time};
 use crate::array::{
-    ArrayRef, BooleanBuilder, BooleanType, DataType, Float32Builder, Float32Type, Float64Builder,
-    Float64Type, Int16Builder, Int16Type, Int32Builder, Int32Type, Int64Builder, Int64Type,
-    Int8Builder, Int8Type, NullArray, PrimitiveBuilder, PrimitiveType, StringBuilder,
-    TimestampBuilder, TimestampType,
+    Array, ArrayRef, BooleanBuilder, BooleanType, DataType, DateBuilder, DateType, Float32Builder,
+    Float32Type, Float64Builder, Float64Type, Int16Builder, Int16Type, Int32Builder, Int32Type,
+    Int64Builder, Int64Type, Int8Builder, Int8Type, NullArray, PrimitiveBuilder, PrimitiveType,
+    StringBuilder, TimeBuilder, TimeType, TimestampBuilder, TimestampType, UInt16Builder,
+    UInt16Type, UInt32Builder, UInt32Type, UInt64Builder, UInt64Type, UInt8Builder, UInt8Type,
 };
 use crate::dataset::{DataSet, Field, Schema, SchemaRef};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CsvOptions {
     #[serde(default = "default_delimiter")]
     pub delimiter: u8,
     #[serde(default)]
     pub has_header: bool,
+    /// Widens the `Int32` columns to `Int64`, and the `Int64` columns to `Float64`, when a value
+    /// is out of their range, instead of failing.
+    ///
+    /// The batches read after the overflow have the widened schema.
+    #[serde(default)]
+    pub widen_on_overflow: bool,
 }
 
 fn default_delimiter() -> u8 {
@@ -37,6 +46,7 @@ impl Default for CsvOptions {
         Self {
             delimiter: b',',
             has_header: false,
+            widen_on_overflow: false,
         }
     }
 }
@@ -51,7 +61,28 @@ impl CsvOptions {
             .delimiter(self.delimiter)
             .has_headers(self.has_header)
             .from_reader(rdr);
-        CsvReader { reader, schema }
+        CsvReader {
+            reader,
+            schema,
+            column_indices: None,
+            widen_on_overflow: self.widen_on_overflow,
+            records: vec![StringRecord::new(); RECORDS_PER_READ],
+            last_batch_len: 0,
+        }
+    }
+
+    /// Returns the names of the columns in the header of the file, `None` if it has no header.
+    pub fn headers_from_path(&self, path: impl AsRef<Path>) -> Result<Option<Vec<String>>> {
+        if !self.has_header {
+            return Ok(None);
+        }
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(true)
+            .from_reader(File::open(path)?);
+        Ok(Some(
+            reader.headers()?.iter().map(ToString::to_string).collect(),
+        ))
     }
 
     pub fn infer_schema_from_path(&self, path: impl AsRef<Path>) -> Result<SchemaRef> {
@@ -96,19 +127,14 @@ impl CsvOptions {
             let field_name = &headers[i];
 
             match possibilities.len() {
+                0 => fields.push(Field::new(field_name, DataType::String)),
                 1 => {
                     for data_type in possibilities.iter() {
                         fields.push(Field::new(field_name, *data_type));
                     }
                 }
-                2 => {
-                    if possibilities.contains(&DataType::Int64)
-                        && possibilities.contains(&DataType::Float64)
-                    {
-                        fields.push(Field::new(field_name, DataType::Float64));
-                    } else {
-                        fields.push(Field::new(field_name, DataType::String));
-                    }
+                _ if possibilities.iter().all(DataType::is_numeric) => {
+                    fields.push(Field::new(field_name, DataType::Float64));
                 }
                 _ => fields.push(Field::new(field_name, DataType::String)),
             }
@@ -118,35 +144,117 @@ impl CsvOptions {
     }
 }
 
+const RECORDS_PER_READ: usize = 100;
+
 pub struct CsvReader<R> {
     reader: csv::Reader<R>,
     schema: SchemaRef,
+    /// The index in the records of each field of the schema, `None` for the fields that are
+    /// missing from the records.
+    column_indices: Option<Vec<Option<usize>>>,
+    widen_on_overflow: bool,
+    /// Reused by every batch, so the buffers of the records are only allocated once.
+    records: Vec<StringRecord>,
+    /// The length of the previous batch, used to preallocate the builders of the next one.
+    last_batch_len: usize,
 }
 
 impl<R: Read> CsvReader<R> {
+    /// Reads the fields of the schema from the columns at `column_indices` instead of by
+    /// position, the fields without a column are null.
+    pub fn with_column_indices(self, column_indices: Vec<Option<usize>>) -> Self {
+        assert_eq!(column_indices.len(), self.schema.fields().len());
+        Self {
+            column_indices: Some(column_indices),
+            ..self
+        }
+    }
+
     pub fn read_batch(&mut self, batch_size: Option<usize>) -> Result<DataSet> {
         let mut total_count = batch_size.unwrap_or(usize::MAX);
-        let mut batch_records = vec![StringRecord::new(); 100];
-        let mut builders = create_builders(&self.schema);
+        let capacity = match batch_size {
+            Some(batch_size) => self.last_batch_len.min(batch_size),
+            None => self.last_batch_len,
+        };
+        let mut builders = create_builders(&self.schema, capacity);
+        let mut batch_len = 0;
 
         while total_count > 0 {
-            let read_count = batch_records.len().min(total_count);
-            let count = self.read_batch_records(&mut batch_records[..read_count])?;
+            let read_count = self.records.len().min(total_count);
+            let count = self.read_batch_records(read_count)?;
             if count == 0 {
                 break;
             }
             total_count -= count;
-            append_data(&self.schema, &mut builders, &batch_records[..count])?;
+            batch_len += count;
+            if self.widen_on_overflow {
+                self.widen_schema(&mut builders, count, capacity)?;
+            }
+            append_data(
+                &self.schema,
+                self.column_indices.as_deref(),
+                &mut builders,
+                &self.records[..count],
+            )?;
         }
 
+        self.last_batch_len = batch_len;
         create_dataset(self.schema.clone(), builders)
     }
 
-    fn read_batch_records(&mut self, records: &mut [StringRecord]) -> Result<usize> {
+    /// The schema of the batches, which differs from the schema the reader was opened with after
+    /// a column was widened.
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Widens the columns that have values out of range in the first `count` records, and
+    /// converts the values already appended to their builders.
+    fn widen_schema(
+        &mut self,
+        builders: &mut [Box<dyn Any>],
+        count: usize,
+        capacity: usize,
+    ) -> Result<()> {
+        let mut fields = None;
+        for (idx, field) in self.schema.fields().iter().enumerate() {
+            let column = match &self.column_indices {
+                Some(column_indices) => column_indices[idx],
+                None => Some(idx),
+            };
+            let column = match column {
+                Some(column) => column,
+                None => continue,
+            };
+
+            let mut data_type = field.data_type;
+            for record in &self.records[..count] {
+                if let Some(widened) = record
+                    .get(column)
+                    .and_then(|value| widened_type(data_type, value))
+                {
+                    data_type = widened;
+                }
+            }
+            if data_type != field.data_type {
+                let builder = std::mem::replace(&mut builders[idx], Box::new(0usize));
+                builders[idx] = widen_builder(builder, field.data_type, data_type, capacity);
+                fields.get_or_insert_with(|| self.schema.fields().to_vec())[idx].data_type =
+                    data_type;
+            }
+        }
+
+        if let Some(fields) = fields {
+            self.schema = Arc::new(Schema::try_new(fields)?);
+        }
+        Ok(())
+    }
+
+    fn read_batch_records(&mut self, count: usize) -> Result<usize> {
         let mut num_records = 0;
 
-        while num_records < records.len() {
-            if !self.reader.read_record(&mut records[num_records])? {
+        while num_records < count {
+            if !self.reader.read_record(&mut self.records[num_records])? {
                 break;
             }
             num_records += 1;
@@ -182,27 +290,96 @@ fn infer_field_schema(string: &str) -> DataType {
     } else if DECIMAL_RE.is_match(string) {
         DataType::Float64
     } else if INTEGER_RE.is_match(string) {
-        DataType::Int64
+        if i64::from_str(string).is_err() && u64::from_str(string).is_ok() {
+            DataType::UInt64
+        } else {
+            DataType::Int64
+        }
     } else {
         DataType::String
     }
 }
 
-fn create_builders(schema: &Schema) -> Vec<Box<dyn Any>> {
+/// Returns the type `data_type` is widened to for `value`, `None` if `value` is in its range or
+/// is not an integer.
+fn widened_type(data_type: DataType, value: &str) -> Option<DataType> {
+    let digits = value
+        .strip_prefix(|c| c == '-' || c == '+')
+        .unwrap_or(value);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    match data_type {
+        DataType::Int32 if i32::from_str(value).is_ok() => None,
+        DataType::Int32 if i64::from_str(value).is_ok() => Some(DataType::Int64),
+        DataType::Int32 => Some(DataType::Float64),
+        DataType::Int64 if i64::from_str(value).is_ok() => None,
+        DataType::Int64 => Some(DataType::Float64),
+        _ => None,
+    }
+}
+
+macro_rules! widen_builder {
+    ($builder:expr, $from:ty, $to:ty, $capacity:expr, $f:expr) => {{
+        let array = $builder
+            .downcast::<PrimitiveBuilder<$from>>()
+            .unwrap()
+            .finish();
+        let mut builder = PrimitiveBuilder::<$to>::with_capacity($capacity.max(array.len()));
+        for value in array.iter_opt() {
+            builder.append_opt(value.map($f));
+        }
+        Box::new(builder) as Box<dyn Any>
+    }};
+}
+
+fn widen_builder(
+    builder: Box<dyn Any>,
+    from: DataType,
+    to: DataType,
+    capacity: usize,
+) -> Box<dyn Any> {
+    match (from, to) {
+        (DataType::Int32, DataType::Int64) => {
+            widen_builder!(builder, Int32Type, Int64Type, capacity, i64::from)
+        }
+        (DataType::Int32, DataType::Float64) => {
+            widen_builder!(builder, Int32Type, Float64Type, capacity, f64::from)
+        }
+        (DataType::Int64, DataType::Float64) => {
+            widen_builder!(builder, Int64Type, Float64Type, capacity, |value| value
+                as f64)
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn create_builders(schema: &Schema, capacity: usize) -> Vec<Box<dyn Any>> {
     schema
         .fields()
         .iter()
         .map(|field| match field.data_type {
             DataType::Null => Box::new(0usize) as Box<dyn Any>,
-            DataType::Int8 => Box::new(Int8Builder::default()) as Box<dyn Any>,
-            DataType::Int16 => Box::new(Int16Builder::default()) as Box<dyn Any>,
-            DataType::Int32 => Box::new(Int32Builder::default()) as Box<dyn Any>,
-            DataType::Int64 => Box::new(Int64Builder::default()) as Box<dyn Any>,
-            DataType::Float32 => Box::new(Float32Builder::default()) as Box<dyn Any>,
-            DataType::Float64 => Box::new(Float64Builder::default()) as Box<dyn Any>,
-            DataType::Boolean => Box::new(BooleanBuilder::default()) as Box<dyn Any>,
-            DataType::Timestamp(_) => Box::new(TimestampBuilder::default()) as Box<dyn Any>,
-            DataType::String => Box::new(StringBuilder::default()) as Box<dyn Any>,
+            DataType::Int8 => Box::new(Int8Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::Int16 => Box::new(Int16Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::Int32 => Box::new(Int32Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::Int64 => Box::new(Int64Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::UInt8 => Box::new(UInt8Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::UInt16 => Box::new(UInt16Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::UInt32 => Box::new(UInt32Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::UInt64 => Box::new(UInt64Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::Float32 => Box::new(Float32Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::Float64 => Box::new(Float64Builder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::Boolean => Box::new(BooleanBuilder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::Timestamp(_) => {
+                Box::new(TimestampBuilder::with_capacity(capacity)) as Box<dyn Any>
+            }
+            DataType::Date => Box::new(DateBuilder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::Time => Box::new(TimeBuilder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::String => Box::new(StringBuilder::with_capacity(capacity)) as Box<dyn Any>,
+            DataType::List(_) | DataType::Struct(_) | DataType::Map(_, _) => {
+                Box::new(0usize) as Box<dyn Any>
+            }
         })
         .collect::<Vec<_>>()
 }
@@ -211,14 +388,14 @@ macro_rules! append_value {
     ($builder:expr, $records:expr, $idx:expr, $ty:ty) => {{
         let builder = $builder.downcast_mut::<PrimitiveBuilder<$ty>>().unwrap();
         for record in $records {
-            match record.get($idx) {
+            match $idx.and_then(|idx| record.get(idx)) {
                 Some(value) => {
                     let value =
                         <$ty as PrimitiveType>::Native::from_str(value).with_context(|| {
                             format!(
                                 "failed to parse csv record as {} at index {}: {}",
                                 <$ty>::DATA_TYPE,
-                                $idx,
+                                $idx.unwrap_or_default(),
                                 value
                             )
                         })?;
@@ -230,28 +407,75 @@ macro_rules! append_value {
     }};
 }
 
+macro_rules! append_temporal_value {
+    ($builder:expr, $records:expr, $idx:expr, $ty:ty, $parse:expr) => {{
+        let builder = $builder.downcast_mut::<PrimitiveBuilder<$ty>>().unwrap();
+        for record in $records {
+            match $idx.and_then(|idx| record.get(idx)) {
+                Some(value) => {
+                    let value = $parse(value).with_context(|| {
+                        format!(
+                            "failed to parse csv record as {} at index {}: {}",
+                            <$ty>::DATA_TYPE,
+                            $idx.unwrap_or_default(),
+                            value
+                        )
+                    })?;
+                    builder.append(value);
+                }
+                None => builder.append_null(),
+            }
+        }
+    }};
+}
+
 fn append_data(
     schema: &Schema,
+    column_indices: Option<&[Option<usize>]>,
     builders: &mut Vec<Box<dyn Any>>,
     records: &[StringRecord],
 ) -> Result<()> {
     for (idx, field) in schema.fields().iter().enumerate() {
+        let column = match column_indices {
+            Some(column_indices) => column_indices[idx],
+            None => Some(idx),
+        };
         match field.data_type {
             DataType::Null => *builders[idx].downcast_mut::<usize>().unwrap() += records.len(),
-            DataType::Int8 => append_value!(builders[idx], records, idx, Int8Type),
-            DataType::Int16 => append_value!(builders[idx], records, idx, Int16Type),
-            DataType::Int32 => append_value!(builders[idx], records, idx, Int32Type),
-            DataType::Int64 => append_value!(builders[idx], records, idx, Int64Type),
-            DataType::Float32 => append_value!(builders[idx], records, idx, Float32Type),
-            DataType::Float64 => append_value!(builders[idx], records, idx, Float64Type),
-            DataType::Boolean => append_value!(builders[idx], records, idx, BooleanType),
-            DataType::Timestamp(_) => append_value!(builders[idx], records, idx, TimestampType),
+            DataType::Int8 => append_value!(builders[idx], records, column, Int8Type),
+            DataType::Int16 => append_value!(builders[idx], records, column, Int16Type),
+            DataType::Int32 => append_value!(builders[idx], records, column, Int32Type),
+            DataType::Int64 => append_value!(builders[idx], records, column, Int64Type),
+            DataType::UInt8 => append_value!(builders[idx], records, column, UInt8Type),
+            DataType::UInt16 => append_value!(builders[idx], records, column, UInt16Type),
+            DataType::UInt32 => append_value!(builders[idx], records, column, UInt32Type),
+            DataType::UInt64 => append_value!(builders[idx], records, column, UInt64Type),
+            DataType::Float32 => append_value!(builders[idx], records, column, Float32Type),
+            DataType::Float64 => append_value!(builders[idx], records, column, Float64Type),
+            DataType::Boolean => append_value!(builders[idx], records, column, BooleanType),
+            DataType::Timestamp(_) => append_value!(builders[idx], records, column, TimestampType),
+            DataType::Date => {
+                append_temporal_value!(builders[idx], records, column, DateType, parse_date)
+            }
+            DataType::Time => {
+                append_temporal_value!(builders[idx], records, column, TimeType, parse_time)
+            }
             DataType::String => {
                 let builder = builders[idx].downcast_mut::<StringBuilder>().unwrap();
                 for record in records {
-                    builder.append_opt(record.get(idx));
+                    builder.append_opt(column.and_then(|column| record.get(column)));
                 }
             }
+            DataType::List(_) if !records.is_empty() => {
+                anyhow::bail!("the list type is not supported by csv: '{}'", field.name)
+            }
+            DataType::Struct(_) if !records.is_empty() => {
+                anyhow::bail!("the struct type is not supported by csv: '{}'", field.name)
+            }
+            DataType::Map(_, _) if !records.is_empty() => {
+                anyhow::bail!("the map type is not supported by csv: '{}'", field.name)
+            }
+            DataType::List(_) | DataType::Struct(_) | DataType::Map(_, _) => {}
         }
     }
 
@@ -276,15 +500,154 @@ fn create_dataset(schema: SchemaRef, builders: Vec<Box<dyn Any>>) -> Result<Data
             DataType::Int16 => create_array!(builder, Int16Type),
             DataType::Int32 => create_array!(builder, Int32Type),
             DataType::Int64 => create_array!(builder, Int64Type),
+            DataType::UInt8 => create_array!(builder, UInt8Type),
+            DataType::UInt16 => create_array!(builder, UInt16Type),
+            DataType::UInt32 => create_array!(builder, UInt32Type),
+            DataType::UInt64 => create_array!(builder, UInt64Type),
             DataType::Float32 => create_array!(builder, Float32Type),
             DataType::Float64 => create_array!(builder, Float64Type),
             DataType::Boolean => create_array!(builder, BooleanType),
             DataType::Timestamp(_) => create_array!(builder, TimestampType),
+            DataType::Date => create_array!(builder, DateType),
+            DataType::Time => create_array!(builder, TimeType),
             DataType::String => {
                 let builder = *builder.downcast::<StringBuilder>().unwrap();
                 Arc::new(builder.finish())
             }
+            DataType::List(_) | DataType::Struct(_) | DataType::Map(_, _) => {
+                new_null_array(field.data_type, *builder.downcast_ref::<usize>().unwrap())
+            }
         });
     }
     DataSet::try_new(schema, columns)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::array::{DateArray, Float64Array, Int32Array, Int64Array, TimeArray, UInt64Array};
+
+    #[test]
+    fn test_widen_on_overflow() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::Int32),
+            ])
+            .unwrap(),
+        );
+        let data = "1,1\n2,3000000000\n3,99999999999999999999\n";
+
+        assert!(CsvOptions::default()
+            .open(schema.clone(), Cursor::new(data))
+            .read_batch(None)
+            .is_err());
+
+        let options = CsvOptions {
+            widen_on_overflow: true,
+            ..CsvOptions::default()
+        };
+        let mut reader = options.open(schema.clone(), Cursor::new(data));
+        let dataset = reader.read_batch(Some(1)).unwrap();
+        assert_eq!(dataset.schema(), schema);
+
+        let dataset = reader.read_batch(Some(1)).unwrap();
+        assert_eq!(dataset.schema().fields()[0].data_type, DataType::Int32);
+        dataset.assert_eq(
+            &DataSet::try_new(
+                dataset.schema(),
+                vec![
+                    Arc::new(Int32Array::from_vec(vec![2])) as ArrayRef,
+                    Arc::new(Int64Array::from_vec(vec![3000000000])),
+                ],
+            )
+            .unwrap(),
+        );
+
+        let dataset = reader.read_batch(Some(1)).unwrap();
+        assert_eq!(dataset.schema().fields()[1].data_type, DataType::Float64);
+        assert_eq!(reader.schema(), dataset.schema());
+
+        let dataset = options
+            .open(schema, Cursor::new(data))
+            .read_batch(None)
+            .unwrap();
+        dataset.assert_eq(
+            &DataSet::try_new(
+                dataset.schema(),
+                vec![
+                    Arc::new(Int32Array::from_vec(vec![1, 2, 3])) as ArrayRef,
+                    Arc::new(Float64Array::from_vec(vec![1.0, 3e9, 1e20])),
+                ],
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_date_and_time() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("d", DataType::Date),
+                Field::new("t", DataType::Time),
+            ])
+            .unwrap(),
+        );
+        let data = "2024-01-01,12:30:00\n1969-12-31,00:00:01.5\n";
+        let dataset = CsvOptions::default()
+            .open(schema.clone(), Cursor::new(data))
+            .read_batch(None)
+            .unwrap();
+        dataset.assert_eq(
+            &DataSet::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(DateArray::from_vec(vec![19723, -1])) as ArrayRef,
+                    Arc::new(TimeArray::from_vec(vec![45_000_000, 1500])),
+                ],
+            )
+            .unwrap(),
+        );
+
+        assert!(CsvOptions::default()
+            .open(schema, Cursor::new("2024-13-01,12:30\n"))
+            .read_batch(None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_infer_unsigned() {
+        let options = CsvOptions {
+            has_header: true,
+            ..CsvOptions::default()
+        };
+        let data = "a,b,c\n1,18446744073709551615,1\n2,1,1.5\n";
+        let schema = options.infer_schema(Cursor::new(data)).unwrap();
+        assert_eq!(
+            schema.fields(),
+            &[
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::Float64),
+                Field::new("c", DataType::Float64),
+            ]
+        );
+
+        let data = "a\n18446744073709551615\n";
+        let schema = options.infer_schema(Cursor::new(data)).unwrap();
+        assert_eq!(schema.fields(), &[Field::new("a", DataType::UInt64)]);
+
+        let dataset = options
+            .open(schema.clone(), Cursor::new(data))
+            .read_batch(None)
+            .unwrap();
+        dataset.assert_eq(
+            &DataSet::try_new(
+                schema,
+                vec![Arc::new(UInt64Array::from_vec(vec![u64::MAX])) as ArrayRef],
+            )
+            .unwrap(),
+        );
+    }
+}
@@ -4,7 +4,7 @@ use std::path::Path;
 
 use anyhow::Result;
 
-use crate::array::{compute, ArrayRef, BooleanArray};
+use crate::array::{compute, ArrayRef, BooleanArray, Scalar};
 use crate::dataset::{CsvOptions, SchemaRef};
 
 #[derive(Debug, Clone)]
@@ -109,6 +109,36 @@ impl DataSet {
                 .collect(),
         )
     }
+
+    fn column_or_err(&self, index: usize) -> Result<&ArrayRef> {
+        self.columns.get(index).ok_or_else(|| {
+            anyhow::anyhow!(
+                "column index {} out of range, the dataset has {} columns",
+                index,
+                self.columns.len()
+            )
+        })
+    }
+
+    /// Returns the sum of the values of the column at `index`, see [`compute::sum`].
+    pub fn sum(&self, index: usize) -> Result<Scalar> {
+        compute::sum(self.column_or_err(index)?)
+    }
+
+    /// Returns the smallest value of the column at `index`, see [`compute::min`].
+    pub fn min(&self, index: usize) -> Result<Scalar> {
+        compute::min(self.column_or_err(index)?)
+    }
+
+    /// Returns the largest value of the column at `index`, see [`compute::max`].
+    pub fn max(&self, index: usize) -> Result<Scalar> {
+        compute::max(self.column_or_err(index)?)
+    }
+
+    /// Returns the mean of the values of the column at `index`, see [`compute::mean`].
+    pub fn mean(&self, index: usize) -> Result<Option<f64>> {
+        compute::mean(self.column_or_err(index)?)
+    }
 }
 
 impl PartialEq for DataSet {
@@ -1,4 +1,4 @@
-use std::fmt::{self, Display, Formatter};
+use std::fmt::{self, Display, Formatter, LowerExp};
 
 use chrono::TimeZone;
 use comfy_table::presets::UTF8_HORIZONTAL_BORDERS_ONLY;
@@ -6,7 +6,8 @@ use comfy_table::{Cell, ContentArrangement, Row, Table, TableComponent};
 
 use crate::array::{
     ArrayExt, BooleanArray, DataType, Float32Array, Float64Array, Int16Array, Int32Array,
-    Int64Array, Int8Array, StringArray, TimestampArray,
+    Int64Array, Int8Array, StringArray, TimestampArray, UInt16Array, UInt32Array, UInt64Array,
+    UInt8Array,
 };
 use crate::dataset::DataSet;
 
@@ -20,9 +21,45 @@ macro_rules! add_table_cell {
     };
 }
 
+/// How the values of a dataset are displayed.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct DisplayOptions {
+    float_precision: Option<usize>,
+    scientific: bool,
+}
+
+impl DisplayOptions {
+    /// Displays floats with `precision` digits after the decimal point, instead of the
+    /// shortest representation that reads back as the same value.
+    pub fn with_float_precision(self, precision: usize) -> Self {
+        Self {
+            float_precision: Some(precision),
+            ..self
+        }
+    }
+
+    /// Displays floats in scientific notation.
+    pub fn with_scientific(self) -> Self {
+        Self {
+            scientific: true,
+            ..self
+        }
+    }
+
+    pub fn format_float<T: Display + LowerExp>(&self, value: T) -> String {
+        match (self.float_precision, self.scientific) {
+            (Some(precision), false) => format!("{:.*}", precision, value),
+            (Some(precision), true) => format!("{:.*e}", precision, value),
+            (None, false) => value.to_string(),
+            (None, true) => format!("{:e}", value),
+        }
+    }
+}
+
 pub struct DataSetDisplay<'a> {
     dataset: &'a DataSet,
     no_header: bool,
+    options: DisplayOptions,
 }
 
 impl DataSet {
@@ -30,6 +67,7 @@ impl DataSet {
         DataSetDisplay {
             dataset: self,
             no_header: false,
+            options: DisplayOptions::default(),
         }
     }
 
@@ -37,10 +75,17 @@ impl DataSet {
         DataSetDisplay {
             dataset: self,
             no_header: true,
+            options: DisplayOptions::default(),
         }
     }
 }
 
+impl<'a> DataSetDisplay<'a> {
+    pub fn with_options(self, options: DisplayOptions) -> Self {
+        Self { options, ..self }
+    }
+}
+
 impl<'a> Display for DataSetDisplay<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut table = Table::new();
@@ -81,18 +126,44 @@ impl<'a> Display for DataSetDisplay<'a> {
                         DataType::Int64 => {
                             add_table_cell!(table_row, self.dataset, row, column, Int64Array)
                         }
-                        DataType::Float32 => {
-                            add_table_cell!(table_row, self.dataset, row, column, Float32Array)
+                        DataType::UInt8 => {
+                            add_table_cell!(table_row, self.dataset, row, column, UInt8Array)
+                        }
+                        DataType::UInt16 => {
+                            add_table_cell!(table_row, self.dataset, row, column, UInt16Array)
                         }
-                        DataType::Float64 => {
-                            add_table_cell!(table_row, self.dataset, row, column, Float64Array)
+                        DataType::UInt32 => {
+                            add_table_cell!(table_row, self.dataset, row, column, UInt32Array)
                         }
+                        DataType::UInt64 => {
+                            add_table_cell!(table_row, self.dataset, row, column, UInt64Array)
+                        }
+                        DataType::Float32 => table_row.add_cell(Cell::new(
+                            self.options.format_float(
+                                self.dataset.columns()[column]
+                                    .downcast_ref::<Float32Array>()
+                                    .value(row),
+                            ),
+                        )),
+                        DataType::Float64 => table_row.add_cell(Cell::new(
+                            self.options.format_float(
+                                self.dataset.columns()[column]
+                                    .downcast_ref::<Float64Array>()
+                                    .value(row),
+                            ),
+                        )),
                         DataType::Boolean => {
                             add_table_cell!(table_row, self.dataset, row, column, BooleanArray)
                         }
                         DataType::String => {
                             add_table_cell!(table_row, self.dataset, row, column, StringArray)
                         }
+                        DataType::List(_)
+                        | DataType::Struct(_)
+                        | DataType::Map(_, _)
+                        | DataType::Date
+                        | DataType::Time => table_row
+                            .add_cell(Cell::new(self.dataset.columns()[column].scalar_value(row))),
                         DataType::Timestamp(tz) => {
                             let tz = tz.unwrap_or(chrono_tz::UTC);
                             table_row.add_cell(Cell::new(
@@ -115,3 +186,36 @@ impl<'a> Display for DataSetDisplay<'a> {
         table.fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_float() {
+        let value = 0.1 + 0.2;
+        assert_eq!(
+            DisplayOptions::default().format_float(value),
+            "0.30000000000000004"
+        );
+        assert_eq!(
+            DisplayOptions::default()
+                .with_float_precision(2)
+                .format_float(value),
+            "0.30"
+        );
+        assert_eq!(
+            DisplayOptions::default()
+                .with_scientific()
+                .format_float(1234.5),
+            "1.2345e3"
+        );
+        assert_eq!(
+            DisplayOptions::default()
+                .with_float_precision(1)
+                .with_scientific()
+                .format_float(1234.5),
+            "1.2e3"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+mod compare;
 mod csv_reader;
 #[allow(clippy::module_inception)]
 mod dataset;
@@ -7,5 +8,5 @@ mod serde;
 
 pub use csv_reader::{CsvOptions, CsvReader};
 pub use dataset::DataSet;
-pub use display::DataSetDisplay;
+pub use display::{DataSetDisplay, DisplayOptions};
 pub use schema::{Field, Schema, SchemaRef};
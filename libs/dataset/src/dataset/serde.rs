@@ -1,14 +1,10 @@
 use std::fmt::{self, Formatter};
-use std::sync::Arc;
 
 use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
 use serde::ser::SerializeTuple;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::array::{
-    ArrayRef, BooleanArray, DataType, Float32Array, Float64Array, Int16Array, Int32Array,
-    Int64Array, Int8Array, NullArray, StringArray, TimestampArray,
-};
+use crate::array::{ArrayRef, ArraySeed};
 use crate::dataset::{DataSet, Field, SchemaRef};
 
 impl Serialize for DataSet {
@@ -81,59 +77,17 @@ impl<'de, 'a> Visitor<'de> for ColumnsVisitor<'a> {
         f.write_str("Columns")
     }
 
-    fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, <A as SeqAccess<'de>>::Error>
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, <A as SeqAccess<'de>>::Error>
     where
         A: SeqAccess<'de>,
     {
         let mut columns = Vec::with_capacity(self.0.len());
 
-        while !self.0.is_empty() {
-            let (field, tail) = self.0.split_first().unwrap();
-
-            match field.data_type {
-                DataType::Null => columns.push(Arc::new(
-                    seq.next_element::<NullArray>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::Int8 => columns.push(Arc::new(
-                    seq.next_element::<Int8Array>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::Int16 => columns.push(Arc::new(
-                    seq.next_element::<Int16Array>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::Int32 => columns.push(Arc::new(
-                    seq.next_element::<Int32Array>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::Int64 => columns.push(Arc::new(
-                    seq.next_element::<Int64Array>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::Float32 => columns.push(Arc::new(
-                    seq.next_element::<Float32Array>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::Float64 => columns.push(Arc::new(
-                    seq.next_element::<Float64Array>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::Boolean => columns.push(Arc::new(
-                    seq.next_element::<BooleanArray>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::Timestamp(_) => columns.push(Arc::new(
-                    seq.next_element::<TimestampArray>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-                DataType::String => columns.push(Arc::new(
-                    seq.next_element::<StringArray>()?
-                        .ok_or_else(|| Error::custom("expect array"))?,
-                ) as ArrayRef),
-            }
-
-            self.0 = tail;
+        for field in self.0 {
+            columns.push(
+                seq.next_element_seed(ArraySeed(field.data_type))?
+                    .ok_or_else(|| Error::custom("expect array"))?,
+            );
         }
 
         Ok(columns)
@@ -142,7 +96,10 @@ impl<'de, 'a> Visitor<'de> for ColumnsVisitor<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
+    use crate::array::{DataType, Int32Array, Int64Array, ListArray, StringArray, TimestampArray};
     use crate::dataset::Schema;
 
     #[test]
@@ -151,6 +108,7 @@ mod tests {
             Field::new("a", DataType::Int32),
             Field::new("b", DataType::String),
             Field::new("c", DataType::Timestamp(None)),
+            Field::new("d", DataType::list(DataType::Int64)),
         ];
         let schema = Arc::new(Schema::try_new(fields).unwrap());
 
@@ -158,6 +116,10 @@ mod tests {
             Arc::new(Int32Array::from_vec(vec![1, 3, 5, 7, 9])) as ArrayRef,
             Arc::new(StringArray::from_vec(vec!["a", "b", "c", "d", "e"])),
             Arc::new(TimestampArray::from_vec(vec![111, 333, 555, 777, 999])),
+            Arc::new(ListArray::new(
+                Arc::new(Int64Array::from_vec(vec![1, 2, 3, 4])),
+                vec![Some(1), None, Some(0), Some(2), Some(1)],
+            )),
         ];
         let dataset = DataSet::try_new(schema, columns).unwrap();
 
@@ -5,5 +5,5 @@ mod source_provider;
 mod sql;
 mod storage;
 
-pub use service::Service;
+pub use service::{CheckPointInfo, NodeStateInfo, Service};
 pub use storage::{Definition, SinkDefinition, SourceDefinition, StreamDefinition};
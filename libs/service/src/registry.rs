@@ -1,9 +1,11 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, Notify};
 
 struct TaskInfo {
     shutdown_tx: Option<oneshot::Sender<()>>,
+    checkpoint_trigger: Arc<Notify>,
 }
 
 #[derive(Default)]
@@ -12,11 +14,17 @@ pub struct Registry {
 }
 
 impl Registry {
-    pub fn add(&mut self, name: &str, shutdown_tx: oneshot::Sender<()>) {
+    pub fn add(
+        &mut self,
+        name: &str,
+        shutdown_tx: oneshot::Sender<()>,
+        checkpoint_trigger: Arc<Notify>,
+    ) {
         self.streams.insert(
             name.to_string(),
             TaskInfo {
                 shutdown_tx: Some(shutdown_tx),
+                checkpoint_trigger,
             },
         );
     }
@@ -29,6 +37,17 @@ impl Registry {
         self.streams.remove(name);
     }
 
+    /// Asks the stream to create a checkpoint now, returns `false` if it is not running.
+    pub fn trigger_checkpoint(&self, name: &str) -> bool {
+        match self.streams.get(name) {
+            Some(info) => {
+                info.checkpoint_trigger.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn stop(&mut self, name: &str) {
         if let Some(info) = self.streams.get_mut(name) {
             if let Some(tx) = info.shutdown_tx.take() {
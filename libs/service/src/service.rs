@@ -1,15 +1,18 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Result;
 use futures_util::future::FutureExt;
-use futures_util::stream::BoxStream;
+use futures_util::stream::{BoxStream, StreamExt};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-use tokio::sync::{oneshot, Mutex};
+use serde::Serialize;
+use tokio::sync::{oneshot, Mutex, Notify};
 use yql_core::array::{ArrayRef, BooleanBuilder, DataType, StringArray, StringBuilder};
 use yql_core::dataset::{DataSet, Field, Schema, SchemaRef};
-use yql_core::sql::SqlSourceProvider;
+use yql_core::sources::{SchemaChangeCallback, SchemaChanged};
+use yql_core::sql::{ParseError, SemanticError, SqlSourceProvider};
 use yql_core::{DataFrame, ExecutionContext, SinkProvider};
 
 use crate::registry::Registry;
@@ -17,7 +20,8 @@ use crate::sink_provider::create_sink_provider;
 use crate::source_provider::create_source_provider;
 use crate::sql::{
     ShowType, Stmt, StmtCreateSink, StmtCreateSource, StmtCreateStream, StmtDeleteSink,
-    StmtDeleteSource, StmtDeleteStream, StmtSelect, StmtShow, StmtStartStream, StmtStopStream,
+    StmtDeleteSource, StmtDeleteStream, StmtDescribe, StmtInsertInto, StmtSelect, StmtShow,
+    StmtStartStream, StmtStopStream,
 };
 use crate::storage::{Definition, SourceDefinition, Storage, StreamState};
 use crate::{SinkDefinition, StreamDefinition};
@@ -55,6 +59,36 @@ static SHOW_SINKS_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
     Arc::new(Schema::try_new(fields).unwrap())
 });
 
+static DESCRIBE_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
+    let fields = vec![
+        Field::new("name", DataType::String),
+        Field::new("type", DataType::String),
+    ];
+    Arc::new(Schema::try_new(fields).unwrap())
+});
+
+/// The checkpoint of a stream.
+#[derive(Debug, Serialize)]
+pub struct CheckPointInfo {
+    pub stream: String,
+    /// The status of the stream, `None` if the stream no longer exists.
+    pub status: Option<String>,
+    pub running: bool,
+    /// The size of the checkpoint in bytes.
+    pub size: usize,
+    /// The sizes of the states of the nodes of the plan, only returned by
+    /// [`Service::inspect_checkpoint`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nodes: Option<Vec<NodeStateInfo>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeStateInfo {
+    pub id: usize,
+    /// The size of the state in bytes.
+    pub size: usize,
+}
+
 fn create_action_result_dataset(action: &str, success: bool) -> Result<DataSet> {
     let columns = vec![
         {
@@ -84,7 +118,13 @@ impl<'a> yql_core::sql::SqlContext for SqlContext<'a> {
                     _ => None,
                 });
         match definition {
-            Some(definition) => Ok(Some(create_source_provider(&definition)?)),
+            Some(definition) => {
+                let storage = self.0.storage.clone();
+                let name = definition.name.clone();
+                let on_schema_change: SchemaChangeCallback =
+                    Arc::new(move |schema: &Schema| storage.update_source_schema(&name, schema));
+                Ok(Some(create_source_provider(&definition, on_schema_change)?))
+            }
             None => Ok(None),
         }
     }
@@ -115,11 +155,45 @@ impl yql_core::Storage for StreamStorage {
 }
 
 pub struct ServiceInner {
-    storage: Storage,
+    /// Shared with the sources, which save their new schemas while the streams are running.
+    storage: Arc<Storage>,
     registry: Registry,
 }
 
 impl ServiceInner {
+    fn checkpoint_info(&self, stream: String, size: usize) -> Result<CheckPointInfo> {
+        let status = match self.storage.get_definition(&stream)? {
+            Some(Definition::Stream(_)) => self
+                .storage
+                .get_stream_state(&stream)?
+                .map(|state| state.to_string()),
+            _ => None,
+        };
+        Ok(CheckPointInfo {
+            running: self.registry.is_running(&stream),
+            stream,
+            status,
+            size,
+            nodes: None,
+        })
+    }
+
+    /// Plans the stream `name` from its definition, returns the query and its sink.
+    fn plan_stream(&self, name: &str) -> Result<(DataFrame, Box<dyn SinkProvider>)> {
+        let definition = self
+            .storage
+            .get_definition(name)?
+            .ok_or_else(|| anyhow::anyhow!("not found"))?;
+        let stream_definition = match definition {
+            Definition::Stream(stream_definition) => stream_definition,
+            _ => anyhow::bail!("not stream"),
+        };
+        let sink = self.create_sink_provider(&stream_definition.to)?;
+        let df = DataFrame::from_sql_select(&SqlContext(self), stream_definition.select)?;
+        df.validate()?;
+        Ok((df, sink))
+    }
+
     fn create_sink_provider(&self, name: &str) -> Result<Box<dyn SinkProvider>> {
         let definition =
             self.storage
@@ -130,7 +204,7 @@ impl ServiceInner {
                 });
         match definition {
             Some(definition) => create_sink_provider(&definition),
-            None => anyhow::bail!("sink '{}' not defined"),
+            None => anyhow::bail!("sink '{}' not defined", name),
         }
     }
 }
@@ -142,7 +216,7 @@ pub struct Service {
 
 impl Service {
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
-        let storage = Storage::open(path)?;
+        let storage = Arc::new(Storage::open(path)?);
         Ok(Self {
             inner: Arc::new(Mutex::new(ServiceInner {
                 storage,
@@ -152,8 +226,106 @@ impl Service {
     }
 
     pub async fn execute(&self, sql: &str) -> Result<BoxStream<'static, Result<DataSet>>> {
-        let (_, stmt) = crate::sql::stmt(sql).map_err(|err| anyhow::anyhow!("{}", err))?;
+        let (_, stream) = self.execute_with_schema(sql).await?;
+        Ok(stream)
+    }
+
+    /// Like [`Service::execute`], but also returns the schema of the rows of the result, which
+    /// is known before the first row is produced.
+    pub async fn execute_with_schema(
+        &self,
+        sql: &str,
+    ) -> Result<(SchemaRef, BoxStream<'static, Result<DataSet>>)> {
+        let (_, stmt) = crate::sql::stmt(sql).map_err(|err| ParseError::new(sql, err))?;
+        self.execute_stmt(stmt)
+            .await
+            .map_err(|err| SemanticError::locate(sql, err))
+    }
+
+    /// Executes a script of several statements in order, and returns the results of all
+    /// statements as a single stream.
+    pub async fn execute_script(&self, sql: &str) -> Result<BoxStream<'static, Result<DataSet>>> {
+        let stmts = crate::sql::parse_statements(sql)?;
+        let service = self.clone();
+        Ok(Box::pin(async_stream::try_stream! {
+            for stmt in stmts {
+                let (_, mut stream) = service.execute_stmt(stmt).await?;
+                while let Some(dataset) = stream.next().await.transpose()? {
+                    yield dataset;
+                }
+            }
+        }))
+    }
+
+    /// Returns the checkpoints of all streams.
+    pub async fn checkpoints(&self) -> Result<Vec<CheckPointInfo>> {
+        let inner = self.inner.lock().await;
+        inner
+            .storage
+            .stream_state_data_sizes()?
+            .into_iter()
+            .map(|(stream, size)| inner.checkpoint_info(stream, size))
+            .collect()
+    }
+
+    /// Returns the checkpoint of the stream `name`, with the sizes of the states of its nodes.
+    pub async fn inspect_checkpoint(&self, name: &str) -> Result<CheckPointInfo> {
+        let inner = self.inner.lock().await;
+        let data = inner
+            .storage
+            .get_stream_state_data(name)?
+            .ok_or_else(|| anyhow::anyhow!("stream '{}' has no checkpoint", name))?;
+        let state: HashMap<usize, Vec<u8>> = bincode::deserialize(&data)?;
+        let mut info = inner.checkpoint_info(name.to_string(), data.len())?;
+        info.nodes = Some(
+            state
+                .into_iter()
+                .map(|(id, state)| NodeStateInfo {
+                    id,
+                    size: state.len(),
+                })
+                .sorted_by_key(|node| node.id)
+                .collect(),
+        );
+        Ok(info)
+    }
+
+    /// Deletes the checkpoints of the streams that no longer exist, and of the streams that are
+    /// not running if `stopped` is true, returns the names of the streams.
+    pub async fn prune_checkpoints(&self, stopped: bool) -> Result<Vec<String>> {
+        let inner = self.inner.lock().await;
+        let mut pruned = Vec::new();
+        for (stream, _) in inner.storage.stream_state_data_sizes()? {
+            let exists = matches!(
+                inner.storage.get_definition(&stream)?,
+                Some(Definition::Stream(_))
+            );
+            if !exists || (stopped && !inner.registry.is_running(&stream)) {
+                inner.storage.delete_stream_state_data(&stream)?;
+                pruned.push(stream);
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Creates a checkpoint of the running stream `name` now, instead of waiting for the next
+    /// periodic checkpoint.
+    ///
+    /// The checkpoint is saved asynchronously.
+    pub async fn trigger_savepoint(&self, name: &str) -> Result<()> {
+        let inner = self.inner.lock().await;
+        anyhow::ensure!(
+            inner.registry.trigger_checkpoint(name),
+            "stream '{}' is not running",
+            name
+        );
+        Ok(())
+    }
 
+    async fn execute_stmt(
+        &self,
+        stmt: Stmt,
+    ) -> Result<(SchemaRef, BoxStream<'static, Result<DataSet>>)> {
         match stmt {
             Stmt::CreateSource(stmt) => Ok(once_stream(self.execute_create_source(stmt).await?)),
             Stmt::CreateStream(stmt) => Ok(once_stream(self.execute_create_stream(stmt).await?)),
@@ -164,7 +336,9 @@ impl Service {
             Stmt::StartStream(stmt) => Ok(once_stream(self.execute_start_stream(stmt).await?)),
             Stmt::StopStream(stmt) => Ok(once_stream(self.execute_stop_stream(stmt).await?)),
             Stmt::Show(stmt) => Ok(once_stream(self.execute_show(stmt).await?)),
+            Stmt::Describe(stmt) => Ok(once_stream(self.execute_describe(stmt).await?)),
             Stmt::Select(stmt) => self.execute_select(stmt).await,
+            Stmt::InsertInto(stmt) => Ok(once_stream(self.execute_insert_into(stmt).await?)),
         }
     }
 
@@ -251,6 +425,8 @@ impl Service {
         inner.storage.delete_stream_state(&stmt.name)?;
         inner.storage.delete_stream_state_data(&stmt.name)?;
         inner.registry.stop(&stmt.name);
+        yql_core::metrics::remove_stream(&stmt.name);
+        yql_core::progress::remove_stream(&stmt.name);
         create_action_result_dataset("Delete Stream", true)
     }
 
@@ -269,60 +445,62 @@ impl Service {
     }
 
     async fn execute_start_stream(&self, stmt: StmtStartStream) -> Result<DataSet> {
-        let service_inner = self.inner.clone();
-
         let mut inner = self.inner.lock().await;
         anyhow::ensure!(!inner.registry.is_running(&stmt.name), "already running");
 
-        let definition = inner
-            .storage
-            .get_definition(&stmt.name)?
-            .ok_or_else(|| anyhow::anyhow!("not found"))?;
-        let stream_definition = match definition {
-            Definition::Stream(stream_definition) => stream_definition,
-            _ => anyhow::bail!("not stream"),
-        };
-        let sink = inner.create_sink_provider(&stream_definition.to)?;
-        let df = DataFrame::from_sql_select(&SqlContext(&*inner), stream_definition.select)?;
-        let ctx = ExecutionContext::new(stmt.name.clone()).with_storage(StreamStorage {
-            name: stmt.name.clone(),
-            inner: service_inner.clone(),
-        });
-        let (tx_shutdown, rx_shutdown) = oneshot::channel::<()>();
-
+        let (df, sink) = inner.plan_stream(&stmt.name)?;
         if stmt.restart {
             inner.storage.delete_stream_state_data(&stmt.name)?;
         }
+        self.spawn_task(&mut inner, &stmt.name, df, sink)?;
+        create_action_result_dataset("Start Stream", true)
+    }
+
+    /// Runs `df` into `sink` in the background as the stream `name`.
+    fn spawn_task(
+        &self,
+        inner: &mut ServiceInner,
+        name: &str,
+        df: DataFrame,
+        sink: Box<dyn SinkProvider>,
+    ) -> Result<()> {
+        let service_inner = self.inner.clone();
+        let ctx = ExecutionContext::new(name).with_storage(StreamStorage {
+            name: name.to_string(),
+            inner: service_inner.clone(),
+        });
+        let (tx_shutdown, rx_shutdown) = oneshot::channel::<()>();
+        let checkpoint_trigger = Arc::new(Notify::new());
+        let ctx = ctx.with_checkpoint_trigger(checkpoint_trigger.clone());
         let fut = df.into_task_with_graceful_shutdown(ctx, sink, Some(rx_shutdown.map(|_| ())));
 
-        inner
-            .storage
-            .set_stream_state(&stmt.name, StreamState::Started)?;
-        inner.registry.add(&stmt.name, tx_shutdown);
+        inner.storage.set_stream_state(name, StreamState::Started)?;
+        inner.registry.add(name, tx_shutdown, checkpoint_trigger);
 
-        let name = stmt.name.clone();
+        let service = self.clone();
+        let name = name.to_string();
         tokio::spawn(async move {
             let res = fut.await;
             let mut inner = service_inner.lock().await;
+            inner.registry.remove(&name);
 
-            match res {
-                Ok(()) => {
-                    inner
-                        .storage
-                        .set_stream_state(&name, StreamState::Stop)
-                        .ok();
-                }
-                Err(err) => {
-                    inner
-                        .storage
-                        .set_stream_state(&name, StreamState::Error(err.to_string()))
-                        .ok();
-                }
+            let res = match res {
+                // The source saved its new schema in its definition, the stream continues from
+                // its last checkpoint, planned with the new schema.
+                Err(err) if err.chain().any(|err| err.is::<SchemaChanged>()) => inner
+                    .plan_stream(&name)
+                    .and_then(|(df, sink)| service.spawn_task(&mut inner, &name, df, sink)),
+                Ok(()) => inner.storage.set_stream_state(&name, StreamState::Stop),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = res {
+                inner
+                    .storage
+                    .set_stream_state(&name, StreamState::Error(err.to_string()))
+                    .ok();
             }
-            inner.registry.remove(&name);
         });
-
-        create_action_result_dataset("Start Stream", true)
+        Ok(())
     }
 
     async fn execute_stop_stream(&self, stmt: StmtStopStream) -> Result<DataSet> {
@@ -431,17 +609,83 @@ impl Service {
         }
     }
 
+    async fn execute_describe(&self, stmt: StmtDescribe) -> Result<DataSet> {
+        let inner = self.inner.lock().await;
+        let source_definition = match inner.storage.get_definition(&stmt.name)? {
+            Some(Definition::Source(source_definition)) => source_definition,
+            Some(_) => anyhow::bail!("not source"),
+            None => anyhow::bail!("not exists"),
+        };
+        let fields = source_definition.schema.fields();
+        DataSet::try_new(
+            DESCRIBE_SCHEMA.clone(),
+            vec![
+                Arc::new(
+                    fields
+                        .iter()
+                        .map(|field| &field.name)
+                        .collect::<StringArray>(),
+                ),
+                Arc::new(
+                    fields
+                        .iter()
+                        .map(|field| field.data_type.to_string())
+                        .collect::<StringArray>(),
+                ),
+            ],
+        )
+    }
+
     async fn execute_select(
         &self,
         stmt: StmtSelect,
-    ) -> Result<BoxStream<'static, Result<DataSet>>> {
+    ) -> Result<(SchemaRef, BoxStream<'static, Result<DataSet>>)> {
         let inner = self.inner.lock().await;
         let df = DataFrame::from_sql_select(&SqlContext(&*inner), stmt.select)?;
+        let schema = df.schema()?;
         let ctx = ExecutionContext::new("noname");
-        Ok(df.into_stream(ctx))
+        Ok((schema, df.into_stream(ctx)))
+    }
+
+    /// Creates the stream `insert_into_<sink>_<n>` that runs the query into the sink, and
+    /// starts it in the background.
+    ///
+    /// Like a stream created with `CREATE STREAM`, it is listed by `SHOW STREAMS`, it can be
+    /// stopped with `STOP STREAM`, and its definition and state are deleted with
+    /// `DELETE STREAM`.
+    async fn execute_insert_into(&self, stmt: StmtInsertInto) -> Result<DataSet> {
+        let mut inner = self.inner.lock().await;
+        let mut n = 1;
+        let name = loop {
+            let name = format!("insert_into_{}_{}", stmt.sink, n);
+            if !inner.storage.definition_exists(&name)? {
+                break name;
+            }
+            n += 1;
+        };
+        inner
+            .storage
+            .create_definition(Definition::Stream(StreamDefinition {
+                name: name.clone(),
+                select: stmt.select,
+                to: stmt.sink,
+            }))?;
+
+        let (df, sink) = match inner.plan_stream(&name) {
+            Ok(res) => res,
+            Err(err) => {
+                inner.storage.delete_definition(&name)?;
+                return Err(err);
+            }
+        };
+        self.spawn_task(&mut inner, &name, df, sink)?;
+        create_action_result_dataset(&format!("Insert Into {}", name), true)
     }
 }
 
-fn once_stream(dataset: DataSet) -> BoxStream<'static, Result<DataSet>> {
-    Box::pin(futures_util::stream::once(async move { Ok(dataset) }))
+fn once_stream(dataset: DataSet) -> (SchemaRef, BoxStream<'static, Result<DataSet>>) {
+    (
+        dataset.schema(),
+        Box::pin(futures_util::stream::once(async move { Ok(dataset) })),
+    )
 }
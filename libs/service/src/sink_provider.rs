@@ -11,5 +11,57 @@ pub fn create_sink_provider(definition: &SinkDefinition) -> Result<Box<dyn SinkP
         return Ok(Box::new(sinks::Console));
     }
 
+    if url.scheme().eq_ignore_ascii_case("prometheus")
+        || url.scheme().eq_ignore_ascii_case("prometheus+https")
+    {
+        let mut metric_name = None;
+        let mut label_columns = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "metric" => metric_name = Some(value.to_string()),
+                "labels" => {
+                    label_columns = Some(value.split(',').map(ToString::to_string).collect())
+                }
+                _ => {}
+            }
+        }
+        let metric_name = metric_name.ok_or_else(|| {
+            anyhow::anyhow!("the 'metric' parameter of prometheus sink is required")
+        })?;
+
+        let mut endpoint = format!(
+            "{}://{}",
+            if url.scheme().eq_ignore_ascii_case("prometheus") {
+                "http"
+            } else {
+                "https"
+            },
+            url.host_str().unwrap_or("localhost"),
+        );
+        if let Some(port) = url.port() {
+            endpoint.push_str(&format!(":{}", port));
+        }
+        endpoint.push_str(url.path());
+
+        let mut sink = sinks::PrometheusRemoteWrite::new(endpoint, metric_name);
+        if let Some(label_columns) = label_columns {
+            sink = sink.with_label_columns(label_columns);
+        }
+        return Ok(Box::new(sink));
+    }
+
+    if url.scheme().eq_ignore_ascii_case("rates") {
+        let mut unit_column = "unit".to_string();
+        let mut rate_column = "rate".to_string();
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "unit" => unit_column = value.to_string(),
+                "rate" => rate_column = value.to_string(),
+                _ => {}
+            }
+        }
+        return Ok(Box::new(sinks::RateTable::new(unit_column, rate_column)));
+    }
+
     anyhow::bail!("unsupported sink: '{}'", definition.uri)
 }
@@ -2,13 +2,36 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use url::Url;
-use yql_core::dataset::CsvOptions;
+use yql_core::dataset::{CsvOptions, SchemaRef};
+use yql_core::sources::{LineFormat, SchemaChangeCallback, SchemaDriftPolicy};
 use yql_core::sql::SqlSourceProvider;
 use yql_core::{sources, SourceProviderWrapper};
 
 use crate::storage::SourceDefinition;
 
-pub fn create_source_provider(definition: &SourceDefinition) -> Result<SqlSourceProvider> {
+#[cfg(unix)]
+fn unix_socket(path: &str, schema: SchemaRef, format: LineFormat) -> Result<sources::Socket> {
+    Ok(sources::Socket::unix(path, schema, format))
+}
+
+#[cfg(not(unix))]
+fn unix_socket(_path: &str, _schema: SchemaRef, _format: LineFormat) -> Result<sources::Socket> {
+    anyhow::bail!("unix domain sockets are not supported on this platform")
+}
+
+/// The `schema_drift` parameter of the source uri.
+fn schema_drift_policy(url: &Url) -> Result<SchemaDriftPolicy> {
+    match url.query_pairs().find(|(key, _)| key == "schema_drift") {
+        Some((_, value)) => value.parse(),
+        None => Ok(SchemaDriftPolicy::default()),
+    }
+}
+
+/// Creates the source of `definition`, its schema changes are reported to `on_schema_change`.
+pub fn create_source_provider(
+    definition: &SourceDefinition,
+    on_schema_change: SchemaChangeCallback,
+) -> Result<SqlSourceProvider> {
     let url: Url = definition
         .uri
         .parse()
@@ -22,6 +45,10 @@ pub fn create_source_provider(definition: &SourceDefinition) -> Result<SqlSource
                 None => CsvOptions::default(),
             };
             let source_provider = sources::Csv::new(options, Some(definition.schema.clone()), path)
+                .and_then(|csv| {
+                    csv.with_schema_change_callback(on_schema_change)
+                        .with_schema_drift_policy(schema_drift_policy(&url)?)
+                })
                 .with_context(|| "failed to create csv reader")?;
             return Ok(SqlSourceProvider {
                 source_provider: Arc::new(SourceProviderWrapper(source_provider)),
@@ -31,5 +58,60 @@ pub fn create_source_provider(definition: &SourceDefinition) -> Result<SqlSource
         }
     }
 
+    if url.scheme().eq_ignore_ascii_case("debezium") {
+        let source_provider = sources::Debezium::new(definition.schema.clone(), url.path())?;
+        return Ok(SqlSourceProvider {
+            source_provider: Arc::new(SourceProviderWrapper(source_provider)),
+            time_expr: definition.time_expr.clone(),
+            watermark_expr: definition.watermark_expr.clone(),
+        });
+    }
+
+    let scheme = url.scheme().to_lowercase();
+    if let "tcp" | "udp" | "unix" | "syslog" | "syslog+tcp" = scheme.as_str() {
+        let format = match url
+            .query_pairs()
+            .find(|(key, _)| key == "format")
+            .map(|(_, value)| value.to_lowercase())
+            .as_deref()
+        {
+            None if scheme.starts_with("syslog") => LineFormat::Syslog,
+            None | Some("json") => LineFormat::Json,
+            Some("csv") => LineFormat::Csv(match url.query() {
+                Some(query) => serde_qs::from_str::<CsvOptions>(query)
+                    .with_context(|| "failed to parse csv options")?,
+                None => CsvOptions::default(),
+            }),
+            Some("syslog") => LineFormat::Syslog,
+            Some(format) => anyhow::bail!("unsupported line format: '{}'", format),
+        };
+        let schema = definition.schema.clone();
+        let schema_drift = schema_drift_policy(&url)?;
+        let widen_on_overflow = url
+            .query_pairs()
+            .any(|(key, value)| key == "widen_on_overflow" && value == "true");
+        let source_provider = if scheme == "unix" {
+            unix_socket(url.path(), schema, format)?
+        } else {
+            let addr = url
+                .socket_addrs(|| Some(514).filter(|_| scheme.starts_with("syslog")))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("invalid address: '{}'", definition.uri))?;
+            match scheme.as_str() {
+                "tcp" | "syslog+tcp" => sources::Socket::tcp(addr, schema, format),
+                _ => sources::Socket::udp(addr, schema, format),
+            }
+        }
+        .with_schema_change_callback(on_schema_change)
+        .with_schema_drift_policy(schema_drift)
+        .with_widen_on_overflow(widen_on_overflow);
+        return Ok(SqlSourceProvider {
+            source_provider: Arc::new(SourceProviderWrapper(source_provider)),
+            time_expr: definition.time_expr.clone(),
+            watermark_expr: definition.watermark_expr.clone(),
+        });
+    }
+
     anyhow::bail!("unsupported source: '{}'", definition.uri)
 }
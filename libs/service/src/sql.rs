@@ -1,17 +1,17 @@
 use chrono_tz::Tz;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
-use nom::character::complete::char;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::character::complete::{char, not_line_ending, one_of};
 use nom::combinator::{cut, eof, map, map_res, opt, value};
 use nom::error::context;
-use nom::multi::separated_list0;
-use nom::sequence::{delimited, terminated, tuple};
-use nom::IResult;
+use nom::multi::{fold_many0, many0, separated_list0};
+use nom::sequence::{delimited, pair, terminated, tuple};
 use yql_core::array::DataType;
 use yql_core::dataset::Field;
 use yql_core::expr::Expr;
 use yql_core::sql::ast::Select;
-use yql_core::sql::parser::{expr, name, select, sp, string};
+use yql_core::sql::parser::{expr, name, select, sp, string, ParseResult};
+use yql_core::sql::ParseError;
 
 #[derive(Debug, PartialEq)]
 pub struct StmtCreateStream {
@@ -85,11 +85,22 @@ pub struct StmtShow {
     pub show_type: ShowType,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct StmtDescribe {
+    pub name: String,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct StmtSelect {
     pub select: Select,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct StmtInsertInto {
+    pub sink: String,
+    pub select: Select,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Stmt {
     CreateSource(StmtCreateSource),
@@ -101,14 +112,16 @@ pub enum Stmt {
     StartStream(StmtStartStream),
     StopStream(StmtStopStream),
     Show(StmtShow),
+    Describe(StmtDescribe),
     Select(StmtSelect),
+    InsertInto(StmtInsertInto),
 }
 
-fn timezone(input: &str) -> IResult<&str, Tz> {
+fn timezone(input: &str) -> ParseResult<Tz> {
     cut(map_res(string, |name| name.parse::<Tz>()))(input)
 }
 
-fn data_type(input: &str) -> IResult<&str, DataType> {
+fn data_type(input: &str) -> ParseResult<DataType> {
     let timezone = map(
         tuple((tag_no_case("timezone"), sp, timezone)),
         |(_, _, tz)| tz,
@@ -121,6 +134,10 @@ fn data_type(input: &str) -> IResult<&str, DataType> {
             value(DataType::Int16, tag_no_case("int16")),
             value(DataType::Int32, tag_no_case("int32")),
             value(DataType::Int64, tag_no_case("int64")),
+            value(DataType::UInt8, tag_no_case("uint8")),
+            value(DataType::UInt16, tag_no_case("uint16")),
+            value(DataType::UInt32, tag_no_case("uint32")),
+            value(DataType::UInt64, tag_no_case("uint64")),
             value(DataType::Float32, tag_no_case("float32")),
             value(DataType::Float64, tag_no_case("float64")),
             value(DataType::Boolean, tag_no_case("boolean")),
@@ -132,11 +149,14 @@ fn data_type(input: &str) -> IResult<&str, DataType> {
                 )),
                 |(_, _, tz)| DataType::Timestamp(tz),
             ),
+            value(DataType::Date, tag_no_case("date")),
+            // After `timestamp`, which starts with `time`.
+            value(DataType::Time, tag_no_case("time")),
         )),
     )(input)
 }
 
-fn stmt_create_source(input: &str) -> IResult<&str, StmtCreateSource> {
+fn stmt_create_source(input: &str) -> ParseResult<StmtCreateSource> {
     let field = map(tuple((name, sp, data_type)), |(name, _, data_type)| Field {
         qualifier: None,
         name,
@@ -184,7 +204,7 @@ fn stmt_create_source(input: &str) -> IResult<&str, StmtCreateSource> {
     )(input)
 }
 
-fn stmt_create_stream(input: &str) -> IResult<&str, StmtCreateStream> {
+fn stmt_create_stream(input: &str) -> ParseResult<StmtCreateStream> {
     context(
         "stmt_create_stream",
         map(
@@ -212,14 +232,14 @@ fn stmt_create_stream(input: &str) -> IResult<&str, StmtCreateStream> {
     )(input)
 }
 
-fn output_format(input: &str) -> IResult<&str, OutputFormat> {
+fn output_format(input: &str) -> ParseResult<OutputFormat> {
     context(
         "output_format",
         value(OutputFormat::Json, tag_no_case("json")),
     )(input)
 }
 
-fn stmt_create_sink(input: &str) -> IResult<&str, StmtCreateSink> {
+fn stmt_create_sink(input: &str) -> ParseResult<StmtCreateSink> {
     let format = map(
         tuple((tag_no_case("format"), sp, output_format)),
         |(_, _, format)| format,
@@ -250,7 +270,7 @@ fn stmt_create_sink(input: &str) -> IResult<&str, StmtCreateSink> {
     )(input)
 }
 
-fn stmt_delete_source(input: &str) -> IResult<&str, StmtDeleteSource> {
+fn stmt_delete_source(input: &str) -> ParseResult<StmtDeleteSource> {
     context(
         "stmt_delete_source",
         map(
@@ -260,7 +280,7 @@ fn stmt_delete_source(input: &str) -> IResult<&str, StmtDeleteSource> {
     )(input)
 }
 
-fn stmt_delete_stream(input: &str) -> IResult<&str, StmtDeleteStream> {
+fn stmt_delete_stream(input: &str) -> ParseResult<StmtDeleteStream> {
     context(
         "stmt_delete_stream",
         map(
@@ -270,7 +290,7 @@ fn stmt_delete_stream(input: &str) -> IResult<&str, StmtDeleteStream> {
     )(input)
 }
 
-fn stmt_delete_sink(input: &str) -> IResult<&str, StmtDeleteSink> {
+fn stmt_delete_sink(input: &str) -> ParseResult<StmtDeleteSink> {
     context(
         "stmt_delete_sink",
         map(
@@ -280,7 +300,7 @@ fn stmt_delete_sink(input: &str) -> IResult<&str, StmtDeleteSink> {
     )(input)
 }
 
-fn stmt_start_stream(input: &str) -> IResult<&str, StmtStartStream> {
+fn stmt_start_stream(input: &str) -> ParseResult<StmtStartStream> {
     context(
         "stmt_start_stream",
         map(
@@ -299,7 +319,7 @@ fn stmt_start_stream(input: &str) -> IResult<&str, StmtStartStream> {
     )(input)
 }
 
-fn stmt_stop_stream(input: &str) -> IResult<&str, StmtStopStream> {
+fn stmt_stop_stream(input: &str) -> ParseResult<StmtStopStream> {
     context(
         "stmt_stop_stream",
         map(
@@ -309,7 +329,7 @@ fn stmt_stop_stream(input: &str) -> IResult<&str, StmtStopStream> {
     )(input)
 }
 
-fn stmt_show_stream(input: &str) -> IResult<&str, StmtShow> {
+fn stmt_show_stream(input: &str) -> ParseResult<StmtShow> {
     let show_type = alt((
         value(ShowType::Sources, tag_no_case("sources")),
         value(ShowType::Streams, tag_no_case("streams")),
@@ -325,32 +345,95 @@ fn stmt_show_stream(input: &str) -> IResult<&str, StmtShow> {
     )(input)
 }
 
-pub fn stmt(input: &str) -> IResult<&str, Stmt> {
+fn stmt_describe(input: &str) -> ParseResult<StmtDescribe> {
     context(
-        "stmt",
-        terminated(
-            alt((
-                map(delimited(sp, stmt_create_source, sp), Stmt::CreateSource),
-                map(delimited(sp, stmt_create_stream, sp), Stmt::CreateStream),
-                map(delimited(sp, stmt_create_sink, sp), Stmt::CreateSink),
-                map(delimited(sp, stmt_delete_source, sp), Stmt::DeleteSource),
-                map(delimited(sp, stmt_delete_stream, sp), Stmt::DeleteStream),
-                map(delimited(sp, stmt_delete_sink, sp), Stmt::DeleteSink),
-                map(delimited(sp, stmt_start_stream, sp), Stmt::StartStream),
-                map(delimited(sp, stmt_stop_stream, sp), Stmt::StopStream),
-                map(delimited(sp, stmt_show_stream, sp), Stmt::Show),
-                map(delimited(sp, select, sp), |select| {
-                    Stmt::Select(StmtSelect { select })
-                }),
+        "stmt_describe",
+        map(
+            tuple((tag_no_case("describe"), sp, name)),
+            |(_, _, name)| StmtDescribe { name },
+        ),
+    )(input)
+}
+
+fn stmt_insert_into(input: &str) -> ParseResult<StmtInsertInto> {
+    context(
+        "stmt_insert_into",
+        map(
+            tuple((
+                tag_no_case("insert"),
+                sp,
+                tag_no_case("into"),
+                sp,
+                name,
+                sp,
+                select,
             )),
+            |(_, _, _, _, sink, _, select)| StmtInsertInto { sink, select },
+        ),
+    )(input)
+}
+
+fn any_stmt(input: &str) -> ParseResult<Stmt> {
+    context(
+        "stmt",
+        alt((
+            map(stmt_create_source, Stmt::CreateSource),
+            map(stmt_create_stream, Stmt::CreateStream),
+            map(stmt_create_sink, Stmt::CreateSink),
+            map(stmt_delete_source, Stmt::DeleteSource),
+            map(stmt_delete_stream, Stmt::DeleteStream),
+            map(stmt_delete_sink, Stmt::DeleteSink),
+            map(stmt_start_stream, Stmt::StartStream),
+            map(stmt_stop_stream, Stmt::StopStream),
+            map(stmt_show_stream, Stmt::Show),
+            map(stmt_describe, Stmt::Describe),
+            map(stmt_insert_into, Stmt::InsertInto),
+            map(select, |select| Stmt::Select(StmtSelect { select })),
+        )),
+    )(input)
+}
+
+pub fn stmt(input: &str) -> ParseResult<Stmt> {
+    terminated(delimited(sp, any_stmt, sp), eof)(input)
+}
+
+fn comment(input: &str) -> ParseResult<()> {
+    value((), pair(tag("--"), not_line_ending))(input)
+}
+
+/// Skips whitespaces and comments.
+fn sp_comment(input: &str) -> ParseResult<()> {
+    fold_many0(alt((value((), one_of(" \t\n\r")), comment)), (), |_, _| ())(input)
+}
+
+/// Skips whitespaces, comments and empty statements.
+fn stmt_separator(input: &str) -> ParseResult<()> {
+    fold_many0(alt((value((), one_of(" \t\n\r;")), comment)), (), |_, _| ())(input)
+}
+
+pub fn stmts(input: &str) -> ParseResult<Vec<Stmt>> {
+    let end_of_stmt = tuple((sp_comment, alt((value((), char(';')), value((), eof)))));
+
+    context(
+        "stmts",
+        delimited(
+            stmt_separator,
+            many0(terminated(any_stmt, pair(end_of_stmt, stmt_separator))),
             eof,
         ),
     )(input)
 }
 
+/// Parses a script of several statements separated by semicolons.
+pub fn parse_statements(sql: &str) -> anyhow::Result<Vec<Stmt>> {
+    let (_, stmts) = stmts(sql).map_err(|err| ParseError::new(sql, err))?;
+    Ok(stmts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use yql_core::dsl::{col, qualified_col};
     use yql_core::sql::ast::{Source, SourceFrom};
 
     #[test]
@@ -368,10 +451,14 @@ mod tests {
         assert_eq!(data_type("int16"), Ok(("", DataType::Int16)));
         assert_eq!(data_type("int32"), Ok(("", DataType::Int32)));
         assert_eq!(data_type("int64"), Ok(("", DataType::Int64)));
+        assert_eq!(data_type("uint8"), Ok(("", DataType::UInt8)));
+        assert_eq!(data_type("uint64"), Ok(("", DataType::UInt64)));
         assert_eq!(data_type("float32"), Ok(("", DataType::Float32)));
         assert_eq!(data_type("float64"), Ok(("", DataType::Float64)));
         assert_eq!(data_type("boolean"), Ok(("", DataType::Boolean)));
         assert_eq!(data_type("timestamp"), Ok(("", DataType::Timestamp(None))));
+        assert_eq!(data_type("date"), Ok(("", DataType::Date)));
+        assert_eq!(data_type("time"), Ok(("", DataType::Time)));
 
         assert_eq!(
             data_type("timestamp timezone \"UTC\""),
@@ -425,14 +512,8 @@ mod tests {
                         Field::new("t", DataType::Timestamp(None)),
                         Field::new("t2", DataType::Timestamp(None)),
                     ],
-                    time: Some(Expr::Column {
-                        qualifier: None,
-                        name: "t".to_string()
-                    }),
-                    watermark: Some(Expr::Column {
-                        qualifier: None,
-                        name: "t2".to_string()
-                    }),
+                    time: Some(col("t")),
+                    watermark: Some(col("t2")),
                 }
             ))
         )
@@ -447,24 +528,21 @@ mod tests {
                 StmtCreateStream {
                     name: "a".to_string(),
                     select: Select {
-                        projection: vec![
-                            Expr::Column {
-                                qualifier: None,
-                                name: "a".to_string()
-                            },
-                            Expr::Column {
-                                qualifier: None,
-                                name: "b".to_string()
-                            }
-                        ],
+                        projection: vec![col("a"), col("b")],
                         source: Source {
                             from: SourceFrom::Named("abc".to_string()),
-                            alias: None
+                            alias: None,
+                            watermark: None,
                         },
                         where_clause: None,
                         having_clause: None,
                         group_clause: None,
-                        window: None
+                        window: None,
+                        window_name: None,
+                        named_windows: vec![],
+                        window_time_zone: None,
+                        allowed_lateness: None,
+                        emit: None,
                     },
                     to: "d".to_string()
                 }
@@ -478,24 +556,21 @@ mod tests {
                 StmtCreateStream {
                     name: "a".to_string(),
                     select: Select {
-                        projection: vec![
-                            Expr::Column {
-                                qualifier: Some("a".to_string()),
-                                name: "a".to_string()
-                            },
-                            Expr::Column {
-                                qualifier: Some("a".to_string()),
-                                name: "b".to_string()
-                            }
-                        ],
+                        projection: vec![qualified_col("a", "a"), qualified_col("a", "b")],
                         source: Source {
                             from: SourceFrom::Named("abc".to_string()),
-                            alias: Some("a".to_string())
+                            alias: Some("a".to_string()),
+                            watermark: None,
                         },
                         where_clause: None,
                         having_clause: None,
                         group_clause: None,
-                        window: None
+                        window: None,
+                        window_name: None,
+                        named_windows: vec![],
+                        window_time_zone: None,
+                        allowed_lateness: None,
+                        emit: None,
                     },
                     to: "d".to_string()
                 }
@@ -568,4 +643,68 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_stmts() {
+        let stmts = parse_statements(
+            r#"
+            -- create objects
+            create source a (a int32) with "csv:///test";
+            create sink b with "console://";;
+            insert into b select a from a; -- run it
+            "#,
+        )
+        .unwrap();
+        assert_eq!(stmts.len(), 3);
+        assert!(matches!(stmts[0], Stmt::CreateSource(_)));
+        assert!(matches!(stmts[1], Stmt::CreateSink(_)));
+        assert!(matches!(stmts[2], Stmt::InsertInto(_)));
+
+        assert_eq!(parse_statements("show sources").unwrap().len(), 1);
+        assert_eq!(parse_statements(" -- nothing\n").unwrap().len(), 0);
+        assert!(parse_statements("show sources show sinks").is_err());
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            stmt_describe(r#"describe a"#),
+            Ok((
+                "",
+                StmtDescribe {
+                    name: "a".to_string(),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_insert_into() {
+        assert_eq!(
+            stmt_insert_into(r#"insert into d select a from abc"#),
+            Ok((
+                "",
+                StmtInsertInto {
+                    sink: "d".to_string(),
+                    select: Select {
+                        projection: vec![col("a")],
+                        source: Source {
+                            from: SourceFrom::Named("abc".to_string()),
+                            alias: None,
+                            watermark: None,
+                        },
+                        where_clause: None,
+                        having_clause: None,
+                        group_clause: None,
+                        window: None,
+                        window_name: None,
+                        named_windows: vec![],
+                        window_time_zone: None,
+                        allowed_lateness: None,
+                        emit: None,
+                    },
+                }
+            ))
+        );
+    }
 }
@@ -1,10 +1,11 @@
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 use anyhow::Result;
 use derive_more::Display;
 use rocksdb::{DBCompressionType, Options, DB};
 use serde::{Deserialize, Serialize};
-use yql_core::dataset::SchemaRef;
+use yql_core::dataset::{Schema, SchemaRef};
 use yql_core::expr::Expr;
 use yql_core::sql::ast::Select;
 
@@ -67,6 +68,9 @@ impl Definition {
 
 pub struct Storage {
     db: DB,
+    /// Serializes the updates of the schemas of the sources by the running streams, the other
+    /// changes of the definitions are serialized by the service.
+    update_lock: Mutex<()>,
 }
 
 impl Storage {
@@ -77,6 +81,7 @@ impl Storage {
 
         let db = Storage {
             db: DB::open(&opts, path)?,
+            update_lock: Mutex::new(()),
         };
         Ok(db)
     }
@@ -118,6 +123,34 @@ impl Storage {
         }
     }
 
+    /// Saves the fields of `schema`, the new schema of the source `name` reported by a running
+    /// stream, in the definition of the source.
+    ///
+    /// The fields are matched by name, so `schema` may be a projection of the schema of the
+    /// source. Does nothing if the source no longer exists.
+    pub fn update_source_schema(&self, name: &str, schema: &Schema) -> Result<()> {
+        let _guard = self.update_lock.lock();
+        let mut source_definition = match self.get_definition(name)? {
+            Some(Definition::Source(source_definition)) => source_definition,
+            _ => return Ok(()),
+        };
+        let mut fields = source_definition.schema.fields().to_vec();
+        for field in schema.fields() {
+            match fields.iter_mut().find(|f| f.name == field.name) {
+                Some(f) => f.data_type = field.data_type,
+                None => fields.push(field.clone()),
+            }
+        }
+        source_definition.schema = Arc::new(Schema::try_new(fields)?);
+
+        let key = format!("definition/{}", name);
+        self.db.put(
+            key,
+            bincode::serialize(&Definition::Source(source_definition))?,
+        )?;
+        Ok(())
+    }
+
     pub fn definition_exists(&self, name: &str) -> Result<bool> {
         let key = format!("definition/{}", name);
         Ok(self.db.get_pinned(key)?.is_some())
@@ -139,6 +172,19 @@ impl Storage {
         Ok(())
     }
 
+    /// Returns the names of the streams with saved state and the sizes of their states.
+    pub fn stream_state_data_sizes(&self) -> Result<Vec<(String, usize)>> {
+        let mut sizes = Vec::new();
+
+        for (key, value) in self.db.prefix_iterator("stream_state_data/") {
+            if let Some(name) = key.strip_prefix(b"stream_state_data/") {
+                sizes.push((String::from_utf8_lossy(name).into_owned(), value.len()));
+            }
+        }
+
+        Ok(sizes)
+    }
+
     pub fn get_stream_state(&self, name: &str) -> Result<Option<StreamState>> {
         let key = format!("stream_state/{}", name);
         match self.db.get_pinned(key)? {
@@ -0,0 +1,81 @@
+//! Streaming SQL over time series.
+//!
+//! This crate re-exports the public API of the `yql-*` crates, so that applications depend on
+//! a single crate:
+//!
+//! - [`array`] and [`dataset`]: the columnar data passed between the operators.
+//! - [`expr`] and [`sql`]: expressions, functions and the parser of the queries.
+//! - [`DataFrame`] and [`dsl`]: building streams, and running them with the `runtime` feature.
+//! - [`sources`] and [`sinks`]: the built-in connectors, with the `connectors` feature.
+//! - [`service`]: the service that stores and runs definitions, with the `server` feature.
+
+pub use yql_dataset::{array, dataset};
+
+pub use yql_core::{dsl, expr, metrics, progress, rates, sql};
+pub use yql_core::{
+    AdaptiveBatchSize, AggregateStateSize, BatchSize, BoxSink, CardinalityHints,
+    CheckPointFailureAction, CheckPointFailureCallback, DataFrame, Emit, ExecutionContext,
+    GenericSourceDataSet, GenericSourceProvider, Sink, SinkProvider, SourceProvider,
+    SourceProviderWrapper, StateSizeReport, Storage, WatermarkSkew, WatermarkSkewCallback, Window,
+    WindowColumnPlacement,
+};
+
+#[cfg(feature = "connectors")]
+pub use yql_core::{sinks, sources};
+
+#[cfg(feature = "server")]
+pub mod service {
+    pub use yql_service::{
+        Definition, Service, SinkDefinition, SourceDefinition, StreamDefinition,
+    };
+}
+
+/// Compiles the way downstream code uses the API, so that a breaking change fails here first.
+#[cfg(test)]
+mod tests {
+    use crate::array::{ArrayExt, ArrayRef, DataType, Int64Array, Scalar};
+    use crate::expr::Expr;
+    use crate::Window;
+
+    fn describe_type(data_type: DataType) -> &'static str {
+        match data_type {
+            DataType::Int64 => "int",
+            DataType::String => "string",
+            _ => "other",
+        }
+    }
+
+    fn describe_window(window: Window) -> &'static str {
+        match window {
+            Window::Fixed { .. } => "fixed",
+            _ => "other",
+        }
+    }
+
+    fn describe_expr(expr: &Expr) -> &'static str {
+        match expr {
+            Expr::Column { .. } => "column",
+            _ => "other",
+        }
+    }
+
+    #[test]
+    fn test_api_stability() {
+        assert_eq!(describe_type(DataType::Int64), "int");
+        assert_eq!(describe_type(DataType::Boolean), "other");
+        assert_eq!(describe_window(Window::Fixed { length: 1000 }), "fixed");
+        assert_eq!(describe_window(Window::Session { gap: 1000 }), "other");
+        assert_eq!(
+            describe_expr(&Expr::Column {
+                qualifier: None,
+                name: "a".to_string(),
+                span: Default::default(),
+            }),
+            "column"
+        );
+
+        let array: ArrayRef = std::sync::Arc::new(Int64Array::from_vec(vec![1, 2]));
+        assert_eq!(array.data_type(), DataType::Int64);
+        assert_eq!(array.scalar_value(1), Scalar::Int64(2));
+    }
+}